@@ -3,6 +3,7 @@ import { ActionInstance } from "@/store/actions.store";
 
 export enum ChangeSetStatus {
   Open = "Open",
+  PendingApproval = "PendingApproval",
   Closed = "Closed",
   Abandoned = "Abandoned",
   Applied = "Applied",
@@ -1,6 +1,10 @@
+export type WorkspaceRole = "Admin" | "Applier" | "Editor" | "Viewer";
+
 export interface Workspace {
   pk: string;
   name: string;
   created_at: IsoDateString;
   updated_at: IsoDateString;
+  // the current user's role, only set on workspaces listed for them
+  role?: WorkspaceRole;
 }
@@ -100,13 +100,24 @@ export function useChangeSetsStore() {
             },
           });
         },
-        async APPLY_CHANGE_SET() {
+        async FETCH_APPLY_PREVIEW() {
+          if (!this.selectedChangeSet) throw new Error("Select a change set");
+          return new ApiRequest<{ conflictCount: number }>({
+            url: "change_set/get_apply_preview",
+            params: {
+              changeSetPk: this.selectedChangeSet.pk,
+            },
+          });
+        },
+        // overriding conflicts overwrites whatever head changed in the meantime
+        async APPLY_CHANGE_SET(overrideConflicts = false) {
           if (!this.selectedChangeSet) throw new Error("Select a change set");
           return new ApiRequest<{ changeSet: ChangeSet }>({
             method: "post",
             url: "change_set/apply_change_set",
             params: {
               changeSetPk: this.selectedChangeSet.pk,
+              overrideConflicts,
             },
             onSuccess: (response) => {
               this.changeSetsById[response.changeSet.pk] = response.changeSet;
@@ -4,6 +4,7 @@ import ReconnectingWebSocket from "reconnecting-websocket";
 import { computed, reactive, ref, watch } from "vue";
 import { API_WS_URL } from "@/store/apis";
 import { useAuthStore } from "../auth.store";
+import { useWorkspacesStore } from "../workspaces.store";
 import { WsEventPayloadMap } from "./realtime_events";
 
 type RawConnectionStatus = "open" | "closed";
@@ -39,11 +40,19 @@ type RealtimeEventMetadata = {
 
 export const useRealtimeStore = defineStore("realtime", () => {
   const authStore = useAuthStore();
+  const workspacesStore = useWorkspacesStore();
 
   // ReconnectingWebsocket is a small wrapper around the native Websocket that should
   // handle basic reconnection logic
   const socket = new ReconnectingWebSocket(
-    () => `${API_WS_URL}/workspace_updates?token=Bearer+${authStore.token}`,
+    () => {
+      // the selected workspace is passed along so users can switch workspaces
+      // without authenticating again
+      const workspacePk = workspacesStore.selectedWorkspacePk;
+      return `${API_WS_URL}/workspace_updates?token=Bearer+${authStore.token}${
+        workspacePk ? `&workspacePk=${workspacePk}` : ""
+      }`;
+    },
     [],
     {
       // see options https://www.npmjs.com/package/reconnecting-websocket#available-options
@@ -68,6 +77,14 @@ export const useRealtimeStore = defineStore("realtime", () => {
     { immediate: true },
   );
 
+  // the socket only receives the updates of the selected workspace
+  watch(
+    () => workspacesStore.selectedWorkspacePk,
+    () => {
+      if (connectionShouldBeEnabled.value) socket.reconnect();
+    },
+  );
+
   const rawConnectionStatus = ref("closed" as RawConnectionStatus);
 
   socket.addEventListener("open", () => {
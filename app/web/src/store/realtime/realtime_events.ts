@@ -20,7 +20,25 @@ export type WsEventPayloadMap = {
   ChangeSetCreated: string;
   ChangeSetApplied: string;
   ChangeSetWritten: string;
+  ChangeSetRebased: string;
   ChangeSetCancelled: string;
+  ChangeSetClosed: string;
+  ChangeSetReopened: string;
+  ChangeSetApprovalRequested: string;
+  ChangeSetApproved: {
+    changeSetPk: string;
+    userPk: string;
+    comment?: string;
+    approvalCount: number;
+    requiredApprovals: number;
+  };
+  ChangeSetRejected: {
+    changeSetPk: string;
+    userPk: string;
+    comment?: string;
+    approvalCount: number;
+    requiredApprovals: number;
+  };
 
   CheckedQualifications: {
     prototypeId: string;
@@ -72,6 +90,10 @@ export type WsEventPayloadMap = {
   ComponentCreated: {
     success: boolean;
   };
+  ComponentDriftChanged: {
+    componentId: ComponentId;
+    status: "drifted" | "inSync" | "unknown";
+  };
 
   // Old fake status update
   // UpdateStatus: {
@@ -92,4 +114,16 @@ export type WsEventPayloadMap = {
       };
     }[];
   };
+
+  WorkspaceForkProgress: {
+    forkWorkspacePk: string;
+    step:
+      | "started"
+      | "exporting"
+      | "importing"
+      | "copyingSecrets"
+      | "finished"
+      | "failed";
+    error?: string;
+  };
 };
@@ -250,6 +250,65 @@ export function useSecretsStore() {
             },
           });
         },
+        async UPDATE_SECRET(
+          id: SecretId,
+          name: string,
+          description?: string,
+          value?: Record<string, string>,
+        ) {
+          const secret = this.secretsById[id];
+
+          if (_.isNil(secret)) return;
+
+          if (_.isEmpty(name)) {
+            throw new Error("All secrets must have a name.");
+          }
+
+          // Only rotate the secret if a new value was given
+          let newSecretData;
+          if (value !== undefined) {
+            if (_.isNil(this.publicKey)) {
+              throw new Error("Couldn't fetch publicKey.");
+            }
+
+            newSecretData = {
+              crypted: await encryptMessage(value, this.publicKey),
+              keyPairPk: this.publicKey.pk,
+              version: SecretVersion.V1,
+              algorithm: SecretAlgorithm.Sealedbox,
+            };
+          }
+
+          return new ApiRequest<Secret>({
+            method: "put",
+            url: "secret",
+            params: {
+              ...visibilityParams,
+              id,
+              name,
+              description,
+              newSecretData,
+            },
+            optimistic: () => {
+              this.secretIsTransitioning[secret.id] = true;
+
+              return () => {
+                this.secretIsTransitioning[secret.id] = false;
+              };
+            },
+            onSuccess: (response) => {
+              const definition =
+                this.secretDefinitionByDefinitionId[secret.definition];
+
+              if (definition !== undefined) {
+                definition.secrets = definition.secrets.map((s) =>
+                  s.id === id ? response : s,
+                );
+              }
+              this.secretIsTransitioning[secret.id] = false;
+            },
+          });
+        },
         async DELETE_SECRET(id: SecretId) {
           const secret = this.secretsById[id];
 
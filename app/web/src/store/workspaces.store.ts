@@ -2,8 +2,9 @@ import { defineStore } from "pinia";
 import * as _ from "lodash-es";
 import { watch } from "vue";
 import { addStoreHooks, ApiRequest } from "@si/vue-lib/pinia";
-import { Workspace } from "@/api/sdf/dal/workspace";
+import { Workspace, WorkspaceRole } from "@/api/sdf/dal/workspace";
 import { useAuthStore } from "./auth.store";
+import { useChangeSetsStore } from "./change_sets.store";
 import { useRouterStore } from "./router.store";
 
 type WorkspacePk = string;
@@ -14,6 +15,27 @@ type WorkspaceExportSummary = {
   createdAt: IsoDateString;
 };
 
+type WorkspaceBackup = {
+  pk: string;
+  module_id: string;
+  name: string;
+  version: string;
+  hash: string;
+  scheduled: boolean;
+  pruned_at: IsoDateString | null;
+  created_at: IsoDateString;
+};
+
+type ImportConflict =
+  | { type: "component"; uniqueId: string; name: string }
+  | { type: "edge"; uniqueId: string }
+  | { type: "func"; uniqueId: string; name: string }
+  | { type: "schema"; uniqueId: string; name: string };
+type ImportSkips = {
+  changeSetPk: string;
+  conflicts: ImportConflict[];
+};
+
 export const useWorkspacesStore = addStoreHooks(
   defineStore("workspaces", {
     state: () => ({
@@ -41,20 +63,70 @@ export const useWorkspacesStore = addStoreHooks(
     actions: {
       async FETCH_USER_WORKSPACES() {
         return new ApiRequest<{
-          workspace: Workspace;
+          workspaces: { workspace: Workspace; role: WorkspaceRole }[];
         }>({
-          // TODO: probably should fetch list of all workspaces here...
-          // something like `/users/USER_PK/workspaces`, `/my/workspaces`, etc
-          url: "/session/load_workspace",
+          url: "/session/list_workspaces",
           onSuccess: (response) => {
-            // this.workspacesByPk = _.keyBy(response.workspaces, "pk");
-            this.workspacesByPk = _.keyBy([response.workspace], "pk");
+            this.workspacesByPk = _.keyBy(
+              _.map(response.workspaces, ({ workspace, role }) => ({
+                ...workspace,
+                role,
+              })),
+              "pk",
+            );
 
             // NOTE - we could cache this stuff in localstorage too to avoid showing loading state
             // but this is a small optimization to make later...
           },
         });
       },
+      async FORK_WORKSPACE(name: string) {
+        return new ApiRequest<{ workspace: Workspace }>({
+          method: "post",
+          url: "/workspace/fork",
+          params: { name },
+          onSuccess: () => {
+            this.FETCH_USER_WORKSPACES();
+          },
+        });
+      },
+      async FETCH_WORKSPACE_BACKUPS() {
+        return new ApiRequest<{ backups: WorkspaceBackup[] }>({
+          url: "/workspace/list_backups",
+        });
+      },
+      async RESTORE_WORKSPACE_BACKUP(
+        backupPk: string,
+        target: { kind: "current" } | { kind: "new"; name: string },
+      ) {
+        return new ApiRequest<{ workspace: Workspace }>({
+          method: "post",
+          url: "/workspace/restore_backup",
+          params: { backupPk, target },
+          onSuccess: () => {
+            this.FETCH_USER_WORKSPACES();
+          },
+        });
+      },
+      async RESTORE_WORKSPACE_BACKUP_SELECTION(
+        backupPk: string,
+        selection: {
+          components?: string[];
+          schemas?: string[];
+          funcs?: string[];
+        },
+      ) {
+        const changeSetsStore = useChangeSetsStore();
+        return new ApiRequest<{ importSkips: ImportSkips }>({
+          method: "post",
+          url: "/workspace/restore_backup_selection",
+          params: {
+            backupPk,
+            selection,
+            visibility_change_set_pk: changeSetsStore.selectedChangeSetId,
+          },
+        });
+      },
     },
 
     onActivated() {
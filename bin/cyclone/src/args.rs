@@ -1,7 +1,7 @@
 use std::{net::SocketAddr, path::PathBuf, time::Duration};
 
 use clap::{ArgAction, Parser};
-use cyclone_server::{Config, ConfigError, IncomingStream};
+use cyclone_server::{Config, ConfigError, ExecutionLimits, IncomingStream};
 
 const NAME: &str = "cyclone";
 
@@ -103,6 +103,35 @@ pub(crate) struct Args {
     /// Cyclone decryption key file location [example: /run/cyclone/cyclone.key]
     #[arg(long)]
     pub(crate) decryption_key: PathBuf,
+
+    /// Directory of the file secret backend, holding one JSON file per referenced secret
+    #[arg(long)]
+    pub(crate) file_secret_backend_dir: Option<PathBuf>,
+
+    /// Limits the CPU time of each function execution, in seconds
+    #[arg(long)]
+    pub(crate) limit_cpu_time: Option<u64>,
+
+    /// Limits the memory of each function execution, in bytes (requires --cgroup-root)
+    #[arg(long)]
+    pub(crate) limit_memory: Option<u64>,
+
+    /// Limits the open files of each function execution
+    #[arg(long)]
+    pub(crate) limit_open_files: Option<u64>,
+
+    /// Limits the processes of each function execution
+    #[arg(long)]
+    pub(crate) limit_processes: Option<u64>,
+
+    /// Delegated cgroup v2 directory under which each function execution gets its own cgroup
+    /// [example: /sys/fs/cgroup/cyclone]
+    #[arg(long)]
+    pub(crate) cgroup_root: Option<PathBuf>,
+
+    /// Runs each function execution without network access
+    #[arg(long)]
+    pub(crate) disable_network: bool,
 }
 
 impl TryFrom<Args> for Config {
@@ -144,6 +173,19 @@ impl TryFrom<Args> for Config {
             builder.limit_requests(limit_requests);
         }
 
+        if let Some(dir) = args.file_secret_backend_dir {
+            builder.file_secret_backend_dir(dir);
+        }
+
+        builder.execution_limits(ExecutionLimits {
+            cpu_time_secs: args.limit_cpu_time,
+            memory_bytes: args.limit_memory,
+            open_files: args.limit_open_files,
+            processes: args.limit_processes,
+            cgroup_root: args.cgroup_root,
+            no_network: args.disable_network,
+        });
+
         builder.build().map_err(Into::into)
     }
 }
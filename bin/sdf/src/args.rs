@@ -74,6 +74,26 @@ pub(crate) struct Args {
     /// Location on disk of available packages
     pub(crate) pkgs_path: Option<String>,
 
+    /// How often, in seconds, resources on head are refreshed in the background [default: 300]
+    #[arg(long)]
+    pub(crate) resource_refresh_interval_secs: Option<u64>,
+
+    /// Disables the background resource refresh scheduler
+    #[arg(long)]
+    pub(crate) disable_resource_refresh: bool,
+
+    /// How often, in seconds, scheduled actions are checked for runs that are due [default: 60]
+    #[arg(long)]
+    pub(crate) action_scheduler_interval_secs: Option<u64>,
+
+    /// Disables the background scheduler for scheduled actions
+    #[arg(long)]
+    pub(crate) disable_action_scheduler: bool,
+
+    /// How many fixes of a batch can run at the same time [default: 4]
+    #[arg(long)]
+    pub(crate) max_concurrent_fixes: Option<u16>,
+
     /// The base URL for the module-index API server
     #[arg(long, env = "SI_MODULE_INDEX_URL")]
     pub(crate) module_index_url: Option<String>,
@@ -114,6 +134,21 @@ impl TryFrom<Args> for Config {
             if let Some(module_index_url) = args.module_index_url {
                 config_map.set("module_index_url", module_index_url);
             }
+            if let Some(interval_secs) = args.resource_refresh_interval_secs {
+                config_map.set("resource_refresh.interval_secs", interval_secs);
+            }
+            if args.disable_resource_refresh {
+                config_map.set("resource_refresh.enabled", false);
+            }
+            if let Some(interval_secs) = args.action_scheduler_interval_secs {
+                config_map.set("action_scheduler.interval_secs", interval_secs);
+            }
+            if args.disable_action_scheduler {
+                config_map.set("action_scheduler.enabled", false);
+            }
+            if let Some(max_concurrent_fixes) = args.max_concurrent_fixes {
+                config_map.set("max_concurrent_fixes", i64::from(max_concurrent_fixes));
+            }
 
             config_map.set("pg.application_name", NAME);
         })?
@@ -81,6 +81,8 @@ async fn run(args: args::Args, mut telemetry: ApplicationTelemetryClient) -> Res
 
     let (_resource_job_client, resource_job_processor) = JobProcessor::connect(&config).await?;
     let (_, status_receiver_job_processor) = JobProcessor::connect(&config).await?;
+    let (_, action_scheduler_job_processor) = JobProcessor::connect(&config).await?;
+    let (_, backup_scheduler_job_processor) = JobProcessor::connect(&config).await?;
 
     let pg_pool = Server::create_pg_pool(config.pg_pool()).await?;
 
@@ -90,6 +92,10 @@ async fn run(args: args::Args, mut telemetry: ApplicationTelemetryClient) -> Res
 
     let module_index_url = config.module_index_url().to_string();
 
+    let resource_refresh_config = config.resource_refresh().clone();
+    let action_scheduler_config = config.action_scheduler().clone();
+    let backup_scheduler_config = config.backup_scheduler().clone();
+
     if let MigrationMode::Run | MigrationMode::RunAndQuit = config.migration_mode() {
         Server::migrate_database(
             &pg_pool,
@@ -128,9 +134,11 @@ async fn run(args: args::Args, mut telemetry: ApplicationTelemetryClient) -> Res
                 jwt_public_signing_key,
                 posthog_client,
                 pkgs_path,
-                module_index_url,
+                module_index_url.clone(),
             )?;
             let second_shutdown_broadcast_rx = initial_shutdown_broadcast_rx.resubscribe();
+            let third_shutdown_broadcast_rx = initial_shutdown_broadcast_rx.resubscribe();
+            let fourth_shutdown_broadcast_rx = initial_shutdown_broadcast_rx.resubscribe();
 
             Server::start_resource_refresh_scheduler(
                 pg_pool.clone(),
@@ -138,10 +146,34 @@ async fn run(args: args::Args, mut telemetry: ApplicationTelemetryClient) -> Res
                 resource_job_processor,
                 veritech.clone(),
                 encryption_key,
+                resource_refresh_config,
                 initial_shutdown_broadcast_rx,
             )
             .await;
 
+            Server::start_action_scheduler(
+                pg_pool.clone(),
+                nats.clone(),
+                action_scheduler_job_processor,
+                veritech.clone(),
+                encryption_key,
+                action_scheduler_config,
+                third_shutdown_broadcast_rx,
+            )
+            .await;
+
+            Server::start_backup_scheduler(
+                pg_pool.clone(),
+                nats.clone(),
+                backup_scheduler_job_processor,
+                veritech.clone(),
+                encryption_key,
+                module_index_url,
+                backup_scheduler_config,
+                fourth_shutdown_broadcast_rx,
+            )
+            .await;
+
             Server::start_status_updater(
                 pg_pool,
                 nats,
@@ -165,10 +197,12 @@ async fn run(args: args::Args, mut telemetry: ApplicationTelemetryClient) -> Res
                 jwt_public_signing_key,
                 posthog_client,
                 pkgs_path,
-                module_index_url,
+                module_index_url.clone(),
             )
             .await?;
             let second_shutdown_broadcast_rx = initial_shutdown_broadcast_rx.resubscribe();
+            let third_shutdown_broadcast_rx = initial_shutdown_broadcast_rx.resubscribe();
+            let fourth_shutdown_broadcast_rx = initial_shutdown_broadcast_rx.resubscribe();
 
             Server::start_resource_refresh_scheduler(
                 pg_pool.clone(),
@@ -176,10 +210,34 @@ async fn run(args: args::Args, mut telemetry: ApplicationTelemetryClient) -> Res
                 resource_job_processor,
                 veritech.clone(),
                 encryption_key,
+                resource_refresh_config,
                 initial_shutdown_broadcast_rx,
             )
             .await;
 
+            Server::start_action_scheduler(
+                pg_pool.clone(),
+                nats.clone(),
+                action_scheduler_job_processor,
+                veritech.clone(),
+                encryption_key,
+                action_scheduler_config,
+                third_shutdown_broadcast_rx,
+            )
+            .await;
+
+            Server::start_backup_scheduler(
+                pg_pool.clone(),
+                nats.clone(),
+                backup_scheduler_job_processor,
+                veritech.clone(),
+                encryption_key,
+                module_index_url,
+                backup_scheduler_config,
+                fourth_shutdown_broadcast_rx,
+            )
+            .await;
+
             Server::start_status_updater(
                 pg_pool,
                 nats,
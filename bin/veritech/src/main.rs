@@ -36,6 +36,9 @@ async fn run(args: args::Args, mut telemetry: ApplicationTelemetryClient) -> Res
     start_tracing_level_signal_handler_task(&telemetry)?;
 
     match config.cyclone_spec() {
+        CycloneSpec::Container(_) => {
+            Server::for_cyclone_container(config).await?.run().await?;
+        }
         CycloneSpec::LocalHttp(_) => {
             Server::for_cyclone_http(config).await?.run().await?;
         }
@@ -455,7 +455,9 @@ mod tests {
         ComponentKind, ComponentView, FunctionResult, ProgressMessage, ResolverFunctionComponent,
         ValidationRequest,
     };
-    use cyclone_server::{Config, ConfigBuilder, DecryptionKey, Server, UdsIncomingStream};
+    use cyclone_server::{
+        Config, ConfigBuilder, DecryptionKey, ExecutionLimits, Server, UdsIncomingStream,
+    };
     use futures::StreamExt;
     use hyper::server::conn::AddrIncoming;
     use serde_json::json;
@@ -788,6 +790,7 @@ mod tests {
                     return v;
                 }"#,
             ),
+            timeout_secs: None,
         };
 
         // Start the protocol
@@ -889,6 +892,7 @@ mod tests {
                     return v;
                 }"#,
             ),
+            timeout_secs: None,
         };
 
         // Start the protocol
@@ -951,6 +955,145 @@ mod tests {
         }
     }
 
+    fn never_ending_resolver_request(timeout_secs: Option<u64>) -> ResolverFunctionRequest {
+        ResolverFunctionRequest {
+            execution_id: "1234".to_string(),
+            handler: "doit".to_string(),
+            component: ResolverFunctionComponent {
+                data: ComponentView {
+                    properties: serde_json::json!({}),
+                    kind: ComponentKind::Standard,
+                },
+                parents: vec![],
+            },
+            response_type: cyclone_core::ResolverFunctionResponseType::Object,
+            code_base64: base64_encode(
+                r#"function doit(input) {
+                    while (true) {}
+                }"#,
+            ),
+            timeout_secs,
+        }
+    }
+
+    #[allow(clippy::disallowed_methods)] // `$RUST_LOG` is checked for in macro
+    #[test(tokio::test)]
+    async fn uds_execute_resolver_past_its_timeout() {
+        let (_, key) = gen_keys();
+        let tmp_socket = rand_uds();
+        let mut builder = Config::builder();
+        let mut client =
+            uds_client_for_running_server(builder.enable_resolver(true), &tmp_socket, key).await;
+
+        // Start the protocol
+        let mut progress = client
+            .execute_resolver(never_ending_resolver_request(Some(1)))
+            .await
+            .expect("failed to establish websocket stream")
+            .start()
+            .await
+            .expect("failed to start protocol");
+
+        // Consume the output messages until the function is killed
+        while let Some(msg) = progress.next().await {
+            if let Err(err) = msg {
+                panic!("failed to receive message: err={err:?}");
+            }
+        }
+        // Get the result
+        let result = progress.finish().await.expect("failed to return result");
+        match result {
+            FunctionResult::Failure(failure) => {
+                assert_eq!(failure.execution_id, "1234");
+                assert!(failure.error.is_timeout());
+            }
+            FunctionResult::Success(success) => {
+                panic!("result should be failure; success={success:?}")
+            }
+        }
+    }
+
+    #[allow(clippy::disallowed_methods)] // `$RUST_LOG` is checked for in macro
+    #[test(tokio::test)]
+    async fn uds_execute_resolver_cancelled() {
+        let (_, key) = gen_keys();
+        let tmp_socket = rand_uds();
+        let mut builder = Config::builder();
+        let mut client =
+            uds_client_for_running_server(builder.enable_resolver(true), &tmp_socket, key).await;
+
+        // Start the protocol
+        let mut progress = client
+            .execute_resolver(never_ending_resolver_request(None))
+            .await
+            .expect("failed to establish websocket stream")
+            .start()
+            .await
+            .expect("failed to start protocol");
+
+        progress.cancel().await.expect("failed to cancel execution");
+
+        // Consume the output messages until the function is killed
+        while let Some(msg) = progress.next().await {
+            if let Err(err) = msg {
+                panic!("failed to receive message: err={err:?}");
+            }
+        }
+        // Get the result
+        let result = progress.finish().await.expect("failed to return result");
+        match result {
+            FunctionResult::Failure(failure) => {
+                assert_eq!(failure.execution_id, "1234");
+                assert!(failure.error.is_cancelled());
+            }
+            FunctionResult::Success(success) => {
+                panic!("result should be failure; success={success:?}")
+            }
+        }
+    }
+
+    #[allow(clippy::disallowed_methods)] // `$RUST_LOG` is checked for in macro
+    #[test(tokio::test)]
+    async fn uds_execute_resolver_past_its_cpu_time_limit() {
+        let (_, key) = gen_keys();
+        let tmp_socket = rand_uds();
+        let mut builder = Config::builder();
+        builder
+            .enable_resolver(true)
+            .execution_limits(ExecutionLimits {
+                cpu_time_secs: Some(1),
+                ..Default::default()
+            });
+        let mut client = uds_client_for_running_server(&mut builder, &tmp_socket, key).await;
+
+        // Start the protocol
+        let mut progress = client
+            .execute_resolver(never_ending_resolver_request(None))
+            .await
+            .expect("failed to establish websocket stream")
+            .start()
+            .await
+            .expect("failed to start protocol");
+
+        // Consume the output messages until the function is killed
+        while let Some(msg) = progress.next().await {
+            if let Err(err) = msg {
+                panic!("failed to receive message: err={err:?}");
+            }
+        }
+        // Get the result
+        let result = progress.finish().await.expect("failed to return result");
+        match result {
+            FunctionResult::Failure(failure) => {
+                assert_eq!(failure.execution_id, "1234");
+                assert!(failure.error.is_limit_exceeded());
+            }
+            FunctionResult::Success(success) => {
+                panic!("result should be failure; success={success:?}")
+            }
+        }
+    }
+
     async fn execute_validation<C, Strm>(mut client: C)
     where
         Strm: AsyncRead + AsyncWrite + Connection + Unpin + Send + 'static,
@@ -971,6 +1114,7 @@ mod tests {
                     }
                 }",
             ),
+            timeout_secs: None,
         };
         let mut progress = client
             .execute_validation(req)
@@ -1068,6 +1212,7 @@ mod tests {
                     return { status: 'ok' };
                 }"#,
             ),
+            timeout_secs: None,
         };
 
         // Start the protocol
@@ -1154,6 +1299,7 @@ mod tests {
                     return { status: 'ok' };
                 }"#,
             ),
+            timeout_secs: None,
         };
 
         // Start the protocol
@@ -1240,6 +1386,7 @@ mod tests {
                     return { updates: { "myid": true }, actions: ["run"] };
                 }"#,
             ),
+            timeout_secs: None,
         };
 
         // Start the protocol
@@ -1316,6 +1463,7 @@ mod tests {
                     return { updates: { "myid": true }, actions: ["run"] };
                 }"#,
             ),
+            timeout_secs: None,
         };
 
         // Start the protocol
@@ -1395,6 +1543,7 @@ mod tests {
                     return new AssetBuilder().build();
                 }"#,
             ),
+            timeout_secs: None,
         };
 
         // Start the protocol
@@ -1474,6 +1623,7 @@ mod tests {
                     return new AssetBuilder().build();
                 }"#,
             ),
+            timeout_secs: None,
         };
 
         // Start the protocol
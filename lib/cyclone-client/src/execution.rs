@@ -4,7 +4,7 @@ use std::{
     task::{Context, Poll},
 };
 
-use cyclone_core::{FunctionResult, Message, ProgressMessage};
+use cyclone_core::{ControlMessage, FunctionResult, Message, ProgressMessage};
 use futures::{Future, SinkExt, Stream, StreamExt};
 use hyper::client::connect::Connection;
 use serde::{de::DeserializeOwned, Serialize};
@@ -119,6 +119,18 @@ where
     pub async fn finish(self) -> Result<FunctionResult<Success>, ExecutionError<Success>> {
         ExecutionClosing::try_from(self)?.finish().await
     }
+
+    /// Asks the server to kill the function. The stream then carries on until the server sends
+    /// the result, which is a failure of the
+    /// [`cancelled`](cyclone_core::FunctionResultFailureError::KIND_CANCELLED) kind.
+    pub async fn cancel(&mut self) -> Result<(), ExecutionError<Success>> {
+        let msg = serde_json::to_string(&ControlMessage::Cancel)
+            .map_err(ExecutionError::JSONSerialize)?;
+        self.stream
+            .send(WebSocketMessage::Text(msg))
+            .await
+            .map_err(ExecutionError::WSSendIO)
+    }
 }
 
 impl<T, Success> Stream for ExecutionStarted<T, Success>
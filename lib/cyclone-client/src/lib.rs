@@ -10,7 +10,7 @@ pub use cyclone_core::{
     ReconciliationResultSuccess, ResolverFunctionRequest, ResolverFunctionResultSuccess,
     SchemaVariantDefinitionRequest, SchemaVariantDefinitionResultSuccess,
 };
-pub use execution::{Execution, ExecutionError};
+pub use execution::{Execution, ExecutionError, ExecutionStarted};
 pub use hyper::client::connect::Connection;
 pub use hyperlocal::UnixStream;
 pub use ping::{PingExecution, PingExecutionError};
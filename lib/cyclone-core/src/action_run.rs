@@ -7,6 +7,10 @@ pub struct ActionRunRequest {
     pub handler: String,
     pub code_base64: String,
     pub args: serde_json::Value,
+    /// How long, in seconds, the function may run before it is killed. If unset, it may run for
+    /// as long as it takes.
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
 }
 
 #[remain::sorted]
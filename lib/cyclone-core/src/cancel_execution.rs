@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+
+/// Asks for the in-flight execution of a function to be cancelled.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelExecutionRequest {
+    pub execution_id: String,
+}
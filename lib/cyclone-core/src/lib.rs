@@ -12,9 +12,11 @@
 )]
 
 mod action_run;
+mod cancel_execution;
 mod canonical_command;
 mod component_view;
 mod encryption_key;
+mod limits;
 mod liveness;
 pub mod process;
 mod progress;
@@ -22,17 +24,20 @@ mod readiness;
 mod reconciliation;
 mod resolver_function;
 mod schema_variant_definition;
+mod secret_reference;
 mod sensitive_container;
 mod validation;
 
 pub use action_run::{ActionRunRequest, ActionRunResultSuccess, ResourceStatus};
+pub use cancel_execution::CancelExecutionRequest;
 pub use canonical_command::{CanonicalCommand, CanonicalCommandError};
 pub use component_view::{ComponentKind, ComponentView};
 pub use encryption_key::{EncryptionKey, EncryptionKeyError};
+pub use limits::{ExecutionLimits, ExecutionLimitsError};
 pub use liveness::{LivenessStatus, LivenessStatusParseError};
 pub use progress::{
-    FunctionResult, FunctionResultFailure, FunctionResultFailureError, Message, OutputStream,
-    ProgressMessage,
+    ControlMessage, FunctionResult, FunctionResultFailure, FunctionResultFailureError, Message,
+    OutputStream, ProgressMessage,
 };
 pub use readiness::{ReadinessStatus, ReadinessStatusParseError};
 pub use reconciliation::{ReconciliationRequest, ReconciliationResultSuccess};
@@ -43,5 +48,6 @@ pub use resolver_function::{
 pub use schema_variant_definition::{
     SchemaVariantDefinitionRequest, SchemaVariantDefinitionResultSuccess,
 };
+pub use secret_reference::{SecretReference, FILE_SECRET_BACKEND_NAME, SECRET_REFERENCE_MARKER};
 pub use sensitive_container::{SensitiveContainer, SensitiveString};
 pub use validation::{ValidationRequest, ValidationResultSuccess};
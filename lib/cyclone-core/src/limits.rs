@@ -0,0 +1,60 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum ExecutionLimitsError {
+    #[error("the {0} limit is only enforced with a cgroup root")]
+    RequiresCgroupRoot(&'static str),
+}
+
+/// The limits every function execution runs under. Each limit is applied to the language server
+/// process of the execution, and to whatever it spawns, as long as it is set.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionLimits {
+    /// How much CPU time, in seconds, the function may use.
+    #[serde(default)]
+    pub cpu_time_secs: Option<u64>,
+    /// How much memory, in bytes, the function may use. Requires a
+    /// [`cgroup_root`](Self::cgroup_root).
+    #[serde(default)]
+    pub memory_bytes: Option<u64>,
+    /// How many files the function may have open at once.
+    #[serde(default)]
+    pub open_files: Option<u64>,
+    /// How many processes (and threads) the function may run at once. Requires a
+    /// [`cgroup_root`](Self::cgroup_root).
+    #[serde(default)]
+    pub processes: Option<u64>,
+    /// A cgroup v2 directory, delegated to the user running cyclone, under which every execution
+    /// gets its own cgroup.
+    #[serde(default)]
+    pub cgroup_root: Option<PathBuf>,
+    /// If `true`, the function runs in a network namespace of its own, with no network access.
+    #[serde(default)]
+    pub no_network: bool,
+}
+
+impl ExecutionLimits {
+    /// Returns `true` if none of the limits are set.
+    pub fn is_unlimited(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Checks every limit that is set can be enforced, so that none is silently ignored.
+    pub fn validate(&self) -> Result<(), ExecutionLimitsError> {
+        if self.cgroup_root.is_none() {
+            if self.memory_bytes.is_some() {
+                return Err(ExecutionLimitsError::RequiresCgroupRoot("memory"));
+            }
+            if self.processes.is_some() {
+                return Err(ExecutionLimitsError::RequiresCgroupRoot("process count"));
+            }
+        }
+        Ok(())
+    }
+}
+
@@ -60,3 +60,15 @@ pub async fn child_shutdown(
         }
     }
 }
+
+/// Kills the process group led by the child, so that whatever the child spawned dies with it,
+/// then waits on the child.
+pub async fn child_kill_group(child: &mut Child) -> Result<ExitStatus, ShutdownError> {
+    if let Some(pid) = child.id() {
+        trace!("sending {} to process group {}", Signal::SIGKILL, pid);
+        let pid = i32::try_from(pid)?;
+        signal::killpg(Pid::from_raw(pid), Signal::SIGKILL)?;
+    }
+
+    child_shutdown(child, None, None).await
+}
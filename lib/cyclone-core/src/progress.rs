@@ -88,6 +88,15 @@ where
     }
 }
 
+/// A message sent by the client while a function is executing.
+#[remain::sorted]
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum ControlMessage {
+    /// Kills the function. Its result is then a failure of the
+    /// [`cancelled`](FunctionResultFailureError::KIND_CANCELLED) kind.
+    Cancel,
+}
+
 #[remain::sorted]
 #[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub enum FunctionResult<S> {
@@ -110,6 +119,31 @@ pub struct FunctionResultFailureError {
     pub message: String,
 }
 
+impl FunctionResultFailureError {
+    /// The kind of error of a function that ran for longer than its request allows.
+    pub const KIND_TIMEOUT: &'static str = "functionTimeout";
+    /// The kind of error of a function whose execution was cancelled.
+    pub const KIND_CANCELLED: &'static str = "functionCancelled";
+    /// The kind of error of a function that was killed for going over one of its
+    /// [`ExecutionLimits`](crate::ExecutionLimits). The message names the limit.
+    pub const KIND_LIMIT_EXCEEDED: &'static str = "functionLimitExceeded";
+
+    /// Returns `true` if the function ran for longer than its request allows.
+    pub fn is_timeout(&self) -> bool {
+        self.kind == Self::KIND_TIMEOUT
+    }
+
+    /// Returns `true` if the execution of the function was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.kind == Self::KIND_CANCELLED
+    }
+
+    /// Returns `true` if the function went over one of its execution limits.
+    pub fn is_limit_exceeded(&self) -> bool {
+        self.kind == Self::KIND_LIMIT_EXCEEDED
+    }
+}
+
 #[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct Fail {
     pub message: String,
@@ -11,6 +11,10 @@ pub struct ResolverFunctionRequest {
     pub component: ResolverFunctionComponent,
     pub response_type: ResolverFunctionResponseType,
     pub code_base64: String,
+    /// How long, in seconds, the function may run before it is killed. If unset, it may run for
+    /// as long as it takes.
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, Default)]
@@ -6,6 +6,10 @@ pub struct SchemaVariantDefinitionRequest {
     pub execution_id: String,
     pub handler: String,
     pub code_base64: String,
+    /// How long, in seconds, the function may run before it is killed. If unset, it may run for
+    /// as long as it takes.
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
 }
 
 #[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
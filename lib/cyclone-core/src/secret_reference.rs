@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// The key under which a secret message holds a [`SecretReference`], i.e. a message of the form
+/// `{ "cycloneSecretReference": { "backend": "file", "key": "docker-hub" } }`.
+pub const SECRET_REFERENCE_MARKER: &str = "cycloneSecretReference";
+
+/// The name of the backend reading secrets from JSON files, which Cyclone provides for tests and
+/// local development.
+pub const FILE_SECRET_BACKEND_NAME: &str = "file";
+
+/// Where the message of a secret that lives in an external store is found.
+///
+/// Instead of the secret itself, the message of such a secret holds a reference, which Cyclone
+/// resolves with the named backend when a function is executed.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretReference {
+    /// The name of the backend holding the secret.
+    pub backend: String,
+    /// What identifies the secret in the backend.
+    pub key: String,
+}
+
+impl SecretReference {
+    pub fn new(backend: impl Into<String>, key: impl Into<String>) -> Self {
+        Self {
+            backend: backend.into(),
+            key: key.into(),
+        }
+    }
+
+    /// Scopes the key of the reference to the given namespace, e.g. the workspace the secret
+    /// belongs to, so that the reference cannot reach the secrets of another namespace.
+    #[must_use]
+    pub fn namespaced(&self, namespace: impl std::fmt::Display) -> Self {
+        Self {
+            backend: self.backend.clone(),
+            key: format!("{namespace}/{}", self.key),
+        }
+    }
+
+    /// Returns the reference held by a secret message, if it holds one.
+    #[must_use]
+    pub fn from_message(message: &Value) -> Option<Self> {
+        message
+            .get(SECRET_REFERENCE_MARKER)
+            .and_then(|reference| serde_json::from_value(reference.clone()).ok())
+    }
+
+    /// Builds the message of a secret holding this reference.
+    #[must_use]
+    pub fn to_message(&self) -> Value {
+        serde_json::json!({ SECRET_REFERENCE_MARKER: self })
+    }
+}
@@ -4,6 +4,7 @@ use std::{
     time::Duration,
 };
 
+use cyclone_core::ExecutionLimits;
 use derive_builder::Builder;
 use si_settings::{CanonicalFile, CanonicalFileError};
 use thiserror::Error;
@@ -24,6 +25,7 @@ pub enum ConfigError {
 type Result<T> = std::result::Result<T, ConfigError>;
 
 #[derive(Debug, Builder)]
+#[builder(build_fn(validate = "Self::validate"))]
 pub struct Config {
     #[builder(default)]
     watch: Option<Duration>,
@@ -54,6 +56,12 @@ pub struct Config {
 
     #[builder(setter(into), default)]
     limit_requests: Option<u32>,
+
+    #[builder(setter(into, strip_option), default)]
+    file_secret_backend_dir: Option<PathBuf>,
+
+    #[builder(default)]
+    execution_limits: ExecutionLimits,
 }
 
 impl Config {
@@ -122,6 +130,20 @@ impl Config {
     pub fn limit_requests(&self) -> Option<u32> {
         self.limit_requests
     }
+
+    /// Gets a reference to the config's file secret backend directory. If set, secrets
+    /// referencing the [`FileSecretBackend`](crate::FileSecretBackend) are read from there.
+    #[must_use]
+    pub fn file_secret_backend_dir(&self) -> Option<&Path> {
+        self.file_secret_backend_dir.as_deref()
+    }
+
+    /// Gets a reference to the config's execution limits, which every function execution runs
+    /// under.
+    #[must_use]
+    pub fn execution_limits(&self) -> &ExecutionLimits {
+        &self.execution_limits
+    }
 }
 
 impl ConfigBuilder {
@@ -132,6 +154,13 @@ impl ConfigBuilder {
     pub fn unix_domain_socket(&mut self, path: impl Into<PathBuf>) -> &mut Self {
         self.incoming_stream(IncomingStream::unix_domain_socket(path))
     }
+
+    fn validate(&self) -> std::result::Result<(), String> {
+        if let Some(execution_limits) = &self.execution_limits {
+            execution_limits.validate().map_err(|err| err.to_string())?;
+        }
+        Ok(())
+    }
 }
 
 #[remain::sorted]
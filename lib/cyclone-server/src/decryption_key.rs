@@ -6,6 +6,8 @@ use telemetry::prelude::*;
 use thiserror::Error;
 use tokio::{fs::File, io::AsyncReadExt};
 
+use crate::SecretBackendError;
+
 #[remain::sorted]
 #[derive(Error, Debug)]
 pub enum DecryptionKeyError {
@@ -21,6 +23,8 @@ pub enum DecryptionKeyError {
     KeyParse,
     #[error("failed to load key from file: {0}")]
     LoadKeyIO(#[source] io::Error),
+    #[error(transparent)]
+    SecretBackend(#[from] SecretBackendError),
     #[error("json serialize/deseialize error: {0}")]
     Serde(#[from] serde_json::Error),
 }
@@ -11,8 +11,8 @@ use axum::extract::ws::WebSocket;
 use bytes_lines_codec::BytesLinesCodec;
 use cyclone_core::{
     process::{self, ShutdownError},
-    FunctionResult, FunctionResultFailure, FunctionResultFailureError, Message, OutputStream,
-    SensitiveString,
+    ControlMessage, ExecutionLimits, FunctionResult, FunctionResultFailure,
+    FunctionResultFailureError, Message, OutputStream, SensitiveString,
 };
 use futures::{SinkExt, StreamExt, TryStreamExt};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
@@ -21,14 +21,15 @@ use telemetry::prelude::*;
 use thiserror::Error;
 use tokio::{
     process::{Child, ChildStderr, ChildStdin, ChildStdout, Command},
-    time,
+    time::{self, Instant},
 };
 use tokio_serde::{formats::SymmetricalJson, Deserializer, Framed, SymmetricallyFramed};
 use tokio_util::codec::{Decoder, FramedRead, FramedWrite};
 
 use crate::{
-    request::{DecryptRequest, ListSecrets},
-    DecryptionKey, DecryptionKeyError, WebSocketMessage,
+    request::{DecryptRequest, ExecutionRequest, ListSecrets},
+    sandbox::{self, Cgroup, LimitViolation, SandboxError},
+    DecryptionKey, DecryptionKeyError, SecretBackends, WebSocketMessage,
 };
 
 const TX_TIMEOUT_SECS: Duration = Duration::from_secs(5);
@@ -37,12 +38,16 @@ pub fn new<Request, LangServerSuccess, Success>(
     lang_server_path: impl Into<PathBuf>,
     lang_server_debugging: bool,
     key: Arc<DecryptionKey>,
+    secret_backends: Arc<SecretBackends>,
+    execution_limits: Arc<ExecutionLimits>,
     command: String,
 ) -> Execution<Request, LangServerSuccess, Success> {
     Execution {
         lang_server_path: lang_server_path.into(),
         lang_server_debugging,
         key,
+        secret_backends,
+        execution_limits,
         command,
         request_marker: PhantomData,
         lang_server_success_marker: PhantomData,
@@ -63,12 +68,16 @@ pub enum ExecutionError {
     ChildShutdown(#[from] ShutdownError),
     #[error("failed to spawn child process; program={0}")]
     ChildSpawn(#[source] io::Error, PathBuf),
+    #[error("failed to join the request decryption task")]
+    DecryptJoin(#[source] tokio::task::JoinError),
     #[error("failed to deserialize json message")]
     JSONDeserialize(#[source] serde_json::Error),
     #[error("failed to serialize json message")]
     JSONSerialize(#[source] serde_json::Error),
     #[error("key pair error: {0}")]
     KeyPair(#[from] DecryptionKeyError),
+    #[error(transparent)]
+    Sandbox(#[from] SandboxError),
     #[error("send timeout")]
     SendTimeout(#[source] tokio::time::error::Elapsed),
     #[error("unexpected websocket message type: {0:?}")]
@@ -90,6 +99,8 @@ pub struct Execution<Request, LangServerSuccess, Success> {
     lang_server_path: PathBuf,
     lang_server_debugging: bool,
     key: Arc<DecryptionKey>,
+    secret_backends: Arc<SecretBackends>,
+    execution_limits: Arc<ExecutionLimits>,
     command: String,
     request_marker: PhantomData<Request>,
     lang_server_success_marker: PhantomData<LangServerSuccess>,
@@ -98,7 +109,15 @@ pub struct Execution<Request, LangServerSuccess, Success> {
 
 impl<Request, LangServerSuccess, Success> Execution<Request, LangServerSuccess, Success>
 where
-    Request: DecryptRequest + ListSecrets + Serialize + DeserializeOwned + Unpin + core::fmt::Debug,
+    Request: DecryptRequest
+        + ExecutionRequest
+        + ListSecrets
+        + Serialize
+        + DeserializeOwned
+        + Unpin
+        + Send
+        + core::fmt::Debug
+        + 'static,
     LangServerSuccess: DeserializeOwned,
     Success: Serialize,
 {
@@ -110,13 +129,19 @@ where
         Self::ws_send_start(ws).await?;
         // Now that the server said to start, I am going to read my message!
         let request = Self::read_request(ws).await?;
-        let credentials: Vec<SensitiveString> = request.list_secrets(&self.key)?;
+        let execution_id = request.execution_id().to_owned();
+        let timeout = request.timeout();
+        let (credentials, request) =
+            Self::decrypt_request(request, self.key.clone(), self.secret_backends.clone()).await?;
         let mut command = Command::new(&self.lang_server_path);
         command
             .arg(&self.command)
+            // The child leads its own process group, so whatever it spawns can be killed with it
+            .process_group(0)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
+        sandbox::configure(&mut command, &self.execution_limits);
         if self.lang_server_debugging {
             command.env("DEBUG", "*").env("DEBUG_DEPTH", "5");
         }
@@ -124,9 +149,19 @@ where
         let mut child = command
             .spawn()
             .map_err(|err| ExecutionError::ChildSpawn(err, self.lang_server_path.clone()))?;
+        // The child waits for its request, so it has not run anything yet
+        let cgroup = match Cgroup::enter(&child, &self.execution_limits).await {
+            Ok(cgroup) => cgroup,
+            Err(err) => {
+                process::child_kill_group(&mut child).await?;
+                return Err(err.into());
+            }
+        };
+        // A timeout too far in the future to be represented is no deadline at all
+        let deadline = timeout.and_then(|timeout| Instant::now().checked_add(timeout));
 
         let stdin = child.stdin.take().ok_or(ExecutionError::ChildIO("stdin"))?;
-        Self::child_send_function_request(stdin, request, &self.key).await?;
+        Self::child_send_function_request(stdin, request).await?;
 
         let stderr = {
             let stderr = child
@@ -150,6 +185,9 @@ where
             stdout,
             stderr,
             credentials,
+            execution_id,
+            deadline,
+            cgroup,
             success_marker: self.success_marker,
         })
     }
@@ -178,13 +216,24 @@ where
         Ok(())
     }
 
-    async fn child_send_function_request(
-        stdin: ChildStdin,
+    /// Lists the credentials of the request and decrypts it. This runs on a blocking thread, as
+    /// resolving the secrets referencing a [`SecretBackend`](crate::SecretBackend) may block.
+    async fn decrypt_request(
         request: Request,
-        key: &DecryptionKey,
-    ) -> Result<()> {
-        let value = request.decrypt_request(key)?;
+        key: Arc<DecryptionKey>,
+        secret_backends: Arc<SecretBackends>,
+    ) -> Result<(Vec<SensitiveString>, Value)> {
+        let decrypted = tokio::task::spawn_blocking(move || {
+            let credentials = request.list_secrets(&key, &secret_backends)?;
+            let value = request.decrypt_request(&key, &secret_backends)?;
+            Ok::<_, DecryptionKeyError>((credentials, value))
+        })
+        .await
+        .map_err(ExecutionError::DecryptJoin)??;
+        Ok(decrypted)
+    }
 
+    async fn child_send_function_request(stdin: ChildStdin, value: Value) -> Result<()> {
         let codec = FramedWrite::new(stdin, BytesLinesCodec::new());
         let mut stdin = SymmetricallyFramed::new(codec, SymmetricalJson::default());
 
@@ -212,6 +261,10 @@ pub struct ExecutionStarted<LangServerSuccess, Success> {
     stdout: SiFramed<SiMessage<LangServerSuccess>>,
     stderr: FramedRead<ChildStderr, BytesLinesCodec>,
     credentials: Vec<SensitiveString>,
+    execution_id: String,
+    /// When the function is killed if it is still running, if ever.
+    deadline: Option<Instant>,
+    cgroup: Option<Cgroup>,
     success_marker: PhantomData<Success>,
 }
 
@@ -251,45 +304,166 @@ where
     SymmetricalJson<SiMessage<LangServerSuccess>>: Deserializer<SiMessage<LangServerSuccess>>,
     SiDecoderError: From<SiJsonError<LangServerSuccess>>,
 {
-    pub async fn process(self, ws: &mut WebSocket) -> Result<ExecutionClosing<Success>> {
+    pub async fn process(mut self, ws: &mut WebSocket) -> Result<ExecutionClosing<Success>> {
         tokio::spawn(handle_stderr(self.stderr, self.credentials.clone()));
 
-        let mut stream = self
-            .stdout
-            .map(|ls_result| match ls_result {
-                Ok(ls_msg) => match ls_msg {
-                    LangServerMessage::Output(mut output) => {
-                        Self::filter_output(&mut output, &self.credentials)?;
-                        Ok(Message::OutputStream(output.into()))
+        let mut stream = self.stdout.map(|ls_result| match ls_result {
+            Ok(ls_msg) => match ls_msg {
+                LangServerMessage::Output(mut output) => {
+                    Self::filter_output(&mut output, &self.credentials)?;
+                    Ok(Message::OutputStream(output.into()))
+                }
+                LangServerMessage::Result(mut result) => {
+                    Self::filter_result(&mut result, &self.credentials)?;
+                    Ok(Message::Result(result.into()))
+                }
+            },
+            Err(err) => Err(ExecutionError::ChildRecvIO(err)),
+        });
+
+        let mut result_sent = false;
+        let timeout = time::sleep_until(self.deadline.unwrap_or_else(Instant::now));
+        tokio::pin!(timeout);
+        loop {
+            tokio::select! {
+                msg = stream.try_next() => match msg? {
+                    Some(Message::Result(FunctionResult::Failure(failure))) => {
+                        result_sent = true;
+                        // A function going over a limit most likely fails on its own, the limit
+                        // is the more useful failure to report
+                        let msg = match sandbox::violation(None, self.cgroup.as_ref()).await {
+                            Some(violation) => Self::limit_exceeded(&self.execution_id, violation),
+                            None => Message::Result(FunctionResult::Failure(failure)),
+                        };
+                        Self::ws_send(ws, msg).await?;
                     }
-                    LangServerMessage::Result(mut result) => {
-                        Self::filter_result(&mut result, &self.credentials)?;
-                        Ok(Message::Result(result.into()))
+                    Some(msg) => {
+                        result_sent |= matches!(msg, Message::Result(_));
+                        Self::ws_send(ws, msg).await?;
+                    }
+                    None => {
+                        if !result_sent {
+                            // The function died without a result, which a limit may explain
+                            let exit_status = time::timeout(TX_TIMEOUT_SECS, self.child.wait())
+                                .await
+                                .ok()
+                                .and_then(|wait_result| wait_result.ok());
+                            if let Some(violation) =
+                                sandbox::violation(exit_status, self.cgroup.as_ref()).await
+                            {
+                                warn!(
+                                    execution_id = %self.execution_id,
+                                    %violation,
+                                    "function went over a limit",
+                                );
+                                let msg = Self::limit_exceeded(&self.execution_id, violation);
+                                Self::ws_send(ws, msg).await?;
+                            }
+                        }
+                        break;
                     }
                 },
-                Err(err) => Err(ExecutionError::ChildRecvIO(err)),
-            })
-            .map(|msg_result: Result<_>| match msg_result {
-                Ok(msg) => match msg
-                    .serialize_to_string()
-                    .map_err(ExecutionError::JSONSerialize)
-                {
-                    Ok(json_str) => Ok(WebSocketMessage::Text(json_str)),
-                    Err(err) => Err(err),
+                _ = &mut timeout, if self.deadline.is_some() => {
+                    warn!(execution_id = %self.execution_id, "function timed out, killing it");
+                    Self::kill_and_fail(
+                        &mut self.child,
+                        ws,
+                        &self.execution_id,
+                        FunctionResultFailureError::KIND_TIMEOUT,
+                        "function timed out",
+                    )
+                    .await?;
+                    break;
+                }
+                control = ws.next() => match control {
+                    Some(Ok(WebSocketMessage::Text(json_str))) => {
+                        match serde_json::from_str(&json_str)
+                            .map_err(ExecutionError::JSONDeserialize)?
+                        {
+                            ControlMessage::Cancel => {
+                                info!(
+                                    execution_id = %self.execution_id,
+                                    "function cancelled, killing it",
+                                );
+                                Self::kill_and_fail(
+                                    &mut self.child,
+                                    ws,
+                                    &self.execution_id,
+                                    FunctionResultFailureError::KIND_CANCELLED,
+                                    "function execution was cancelled",
+                                )
+                                .await?;
+                                break;
+                            }
+                        }
+                    }
+                    Some(Ok(WebSocketMessage::Ping(_) | WebSocketMessage::Pong(_))) => {}
+                    // Nobody is waiting on the function anymore, so it is killed
+                    Some(Ok(WebSocketMessage::Close(_))) | None => {
+                        process::child_kill_group(&mut self.child).await?;
+                        return Err(ExecutionError::WSRecvClosed);
+                    }
+                    Some(Ok(unexpected)) => {
+                        process::child_kill_group(&mut self.child).await?;
+                        return Err(ExecutionError::UnexpectedMessageType(unexpected));
+                    }
+                    Some(Err(err)) => {
+                        process::child_kill_group(&mut self.child).await?;
+                        return Err(ExecutionError::WSRecvIO(err));
+                    }
                 },
-                Err(err) => Err(err),
-            });
-
-        while let Some(msg) = stream.try_next().await? {
-            ws.send(msg).await.map_err(ExecutionError::WSSendIO)?;
+            }
         }
 
         Ok(ExecutionClosing {
             child: self.child,
+            cgroup: self.cgroup,
             success_marker: PhantomData,
         })
     }
 
+    /// Kills the function, then sends a failure result of the given kind in place of its own.
+    async fn kill_and_fail(
+        child: &mut Child,
+        ws: &mut WebSocket,
+        execution_id: &str,
+        kind: &str,
+        message: &str,
+    ) -> Result<()> {
+        process::child_kill_group(child).await?;
+        Self::ws_send(ws, Self::failure(execution_id, kind, message.to_owned())).await
+    }
+
+    fn limit_exceeded(execution_id: &str, violation: LimitViolation) -> Message<Success> {
+        Self::failure(
+            execution_id,
+            FunctionResultFailureError::KIND_LIMIT_EXCEEDED,
+            format!("function exceeded its {violation} limit"),
+        )
+    }
+
+    fn failure(execution_id: &str, kind: &str, message: String) -> Message<Success> {
+        Message::Result(FunctionResult::Failure(FunctionResultFailure {
+            execution_id: execution_id.to_owned(),
+            error: FunctionResultFailureError {
+                kind: kind.to_owned(),
+                message,
+            },
+            timestamp: crate::timestamp(),
+        }))
+    }
+
+    async fn ws_send(ws: &mut WebSocket, msg: Message<Success>) -> Result<()> {
+        let msg = msg
+            .serialize_to_string()
+            .map_err(ExecutionError::JSONSerialize)?;
+        time::timeout(TX_TIMEOUT_SECS, ws.send(WebSocketMessage::Text(msg)))
+            .await
+            .map_err(ExecutionError::SendTimeout)?
+            .map_err(ExecutionError::WSSendIO)?;
+        Ok(())
+    }
+
     fn filter_output(output: &mut LangServerOutput, credentials: &[SensitiveString]) -> Result<()> {
         // Note: This brings a possibility of random substrings being matched out of context,
         // exposing that we have a secret by censoring it But trying to infer word boundary might
@@ -338,6 +512,7 @@ where
 #[derive(Debug)]
 pub struct ExecutionClosing<Success> {
     child: Child,
+    cgroup: Option<Cgroup>,
     success_marker: PhantomData<Success>,
 }
 
@@ -353,6 +528,9 @@ where
                 .await
                 .map_err(Into::into);
         drop(self.child);
+        if let Some(cgroup) = self.cgroup {
+            cgroup.remove().await;
+        }
 
         match (finished, closed, shutdown) {
             // Everything succeeds, great!
@@ -25,12 +25,15 @@ use telemetry::prelude::*;
 use super::extract::LimitRequestGuard;
 use crate::{
     execution::{self, Execution},
-    request::{DecryptRequest, ListSecrets},
+    request::{DecryptRequest, ExecutionRequest, ListSecrets},
     result::{
         LangServerActionRunResultSuccess, LangServerReconciliationResultSuccess,
         LangServerResolverFunctionResultSuccess, LangServerValidationResultSuccess,
     },
-    state::{DecryptionKey, LangServerPath, TelemetryLevel, WatchKeepalive},
+    state::{
+        DecryptionKey, ExecutionLimits, LangServerPath, SecretBackends, TelemetryLevel,
+        WatchKeepalive,
+    },
     watch,
 };
 
@@ -85,6 +88,8 @@ pub async fn ws_execute_resolver(
     wsu: WebSocketUpgrade,
     State(lang_server_path): State<LangServerPath>,
     State(key): State<DecryptionKey>,
+    State(secret_backends): State<SecretBackends>,
+    State(execution_limits): State<ExecutionLimits>,
     State(telemetry_level): State<TelemetryLevel>,
     limit_request_guard: LimitRequestGuard,
 ) -> impl IntoResponse {
@@ -98,6 +103,8 @@ pub async fn ws_execute_resolver(
             lang_server_path,
             telemetry_level.is_debug_or_lower(),
             key.into(),
+            secret_backends.into(),
+            execution_limits.into(),
             limit_request_guard,
             "resolverfunction".to_owned(),
             request,
@@ -112,6 +119,8 @@ pub async fn ws_execute_validation(
     wsu: WebSocketUpgrade,
     State(lang_server_path): State<LangServerPath>,
     State(key): State<DecryptionKey>,
+    State(secret_backends): State<SecretBackends>,
+    State(execution_limits): State<ExecutionLimits>,
     State(telemetry_level): State<TelemetryLevel>,
     limit_request_guard: LimitRequestGuard,
 ) -> impl IntoResponse {
@@ -125,6 +134,8 @@ pub async fn ws_execute_validation(
             lang_server_path,
             telemetry_level.is_debug_or_lower(),
             key.into(),
+            secret_backends.into(),
+            execution_limits.into(),
             limit_request_guard,
             "validation".to_owned(),
             request,
@@ -139,6 +150,8 @@ pub async fn ws_execute_action_run(
     wsu: WebSocketUpgrade,
     State(lang_server_path): State<LangServerPath>,
     State(key): State<DecryptionKey>,
+    State(secret_backends): State<SecretBackends>,
+    State(execution_limits): State<ExecutionLimits>,
     State(telemetry_level): State<TelemetryLevel>,
     limit_request_guard: LimitRequestGuard,
 ) -> impl IntoResponse {
@@ -152,6 +165,8 @@ pub async fn ws_execute_action_run(
             lang_server_path,
             telemetry_level.is_debug_or_lower(),
             key.into(),
+            secret_backends.into(),
+            execution_limits.into(),
             limit_request_guard,
             "actionRun".to_owned(),
             request,
@@ -166,6 +181,8 @@ pub async fn ws_execute_reconciliation(
     wsu: WebSocketUpgrade,
     State(lang_server_path): State<LangServerPath>,
     State(key): State<DecryptionKey>,
+    State(secret_backends): State<SecretBackends>,
+    State(execution_limits): State<ExecutionLimits>,
     State(telemetry_level): State<TelemetryLevel>,
     limit_request_guard: LimitRequestGuard,
 ) -> impl IntoResponse {
@@ -179,6 +196,8 @@ pub async fn ws_execute_reconciliation(
             lang_server_path,
             telemetry_level.is_debug_or_lower(),
             key.into(),
+            secret_backends.into(),
+            execution_limits.into(),
             limit_request_guard,
             "reconciliation".to_owned(),
             request,
@@ -193,6 +212,8 @@ pub async fn ws_execute_schema_variant_definition(
     wsu: WebSocketUpgrade,
     State(lang_server_path): State<LangServerPath>,
     State(key): State<DecryptionKey>,
+    State(secret_backends): State<SecretBackends>,
+    State(execution_limits): State<ExecutionLimits>,
     State(telemetry_level): State<TelemetryLevel>,
     limit_request_guard: LimitRequestGuard,
 ) -> impl IntoResponse {
@@ -206,6 +227,8 @@ pub async fn ws_execute_schema_variant_definition(
             lang_server_path,
             telemetry_level.is_debug_or_lower(),
             key.into(),
+            secret_backends.into(),
+            execution_limits.into(),
             limit_request_guard,
             "schemaVariantDefinition".to_owned(),
             request,
@@ -221,19 +244,33 @@ async fn handle_socket<Request, LangServerSuccess, Success>(
     lang_server_path: PathBuf,
     lang_server_debugging: bool,
     key: Arc<crate::DecryptionKey>,
+    secret_backends: Arc<crate::SecretBackends>,
+    execution_limits: Arc<cyclone_core::ExecutionLimits>,
     _limit_request_guard: LimitRequestGuard,
     sub_command: String,
     _request_marker: PhantomData<Request>,
     _lang_server_success_marker: PhantomData<LangServerSuccess>,
     success_marker: PhantomData<Success>,
 ) where
-    Request: DecryptRequest + ListSecrets + Serialize + DeserializeOwned + Unpin + fmt::Debug,
+    Request: DecryptRequest
+        + ExecutionRequest
+        + ListSecrets
+        + Serialize
+        + DeserializeOwned
+        + Unpin
+        + fmt::Debug,
     Success: Serialize + Unpin + fmt::Debug,
     LangServerSuccess: Serialize + DeserializeOwned + Unpin + fmt::Debug + Into<Success>,
 {
     let proto = {
-        let execution: Execution<Request, LangServerSuccess, Success> =
-            execution::new(lang_server_path, lang_server_debugging, key, sub_command);
+        let execution: Execution<Request, LangServerSuccess, Success> = execution::new(
+            lang_server_path,
+            lang_server_debugging,
+            key,
+            secret_backends,
+            execution_limits,
+            sub_command,
+        );
         match execution.start(&mut socket).await {
             Ok(started) => started,
             Err(err) => {
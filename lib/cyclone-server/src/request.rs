@@ -1,24 +1,45 @@
+use std::time::Duration;
+
 use cyclone_core::{
     ActionRunRequest, ComponentKind, ComponentView, ReconciliationRequest, ResolverFunctionRequest,
     SchemaVariantDefinitionRequest, SensitiveString, ValidationRequest,
 };
 use serde_json::Value;
 
-use crate::{DecryptionKey, DecryptionKeyError};
+use crate::{DecryptionKey, DecryptionKeyError, SecretBackends};
 
+/// Lists the plaintext credentials of a request, so they can be redacted from the output of the
+/// function. Secrets referencing a [`SecretBackend`](crate::SecretBackend) are resolved with it.
 pub trait ListSecrets {
-    fn list_secrets(&self, key: &DecryptionKey)
-        -> Result<Vec<SensitiveString>, DecryptionKeyError>;
+    fn list_secrets(
+        &self,
+        key: &DecryptionKey,
+        backends: &SecretBackends,
+    ) -> Result<Vec<SensitiveString>, DecryptionKeyError>;
 }
 
+/// Decrypts the secrets of a request before it is handed to the lang server. Secrets referencing
+/// a [`SecretBackend`](crate::SecretBackend) are resolved with it.
 pub trait DecryptRequest {
-    fn decrypt_request(self, key: &DecryptionKey) -> Result<serde_json::Value, DecryptionKeyError>;
+    fn decrypt_request(
+        self,
+        key: &DecryptionKey,
+        backends: &SecretBackends,
+    ) -> Result<serde_json::Value, DecryptionKeyError>;
+}
+
+/// Identifies the execution a request is for, and bounds how long its function may run.
+pub trait ExecutionRequest {
+    fn execution_id(&self) -> &str;
+
+    fn timeout(&self) -> Option<Duration>;
 }
 
 impl ListSecrets for ComponentView {
     fn list_secrets(
         &self,
         key: &DecryptionKey,
+        backends: &SecretBackends,
     ) -> Result<Vec<SensitiveString>, DecryptionKeyError> {
         if self.kind != ComponentKind::Credential {
             return Ok(vec![]);
@@ -48,7 +69,11 @@ impl ListSecrets for ComponentView {
                             .as_str()
                             .ok_or(DecryptionKeyError::EncryptedSecretNotFound)?;
                         let decrypted = key.decode_and_decrypt(encoded)?;
-                        secret_objects.push(serde_json::de::from_slice::<Value>(&decrypted)?);
+                        secret_objects.push(
+                            backends.resolve_message(serde_json::de::from_slice::<Value>(
+                                &decrypted,
+                            )?)?,
+                        );
                     } else {
                         object.into_iter().for_each(|(_, v)| work_queue.push(v));
                     }
@@ -79,7 +104,11 @@ impl ListSecrets for ComponentView {
 }
 
 impl DecryptRequest for ComponentView {
-    fn decrypt_request(self, key: &DecryptionKey) -> Result<Value, DecryptionKeyError> {
+    fn decrypt_request(
+        self,
+        key: &DecryptionKey,
+        backends: &SecretBackends,
+    ) -> Result<Value, DecryptionKeyError> {
         let mut value = serde_json::to_value(&self)?;
         if self.kind != ComponentKind::Credential {
             return Ok(value);
@@ -110,7 +139,7 @@ impl DecryptRequest for ComponentView {
                             .as_str()
                             .ok_or(DecryptionKeyError::EncryptedSecretNotFound)?;
                         let decrypted = key.decode_and_decrypt(encoded)?;
-                        serde_json::de::from_slice(&decrypted)?
+                        backends.resolve_message(serde_json::de::from_slice(&decrypted)?)?
                     } else {
                         work_queue.extend(object.iter().map(|(key, _)| format!("{pointer}/{key}")));
                         continue;
@@ -136,23 +165,28 @@ impl ListSecrets for ResolverFunctionRequest {
     fn list_secrets(
         &self,
         key: &DecryptionKey,
+        backends: &SecretBackends,
     ) -> Result<Vec<SensitiveString>, DecryptionKeyError> {
-        let mut secrets = self.component.data.list_secrets(key)?;
+        let mut secrets = self.component.data.list_secrets(key, backends)?;
         for component in &self.component.parents {
-            secrets.extend(component.list_secrets(key)?);
+            secrets.extend(component.list_secrets(key, backends)?);
         }
         Ok(secrets)
     }
 }
 
 impl DecryptRequest for ResolverFunctionRequest {
-    fn decrypt_request(self, key: &DecryptionKey) -> Result<serde_json::Value, DecryptionKeyError> {
+    fn decrypt_request(
+        self,
+        key: &DecryptionKey,
+        backends: &SecretBackends,
+    ) -> Result<serde_json::Value, DecryptionKeyError> {
         let mut value = serde_json::to_value(&self)?;
 
         let (component, parents) = (self.component.data, self.component.parents);
 
         match value.pointer_mut("/component/data") {
-            Some(v) => *v = component.decrypt_request(key)?,
+            Some(v) => *v = component.decrypt_request(key, backends)?,
             None => {
                 return Err(DecryptionKeyError::JSONPointerNotFound(
                     value,
@@ -163,7 +197,7 @@ impl DecryptRequest for ResolverFunctionRequest {
 
         let mut decrypted_parents = Vec::with_capacity(parents.len());
         for parent in parents {
-            decrypted_parents.push(parent.decrypt_request(key)?);
+            decrypted_parents.push(parent.decrypt_request(key, backends)?);
         }
         match value.pointer_mut("/component/parents") {
             Some(v) => *v = serde_json::Value::Array(decrypted_parents),
@@ -182,6 +216,7 @@ impl ListSecrets for ActionRunRequest {
     fn list_secrets(
         &self,
         _key: &DecryptionKey,
+        _backends: &SecretBackends,
     ) -> Result<Vec<SensitiveString>, DecryptionKeyError> {
         // TODO(fnichol): we'll need to populate/consume secrets here shortly
         Ok(vec![])
@@ -192,6 +227,7 @@ impl DecryptRequest for ActionRunRequest {
     fn decrypt_request(
         self,
         _key: &DecryptionKey,
+        _backends: &SecretBackends,
     ) -> Result<serde_json::Value, DecryptionKeyError> {
         let value = serde_json::to_value(&self)?;
         // TODO(fnichol): we'll need to process the request with decrypted secrets
@@ -203,6 +239,7 @@ impl ListSecrets for ReconciliationRequest {
     fn list_secrets(
         &self,
         _key: &DecryptionKey,
+        _backends: &SecretBackends,
     ) -> Result<Vec<SensitiveString>, DecryptionKeyError> {
         // TODO(fnichol): we'll need to populate/consume secrets here shortly
         Ok(vec![])
@@ -213,6 +250,7 @@ impl DecryptRequest for ReconciliationRequest {
     fn decrypt_request(
         self,
         _key: &DecryptionKey,
+        _backends: &SecretBackends,
     ) -> Result<serde_json::Value, DecryptionKeyError> {
         let value = serde_json::to_value(&self)?;
         // TODO(fnichol): we'll need to process the request with decrypted secrets
@@ -224,6 +262,7 @@ impl ListSecrets for ValidationRequest {
     fn list_secrets(
         &self,
         _key: &DecryptionKey,
+        _backends: &SecretBackends,
     ) -> Result<Vec<SensitiveString>, DecryptionKeyError> {
         // TODO(fnichol): we'll need to populate/consume secrets here shortly
         Ok(vec![])
@@ -234,6 +273,7 @@ impl DecryptRequest for ValidationRequest {
     fn decrypt_request(
         self,
         _key: &DecryptionKey,
+        _backends: &SecretBackends,
     ) -> Result<serde_json::Value, DecryptionKeyError> {
         let value = serde_json::to_value(&self)?;
         // TODO(fnichol): we'll need to process the request with decrypted secrets
@@ -245,6 +285,7 @@ impl ListSecrets for SchemaVariantDefinitionRequest {
     fn list_secrets(
         &self,
         _key: &DecryptionKey,
+        _backends: &SecretBackends,
     ) -> Result<Vec<SensitiveString>, DecryptionKeyError> {
         // TODO(fnichol): we'll need to populate/consume secrets here shortly
         Ok(vec![])
@@ -255,6 +296,7 @@ impl DecryptRequest for SchemaVariantDefinitionRequest {
     fn decrypt_request(
         self,
         _key: &DecryptionKey,
+        _backends: &SecretBackends,
     ) -> Result<serde_json::Value, DecryptionKeyError> {
         let value = serde_json::to_value(self)?;
         // TODO(fnichol): we'll need to process the request with decrypted secrets
@@ -262,12 +304,63 @@ impl DecryptRequest for SchemaVariantDefinitionRequest {
     }
 }
 
+impl ExecutionRequest for ResolverFunctionRequest {
+    fn execution_id(&self) -> &str {
+        &self.execution_id
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout_secs.map(Duration::from_secs)
+    }
+}
+
+impl ExecutionRequest for ActionRunRequest {
+    fn execution_id(&self) -> &str {
+        &self.execution_id
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout_secs.map(Duration::from_secs)
+    }
+}
+
+impl ExecutionRequest for ReconciliationRequest {
+    fn execution_id(&self) -> &str {
+        &self.execution_id
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout_secs.map(Duration::from_secs)
+    }
+}
+
+impl ExecutionRequest for ValidationRequest {
+    fn execution_id(&self) -> &str {
+        &self.execution_id
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout_secs.map(Duration::from_secs)
+    }
+}
+
+impl ExecutionRequest for SchemaVariantDefinitionRequest {
+    fn execution_id(&self) -> &str {
+        &self.execution_id
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout_secs.map(Duration::from_secs)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use base64::{engine::general_purpose, Engine};
     use sodiumoxide::crypto::box_::{PublicKey, SecretKey};
 
     use super::*;
+    use crate::{FileSecretBackend, SecretBackendError};
 
     fn encrypt_and_encode(message: &[u8], pkey: &PublicKey) -> String {
         general_purpose::STANDARD_NO_PAD.encode(sodiumoxide::crypto::sealedbox::seal(message, pkey))
@@ -306,7 +399,7 @@ mod tests {
                 },
             }),
         }
-        .list_secrets(&decryption_key)
+        .list_secrets(&decryption_key, &SecretBackends::default())
         .expect("Unable to list secrets");
         assert_eq!(secrets[0].as_str(), "Varginha's UFO");
     }
@@ -340,7 +433,7 @@ mod tests {
                 },
             }),
         }
-        .decrypt_request(&decryption_key)
+        .decrypt_request(&decryption_key, &SecretBackends::default())
         .expect("Unable to decrypt component view");
 
         let decrypted_json = serde_json::json!({
@@ -356,4 +449,67 @@ mod tests {
         });
         assert_eq!(json, decrypted_json);
     }
+
+    #[test]
+    fn resolve_secret_reference() {
+        let (pkey, skey) = gen_keypair();
+        let decryption_key = DecryptionKey::from(skey);
+
+        let dir = tempfile::TempDir::new().expect("Unable to create secrets dir");
+        std::fs::create_dir_all(dir.path().join("workspace")).expect("Unable to create namespace");
+        let secret_json = serde_json::json!({ "my-super-secret": "Varginha's UFO" });
+        std::fs::write(
+            dir.path().join("workspace").join("ufo.json"),
+            serde_json::to_vec(&secret_json).expect("Unable to serialize secret"),
+        )
+        .expect("Unable to write secret");
+        let mut backends = SecretBackends::default();
+        backends.insert(FileSecretBackend::new(dir.path()));
+
+        let component_view = |reference: cyclone_core::SecretReference| {
+            let reference = serde_json::to_string(&reference.to_message())
+                .expect("Unable to serialize secret reference");
+            let encoded = encrypt_and_encode(reference.as_bytes(), &pkey);
+            ComponentView {
+                kind: ComponentKind::Credential,
+                properties: serde_json::json!({
+                    "secret": {
+                        "name": "ufo",
+                        "message": { "cycloneEncryptedDataMarker": true, "encryptedSecret": encoded },
+                    },
+                }),
+            }
+        };
+        let reference = cyclone_core::SecretReference::new(FileSecretBackend::NAME, "ufo");
+
+        // References must be namespaced, so that one workspace can't read the secrets of another
+        let err = component_view(reference.clone())
+            .decrypt_request(&decryption_key, &backends)
+            .expect_err("Resolved a secret reference without a namespace");
+        assert!(matches!(
+            err,
+            DecryptionKeyError::SecretBackend(SecretBackendError::InvalidKey(_, _))
+        ));
+
+        let component_view = component_view(reference.namespaced("workspace"));
+
+        let secrets = component_view
+            .list_secrets(&decryption_key, &backends)
+            .expect("Unable to list secrets");
+        assert_eq!(secrets[0].as_str(), "Varginha's UFO");
+
+        let json = component_view
+            .clone()
+            .decrypt_request(&decryption_key, &backends)
+            .expect("Unable to decrypt component view");
+        assert_eq!(json["properties"]["secret"]["message"], secret_json);
+
+        let err = component_view
+            .decrypt_request(&decryption_key, &SecretBackends::default())
+            .expect_err("Resolved a secret with an unknown backend");
+        assert!(matches!(
+            err,
+            DecryptionKeyError::SecretBackend(SecretBackendError::UnknownBackend(_))
+        ));
+    }
 }
@@ -0,0 +1,316 @@
+//! Applies the [`ExecutionLimits`] to the language server process of an execution: CPU time and
+//! open files are limited with rlimits, memory and processes with a cgroup v2 of its own, and the
+//! network with a network namespace of its own.
+
+use std::{
+    fmt, io,
+    os::unix::process::ExitStatusExt,
+    path::{Path, PathBuf},
+    process::ExitStatus,
+};
+
+use cyclone_core::ExecutionLimits;
+use nix::sys::{
+    resource::{setrlimit, Resource},
+    signal::Signal,
+};
+use telemetry::prelude::*;
+use thiserror::Error;
+use tokio::{
+    fs,
+    process::{Child, Command},
+    runtime::Handle,
+};
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum SandboxError {
+    #[error("failed to set up cgroup {1}")]
+    Cgroup(#[source] io::Error, PathBuf),
+    #[error("child process exited before entering its cgroup")]
+    ChildExited,
+}
+
+type Result<T> = std::result::Result<T, SandboxError>;
+
+/// A limit a function was killed, or failed, for going over.
+#[remain::sorted]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LimitViolation {
+    CpuTime,
+    Memory,
+    Processes,
+}
+
+impl fmt::Display for LimitViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CpuTime => f.write_str("cpu time"),
+            Self::Memory => f.write_str("memory"),
+            Self::Processes => f.write_str("process count"),
+        }
+    }
+}
+
+/// Sets the rlimits, and the network namespace, the command is spawned with.
+pub fn configure(command: &mut Command, limits: &ExecutionLimits) {
+    if limits.is_unlimited() {
+        return;
+    }
+
+    let cpu_time_secs = limits.cpu_time_secs;
+    let open_files = limits.open_files;
+    let no_network = limits.no_network;
+
+    // SAFETY: the closure only makes system calls, which are safe to make between `fork` and
+    // `exec`
+    unsafe {
+        command.pre_exec(move || {
+            if let Some(secs) = cpu_time_secs {
+                // `SIGXCPU` at the soft limit, `SIGKILL` past the hard one
+                setrlimit(Resource::RLIMIT_CPU, secs, secs.saturating_add(1))?;
+            }
+            if let Some(open_files) = open_files {
+                setrlimit(Resource::RLIMIT_NOFILE, open_files, open_files)?;
+            }
+            if no_network {
+                unshare_network()?;
+            }
+            Ok(())
+        });
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn unshare_network() -> io::Result<()> {
+    use nix::{errno::Errno, sched::CloneFlags};
+
+    match nix::sched::unshare(CloneFlags::CLONE_NEWNET) {
+        // Without `CAP_SYS_ADMIN`, a user namespace of its own lets the child create one
+        Err(Errno::EPERM) => {
+            nix::sched::unshare(CloneFlags::CLONE_NEWUSER | CloneFlags::CLONE_NEWNET)?;
+        }
+        result => result?,
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn unshare_network() -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "network namespaces are only supported on Linux",
+    ))
+}
+
+/// The cgroup of an execution. It is meant to be [removed](Self::remove) once the execution is
+/// done, and is removed on a blocking thread if it is dropped before.
+#[derive(Debug)]
+pub struct Cgroup {
+    path: PathBuf,
+    removed: bool,
+}
+
+impl Cgroup {
+    /// Creates a cgroup under the root of the limits for the child, and moves the child into it.
+    /// Returns `None` if the limits have no cgroup root.
+    ///
+    /// The child is expected to wait for its request, so nothing it runs escapes the cgroup.
+    pub async fn enter(child: &Child, limits: &ExecutionLimits) -> Result<Option<Self>> {
+        let root = match &limits.cgroup_root {
+            Some(root) => root,
+            None => return Ok(None),
+        };
+        let pid = child.id().ok_or(SandboxError::ChildExited)?;
+
+        let cgroup = Self {
+            path: root.join(format!("execution-{pid}")),
+            removed: false,
+        };
+        fs::create_dir(&cgroup.path)
+            .await
+            .map_err(|err| SandboxError::Cgroup(err, cgroup.path.clone()))?;
+        if let Some(memory_bytes) = limits.memory_bytes {
+            cgroup.write("memory.max", memory_bytes).await?;
+            // Swapping would let the function go over its memory limit
+            match fs::write(cgroup.path.join("memory.swap.max"), "0").await {
+                Err(err) if err.kind() != io::ErrorKind::NotFound => {
+                    return Err(SandboxError::Cgroup(err, cgroup.path.clone()));
+                }
+                _ => {}
+            }
+        }
+        if let Some(processes) = limits.processes {
+            cgroup.write("pids.max", processes).await?;
+        }
+        cgroup.write("cgroup.procs", pid).await?;
+
+        Ok(Some(cgroup))
+    }
+
+    async fn write(&self, file: &str, value: impl ToString) -> Result<()> {
+        fs::write(self.path.join(file), value.to_string())
+            .await
+            .map_err(|err| SandboxError::Cgroup(err, self.path.clone()))
+    }
+
+    /// Returns the count of the given event, or 0 if it cannot be read.
+    async fn event_count(&self, file: &str, event: &str) -> u64 {
+        let events = match fs::read_to_string(self.path.join(file)).await {
+            Ok(events) => events,
+            Err(err) => {
+                debug!(error = ?err, file, "failed to read cgroup events");
+                return 0;
+            }
+        };
+        events
+            .lines()
+            .filter_map(|line| line.split_once(' '))
+            .find(|(name, _)| *name == event)
+            .and_then(|(_, count)| count.trim().parse().ok())
+            .unwrap_or(0)
+    }
+
+    /// Kills whatever is left in the cgroup, then removes it.
+    pub async fn remove(mut self) {
+        self.removed = true;
+        let path = self.path.clone();
+        if let Err(err) = tokio::task::spawn_blocking(move || remove_cgroup(&path)).await {
+            warn!(error = ?err, cgroup = %self.path.display(), "failed to remove cgroup");
+        }
+    }
+}
+
+impl Drop for Cgroup {
+    fn drop(&mut self) {
+        if self.removed {
+            return;
+        }
+        // Dropped on an error path, so the removal is not waited on
+        match Handle::try_current() {
+            Ok(handle) => {
+                let path = self.path.clone();
+                handle.spawn_blocking(move || remove_cgroup(&path));
+            }
+            Err(_) => remove_cgroup(&self.path),
+        }
+    }
+}
+
+/// Kills whatever is left in the cgroup, which must be empty to be removed, then removes it.
+fn remove_cgroup(path: &Path) {
+    if let Err(err) = std::fs::write(path.join("cgroup.kill"), "1") {
+        debug!(error = ?err, cgroup = %path.display(), "failed to kill cgroup");
+    }
+    if let Err(err) = std::fs::remove_dir(path) {
+        warn!(error = ?err, cgroup = %path.display(), "failed to remove cgroup");
+    }
+}
+
+/// Returns the limit the function went over, if any, from the exit status of its process (when
+/// it has exited) and the events of its cgroup (when it has one).
+pub async fn violation(
+    exit_status: Option<ExitStatus>,
+    cgroup: Option<&Cgroup>,
+) -> Option<LimitViolation> {
+    if exit_status.and_then(|status| status.signal()) == Some(Signal::SIGXCPU as i32) {
+        return Some(LimitViolation::CpuTime);
+    }
+    if let Some(cgroup) = cgroup {
+        if cgroup.event_count("memory.events", "oom_kill").await > 0 {
+            return Some(LimitViolation::Memory);
+        }
+        if cgroup.event_count("pids.events", "max").await > 0 {
+            return Some(LimitViolation::Processes);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    /// A cgroup whose event files are written to a temporary directory, rather than by the kernel.
+    fn cgroup(dir: &TempDir, events: &[(&str, &str)]) -> Cgroup {
+        for (file, contents) in events {
+            std::fs::write(dir.path().join(file), contents).expect("failed to write events");
+        }
+        Cgroup {
+            path: dir.path().to_path_buf(),
+            removed: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn event_count() {
+        let dir = TempDir::new().expect("failed to create temp dir");
+        let cgroup = cgroup(
+            &dir,
+            &[(
+                "memory.events",
+                "low 0\nhigh 0\nmax 4\noom 2\noom_kill 1\noom_group_kill 0\n",
+            )],
+        );
+
+        assert_eq!(1, cgroup.event_count("memory.events", "oom_kill").await);
+        assert_eq!(2, cgroup.event_count("memory.events", "oom").await);
+        assert_eq!(0, cgroup.event_count("memory.events", "unknown").await);
+        assert_eq!(0, cgroup.event_count("pids.events", "max").await);
+    }
+
+    #[tokio::test]
+    async fn violation_without_cgroup() {
+        let killed_by_sigxcpu = ExitStatus::from_raw(Signal::SIGXCPU as i32);
+        let killed_by_sigkill = ExitStatus::from_raw(Signal::SIGKILL as i32);
+
+        assert_eq!(
+            Some(LimitViolation::CpuTime),
+            violation(Some(killed_by_sigxcpu), None).await
+        );
+        assert_eq!(None, violation(Some(killed_by_sigkill), None).await);
+        assert_eq!(None, violation(Some(ExitStatus::from_raw(0)), None).await);
+        assert_eq!(None, violation(None, None).await);
+    }
+
+    #[tokio::test]
+    async fn violation_with_cgroup() {
+        let dir = TempDir::new().expect("failed to create temp dir");
+        let within_limits = cgroup(
+            &dir,
+            &[
+                ("memory.events", "max 3\noom 0\noom_kill 0\n"),
+                ("pids.events", "max 0\n"),
+            ],
+        );
+        assert_eq!(None, violation(None, Some(&within_limits)).await);
+
+        let dir = TempDir::new().expect("failed to create temp dir");
+        let out_of_memory = cgroup(
+            &dir,
+            &[
+                ("memory.events", "max 3\noom 1\noom_kill 1\n"),
+                ("pids.events", "max 0\n"),
+            ],
+        );
+        assert_eq!(
+            Some(LimitViolation::Memory),
+            violation(None, Some(&out_of_memory)).await
+        );
+
+        let dir = TempDir::new().expect("failed to create temp dir");
+        let out_of_processes = cgroup(
+            &dir,
+            &[
+                ("memory.events", "max 0\noom 0\noom_kill 0\n"),
+                ("pids.events", "max 2\n"),
+            ],
+        );
+        assert_eq!(
+            Some(LimitViolation::Processes),
+            violation(None, Some(&out_of_processes)).await
+        );
+    }
+}
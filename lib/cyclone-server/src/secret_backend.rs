@@ -0,0 +1,106 @@
+use std::{
+    collections::HashMap,
+    fmt, fs, io,
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+};
+
+use cyclone_core::{SecretReference, FILE_SECRET_BACKEND_NAME};
+use serde_json::Value;
+use telemetry::prelude::*;
+use thiserror::Error;
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum SecretBackendError {
+    #[error("invalid key for {0} secret backend: {1}")]
+    InvalidKey(&'static str, String),
+    #[error("failed to read secret {1} from {0} secret backend")]
+    ReadIO(&'static str, String, #[source] io::Error),
+    #[error("json serialize/deserialize error: {0}")]
+    Serde(#[from] serde_json::Error),
+    #[error("secret backend not configured: {0}")]
+    UnknownBackend(String),
+}
+
+/// A store holding secrets outside of SI, which Cyclone resolves
+/// [`SecretReferences`](SecretReference) against when executing a function.
+pub trait SecretBackend: fmt::Debug + Send + Sync {
+    /// The name secret references use for this backend.
+    fn name(&self) -> &'static str;
+
+    /// Returns the message of the secret stored under the given key. This is called from a
+    /// blocking thread, so it may block.
+    fn resolve(&self, key: &str) -> Result<Value, SecretBackendError>;
+}
+
+/// The [`SecretBackends`](SecretBackend) configured for a Cyclone server, by name.
+#[derive(Clone, Debug, Default)]
+pub struct SecretBackends {
+    backends: HashMap<&'static str, Arc<dyn SecretBackend>>,
+}
+
+impl SecretBackends {
+    pub fn insert(&mut self, backend: impl SecretBackend + 'static) {
+        self.backends.insert(backend.name(), Arc::new(backend));
+    }
+
+    /// Returns the message of the referenced secret.
+    pub fn resolve(&self, reference: &SecretReference) -> Result<Value, SecretBackendError> {
+        let backend = self
+            .backends
+            .get(reference.backend.as_str())
+            .ok_or_else(|| SecretBackendError::UnknownBackend(reference.backend.clone()))?;
+        debug!(backend = backend.name(), "resolving secret reference");
+        backend.resolve(&reference.key)
+    }
+
+    /// Resolves the given decrypted secret message if it holds a [`SecretReference`], otherwise
+    /// returns it as is.
+    pub fn resolve_message(&self, message: Value) -> Result<Value, SecretBackendError> {
+        match SecretReference::from_message(&message) {
+            Some(reference) => self.resolve(&reference),
+            None => Ok(message),
+        }
+    }
+}
+
+/// A [`SecretBackend`] reading every secret from a JSON file in a directory, named after the
+/// key of the secret. Meant for tests and local development, as a stand-in for a real store.
+///
+/// Keys are namespaced by SI with the workspace of the secret, e.g. `<workspace pk>/docker-hub`,
+/// and each namespace is a directory of its own, so a workspace can only reach its own secrets.
+#[derive(Clone, Debug)]
+pub struct FileSecretBackend {
+    dir: PathBuf,
+}
+
+impl FileSecretBackend {
+    pub const NAME: &'static str = FILE_SECRET_BACKEND_NAME;
+
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+impl SecretBackend for FileSecretBackend {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn resolve(&self, key: &str) -> Result<Value, SecretBackendError> {
+        // Keys must name a file directly inside of a namespace directory
+        let mut components = Path::new(key).components();
+        if !matches!(
+            (components.next(), components.next(), components.next()),
+            (Some(Component::Normal(_)), Some(Component::Normal(_)), None)
+        ) {
+            return Err(SecretBackendError::InvalidKey(Self::NAME, key.to_owned()));
+        }
+
+        let path = self.dir.join(format!("{key}.json"));
+        let contents = fs::read(path)
+            .map_err(|err| SecretBackendError::ReadIO(Self::NAME, key.to_owned(), err))?;
+        Ok(serde_json::from_slice(&contents)?)
+    }
+}
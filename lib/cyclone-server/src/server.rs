@@ -17,8 +17,8 @@ use tokio::{
 use tower_http::trace::{DefaultMakeSpan, TraceLayer};
 
 use crate::{
-    routes::routes, state::AppState, Config, DecryptionKey, DecryptionKeyError, IncomingStream,
-    UdsIncomingStream, UdsIncomingStreamError,
+    routes::routes, state::AppState, Config, DecryptionKey, DecryptionKeyError, FileSecretBackend,
+    IncomingStream, SecretBackends, UdsIncomingStream, UdsIncomingStreamError,
 };
 
 #[remain::sorted]
@@ -149,7 +149,18 @@ fn build_service(
 ) -> Result<(IntoMakeService<Router>, oneshot::Receiver<()>)> {
     let (shutdown_tx, shutdown_rx) = mpsc::channel(4);
 
-    let state = AppState::new(config.lang_server_path(), decryption_key, telemetry_level);
+    let mut secret_backends = SecretBackends::default();
+    if let Some(dir) = config.file_secret_backend_dir() {
+        secret_backends.insert(FileSecretBackend::new(dir));
+    }
+
+    let state = AppState::new(
+        config.lang_server_path(),
+        decryption_key,
+        secret_backends,
+        config.execution_limits().clone(),
+        telemetry_level,
+    );
 
     let routes = routes(config, state, shutdown_tx)
         // TODO(fnichol): customize http tracing further, using:
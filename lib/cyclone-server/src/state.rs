@@ -12,6 +12,8 @@ use tokio::sync::mpsc;
 pub struct AppState {
     lang_server_path: LangServerPath,
     decryption_key: DecryptionKey,
+    secret_backends: SecretBackends,
+    execution_limits: ExecutionLimits,
     telemetry_level: TelemetryLevel,
 }
 
@@ -19,11 +21,15 @@ impl AppState {
     pub fn new(
         lang_server_path: impl Into<PathBuf>,
         decryption_key: crate::DecryptionKey,
+        secret_backends: crate::SecretBackends,
+        execution_limits: cyclone_core::ExecutionLimits,
         telemetry_level: Box<dyn telemetry::TelemetryLevel>,
     ) -> Self {
         Self {
             lang_server_path: LangServerPath(Arc::new(lang_server_path.into())),
             decryption_key: DecryptionKey(Arc::new(decryption_key)),
+            secret_backends: SecretBackends(Arc::new(secret_backends)),
+            execution_limits: ExecutionLimits(Arc::new(execution_limits)),
             telemetry_level: TelemetryLevel(Arc::new(telemetry_level)),
         }
     }
@@ -55,6 +61,32 @@ impl From<DecryptionKey> for Arc<crate::DecryptionKey> {
     }
 }
 
+#[derive(Clone, Debug, FromRef)]
+pub struct SecretBackends(Arc<crate::SecretBackends>);
+
+impl Deref for SecretBackends {
+    type Target = crate::SecretBackends;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<SecretBackends> for Arc<crate::SecretBackends> {
+    fn from(value: SecretBackends) -> Self {
+        value.0
+    }
+}
+
+#[derive(Clone, Debug, FromRef)]
+pub struct ExecutionLimits(Arc<cyclone_core::ExecutionLimits>);
+
+impl From<ExecutionLimits> for Arc<cyclone_core::ExecutionLimits> {
+    fn from(value: ExecutionLimits) -> Self {
+        value.0
+    }
+}
+
 #[derive(Clone, FromRef)]
 pub struct TelemetryLevel(Arc<Box<dyn telemetry::TelemetryLevel>>);
 
@@ -1,4 +1,5 @@
 use std::default::Default;
+use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 use strum::{AsRefStr, Display};
@@ -6,8 +7,9 @@ use thiserror::Error;
 
 use si_data_nats::NatsError;
 use si_data_pg::PgError;
-use si_pkg::ActionFuncSpecKind;
+use si_pkg::{ActionFuncRetryPolicySpec, ActionFuncRetryableStatus, ActionFuncSpecKind};
 use telemetry::prelude::*;
+use veritech_client::{OutputStream, ResourceStatus};
 
 use crate::{
     component::view::ComponentViewError, func::backend::js_action::ActionRunResult,
@@ -142,6 +144,124 @@ impl From<&ActionKind> for ActionFuncSpecKind {
     }
 }
 
+/// Everything that happened while running an [`ActionPrototype`], as returned by
+/// [`ActionPrototype::run_with_details()`].
+#[derive(Debug, Clone)]
+pub struct ActionRunDetails {
+    /// The arguments the action function was executed with.
+    pub arguments: serde_json::Value,
+    /// Every line the action function streamed, in order.
+    pub output_stream: Vec<OutputStream>,
+    /// [`None`] if the action function did not return a value.
+    pub result: Option<ActionRunResult>,
+}
+
+/// Describes how [`Fixes`](crate::Fix) running an [`ActionPrototype`] are retried when they do not
+/// succeed.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionRetryPolicy {
+    /// The total number of attempts, including the first one.
+    pub max_attempts: u32,
+    /// How long to wait before the first retry.
+    pub backoff_ms: u64,
+    /// What the wait is multiplied by after every retry.
+    pub backoff_multiplier: u32,
+    /// The [`ResourceStatuses`](ResourceStatus) that are worth retrying. Runs that did not
+    /// return a resource at all are always retryable.
+    pub retryable_statuses: Vec<ResourceStatus>,
+}
+
+impl ActionRetryPolicy {
+    /// Whether a run that ended with the given [`ResourceStatus`] (or no resource) should be
+    /// retried, given how many attempts were already made.
+    pub fn should_retry(&self, attempts: u32, status: Option<ResourceStatus>) -> bool {
+        if attempts >= self.max_attempts {
+            return false;
+        }
+        match status {
+            None => true,
+            Some(ResourceStatus::Ok) => false,
+            Some(status) => self.retryable_statuses.contains(&status),
+        }
+    }
+
+    /// How long to wait after the given (1-based) attempt before trying again.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let multiplier =
+            u64::from(self.backoff_multiplier.max(1)).saturating_pow(attempt.saturating_sub(1));
+        Duration::from_millis(self.backoff_ms.saturating_mul(multiplier))
+    }
+}
+
+impl From<&ActionFuncRetryPolicySpec> for ActionRetryPolicy {
+    fn from(value: &ActionFuncRetryPolicySpec) -> Self {
+        Self {
+            max_attempts: value.max_attempts,
+            backoff_ms: value.backoff_ms,
+            backoff_multiplier: value.backoff_multiplier,
+            retryable_statuses: value
+                .retryable_statuses
+                .iter()
+                .map(|status| match status {
+                    ActionFuncRetryableStatus::Error => ResourceStatus::Error,
+                    ActionFuncRetryableStatus::Warning => ResourceStatus::Warning,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<&ActionRetryPolicy> for ActionFuncRetryPolicySpec {
+    fn from(value: &ActionRetryPolicy) -> Self {
+        Self {
+            max_attempts: value.max_attempts,
+            backoff_ms: value.backoff_ms,
+            backoff_multiplier: value.backoff_multiplier,
+            retryable_statuses: value
+                .retryable_statuses
+                .iter()
+                .filter_map(|status| match status {
+                    ResourceStatus::Error => Some(ActionFuncRetryableStatus::Error),
+                    ResourceStatus::Warning => Some(ActionFuncRetryableStatus::Warning),
+                    // A successful run is never retried.
+                    ResourceStatus::Ok => None,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl postgres_types::ToSql for ActionRetryPolicy {
+    fn to_sql(
+        &self,
+        ty: &postgres_types::Type,
+        out: &mut postgres_types::private::BytesMut,
+    ) -> Result<postgres_types::IsNull, Box<dyn std::error::Error + Sync + Send>>
+    where
+        Self: Sized,
+    {
+        let json = serde_json::to_value(self)?;
+        postgres_types::ToSql::to_sql(&json, ty, out)
+    }
+
+    fn accepts(ty: &postgres_types::Type) -> bool
+    where
+        Self: Sized,
+    {
+        ty == &postgres_types::Type::JSONB
+    }
+
+    fn to_sql_checked(
+        &self,
+        ty: &postgres_types::Type,
+        out: &mut postgres_types::private::BytesMut,
+    ) -> Result<postgres_types::IsNull, Box<dyn std::error::Error + Sync + Send>> {
+        let json = serde_json::to_value(self)?;
+        postgres_types::ToSql::to_sql(&json, ty, out)
+    }
+}
+
 // Hrm - is this a universal resolver context? -- Adam
 impl Default for ActionPrototypeContext {
     fn default() -> Self {
@@ -185,6 +305,9 @@ pub struct ActionPrototype {
     kind: ActionKind,
     name: Option<String>,
     schema_variant_id: SchemaVariantId,
+    /// When unset, [`Fixes`](crate::Fix) running this prototype are only attempted once.
+    #[serde(default)]
+    retry_policy: Option<ActionRetryPolicy>,
     #[serde(flatten)]
     tenancy: Tenancy,
     #[serde(flatten)]
@@ -338,6 +461,11 @@ impl ActionPrototype {
     standard_model_accessor!(name, Option<String>, ActionPrototypeResult);
     standard_model_accessor!(func_id, Pk(FuncId), ActionPrototypeResult);
     standard_model_accessor!(kind, Enum(ActionKind), ActionPrototypeResult);
+    standard_model_accessor!(
+        retry_policy,
+        OptionJson<ActionRetryPolicy>,
+        ActionPrototypeResult
+    );
 
     pub async fn set_kind_checked(
         &mut self,
@@ -372,29 +500,34 @@ impl ActionPrototype {
         ctx: &DalContext,
         component_id: ComponentId,
     ) -> ActionPrototypeResult<Option<ActionRunResult>> {
+        Ok(self.run_with_details(ctx, component_id).await?.result)
+    }
+
+    /// Runs the action like [`Self::run()`], but also returns what the function was given and
+    /// everything it streamed while running.
+    pub async fn run_with_details(
+        &self,
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> ActionPrototypeResult<ActionRunDetails> {
         let component_view = ComponentView::new(ctx, component_id).await?;
-        let (_, return_value) = FuncBinding::create_and_execute(
-            ctx,
-            serde_json::to_value(component_view)?,
-            self.func_id(),
-        )
-        .await?;
+        let arguments = serde_json::to_value(component_view)?;
+        let (_, return_value) =
+            FuncBinding::create_and_execute(ctx, arguments.clone(), self.func_id()).await?;
 
-        let mut logs = vec![];
-        for stream_part in return_value
+        let mut output_stream = return_value
             .get_output_stream(ctx)
             .await?
-            .unwrap_or_default()
-        {
-            logs.push(stream_part);
-        }
-
-        logs.sort_by_key(|log| log.timestamp);
+            .unwrap_or_default();
+        output_stream.sort_by_key(|output| output.timestamp);
 
-        Ok(match return_value.value() {
+        let result = match return_value.value() {
             Some(value) => {
                 let mut run_result: ActionRunResult = serde_json::from_value(value.clone())?;
-                run_result.logs = logs.iter().map(|l| l.message.clone()).collect();
+                run_result.logs = output_stream
+                    .iter()
+                    .map(|output| output.message.clone())
+                    .collect();
 
                 let deleted_ctx = &ctx.clone_with_delete_visibility();
                 let mut component = Component::get_by_id(deleted_ctx, &component_id)
@@ -422,6 +555,58 @@ impl ActionPrototype {
                 Some(run_result)
             }
             None => None,
+        };
+
+        Ok(ActionRunDetails {
+            arguments,
+            output_stream,
+            result,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn retry_policy() -> ActionRetryPolicy {
+        ActionRetryPolicy {
+            max_attempts: 3,
+            backoff_ms: 100,
+            backoff_multiplier: 2,
+            retryable_statuses: vec![ResourceStatus::Error],
+        }
+    }
+
+    #[test]
+    fn should_retry() {
+        let policy = retry_policy();
+
+        assert!(policy.should_retry(1, None));
+        assert!(policy.should_retry(2, Some(ResourceStatus::Error)));
+        assert!(!policy.should_retry(1, Some(ResourceStatus::Ok)));
+        assert!(!policy.should_retry(1, Some(ResourceStatus::Warning)));
+        // No retries past the maximum number of attempts, whatever happened.
+        assert!(!policy.should_retry(3, None));
+        assert!(!policy.should_retry(3, Some(ResourceStatus::Error)));
+    }
+
+    #[test]
+    fn backoff() {
+        let policy = retry_policy();
+
+        assert_eq!(Duration::from_millis(100), policy.backoff(1));
+        assert_eq!(Duration::from_millis(200), policy.backoff(2));
+        assert_eq!(Duration::from_millis(400), policy.backoff(3));
+
+        // A multiplier of zero keeps the wait constant rather than dropping it.
+        let constant = ActionRetryPolicy {
+            backoff_multiplier: 0,
+            ..retry_policy()
+        };
+        assert_eq!(Duration::from_millis(100), constant.backoff(3));
+
+        // Large attempt numbers saturate instead of overflowing.
+        assert_eq!(Duration::from_millis(u64::MAX), policy.backoff(u32::MAX));
+    }
+}
@@ -0,0 +1,323 @@
+//! This module contains [`ActionSchedule`], which runs an [`ActionPrototype`] against a
+//! [`Component`] on head at a given time or on a recurrence, without going through a
+//! [`ChangeSet`](crate::ChangeSet).
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use si_data_pg::PgError;
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::fix::FixError;
+use crate::job::definition::{FixItem, FixesJob};
+use crate::standard_model::TypeHint;
+use crate::{
+    impl_standard_model, pk, standard_model, standard_model_accessor, standard_model_accessor_ro,
+    ActionKind, ActionPrototype, ActionPrototypeId, Component, ComponentError, ComponentId,
+    DalContext, Fix, FixBatch, FixBatchId, HistoryEventError, StandardModel, StandardModelError,
+    Tenancy, Timestamp, TransactionsError, Visibility,
+};
+
+pub mod recurrence;
+
+pub use recurrence::{ActionScheduleRecurrence, RecurrenceParseError};
+
+const CLAIM_DUE: &str = include_str!("queries/action_schedule/claim_due.sql");
+const LIST_DUE: &str = include_str!("queries/action_schedule/list_due.sql");
+const LIST_FOR_COMPONENT: &str = include_str!("queries/action_schedule/list_for_component.sql");
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum ActionScheduleError {
+    #[error(transparent)]
+    Component(#[from] ComponentError),
+    #[error("component not found: {0}")]
+    ComponentNotFound(ComponentId),
+    #[error(transparent)]
+    Fix(#[from] FixError),
+    #[error("history event: {0}")]
+    HistoryEvent(#[from] HistoryEventError),
+    #[error("action schedules can only be created on head")]
+    NotInHead,
+    #[error("only \"other\" actions can be scheduled, found: {0}")]
+    NotSchedulable(ActionKind),
+    #[error("action schedule has no upcoming run: {0}")]
+    NoUpcomingRun(ActionScheduleId),
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("action prototype not found: {0}")]
+    PrototypeNotFound(ActionPrototypeId),
+    #[error(transparent)]
+    Recurrence(#[from] RecurrenceParseError),
+    #[error("recurrence \"{0}\" never matches")]
+    RecurrenceNeverMatches(String),
+    #[error("an action schedule needs a time to run at, a recurrence or both")]
+    RunAtOrRecurrenceRequired,
+    #[error("standard model error: {0}")]
+    StandardModelError(#[from] StandardModelError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type ActionScheduleResult<T> = Result<T, ActionScheduleError>;
+
+pk!(ActionSchedulePk);
+pk!(ActionScheduleId);
+
+/// An ActionSchedule runs an [`ActionPrototype`] against a [`Component`] on head, either once (at
+/// `next_run_at`) or every time its recurrence matches.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ActionSchedule {
+    pk: ActionSchedulePk,
+    id: ActionScheduleId,
+    action_prototype_id: ActionPrototypeId,
+    component_id: ComponentId,
+    /// The email the [`FixBatches`](FixBatch) created by the schedule are attributed to.
+    author: String,
+    recurrence: Option<ActionScheduleRecurrence>,
+    /// [`None`] once a one-off schedule has run.
+    next_run_at: Option<DateTime<Utc>>,
+    last_run_at: Option<DateTime<Utc>>,
+    last_fix_batch_id: Option<FixBatchId>,
+    paused: bool,
+    #[serde(flatten)]
+    tenancy: Tenancy,
+    #[serde(flatten)]
+    timestamp: Timestamp,
+    #[serde(flatten)]
+    visibility: Visibility,
+}
+
+impl_standard_model! {
+    model: ActionSchedule,
+    pk: ActionSchedulePk,
+    id: ActionScheduleId,
+    table_name: "action_schedules",
+    history_event_label_base: "action_schedule",
+    history_event_message_name: "Action Schedule"
+}
+
+impl ActionSchedule {
+    /// Schedules the [`ActionPrototype`] to run against the [`Component`]. If `run_at` is given,
+    /// the first run happens then, otherwise it happens the next time the `recurrence` matches.
+    /// Without a `recurrence`, the schedule only runs once.
+    #[instrument(skip_all)]
+    pub async fn new(
+        ctx: &DalContext,
+        action_prototype_id: ActionPrototypeId,
+        component_id: ComponentId,
+        author: impl AsRef<str>,
+        run_at: Option<DateTime<Utc>>,
+        recurrence: Option<ActionScheduleRecurrence>,
+    ) -> ActionScheduleResult<Self> {
+        if !ctx.visibility().is_head() {
+            return Err(ActionScheduleError::NotInHead);
+        }
+
+        let prototype = ActionPrototype::get_by_id(ctx, &action_prototype_id)
+            .await?
+            .ok_or(ActionScheduleError::PrototypeNotFound(action_prototype_id))?;
+        if *prototype.kind() != ActionKind::Other {
+            return Err(ActionScheduleError::NotSchedulable(*prototype.kind()));
+        }
+        Component::get_by_id(ctx, &component_id)
+            .await?
+            .ok_or(ActionScheduleError::ComponentNotFound(component_id))?;
+
+        let next_run_at = match (run_at, &recurrence) {
+            (Some(run_at), _) => run_at,
+            (None, Some(recurrence)) => recurrence.next_after(Utc::now()).ok_or_else(|| {
+                ActionScheduleError::RecurrenceNeverMatches(recurrence.to_string())
+            })?,
+            (None, None) => return Err(ActionScheduleError::RunAtOrRecurrenceRequired),
+        };
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM action_schedule_create_v1($1, $2, $3, $4, $5, $6, $7)",
+                &[
+                    ctx.tenancy(),
+                    ctx.visibility(),
+                    &action_prototype_id,
+                    &component_id,
+                    &author.as_ref(),
+                    &recurrence.as_ref().map(ToString::to_string),
+                    &next_run_at,
+                ],
+            )
+            .await?;
+        let object = standard_model::finish_create_from_row(ctx, row).await?;
+
+        Ok(object)
+    }
+
+    standard_model_accessor_ro!(action_prototype_id, ActionPrototypeId);
+    standard_model_accessor_ro!(component_id, ComponentId);
+    standard_model_accessor_ro!(author, String);
+    standard_model_accessor!(paused, bool, ActionScheduleResult);
+
+    pub fn recurrence(&self) -> Option<&ActionScheduleRecurrence> {
+        self.recurrence.as_ref()
+    }
+
+    pub fn next_run_at(&self) -> Option<DateTime<Utc>> {
+        self.next_run_at
+    }
+
+    pub fn last_run_at(&self) -> Option<DateTime<Utc>> {
+        self.last_run_at
+    }
+
+    pub fn last_fix_batch_id(&self) -> Option<FixBatchId> {
+        self.last_fix_batch_id
+    }
+
+    pub async fn list_for_component(
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> ActionScheduleResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                LIST_FOR_COMPONENT,
+                &[ctx.tenancy(), ctx.visibility(), &component_id],
+            )
+            .await?;
+        Ok(standard_model::objects_from_rows(rows)?)
+    }
+
+    /// Lists the unpaused schedules of _every_ workspace that are due at the given time. Meant
+    /// for the [`ActionScheduler`](crate::tasks::ActionScheduler). The schedules are only
+    /// candidates: each of them must be claimed with [`Self::claim_due`] before being enqueued.
+    pub async fn list_due(ctx: &DalContext, now: DateTime<Utc>) -> ActionScheduleResult<Vec<Self>> {
+        let rows = ctx.txns().await?.pg().query(LIST_DUE, &[&now]).await?;
+        Ok(standard_model::objects_from_rows(rows)?)
+    }
+
+    /// Locks the schedule until the end of the transaction if it is still due at the given time.
+    /// Returns [`None`] if another scheduler holds the lock, or if the schedule was paused or
+    /// moved on to its next run since it was listed.
+    pub async fn claim_due(
+        ctx: &DalContext,
+        id: ActionScheduleId,
+        now: DateTime<Utc>,
+    ) -> ActionScheduleResult<Option<Self>> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(CLAIM_DUE, &[&id, &now])
+            .await?;
+        Ok(standard_model::option_object_from_row(row)?)
+    }
+
+    pub async fn pause(&mut self, ctx: &DalContext) -> ActionScheduleResult<()> {
+        self.set_paused(ctx, true).await
+    }
+
+    /// Unpauses the schedule. Recurring schedules skip the runs they missed while paused instead
+    /// of catching up on them.
+    pub async fn resume(&mut self, ctx: &DalContext) -> ActionScheduleResult<()> {
+        let now = Utc::now();
+        if let Some(recurrence) = self.recurrence.clone() {
+            if self
+                .next_run_at
+                .map_or(true, |next_run_at| next_run_at < now)
+            {
+                let next_run_at = recurrence
+                    .next_after(now)
+                    .ok_or(ActionScheduleError::NoUpcomingRun(self.id))?;
+                self.set_next_run_at(ctx, Some(next_run_at)).await?;
+            }
+        }
+        self.set_paused(ctx, false).await
+    }
+
+    /// Creates a [`FixBatch`] running the scheduled action, enqueues it and moves the schedule on
+    /// to its next run. The caller is responsible for committing.
+    #[instrument(skip_all, fields(action_schedule_id = %self.id))]
+    pub async fn enqueue(&mut self, ctx: &DalContext) -> ActionScheduleResult<FixBatchId> {
+        // Either side may have been deleted since the schedule was created.
+        ActionPrototype::get_by_id(ctx, &self.action_prototype_id)
+            .await?
+            .ok_or(ActionScheduleError::PrototypeNotFound(
+                self.action_prototype_id,
+            ))?;
+        Component::get_by_id(ctx, &self.component_id)
+            .await?
+            .ok_or(ActionScheduleError::ComponentNotFound(self.component_id))?;
+
+        let batch = FixBatch::new(ctx, &self.author).await?;
+        let fix = Fix::new(
+            ctx,
+            *batch.id(),
+            self.component_id,
+            self.action_prototype_id,
+        )
+        .await?;
+        ctx.enqueue_job(FixesJob::new(
+            ctx,
+            vec![FixItem {
+                id: *fix.id(),
+                action_prototype_id: self.action_prototype_id,
+                component_id: self.component_id,
+            }],
+            *batch.id(),
+        ))
+        .await?;
+
+        let ran_at = Utc::now();
+        let next_run_at = self
+            .recurrence
+            .as_ref()
+            .and_then(|recurrence| recurrence.next_after(ran_at));
+        self.set_next_run_at(ctx, next_run_at).await?;
+        self.update_column(
+            ctx,
+            "last_run_at",
+            &Some(ran_at),
+            TypeHint::TimestampWithTimeZone,
+        )
+        .await?;
+        self.last_run_at = Some(ran_at);
+        self.update_column(ctx, "last_fix_batch_id", batch.id(), TypeHint::Ident)
+            .await?;
+        self.last_fix_batch_id = Some(*batch.id());
+
+        Ok(*batch.id())
+    }
+
+    async fn set_next_run_at(
+        &mut self,
+        ctx: &DalContext,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> ActionScheduleResult<()> {
+        self.update_column(
+            ctx,
+            "next_run_at",
+            &next_run_at,
+            TypeHint::TimestampWithTimeZone,
+        )
+        .await?;
+        self.next_run_at = next_run_at;
+        Ok(())
+    }
+
+    async fn update_column<V: Send + Sync + postgres_types::ToSql>(
+        &mut self,
+        ctx: &DalContext,
+        column: &str,
+        value: &V,
+        hint: TypeHint,
+    ) -> ActionScheduleResult<()> {
+        let updated_at =
+            standard_model::update(ctx, Self::table_name(), column, self.id(), value, hint).await?;
+        self.timestamp.updated_at = updated_at;
+        Ok(())
+    }
+}
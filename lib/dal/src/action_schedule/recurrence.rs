@@ -0,0 +1,267 @@
+//! This module contains [`ActionScheduleRecurrence`], a cron-like expression describing when a
+//! recurring [`ActionSchedule`](crate::ActionSchedule) runs.
+
+use chrono::{DateTime, Datelike, Duration, TimeZone, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// How far ahead [`ActionScheduleRecurrence::next_after()`] looks before giving up. Expressions
+/// like "every February 30th" are valid but never match.
+const MAX_LOOKAHEAD_DAYS: i64 = 366 * 5;
+
+#[remain::sorted]
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum RecurrenceParseError {
+    #[error("invalid {field} field: {value}")]
+    InvalidField { field: &'static str, value: String },
+    #[error("expected 5 fields (minute, hour, day of month, month, day of week), found {0}")]
+    WrongFieldCount(usize),
+}
+
+/// A standard five field cron expression (minute, hour, day of month, month and day of week),
+/// always evaluated in UTC. Every field supports `*`, single values, ranges (`1-5`), lists
+/// (`1,15`) and steps (`*/15`, `0-30/10`). The `@hourly`, `@daily`, `@midnight`, `@weekly`,
+/// `@monthly`, `@yearly` and `@annually` shorthands are accepted as well.
+///
+/// Like cron, if both the day of month and the day of week are restricted, a day matches when
+/// _either_ of them does.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(try_from = "String", into = "String")]
+pub struct ActionScheduleRecurrence {
+    expression: String,
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    days_of_month_restricted: bool,
+    days_of_week_restricted: bool,
+}
+
+impl ActionScheduleRecurrence {
+    /// Returns the first time strictly after the given one that matches the expression, if there
+    /// is one within the next few years.
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let start = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = start + Duration::days(MAX_LOOKAHEAD_DAYS);
+
+        let mut candidate = start;
+        while candidate < limit {
+            if !contains(self.months, candidate.month()) {
+                candidate = first_of_next_month(candidate)?;
+                continue;
+            }
+            if !self.day_matches(candidate) {
+                candidate = start_of_day(candidate)? + Duration::days(1);
+                continue;
+            }
+            if !contains(self.hours, candidate.hour()) {
+                candidate = candidate.with_minute(0)? + Duration::hours(1);
+                continue;
+            }
+            if !contains(self.minutes, candidate.minute()) {
+                candidate += Duration::minutes(1);
+                continue;
+            }
+            return Some(candidate);
+        }
+        None
+    }
+
+    fn day_matches(&self, time: DateTime<Utc>) -> bool {
+        let day_of_month = contains(self.days_of_month, time.day());
+        let day_of_week = contains(self.days_of_week, time.weekday().num_days_from_sunday());
+        match (self.days_of_month_restricted, self.days_of_week_restricted) {
+            (true, true) => day_of_month || day_of_week,
+            (true, false) => day_of_month,
+            (false, true) => day_of_week,
+            (false, false) => true,
+        }
+    }
+
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+}
+
+impl FromStr for ActionScheduleRecurrence {
+    type Err = RecurrenceParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let expression = s.trim();
+        let expanded = match expression {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            other => other,
+        };
+
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(RecurrenceParseError::WrongFieldCount(fields.len()));
+        }
+
+        let mut days_of_week = parse_field("day of week", fields[4], 0, 7)?;
+        // Both 0 and 7 are Sunday.
+        if contains(days_of_week, 7) {
+            days_of_week = (days_of_week | 1) & !(1 << 7);
+        }
+
+        Ok(Self {
+            expression: expression.to_owned(),
+            minutes: parse_field("minute", fields[0], 0, 59)?,
+            hours: parse_field("hour", fields[1], 0, 23)?,
+            days_of_month: parse_field("day of month", fields[2], 1, 31)?,
+            months: parse_field("month", fields[3], 1, 12)?,
+            days_of_week,
+            days_of_month_restricted: fields[2] != "*",
+            days_of_week_restricted: fields[4] != "*",
+        })
+    }
+}
+
+impl TryFrom<String> for ActionScheduleRecurrence {
+    type Error = RecurrenceParseError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<ActionScheduleRecurrence> for String {
+    fn from(value: ActionScheduleRecurrence) -> Self {
+        value.expression
+    }
+}
+
+impl fmt::Display for ActionScheduleRecurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
+
+/// Parses a single cron field into a bit set of the values it matches.
+fn parse_field(
+    field: &'static str,
+    value: &str,
+    min: u32,
+    max: u32,
+) -> Result<u64, RecurrenceParseError> {
+    let invalid = || RecurrenceParseError::InvalidField {
+        field,
+        value: value.to_owned(),
+    };
+    let parse_number = |number: &str| -> Result<u32, RecurrenceParseError> {
+        let number: u32 = number.parse().map_err(|_| invalid())?;
+        if number < min || number > max {
+            return Err(invalid());
+        }
+        Ok(number)
+    };
+
+    let mut set = 0;
+    for part in value.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step.parse().map_err(|_| invalid())?;
+                if step == 0 {
+                    return Err(invalid());
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (parse_number(start)?, parse_number(end)?)
+        } else {
+            let start = parse_number(range)?;
+            // "5/15" means "from 5 to the end, every 15".
+            (start, if step > 1 { max } else { start })
+        };
+        if start > end {
+            return Err(invalid());
+        }
+
+        for number in (start..=end).step_by(step as usize) {
+            set |= 1 << number;
+        }
+    }
+    Ok(set)
+}
+
+fn contains(set: u64, value: u32) -> bool {
+    set & (1 << value) != 0
+}
+
+fn start_of_day(time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    Utc.with_ymd_and_hms(time.year(), time.month(), time.day(), 0, 0, 0)
+        .single()
+}
+
+fn first_of_next_month(time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let (year, month) = if time.month() == 12 {
+        (time.year() + 1, 1)
+    } else {
+        (time.year(), time.month() + 1)
+    };
+    Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).single()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_after() {
+        let at = |y, mo, d, h, mi| {
+            Utc.with_ymd_and_hms(y, mo, d, h, mi, 0)
+                .single()
+                .expect("invalid time")
+        };
+        // A Thursday.
+        let now = at(2023, 6, 1, 10, 7);
+
+        let nightly: ActionScheduleRecurrence = "0 2 * * *".parse().expect("could not parse");
+        assert_eq!(Some(at(2023, 6, 2, 2, 0)), nightly.next_after(now));
+        // The next run is always strictly after the given time.
+        assert_eq!(
+            Some(at(2023, 6, 3, 2, 0)),
+            nightly.next_after(at(2023, 6, 2, 2, 0))
+        );
+
+        let quarterly: ActionScheduleRecurrence = "*/15 * * * *".parse().expect("could not parse");
+        assert_eq!(Some(at(2023, 6, 1, 10, 15)), quarterly.next_after(now));
+
+        let sundays: ActionScheduleRecurrence = "30 4 * * 7".parse().expect("could not parse");
+        assert_eq!(Some(at(2023, 6, 4, 4, 30)), sundays.next_after(now));
+        let weekly: ActionScheduleRecurrence = "@weekly".parse().expect("could not parse");
+        assert_eq!(Some(at(2023, 6, 4, 0, 0)), weekly.next_after(now));
+
+        // Restricting both the day of month and the day of week matches either of them.
+        let fridays_or_the_13th: ActionScheduleRecurrence =
+            "0 0 13 * 5".parse().expect("could not parse");
+        assert_eq!(
+            Some(at(2023, 6, 2, 0, 0)),
+            fridays_or_the_13th.next_after(now)
+        );
+        assert_eq!(
+            Some(at(2023, 6, 13, 0, 0)),
+            fridays_or_the_13th.next_after(at(2023, 6, 9, 0, 0))
+        );
+
+        let never: ActionScheduleRecurrence = "0 0 30 2 *".parse().expect("could not parse");
+        assert_eq!(None, never.next_after(now));
+
+        assert!("0 2 * *".parse::<ActionScheduleRecurrence>().is_err());
+        assert!("60 * * * *".parse::<ActionScheduleRecurrence>().is_err());
+        assert!("*/0 * * * *".parse::<ActionScheduleRecurrence>().is_err());
+        assert!("5-1 * * * *".parse::<ActionScheduleRecurrence>().is_err());
+    }
+}
@@ -0,0 +1,319 @@
+//! This module contains [`ApiToken`], a revocable credential for driving a
+//! [`Workspace`](crate::Workspace) headlessly, e.g. from CI pipelines, on behalf of a [`User`].
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use si_data_pg::PgError;
+use sodiumoxide::crypto::hash::sha256;
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::standard_model::{object_option_from_row_option, objects_from_rows};
+use crate::{
+    pk, DalContext, HistoryActor, HistoryEvent, HistoryEventError, StandardModelError, Tenancy,
+    TransactionsError, User, UserError, UserPk, WorkspacePermission, WorkspaceRole,
+};
+
+const GET_BY_PK: &str = include_str!("queries/api_token/get_by_pk.sql");
+const GET_BY_TOKEN_HASH: &str = include_str!("queries/api_token/get_by_token_hash.sql");
+const LIST: &str = include_str!("queries/api_token/list.sql");
+
+/// Every [`ApiToken`] starts with this, which tells them apart from session JWTs.
+pub const API_TOKEN_PREFIX: &str = "si_api_";
+
+/// How many random bytes make up an [`ApiToken`].
+const API_TOKEN_BYTES: usize = 32;
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum ApiTokenError {
+    #[error("api token expired: {0}")]
+    Expired(ApiTokenPk),
+    #[error("api token cannot expire at {0}, after the token creating it does at {1}")]
+    ExpiresAfterCaller(DateTime<Utc>, DateTime<Utc>),
+    #[error("history event error: {0}")]
+    HistoryEvent(#[from] HistoryEventError),
+    #[error("invalid api token")]
+    Invalid,
+    #[error("api token not found: {0}")]
+    NotFound(ApiTokenPk),
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("api tokens can only be created by a user")]
+    RequiresUser,
+    #[error("api token revoked: {0}")]
+    Revoked(ApiTokenPk),
+    #[error("api token cannot be restricted to {0}, which grants more than {1}")]
+    RoleAboveCaller(WorkspaceRole, WorkspaceRole),
+    #[error("error serializing/deserializing json: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("standard model error: {0}")]
+    StandardModel(#[from] StandardModelError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+    #[error(transparent)]
+    User(#[from] UserError),
+}
+
+impl ApiTokenError {
+    /// Returns `true` if the error is the result of presenting a token that does not grant
+    /// access, as opposed to something going wrong.
+    pub fn is_unauthorized(&self) -> bool {
+        matches!(self, Self::Expired(_) | Self::Invalid | Self::Revoked(_))
+    }
+
+    /// Returns `true` if the error is the result of asking for a token that grants more than
+    /// its creator has.
+    pub fn is_above_caller(&self) -> bool {
+        matches!(
+            self,
+            Self::ExpiresAfterCaller(_, _) | Self::RoleAboveCaller(_, _)
+        )
+    }
+}
+
+pub type ApiTokenResult<T> = Result<T, ApiTokenError>;
+
+pk!(ApiTokenPk);
+
+/// A token that authenticates as the [`User`] that created it, in the workspace it was created
+/// in, until it expires or is revoked. Only a hash of the token is stored: the token itself is
+/// handed out once, by [`ApiToken::new()`].
+///
+/// A token may be restricted to the permissions of a [`WorkspaceRole`], in which case it never
+/// grants more than that role, nor more than the role of its user.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ApiToken {
+    pub pk: ApiTokenPk,
+    pub user_pk: UserPk,
+    pub name: String,
+    pub max_role: Option<WorkspaceRole>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub tenancy: Tenancy,
+}
+
+impl ApiToken {
+    /// Creates a token for the current user in the workspace of the context's tenancy. Returns
+    /// the token alongside its record: it cannot be retrieved again.
+    ///
+    /// The token never grants more than its creator: it cannot be restricted to a role above the
+    /// role of the user, nor above the [`max_role`](DalContext::max_role()) of the context. When
+    /// the context [authenticated](DalContext::api_token_pk()) with a token, the new token
+    /// inherits its restriction and its expiry if none are given, and cannot expire later.
+    #[instrument(skip_all)]
+    pub async fn new(
+        ctx: &DalContext,
+        name: impl AsRef<str>,
+        max_role: Option<WorkspaceRole>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> ApiTokenResult<(Self, String)> {
+        let user_pk = match ctx.history_actor() {
+            HistoryActor::User(user_pk) => *user_pk,
+            HistoryActor::SystemInit => return Err(ApiTokenError::RequiresUser),
+        };
+        User::ensure_authorized(ctx, WorkspacePermission::Read).await?;
+
+        let workspace_pk = ctx
+            .tenancy()
+            .workspace_pk()
+            .ok_or(UserError::NoWorkspaceInTenancy)?;
+        let user_role = User::role_in_workspace(ctx, user_pk, workspace_pk)
+            .await?
+            .ok_or(UserError::NotAMember(user_pk, workspace_pk))?;
+        let max_role = max_role.or(ctx.max_role());
+        if let Some(max_role) = max_role {
+            for role in std::iter::once(user_role).chain(ctx.max_role()) {
+                if !role.includes(max_role) {
+                    return Err(ApiTokenError::RoleAboveCaller(max_role, role));
+                }
+            }
+        }
+
+        let caller_expires_at = match ctx.api_token_pk() {
+            Some(caller_pk) => {
+                Self::get_by_pk(ctx, caller_pk)
+                    .await?
+                    .ok_or(ApiTokenError::NotFound(caller_pk))?
+                    .expires_at
+            }
+            None => None,
+        };
+        let expires_at = match (expires_at, caller_expires_at) {
+            (Some(expires_at), Some(caller_expires_at)) if expires_at > caller_expires_at => {
+                return Err(ApiTokenError::ExpiresAfterCaller(
+                    expires_at,
+                    caller_expires_at,
+                ));
+            }
+            (expires_at, caller_expires_at) => expires_at.or(caller_expires_at),
+        };
+
+        let token = format!(
+            "{API_TOKEN_PREFIX}{}",
+            hex::encode(sodiumoxide::randombytes::randombytes(API_TOKEN_BYTES))
+        );
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM api_token_create_v1($1, $2, $3, $4, $5, $6)",
+                &[
+                    ctx.tenancy(),
+                    &user_pk,
+                    &name.as_ref(),
+                    &hash_token(&token),
+                    &max_role.map(|role| role.to_string()),
+                    &expires_at,
+                ],
+            )
+            .await?;
+        let json: serde_json::Value = row.try_get("object")?;
+        let api_token: Self = serde_json::from_value(json)?;
+
+        let _history_event = HistoryEvent::new(
+            ctx,
+            "api_token.create",
+            "API Token created",
+            &serde_json::json![{ "pk": &api_token.pk, "name": &api_token.name }],
+        )
+        .await?;
+
+        Ok((api_token, token))
+    }
+
+    #[instrument(skip(ctx))]
+    pub async fn get_by_pk(ctx: &DalContext, pk: ApiTokenPk) -> ApiTokenResult<Option<Self>> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(GET_BY_PK, &[ctx.tenancy(), &pk])
+            .await?;
+        Ok(object_option_from_row_option(row)?)
+    }
+
+    /// Lists the tokens of the current user in the workspace of the context's tenancy, most
+    /// recent first, including the expired and revoked ones.
+    #[instrument(skip(ctx))]
+    pub async fn list(ctx: &DalContext) -> ApiTokenResult<Vec<Self>> {
+        let user_pk = match ctx.history_actor() {
+            HistoryActor::User(user_pk) => *user_pk,
+            HistoryActor::SystemInit => return Ok(Vec::new()),
+        };
+        Self::list_with_user(ctx, Some(user_pk)).await
+    }
+
+    /// Lists the tokens of every user in the workspace of the context's tenancy, most recent
+    /// first, including the expired and revoked ones.
+    #[instrument(skip(ctx))]
+    pub async fn list_for_workspace(ctx: &DalContext) -> ApiTokenResult<Vec<Self>> {
+        User::ensure_authorized(ctx, WorkspacePermission::ManageWorkspace).await?;
+        Self::list_with_user(ctx, None).await
+    }
+
+    async fn list_with_user(
+        ctx: &DalContext,
+        user_pk: Option<UserPk>,
+    ) -> ApiTokenResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(LIST, &[ctx.tenancy(), &user_pk])
+            .await?;
+        Ok(objects_from_rows(rows)?)
+    }
+
+    /// Revokes the token for good. Users can revoke their own tokens, revoking the tokens of
+    /// others takes managing the workspace. Revoking a revoked token does nothing.
+    #[instrument(skip_all, fields(api_token_pk = %self.pk))]
+    pub async fn revoke(&mut self, ctx: &DalContext) -> ApiTokenResult<()> {
+        if *ctx.history_actor() != HistoryActor::User(self.user_pk) {
+            User::ensure_authorized(ctx, WorkspacePermission::ManageWorkspace).await?;
+        }
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT revoked_at FROM api_token_revoke_v1($1, $2)",
+                &[&self.pk, ctx.tenancy()],
+            )
+            .await?;
+        let revoked_at: Option<DateTime<Utc>> = row.try_get("revoked_at")?;
+        self.revoked_at = Some(revoked_at.ok_or(ApiTokenError::NotFound(self.pk))?);
+
+        let _history_event = HistoryEvent::new(
+            ctx,
+            "api_token.revoke",
+            "API Token revoked",
+            &serde_json::json![{ "pk": &self.pk, "name": &self.name }],
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Looks up the token, across workspaces, and records its use if it is neither expired nor
+    /// revoked. The use is recorded as a [`HistoryEvent`] of the token's user, in the token's
+    /// workspace. The caller is responsible for committing.
+    #[instrument(skip_all)]
+    pub async fn authenticate(ctx: &DalContext, token: impl AsRef<str>) -> ApiTokenResult<Self> {
+        let token = token.as_ref();
+        if !token.starts_with(API_TOKEN_PREFIX) {
+            return Err(ApiTokenError::Invalid);
+        }
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(GET_BY_TOKEN_HASH, &[&hash_token(token)])
+            .await?;
+        let mut api_token: Self =
+            object_option_from_row_option(row)?.ok_or(ApiTokenError::Invalid)?;
+        if api_token.revoked_at.is_some() {
+            return Err(ApiTokenError::Revoked(api_token.pk));
+        }
+        if api_token
+            .expires_at
+            .map_or(false, |expires_at| expires_at <= Utc::now())
+        {
+            return Err(ApiTokenError::Expired(api_token.pk));
+        }
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT last_used_at FROM api_token_record_use_v1($1)",
+                &[&api_token.pk],
+            )
+            .await?;
+        api_token.last_used_at = row.try_get("last_used_at")?;
+
+        let mut token_ctx = ctx.clone_with_new_history_actor(api_token.user_pk.into());
+        token_ctx.update_tenancy(api_token.tenancy);
+        let _history_event = HistoryEvent::new(
+            &token_ctx,
+            "api_token.use",
+            "API Token used",
+            &serde_json::json![{ "pk": &api_token.pk, "name": &api_token.name }],
+        )
+        .await?;
+
+        Ok(api_token)
+    }
+}
+
+fn hash_token(token: &str) -> String {
+    hex::encode(sha256::hash(token.as_bytes()))
+}
@@ -27,6 +27,7 @@ struct FunctionMetadata {
     arguments: Option<Vec<FunctionMetadataArgument>>,
     response_type: FuncBackendResponseType,
     hidden: Option<bool>,
+    impure: Option<bool>,
     display_name: Option<String>,
     description: Option<String>,
     link: Option<String>,
@@ -175,6 +176,10 @@ pub async fn migrate(ctx: &DalContext) -> BuiltinsResult<()> {
             .set_hidden(ctx, func_metadata.hidden.unwrap_or(false))
             .await
             .expect("cannot set func hidden");
+        new_func
+            .set_impure(ctx, func_metadata.impure.unwrap_or(false))
+            .await
+            .expect("cannot set func impure");
         new_func
             .set_builtin(ctx, true)
             .await
@@ -6,22 +6,49 @@ use strum::{Display, EnumString};
 use telemetry::prelude::*;
 use thiserror::Error;
 
+use crate::change_status::ChangeStatusError;
 use crate::standard_model::{object_option_from_row_option, objects_from_rows};
 use crate::ws_event::{WsEvent, WsEventError, WsPayload};
 use crate::{
-    pk, Action, ActionError, HistoryEvent, HistoryEventError, LabelListError, StandardModelError,
-    Tenancy, Timestamp, TransactionsError, UserError, UserPk, Visibility,
+    pk, Action, ActionError, AttributeValueId, ComponentId, DependentValuesUpdate, HistoryActor,
+    HistoryEvent, HistoryEventError, LabelListError, PropId, StandardModel, StandardModelError,
+    Tenancy, Timestamp, TransactionsError, User, UserError, UserPk, Visibility, Workspace,
+    WorkspaceError, WorkspacePermission,
 };
 use crate::{ComponentError, DalContext, WsEventResult};
 
+pub mod apply_preview;
+pub mod approval;
+
+pub use apply_preview::ChangeSetApplyPreview;
+pub use approval::{
+    ChangeSetApproval, ChangeSetApprovalDecision, ChangeSetApprovalPk, ChangeSetApprovalStatus,
+};
+
+const CHANGE_SET_CLOSED_LIST: &str = include_str!("queries/change_set/closed_list.sql");
 const CHANGE_SET_OPEN_LIST: &str = include_str!("queries/change_set/open_list.sql");
 const CHANGE_SET_GET_BY_PK: &str = include_str!("queries/change_set/get_by_pk.sql");
+const LIST_APPROVALS: &str = include_str!("queries/change_set/list_approvals.sql");
+const LIST_ATTRIBUTE_VALUE_CONFLICTS: &str =
+    include_str!("queries/change_set/list_attribute_value_conflicts.sql");
+const LIST_HEAD_ATTRIBUTE_VALUES_UPDATED_SINCE: &str =
+    include_str!("queries/change_set/list_head_attribute_values_updated_since.sql");
 
 #[remain::sorted]
 #[derive(Error, Debug)]
 pub enum ChangeSetError {
     #[error(transparent)]
     Action(#[from] ActionError),
+    #[error("change set {0} has {1} conflict(s) with head; rebase or override to apply")]
+    ApplyConflicts(ChangeSetPk, usize),
+    #[error("only users can approve or reject change sets")]
+    ApprovalRequiresUser,
+    #[error("change set not found: {0}")]
+    ChangeSetNotFound(ChangeSetPk),
+    #[error(transparent)]
+    ChangeStatus(#[from] ChangeStatusError),
+    #[error(transparent)]
+    ChangeStatusParse(#[from] strum::ParseError),
     #[error(transparent)]
     Component(#[from] ComponentError),
     #[error(transparent)]
@@ -32,8 +59,18 @@ pub enum ChangeSetError {
     LabelList(#[from] LabelListError),
     #[error(transparent)]
     Nats(#[from] NatsError),
+    #[error("change set {0} is not closed (status: {1})")]
+    NotClosed(ChangeSetPk, ChangeSetStatus),
+    #[error("change set {0} needs {2} approval(s) before it can be applied, it has {1}")]
+    NotEnoughApprovals(ChangeSetPk, i32, i32),
+    #[error("change set {0} is not open (status: {1})")]
+    NotOpen(ChangeSetPk, ChangeSetStatus),
+    #[error("change set {0} is not pending approval (status: {1})")]
+    NotPendingApproval(ChangeSetPk, ChangeSetStatus),
     #[error(transparent)]
     Pg(#[from] PgError),
+    #[error("change set {0} cannot be approved by the user who requested the approval")]
+    SelfApproval(ChangeSetPk),
     #[error(transparent)]
     SerdeJson(#[from] serde_json::Error),
     #[error(transparent)]
@@ -43,6 +80,8 @@ pub enum ChangeSetError {
     #[error(transparent)]
     User(#[from] UserError),
     #[error(transparent)]
+    Workspace(#[from] WorkspaceError),
+    #[error(transparent)]
     WsEvent(#[from] WsEventError),
 }
 
@@ -56,6 +95,7 @@ pub enum ChangeSetStatus {
     Closed,
     Failed,
     Open,
+    PendingApproval,
 }
 
 pk!(ChangeSetPk);
@@ -66,6 +106,14 @@ pub struct ChangeSet {
     pub name: String,
     pub note: Option<String>,
     pub status: ChangeSetStatus,
+    /// The last time head was pulled into this change set with [`Self::rebase()`].
+    #[serde(default)]
+    pub rebased_at: Option<DateTime<Utc>>,
+    /// When the current round of approvals was requested with [`Self::request_approval()`].
+    #[serde(default)]
+    pub approval_requested_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub approval_requested_by: Option<UserPk>,
     #[serde(flatten)]
     pub tenancy: Tenancy,
     #[serde(flatten)]
@@ -110,8 +158,35 @@ impl ChangeSet {
         Utc::now().format("%Y-%m-%d-%H:%M").to_string()
     }
 
+    /// Applies the change set to head, refusing to do so if it is neither open nor pending
+    /// approval, if the [`apply preview`](ChangeSetApplyPreview) reports conflicts with head, if
+    /// the workspace requires more approvals than the change set has or if the current user's
+    /// [`WorkspaceRole`](crate::WorkspaceRole) does not allow applying.
     #[instrument(skip(ctx))]
     pub async fn apply(&mut self, ctx: &mut DalContext) -> ChangeSetResult<()> {
+        self.ensure_can_apply(ctx).await?;
+        let preview = ChangeSetApplyPreview::new_without_diffs(ctx, self).await?;
+        if preview.has_conflicts() {
+            return Err(ChangeSetError::ApplyConflicts(
+                self.pk,
+                preview.conflict_count(),
+            ));
+        }
+        self.apply_unchecked(ctx).await
+    }
+
+    /// Applies the change set to head, overwriting anything head changed in the meantime.
+    /// Overriding conflicts does not override the required approvals.
+    #[instrument(skip(ctx))]
+    pub async fn apply_overriding_conflicts(
+        &mut self,
+        ctx: &mut DalContext,
+    ) -> ChangeSetResult<()> {
+        self.ensure_can_apply(ctx).await?;
+        self.apply_unchecked(ctx).await
+    }
+
+    async fn apply_unchecked(&mut self, ctx: &mut DalContext) -> ChangeSetResult<()> {
         let actor = serde_json::to_value(ctx.history_actor())?;
         let row = ctx
             .txns()
@@ -144,6 +219,500 @@ impl ChangeSet {
         Ok(())
     }
 
+    /// Abandons an open or pending approval change set, discarding the [`Actions`](Action)
+    /// queued in it along with its approval request. Abandoned change sets cannot be reopened.
+    #[instrument(skip(ctx))]
+    pub async fn abandon(&mut self, ctx: &DalContext) -> ChangeSetResult<()> {
+        match self.status {
+            ChangeSetStatus::Open | ChangeSetStatus::PendingApproval => {}
+            _ => return Err(ChangeSetError::NotOpen(self.pk, self.status.clone())),
+        }
+        User::ensure_authorized(ctx, WorkspacePermission::EditChangeSets).await?;
+
+        let change_set_ctx =
+            ctx.clone_with_new_visibility(Visibility::new_change_set(self.pk, false));
+        for action in Action::find_for_change_set(&change_set_ctx).await? {
+            action.hard_delete(&change_set_ctx).await?;
+        }
+
+        self.discard_approval_request(ctx, ChangeSetStatus::Abandoned)
+            .await?;
+        let _history_event = HistoryEvent::new(
+            ctx,
+            "change_set.abandon",
+            "Change Set abandoned",
+            &serde_json::json![{ "pk": &self.pk }],
+        )
+        .await?;
+
+        WsEvent::change_set_canceled(ctx, self.pk)
+            .await?
+            .publish_on_commit(ctx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Closes an open or pending approval change set without applying it, discarding its
+    /// approval request. Unlike [`Self::abandon()`], its [`Actions`](Action) are kept so that it
+    /// can be brought back with [`Self::reopen()`], as an open change set.
+    #[instrument(skip(ctx))]
+    pub async fn close(&mut self, ctx: &DalContext) -> ChangeSetResult<()> {
+        match self.status {
+            ChangeSetStatus::Open | ChangeSetStatus::PendingApproval => {}
+            _ => return Err(ChangeSetError::NotOpen(self.pk, self.status.clone())),
+        }
+        User::ensure_authorized(ctx, WorkspacePermission::EditChangeSets).await?;
+
+        self.discard_approval_request(ctx, ChangeSetStatus::Closed)
+            .await?;
+        let _history_event = HistoryEvent::new(
+            ctx,
+            "change_set.close",
+            "Change Set closed",
+            &serde_json::json![{ "pk": &self.pk }],
+        )
+        .await?;
+
+        WsEvent::change_set_closed(ctx, self.pk)
+            .await?
+            .publish_on_commit(ctx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Reopens a change set that was previously [`closed`](Self::close()).
+    #[instrument(skip(ctx))]
+    pub async fn reopen(&mut self, ctx: &DalContext) -> ChangeSetResult<()> {
+        if self.status != ChangeSetStatus::Closed {
+            return Err(ChangeSetError::NotClosed(self.pk, self.status.clone()));
+        }
+        User::ensure_authorized(ctx, WorkspacePermission::EditChangeSets).await?;
+
+        self.update_status(ctx, ChangeSetStatus::Open).await?;
+        let _history_event = HistoryEvent::new(
+            ctx,
+            "change_set.reopen",
+            "Change Set reopened",
+            &serde_json::json![{ "pk": &self.pk }],
+        )
+        .await?;
+
+        WsEvent::change_set_reopened(ctx, self.pk)
+            .await?
+            .publish_on_commit(ctx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Submits an open change set for review. It cannot be applied until enough users approved
+    /// it, and a single rejection sends it back to being open. So does editing the change set
+    /// while it is pending approval, which discards the approvals it collected so far: it is
+    /// noticed when the change set is next approved or applied.
+    #[instrument(skip(ctx))]
+    pub async fn request_approval(&mut self, ctx: &DalContext) -> ChangeSetResult<()> {
+        if self.status != ChangeSetStatus::Open {
+            return Err(ChangeSetError::NotOpen(self.pk, self.status.clone()));
+        }
+        User::ensure_authorized(ctx, WorkspacePermission::EditChangeSets).await?;
+        let user_pk = match ctx.history_actor() {
+            HistoryActor::User(user_pk) => Some(*user_pk),
+            HistoryActor::SystemInit => None,
+        };
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT approval_requested_at FROM change_set_request_approval_v1($1, $2, $3)",
+                &[&self.pk, &user_pk, &self.tenancy],
+            )
+            .await?;
+        let approval_requested_at: DateTime<Utc> = row.try_get("approval_requested_at")?;
+        self.status = ChangeSetStatus::PendingApproval;
+        self.approval_requested_at = Some(approval_requested_at);
+        self.approval_requested_by = user_pk;
+        self.timestamp.updated_at = approval_requested_at;
+
+        let _history_event = HistoryEvent::new(
+            ctx,
+            "change_set.request_approval",
+            "Change Set approval requested",
+            &serde_json::json![{ "pk": &self.pk }],
+        )
+        .await?;
+
+        WsEvent::change_set_approval_requested(ctx, self.pk)
+            .await?
+            .publish_on_commit(ctx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Records the current user's approval of a change set that is pending approval. Approving
+    /// takes the same permission as applying.
+    #[instrument(skip(ctx, comment))]
+    pub async fn approve(
+        &mut self,
+        ctx: &DalContext,
+        comment: Option<String>,
+    ) -> ChangeSetResult<ChangeSetApproval> {
+        let approval = self
+            .record_decision(ctx, ChangeSetApprovalDecision::Approved, comment)
+            .await?;
+        let status = self.approval_status(ctx).await?;
+
+        let _history_event = HistoryEvent::new(
+            ctx,
+            "change_set.approve",
+            "Change Set approved",
+            &serde_json::json![{ "pk": &self.pk, "approval": &approval }],
+        )
+        .await?;
+
+        WsEvent::change_set_approved(ctx, &approval, &status)
+            .await?
+            .publish_on_commit(ctx)
+            .await?;
+
+        Ok(approval)
+    }
+
+    /// Records the current user's rejection of a change set that is pending approval and sends
+    /// it back to being open. The approvals it collected so far no longer count.
+    #[instrument(skip(ctx, comment))]
+    pub async fn reject(
+        &mut self,
+        ctx: &DalContext,
+        comment: Option<String>,
+    ) -> ChangeSetResult<ChangeSetApproval> {
+        let approval = self
+            .record_decision(ctx, ChangeSetApprovalDecision::Rejected, comment)
+            .await?;
+        let status = self.approval_status(ctx).await?;
+        self.update_status(ctx, ChangeSetStatus::Open).await?;
+
+        let _history_event = HistoryEvent::new(
+            ctx,
+            "change_set.reject",
+            "Change Set rejected",
+            &serde_json::json![{ "pk": &self.pk, "approval": &approval }],
+        )
+        .await?;
+
+        WsEvent::change_set_rejected(ctx, &approval, &status)
+            .await?
+            .publish_on_commit(ctx)
+            .await?;
+
+        Ok(approval)
+    }
+
+    async fn record_decision(
+        &mut self,
+        ctx: &DalContext,
+        decision: ChangeSetApprovalDecision,
+        comment: Option<String>,
+    ) -> ChangeSetResult<ChangeSetApproval> {
+        self.refresh_approval_state(ctx).await?;
+        if self.status != ChangeSetStatus::PendingApproval {
+            return Err(ChangeSetError::NotPendingApproval(
+                self.pk,
+                self.status.clone(),
+            ));
+        }
+        let user_pk = match ctx.history_actor() {
+            HistoryActor::User(user_pk) => *user_pk,
+            HistoryActor::SystemInit => return Err(ChangeSetError::ApprovalRequiresUser),
+        };
+        if decision == ChangeSetApprovalDecision::Approved
+            && self.approval_requested_by == Some(user_pk)
+        {
+            return Err(ChangeSetError::SelfApproval(self.pk));
+        }
+        User::ensure_authorized(ctx, WorkspacePermission::ApplyChangeSets).await?;
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM change_set_approval_create_v1($1, $2, $3, $4, $5)",
+                &[
+                    &self.tenancy,
+                    &self.pk,
+                    &user_pk,
+                    &decision.to_string(),
+                    &comment,
+                ],
+            )
+            .await?;
+        let json: serde_json::Value = row.try_get("object")?;
+        Ok(serde_json::from_value(json)?)
+    }
+
+    /// Lists the approvals and rejections made since the approval was last requested, oldest
+    /// first.
+    pub async fn approvals(&self, ctx: &DalContext) -> ChangeSetResult<Vec<ChangeSetApproval>> {
+        let approval_requested_at = match self.approval_requested_at {
+            Some(approval_requested_at) => approval_requested_at,
+            None => return Ok(Vec::new()),
+        };
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                LIST_APPROVALS,
+                &[&self.tenancy, &self.pk, &approval_requested_at],
+            )
+            .await?;
+        Ok(objects_from_rows(rows)?)
+    }
+
+    /// Compares the approvals made since the approval was last requested with the number the
+    /// workspace requires.
+    pub async fn approval_status(
+        &self,
+        ctx: &DalContext,
+    ) -> ChangeSetResult<ChangeSetApprovalStatus> {
+        let required_approvals = match self.tenancy.workspace_pk() {
+            Some(workspace_pk) => Workspace::get_by_pk(ctx, &workspace_pk)
+                .await?
+                .map_or(0, |workspace| workspace.required_change_set_approvals()),
+            None => 0,
+        };
+        Ok(ChangeSetApprovalStatus::new(
+            required_approvals,
+            self.approvals(ctx).await?,
+        ))
+    }
+
+    /// Reloads the status and the approval request of the change set, which other users may
+    /// have changed, and sends it back to being open if it was edited since the approval was
+    /// requested.
+    async fn refresh_approval_state(&mut self, ctx: &DalContext) -> ChangeSetResult<()> {
+        let current = Self::get_by_pk(ctx, &self.pk)
+            .await?
+            .ok_or(ChangeSetError::ChangeSetNotFound(self.pk))?;
+        self.status = current.status;
+        self.approval_requested_at = current.approval_requested_at;
+        self.approval_requested_by = current.approval_requested_by;
+
+        if let Some(approval_requested_at) = self.approval_requested_at {
+            if self.status == ChangeSetStatus::PendingApproval
+                && self
+                    .last_edited_at(ctx)
+                    .await?
+                    .map_or(false, |last_edited_at| {
+                        last_edited_at > approval_requested_at
+                    })
+            {
+                self.discard_approval_request(ctx, ChangeSetStatus::Open)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// When a model of the change set was last written. The values computed by functions do
+    /// not count, so that the jobs computing them after an edit do not count as edits.
+    pub async fn last_edited_at(&self, ctx: &DalContext) -> ChangeSetResult<Option<DateTime<Utc>>> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT last_edited_at FROM change_set_last_edited_at_v1($1, $2)",
+                &[&self.tenancy, &self.pk],
+            )
+            .await?;
+        Ok(row.try_get("last_edited_at")?)
+    }
+
+    /// Moves the change set to the given status and discards its approval request, along with
+    /// the approvals collected for it.
+    async fn discard_approval_request(
+        &mut self,
+        ctx: &DalContext,
+        status: ChangeSetStatus,
+    ) -> ChangeSetResult<()> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT timestamp_updated_at FROM change_set_discard_approval_request_v1($1, $2, $3)",
+                &[&self.pk, &status.to_string(), &self.tenancy],
+            )
+            .await?;
+        let updated_at: DateTime<Utc> = row.try_get("timestamp_updated_at")?;
+        self.timestamp.updated_at = updated_at;
+        self.status = status;
+        self.approval_requested_at = None;
+        self.approval_requested_by = None;
+        Ok(())
+    }
+
+    async fn ensure_can_apply(&mut self, ctx: &DalContext) -> ChangeSetResult<()> {
+        self.refresh_approval_state(ctx).await?;
+        match self.status {
+            ChangeSetStatus::Open | ChangeSetStatus::PendingApproval => {}
+            _ => return Err(ChangeSetError::NotOpen(self.pk, self.status.clone())),
+        }
+        User::ensure_authorized(ctx, WorkspacePermission::ApplyChangeSets).await?;
+
+        let status = self.approval_status(ctx).await?;
+        if status.required_approvals == 0 {
+            return Ok(());
+        }
+        if self.status != ChangeSetStatus::PendingApproval || !status.is_satisfied() {
+            let approval_count = if self.status == ChangeSetStatus::PendingApproval {
+                status.approval_count
+            } else {
+                0
+            };
+            return Err(ChangeSetError::NotEnoughApprovals(
+                self.pk,
+                approval_count,
+                status.required_approvals,
+            ));
+        }
+        Ok(())
+    }
+
+    async fn update_status(
+        &mut self,
+        ctx: &DalContext,
+        status: ChangeSetStatus,
+    ) -> ChangeSetResult<()> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT timestamp_updated_at FROM change_set_update_status_v1($1, $2, $3)",
+                &[&self.pk, &status.to_string(), &self.tenancy],
+            )
+            .await?;
+        let updated_at: DateTime<Utc> = row.try_get("timestamp_updated_at")?;
+        self.timestamp.updated_at = updated_at;
+        self.status = status;
+        Ok(())
+    }
+
+    /// Lists everything that [`Self::apply()`] would write to head without applying anything.
+    pub async fn apply_preview(&self, ctx: &DalContext) -> ChangeSetResult<ChangeSetApplyPreview> {
+        ChangeSetApplyPreview::new(ctx, self).await
+    }
+
+    /// The point in time at which this change set last matched head: either when it was created
+    /// or when it was last rebased.
+    pub fn based_on_head_at(&self) -> DateTime<Utc> {
+        self.rebased_at.unwrap_or(self.timestamp.created_at)
+    }
+
+    /// Pulls changes that landed on head since this change set was opened (or last rebased) into
+    /// it.
+    ///
+    /// Rows that the change set never touched already resolve to head, so the work here is to
+    /// find the [`AttributeValues`](crate::AttributeValue) that were changed on both sides and to
+    /// rerun the values that depend on anything head changed, within the change set. The
+    /// change set's own value is kept for every conflict returned.
+    #[instrument(skip(ctx))]
+    pub async fn rebase(
+        &mut self,
+        ctx: &DalContext,
+    ) -> ChangeSetResult<Vec<AttributeValueConflict>> {
+        if self.status != ChangeSetStatus::Open {
+            return Err(ChangeSetError::NotOpen(self.pk, self.status.clone()));
+        }
+        User::ensure_authorized(ctx, WorkspacePermission::EditChangeSets).await?;
+
+        let base = self.based_on_head_at();
+        let change_set_visibility = Visibility::new_change_set(self.pk, false);
+
+        let txns = ctx.txns().await?;
+        let conflict_rows = txns
+            .pg()
+            .query(
+                LIST_ATTRIBUTE_VALUE_CONFLICTS,
+                &[
+                    ctx.tenancy(),
+                    &self.pk,
+                    &base,
+                    &Visibility::new_head(false),
+                    &change_set_visibility,
+                ],
+            )
+            .await?;
+        let mut conflicts = Vec::with_capacity(conflict_rows.len());
+        for row in conflict_rows {
+            conflicts.push(AttributeValueConflict {
+                attribute_value_id: row.try_get("attribute_value_id")?,
+                component_id: row.try_get("component_id")?,
+                prop_id: row.try_get("prop_id")?,
+                head_value: row.try_get("head_value")?,
+                change_set_value: row.try_get("change_set_value")?,
+                head_updated_at: row.try_get("head_updated_at")?,
+                change_set_updated_at: row.try_get("change_set_updated_at")?,
+            });
+        }
+
+        let updated_rows = txns
+            .pg()
+            .query(
+                LIST_HEAD_ATTRIBUTE_VALUES_UPDATED_SINCE,
+                &[ctx.tenancy(), &base],
+            )
+            .await?;
+        let mut updated_on_head: Vec<AttributeValueId> = Vec::with_capacity(updated_rows.len());
+        for row in updated_rows {
+            updated_on_head.push(row.try_get("id")?);
+        }
+
+        let row = txns
+            .pg()
+            .query_one(
+                "SELECT rebased_at FROM change_set_rebase_v1($1, $2)",
+                &[&self.pk, ctx.tenancy()],
+            )
+            .await?;
+        drop(txns);
+
+        let rebased_at: DateTime<Utc> = row.try_get("rebased_at")?;
+        self.rebased_at = Some(rebased_at);
+        self.timestamp.updated_at = rebased_at;
+
+        if !updated_on_head.is_empty() {
+            ctx.enqueue_job(DependentValuesUpdate::new(
+                ctx.access_builder(),
+                change_set_visibility,
+                updated_on_head,
+            ))
+            .await?;
+        }
+
+        let _history_event = HistoryEvent::new(
+            ctx,
+            "change_set.rebase",
+            "Change Set rebased",
+            &serde_json::json![{ "pk": &self.pk, "conflicts": &conflicts }],
+        )
+        .await?;
+
+        WsEvent::change_set_rebased(ctx, self.pk)
+            .await?
+            .publish_on_commit(ctx)
+            .await?;
+
+        Ok(conflicts)
+    }
+
     #[instrument(skip_all)]
     pub async fn list_open(ctx: &DalContext) -> ChangeSetResult<Vec<Self>> {
         let rows = ctx
@@ -156,6 +725,20 @@ impl ChangeSet {
         Ok(results)
     }
 
+    /// Lists the change sets that were [`closed`](Self::close()) and can still be
+    /// [`reopened`](Self::reopen()).
+    #[instrument(skip_all)]
+    pub async fn list_closed(ctx: &DalContext) -> ChangeSetResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(CHANGE_SET_CLOSED_LIST, &[ctx.tenancy()])
+            .await?;
+        let results = objects_from_rows(rows)?;
+        Ok(results)
+    }
+
     #[instrument(skip_all)]
     pub async fn get_by_pk(
         ctx: &DalContext,
@@ -184,6 +767,42 @@ impl ChangeSet {
     }
 }
 
+/// An [`AttributeValue`](crate::AttributeValue) that was changed both in a [`ChangeSet`] and on
+/// head after the [`ChangeSet`] was last based on head.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AttributeValueConflict {
+    pub attribute_value_id: AttributeValueId,
+    pub component_id: ComponentId,
+    pub prop_id: PropId,
+    pub head_value: Option<serde_json::Value>,
+    pub change_set_value: Option<serde_json::Value>,
+    pub head_updated_at: DateTime<Utc>,
+    pub change_set_updated_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSetApprovalPayload {
+    change_set_pk: ChangeSetPk,
+    user_pk: UserPk,
+    comment: Option<String>,
+    approval_count: i32,
+    required_approvals: i32,
+}
+
+impl ChangeSetApprovalPayload {
+    fn new(approval: &ChangeSetApproval, status: &ChangeSetApprovalStatus) -> Self {
+        Self {
+            change_set_pk: approval.change_set_pk,
+            user_pk: approval.user_pk,
+            comment: approval.comment.clone(),
+            approval_count: status.approval_count,
+            required_approvals: status.required_approvals,
+        }
+    }
+}
+
 impl WsEvent {
     pub async fn change_set_created(
         ctx: &DalContext,
@@ -199,6 +818,13 @@ impl WsEvent {
         WsEvent::new(ctx, WsPayload::ChangeSetApplied(change_set_pk)).await
     }
 
+    pub async fn change_set_rebased(
+        ctx: &DalContext,
+        change_set_pk: ChangeSetPk,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(ctx, WsPayload::ChangeSetRebased(change_set_pk)).await
+    }
+
     pub async fn change_set_canceled(
         ctx: &DalContext,
         change_set_pk: ChangeSetPk,
@@ -206,6 +832,51 @@ impl WsEvent {
         WsEvent::new(ctx, WsPayload::ChangeSetCanceled(change_set_pk)).await
     }
 
+    pub async fn change_set_closed(
+        ctx: &DalContext,
+        change_set_pk: ChangeSetPk,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(ctx, WsPayload::ChangeSetClosed(change_set_pk)).await
+    }
+
+    pub async fn change_set_reopened(
+        ctx: &DalContext,
+        change_set_pk: ChangeSetPk,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(ctx, WsPayload::ChangeSetReopened(change_set_pk)).await
+    }
+
+    pub async fn change_set_approval_requested(
+        ctx: &DalContext,
+        change_set_pk: ChangeSetPk,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(ctx, WsPayload::ChangeSetApprovalRequested(change_set_pk)).await
+    }
+
+    pub async fn change_set_approved(
+        ctx: &DalContext,
+        approval: &ChangeSetApproval,
+        status: &ChangeSetApprovalStatus,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(
+            ctx,
+            WsPayload::ChangeSetApproved(ChangeSetApprovalPayload::new(approval, status)),
+        )
+        .await
+    }
+
+    pub async fn change_set_rejected(
+        ctx: &DalContext,
+        approval: &ChangeSetApproval,
+        status: &ChangeSetApprovalStatus,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(
+            ctx,
+            WsPayload::ChangeSetRejected(ChangeSetApprovalPayload::new(approval, status)),
+        )
+        .await
+    }
+
     pub async fn change_set_written(ctx: &DalContext) -> WsEventResult<Self> {
         WsEvent::new(
             ctx,
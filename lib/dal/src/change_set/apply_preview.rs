@@ -0,0 +1,224 @@
+//! This module contains [`ChangeSetApplyPreview`], a dry-run of [`ChangeSet::apply()`].
+
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use si_data_pg::PgRow;
+use telemetry::prelude::*;
+
+use crate::change_set::{ChangeSet, ChangeSetPk, ChangeSetResult};
+use crate::change_status::{ChangeStatus, ComponentChangeStatus};
+use crate::component::diff::ComponentDiff;
+use crate::edge::EdgeId;
+use crate::{AttributeValueId, ComponentId, DalContext, FuncId, PropId, Visibility};
+
+const PREVIEW_ATTRIBUTE_VALUES: &str =
+    include_str!("../queries/change_set/preview_attribute_values.sql");
+const PREVIEW_COMPONENTS: &str = include_str!("../queries/change_set/preview_components.sql");
+const PREVIEW_EDGES: &str = include_str!("../queries/change_set/preview_edges.sql");
+const PREVIEW_FUNCS: &str = include_str!("../queries/change_set/preview_funcs.sql");
+
+/// Everything that applying a [`ChangeSet`] would write to head. Entries flagged as a `conflict`
+/// were also changed on head after the [`ChangeSet`] was last based on head (see
+/// [`ChangeSet::based_on_head_at()`]).
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSetApplyPreview {
+    pub change_set_pk: ChangeSetPk,
+    pub components: Vec<ComponentApplyPreview>,
+    pub edges: Vec<EdgeApplyPreview>,
+    pub funcs: Vec<FuncApplyPreview>,
+    pub attribute_values: Vec<AttributeValueApplyPreview>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentApplyPreview {
+    pub component_id: ComponentId,
+    pub component_name: String,
+    pub change_status: ChangeStatus,
+    pub conflict: bool,
+    /// Only populated for [`Components`](crate::Component) that still exist in the
+    /// [`ChangeSet`] and only when built with [`ChangeSetApplyPreview::new()`].
+    pub diff: Option<ComponentDiff>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EdgeApplyPreview {
+    pub edge_id: EdgeId,
+    pub change_status: ChangeStatus,
+    pub conflict: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FuncApplyPreview {
+    pub func_id: FuncId,
+    pub func_name: String,
+    pub change_status: ChangeStatus,
+    pub conflict: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AttributeValueApplyPreview {
+    pub attribute_value_id: AttributeValueId,
+    pub component_id: ComponentId,
+    pub prop_id: PropId,
+    pub change_status: ChangeStatus,
+    pub conflict: bool,
+}
+
+impl ChangeSetApplyPreview {
+    pub async fn new(ctx: &DalContext, change_set: &ChangeSet) -> ChangeSetResult<Self> {
+        Self::assemble(ctx, change_set, true).await
+    }
+
+    /// Builds the preview without the per-[`Component`](crate::Component)
+    /// [`diffs`](ComponentDiff), which is all that is needed to check for conflicts.
+    pub async fn new_without_diffs(
+        ctx: &DalContext,
+        change_set: &ChangeSet,
+    ) -> ChangeSetResult<Self> {
+        Self::assemble(ctx, change_set, false).await
+    }
+
+    #[instrument(skip(ctx))]
+    async fn assemble(
+        ctx: &DalContext,
+        change_set: &ChangeSet,
+        with_diffs: bool,
+    ) -> ChangeSetResult<Self> {
+        let change_set_visibility = Visibility::new_change_set(change_set.pk, false);
+        let change_set_ctx = ctx.clone_with_new_visibility(change_set_visibility);
+        let base = change_set.based_on_head_at();
+
+        let (component_rows, edge_rows, func_rows, attribute_value_rows) = {
+            let txns = ctx.txns().await?;
+            (
+                txns.pg()
+                    .query(PREVIEW_COMPONENTS, &[ctx.tenancy(), &change_set.pk, &base])
+                    .await?,
+                txns.pg()
+                    .query(PREVIEW_EDGES, &[ctx.tenancy(), &change_set.pk, &base])
+                    .await?,
+                txns.pg()
+                    .query(PREVIEW_FUNCS, &[ctx.tenancy(), &change_set.pk, &base])
+                    .await?,
+                txns.pg()
+                    .query(
+                        PREVIEW_ATTRIBUTE_VALUES,
+                        &[
+                            ctx.tenancy(),
+                            &change_set.pk,
+                            &base,
+                            &Visibility::new_head(false),
+                            &change_set_visibility,
+                        ],
+                    )
+                    .await?,
+            )
+        };
+
+        let mut attribute_values = Vec::with_capacity(attribute_value_rows.len());
+        for row in attribute_value_rows {
+            attribute_values.push(AttributeValueApplyPreview {
+                attribute_value_id: row.try_get("attribute_value_id")?,
+                component_id: row.try_get("component_id")?,
+                prop_id: row.try_get("prop_id")?,
+                change_status: change_status_from_row(&row)?,
+                conflict: row.try_get("conflict")?,
+            });
+        }
+
+        let mut edges = Vec::with_capacity(edge_rows.len());
+        for row in edge_rows {
+            edges.push(EdgeApplyPreview {
+                edge_id: row.try_get("id")?,
+                change_status: change_status_from_row(&row)?,
+                conflict: row.try_get("conflict")?,
+            });
+        }
+
+        let mut funcs = Vec::with_capacity(func_rows.len());
+        for row in func_rows {
+            funcs.push(FuncApplyPreview {
+                func_id: row.try_get("id")?,
+                func_name: row.try_get("func_name")?,
+                change_status: change_status_from_row(&row)?,
+                conflict: row.try_get("conflict")?,
+            });
+        }
+
+        // A component conflicts if either its own row or any of its values conflict.
+        let mut conflicting_components: HashSet<ComponentId> = attribute_values
+            .iter()
+            .filter(|value| value.conflict)
+            .map(|value| value.component_id)
+            .collect();
+        for row in component_rows {
+            let conflict: bool = row.try_get("conflict")?;
+            if conflict {
+                conflicting_components.insert(row.try_get("id")?);
+            }
+        }
+
+        let component_change_status = ComponentChangeStatus::new(&change_set_ctx).await?;
+        let mut components = Vec::new();
+        for group in component_change_status.stats() {
+            let diff = match group.component_status {
+                ChangeStatus::Deleted => None,
+                _ if !with_diffs => None,
+                _ => Some(ComponentDiff::new(&change_set_ctx, group.component_id).await?),
+            };
+            components.push(ComponentApplyPreview {
+                component_id: group.component_id,
+                component_name: group.component_name().to_owned(),
+                change_status: group.component_status,
+                conflict: conflicting_components.contains(&group.component_id),
+                diff,
+            });
+        }
+
+        Ok(Self {
+            change_set_pk: change_set.pk,
+            components,
+            edges,
+            funcs,
+            attribute_values,
+        })
+    }
+
+    /// The number of objects conflicting with head. Conflicting attribute values are counted
+    /// through the [`Component`](crate::Component) they belong to, so a component is counted
+    /// once no matter how many of its values conflict.
+    pub fn conflict_count(&self) -> usize {
+        let conflicting_components: HashSet<ComponentId> = self
+            .components
+            .iter()
+            .filter(|component| component.conflict)
+            .map(|component| component.component_id)
+            .chain(
+                self.attribute_values
+                    .iter()
+                    .filter(|value| value.conflict)
+                    .map(|value| value.component_id),
+            )
+            .collect();
+
+        conflicting_components.len()
+            + self.edges.iter().filter(|e| e.conflict).count()
+            + self.funcs.iter().filter(|f| f.conflict).count()
+    }
+
+    pub fn has_conflicts(&self) -> bool {
+        self.conflict_count() > 0
+    }
+}
+
+fn change_status_from_row(row: &PgRow) -> ChangeSetResult<ChangeStatus> {
+    let change_status: String = row.try_get("change_status")?;
+    Ok(ChangeStatus::from_str(&change_status)?)
+}
@@ -0,0 +1,64 @@
+//! This module contains [`ChangeSetApproval`], the decision a reviewer made about a
+//! [`ChangeSet`](crate::ChangeSet) that is pending approval.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+
+use crate::{pk, ChangeSetPk, Tenancy, UserPk};
+
+pk!(ChangeSetApprovalPk);
+
+#[remain::sorted]
+#[derive(Deserialize, Serialize, Debug, Display, EnumString, PartialEq, Eq, Clone, Copy)]
+pub enum ChangeSetApprovalDecision {
+    Approved,
+    Rejected,
+}
+
+/// A single approval or rejection of a [`ChangeSet`](crate::ChangeSet). Approvals are never
+/// modified or deleted; only the ones made since the approval was last requested count.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ChangeSetApproval {
+    pub pk: ChangeSetApprovalPk,
+    pub change_set_pk: ChangeSetPk,
+    pub user_pk: UserPk,
+    pub decision: ChangeSetApprovalDecision,
+    pub comment: Option<String>,
+    pub created_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub tenancy: Tenancy,
+}
+
+/// Where a [`ChangeSet`](crate::ChangeSet) stands with regard to the approvals its workspace
+/// requires before it can be applied.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSetApprovalStatus {
+    pub required_approvals: i32,
+    /// The number of distinct users that approved since the approval was last requested.
+    pub approval_count: i32,
+    pub approvals: Vec<ChangeSetApproval>,
+}
+
+impl ChangeSetApprovalStatus {
+    pub fn new(required_approvals: i32, approvals: Vec<ChangeSetApproval>) -> Self {
+        let mut approved_by: Vec<UserPk> = approvals
+            .iter()
+            .filter(|approval| approval.decision == ChangeSetApprovalDecision::Approved)
+            .map(|approval| approval.user_pk)
+            .collect();
+        approved_by.sort();
+        approved_by.dedup();
+
+        Self {
+            required_approvals,
+            approval_count: approved_by.len() as i32,
+            approvals,
+        }
+    }
+
+    pub fn is_satisfied(&self) -> bool {
+        self.approval_count >= self.required_approvals
+    }
+}
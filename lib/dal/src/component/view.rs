@@ -47,6 +47,8 @@ pub enum ComponentViewError {
     Secret(#[from] SecretError),
     #[error("secret not found: {0}")]
     SecretNotFound(SecretId),
+    #[error("secret has no workspace: {0}")]
+    SecretWithoutWorkspace(SecretId),
     #[error(transparent)]
     SerdeJson(#[from] serde_json::Error),
     #[error(transparent)]
@@ -142,14 +144,24 @@ impl ComponentView {
             for (_key, value) in object {
                 if let Some(raw_id) = value.as_str() {
                     let id = SecretId::from_str(raw_id)?;
-                    let decrypted_secret = EncryptedSecret::get_by_id(ctx, &id)
+                    let encrypted_secret = EncryptedSecret::get_by_id(ctx, &id)
                         .await?
-                        .ok_or(ComponentViewError::SecretNotFound(id))?
-                        .decrypt(ctx)
-                        .await?;
-                    let encoded = ctx
-                        .encryption_key()
-                        .encrypt_and_encode(serde_json::to_string(&decrypted_secret.message())?);
+                        .ok_or(ComponentViewError::SecretNotFound(id))?;
+                    let backend_namespace = encrypted_secret.backend_namespace();
+                    let decrypted_secret = encrypted_secret.decrypt(ctx).await?;
+                    // References only reach the secrets of the workspace the secret belongs to,
+                    // or was forked from
+                    let message = match decrypted_secret.reference() {
+                        Some(reference) => {
+                            let backend_namespace = backend_namespace
+                                .ok_or(ComponentViewError::SecretWithoutWorkspace(id))?;
+                            serde_json::to_string(
+                                &reference.namespaced(backend_namespace).to_message(),
+                            )?
+                        }
+                        None => serde_json::to_string(&decrypted_secret.message())?,
+                    };
+                    let encoded = ctx.encryption_key().encrypt_and_encode(message);
 
                     *value = serde_json::to_value(&decrypted_secret)?;
                     match value.pointer_mut("/message") {
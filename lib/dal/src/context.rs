@@ -14,7 +14,7 @@ use crate::{
         processor::{JobQueueProcessor, JobQueueProcessorError},
         producer::{BlockingJobError, BlockingJobResult, JobProducer},
     },
-    HistoryActor, StandardModel, Tenancy, TenancyError, Visibility,
+    ApiTokenPk, HistoryActor, StandardModel, Tenancy, TenancyError, Visibility, WorkspaceRole,
 };
 
 /// A context type which contains handles to common core service dependencies.
@@ -199,6 +199,11 @@ pub struct DalContext {
     visibility: Visibility,
     /// A suitable [`HistoryActor`] for the consuming DAL objects.
     history_actor: HistoryActor,
+    /// If set, the [`HistoryActor`] only has the permissions of this role, even if their own role
+    /// grants more.
+    max_role: Option<WorkspaceRole>,
+    /// The [`ApiToken`](crate::ApiToken) the [`HistoryActor`] authenticated with, if any.
+    api_token_pk: Option<ApiTokenPk>,
     /// Determines if regular commits block until the jobs get executed.
     /// This is useful to ensure child jobs of blocking jobs also block so there is no race-condition in the DAL.
     /// And also for SDF routes to block the HTTP request until the jobs get executed, so SDF tests don't race.
@@ -281,6 +286,8 @@ impl DalContext {
     pub fn update_access_builder(&mut self, access_builder: AccessBuilder) {
         self.tenancy = access_builder.tenancy;
         self.history_actor = access_builder.history_actor;
+        self.max_role = access_builder.max_role;
+        self.api_token_pk = access_builder.api_token_pk;
     }
 
     /// Runs a block of code with a custom [`Visibility`] DalContext using the same transactions
@@ -457,6 +464,17 @@ impl DalContext {
         &self.history_actor
     }
 
+    /// Gets the role the permissions of the dal context's history actor are capped to, if any.
+    pub fn max_role(&self) -> Option<WorkspaceRole> {
+        self.max_role
+    }
+
+    /// Gets the [`ApiToken`](crate::ApiToken) the dal context's history actor authenticated
+    /// with, if any.
+    pub fn api_token_pk(&self) -> Option<ApiTokenPk> {
+        self.api_token_pk
+    }
+
     /// Gets an optional reference to the dal context's pkgs path
     pub fn pkgs_path(&self) -> Option<&PathBuf> {
         self.services_context.pkgs_path.as_ref()
@@ -494,7 +512,12 @@ impl DalContext {
     }
 
     pub fn access_builder(&self) -> AccessBuilder {
-        AccessBuilder::new(self.tenancy, self.history_actor)
+        AccessBuilder {
+            tenancy: self.tenancy,
+            history_actor: self.history_actor,
+            max_role: self.max_role,
+            api_token_pk: self.api_token_pk,
+        }
     }
 }
 
@@ -508,6 +531,10 @@ pub struct RequestContext {
     pub visibility: Visibility,
     /// A suitable [`HistoryActor`] for the consuming DAL objects.
     pub history_actor: HistoryActor,
+    /// If set, caps the permissions of the [`HistoryActor`] to those of this role.
+    pub max_role: Option<WorkspaceRole>,
+    /// The [`ApiToken`](crate::ApiToken) the [`HistoryActor`] authenticated with, if any.
+    pub api_token_pk: Option<ApiTokenPk>,
 }
 
 impl Default for RequestContext {
@@ -518,6 +545,8 @@ impl Default for RequestContext {
             tenancy: Tenancy::new_empty(),
             visibility: Visibility::new_head(false),
             history_actor: HistoryActor::SystemInit,
+            max_role: None,
+            api_token_pk: None,
         }
     }
 }
@@ -529,6 +558,12 @@ pub struct AccessBuilder {
     tenancy: Tenancy,
     /// A suitable [`HistoryActor`] for the consuming DAL objects.
     history_actor: HistoryActor,
+    /// If set, caps the permissions of the [`HistoryActor`] to those of this role.
+    #[serde(default)]
+    max_role: Option<WorkspaceRole>,
+    /// The [`ApiToken`](crate::ApiToken) the [`HistoryActor`] authenticated with, if any.
+    #[serde(default)]
+    api_token_pk: Option<ApiTokenPk>,
 }
 
 impl AccessBuilder {
@@ -537,15 +572,32 @@ impl AccessBuilder {
         Self {
             tenancy,
             history_actor,
+            max_role: None,
+            api_token_pk: None,
         }
     }
 
+    /// Caps the permissions of the [`HistoryActor`] to those of the given role, whatever their
+    /// own role is.
+    pub fn with_max_role(mut self, max_role: WorkspaceRole) -> Self {
+        self.max_role = Some(max_role);
+        self
+    }
+
+    /// Records the [`ApiToken`](crate::ApiToken) the [`HistoryActor`] authenticated with.
+    pub fn with_api_token_pk(mut self, api_token_pk: ApiTokenPk) -> Self {
+        self.api_token_pk = Some(api_token_pk);
+        self
+    }
+
     /// Builds and returns a new [`RequestContext`] using the given [`Visibility`].
     pub fn build(self, visibility: Visibility) -> RequestContext {
         RequestContext {
             tenancy: self.tenancy,
             visibility,
             history_actor: self.history_actor,
+            max_role: self.max_role,
+            api_token_pk: self.api_token_pk,
         }
     }
 
@@ -558,11 +610,27 @@ impl AccessBuilder {
     pub fn history_actor(&self) -> &HistoryActor {
         &self.history_actor
     }
+
+    /// Gets the role the permissions of the dal context's history actor are capped to, if any.
+    pub fn max_role(&self) -> Option<WorkspaceRole> {
+        self.max_role
+    }
+
+    /// Gets the [`ApiToken`](crate::ApiToken) the dal context's history actor authenticated
+    /// with, if any.
+    pub fn api_token_pk(&self) -> Option<ApiTokenPk> {
+        self.api_token_pk
+    }
 }
 
 impl From<DalContext> for AccessBuilder {
     fn from(ctx: DalContext) -> Self {
-        Self::new(ctx.tenancy, ctx.history_actor)
+        Self {
+            tenancy: ctx.tenancy,
+            history_actor: ctx.history_actor,
+            max_role: ctx.max_role,
+            api_token_pk: ctx.api_token_pk,
+        }
     }
 }
 
@@ -591,6 +659,8 @@ impl DalContextBuilder {
             tenancy: Tenancy::new_empty(),
             visibility: Visibility::new_head(false),
             history_actor: HistoryActor::SystemInit,
+            max_role: None,
+            api_token_pk: None,
             no_dependent_values: self.no_dependent_values,
         })
     }
@@ -607,6 +677,8 @@ impl DalContextBuilder {
             conns_state: Arc::new(Mutex::new(ConnectionState::new_from_conns(conns))),
             tenancy: access_builder.tenancy,
             history_actor: access_builder.history_actor,
+            max_role: access_builder.max_role,
+            api_token_pk: access_builder.api_token_pk,
             visibility: Visibility::new_head(false),
             no_dependent_values: self.no_dependent_values,
         })
@@ -625,6 +697,8 @@ impl DalContextBuilder {
             tenancy: request_context.tenancy,
             visibility: request_context.visibility,
             history_actor: request_context.history_actor,
+            max_role: request_context.max_role,
+            api_token_pk: request_context.api_token_pk,
             no_dependent_values: self.no_dependent_values,
         })
     }
@@ -0,0 +1,364 @@
+//! This module contains [`ComponentDrift`], which tracks whether a [`Component's`](Component)
+//! `/root/resource` still matches its `/root/domain`, and [`DriftSummary`], which aggregates
+//! those statuses for a workspace.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use si_data_pg::PgError;
+use std::collections::HashMap;
+use strum::{AsRefStr, Display, EnumString};
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::func::backend::js_reconciliation::{ReconciliationDiff, ReconciliationDiffDomain};
+use crate::standard_model::{object_option_from_row_option, objects_from_rows};
+use crate::{
+    AttributeReadContext, AttributeValue, AttributeValueError, AttributeView, Component,
+    ComponentError, ComponentId, DalContext, ExternalProviderId, Func, FuncBinding,
+    FuncBindingError, FuncError, FuncId, InternalProviderId, Prop, PropId, StandardModel,
+    StandardModelError, Tenancy, TransactionsError, WsEvent, WsEventError, WsEventResult,
+    WsPayload,
+};
+
+const GET_FOR_COMPONENT: &str = include_str!("queries/drift/get_for_component.sql");
+const LIST: &str = include_str!("queries/drift/list.sql");
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum DriftError {
+    #[error(transparent)]
+    AttributeValue(#[from] AttributeValueError),
+    #[error("attribute value not found for prop {0} and component {1}")]
+    AttributeValueNotFound(PropId, ComponentId),
+    #[error(transparent)]
+    Component(#[from] ComponentError),
+    #[error("component not found: {0}")]
+    ComponentNotFound(ComponentId),
+    #[error(transparent)]
+    Func(#[from] FuncError),
+    #[error(transparent)]
+    FuncBinding(#[from] FuncBindingError),
+    #[error("func not found: {0}")]
+    FuncNotFound(FuncId),
+    #[error(transparent)]
+    Pg(#[from] PgError),
+    #[error("schema variant not found for component {0}")]
+    SchemaVariantNotFound(ComponentId),
+    #[error(transparent)]
+    SerdeJson(#[from] serde_json::Error),
+    #[error(transparent)]
+    StandardModel(#[from] StandardModelError),
+    #[error(transparent)]
+    Transactions(#[from] TransactionsError),
+    #[error(transparent)]
+    WsEvent(#[from] WsEventError),
+}
+
+pub type DriftResult<T> = Result<T, DriftError>;
+
+/// Whether a [`Component's`](Component) resource has drifted away from its domain.
+#[remain::sorted]
+#[derive(
+    Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Display, EnumString, AsRefStr,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum DriftStatus {
+    /// At least one resource value differs from the domain value it refers to.
+    Drifted,
+    /// Every resource value matches the domain value it refers to.
+    InSync,
+    /// The [`Component`] has no resource yet, so there is nothing to compare.
+    Unknown,
+}
+
+/// The last computed [`DriftStatus`] of a [`Component`] on head, along with the paths of the
+/// resource props that drifted.
+///
+/// Like a [`StatusUpdate`](crate::StatusUpdate), a `ComponentDrift` is not a standard model: it is
+/// only tracked on head and is overwritten every time it is recomputed.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ComponentDrift {
+    pub component_id: ComponentId,
+    pub status: DriftStatus,
+    pub drifted_paths: Vec<String>,
+    pub checked_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub tenancy: Tenancy,
+}
+
+impl ComponentDrift {
+    /// Computes the "diffs" between the resource props of a [`Component`] and the domain props
+    /// they refer to, keyed by resource prop path. Only props with a diff func are compared.
+    #[instrument(skip_all)]
+    pub async fn diff(
+        ctx: &DalContext,
+        component: &Component,
+    ) -> DriftResult<HashMap<String, ReconciliationDiff>> {
+        let schema_variant = component
+            .schema_variant(ctx)
+            .await?
+            .ok_or(DriftError::SchemaVariantNotFound(*component.id()))?;
+
+        let props = Prop::find_by_attr(ctx, "schema_variant_id", schema_variant.id()).await?;
+
+        let view_context = AttributeReadContext {
+            prop_id: None,
+            internal_provider_id: Some(InternalProviderId::NONE),
+            external_provider_id: Some(ExternalProviderId::NONE),
+            component_id: Some(*component.id()),
+        };
+
+        let mut diff = HashMap::new();
+        for prop in props {
+            let (domain_prop_id, resource_prop_id) = match prop.refers_to_prop_id() {
+                None => continue,
+                Some(prop_id) => (*prop_id, *prop.id()),
+            };
+
+            let func_id = match prop.diff_func_id() {
+                Some(func_id) => *func_id,
+                None => {
+                    warn!("Prop {} does not have diff functions set, therefore can't be diffed with prop {domain_prop_id:?}", prop.path().as_str());
+                    continue;
+                }
+            };
+
+            let resource_prop_av =
+                Self::attribute_value_for_prop(ctx, resource_prop_id, *component.id()).await?;
+            let resource_prop_view =
+                AttributeView::new(ctx, view_context, Some(*resource_prop_av.id())).await?;
+
+            let domain_prop_av =
+                Self::attribute_value_for_prop(ctx, domain_prop_id, *component.id()).await?;
+            let domain_prop_view =
+                AttributeView::new(ctx, view_context, Some(*domain_prop_av.id())).await?;
+
+            let func = Func::get_by_id(ctx, &func_id)
+                .await?
+                .ok_or(DriftError::FuncNotFound(func_id))?;
+            let func_binding = FuncBinding::new(
+                ctx,
+                serde_json::json!({
+                    "first": domain_prop_view.value(),
+                    "second": resource_prop_view.value(),
+                }),
+                *func.id(),
+                *func.backend_kind(),
+            )
+            .await?;
+            let diff_value = func_binding
+                .execute(ctx)
+                .await?
+                .value()
+                .cloned()
+                .unwrap_or(serde_json::Value::Null);
+
+            let diff_value = DiffValue::deserialize(&diff_value)?;
+
+            // TODO: Should we treat unset as equal or not?
+            if diff_value.diff {
+                diff.insert(
+                    prop.path().with_replaced_sep("/"),
+                    ReconciliationDiff {
+                        normalized_resource: diff_value.new_value,
+                        resource: resource_prop_view.value().clone(),
+                        domain: ReconciliationDiffDomain {
+                            id: *domain_prop_av.id(),
+                            value: domain_prop_view.value().clone(),
+                        },
+                    },
+                );
+            }
+        }
+
+        Ok(diff)
+    }
+
+    /// Computes the [`DriftStatus`] of a [`Component`] and stores it. If the status changed since
+    /// it was last computed, a [`WsEvent`] is published.
+    #[instrument(skip(ctx))]
+    pub async fn detect(ctx: &DalContext, component_id: ComponentId) -> DriftResult<Self> {
+        let component = Component::get_by_id(ctx, &component_id)
+            .await?
+            .ok_or(DriftError::ComponentNotFound(component_id))?;
+
+        let (status, mut drifted_paths) = if component.resource(ctx).await?.payload.is_none() {
+            (DriftStatus::Unknown, Vec::new())
+        } else {
+            let diff = Self::diff(ctx, &component).await?;
+            if diff.is_empty() {
+                (DriftStatus::InSync, Vec::new())
+            } else {
+                (DriftStatus::Drifted, diff.into_keys().collect())
+            }
+        };
+        drifted_paths.sort();
+
+        let previous = Self::get_for_component(ctx, component_id).await?;
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM component_drift_upsert_v1($1, $2, $3, $4)",
+                &[
+                    ctx.tenancy(),
+                    &component_id,
+                    &status.as_ref(),
+                    &serde_json::to_value(&drifted_paths)?,
+                ],
+            )
+            .await?;
+        let json: serde_json::Value = row.try_get("object")?;
+        let drift: Self = serde_json::from_value(json)?;
+
+        if previous.map(|p| p.status) != Some(drift.status) {
+            WsEvent::component_drift_changed(ctx, component_id, drift.status)
+                .await?
+                .publish_on_commit(ctx)
+                .await?;
+        }
+
+        Ok(drift)
+    }
+
+    #[instrument(skip(ctx))]
+    pub async fn get_for_component(
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> DriftResult<Option<Self>> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(GET_FOR_COMPONENT, &[ctx.tenancy(), &component_id])
+            .await?;
+        Ok(object_option_from_row_option(row)?)
+    }
+
+    #[instrument(skip_all)]
+    pub async fn list(ctx: &DalContext) -> DriftResult<Vec<Self>> {
+        let rows = ctx.txns().await?.pg().query(LIST, &[ctx.tenancy()]).await?;
+        Ok(objects_from_rows(rows)?)
+    }
+
+    async fn attribute_value_for_prop(
+        ctx: &DalContext,
+        prop_id: PropId,
+        component_id: ComponentId,
+    ) -> DriftResult<AttributeValue> {
+        let context = AttributeReadContext {
+            prop_id: Some(prop_id),
+            internal_provider_id: Some(InternalProviderId::NONE),
+            external_provider_id: Some(ExternalProviderId::NONE),
+            component_id: Some(component_id),
+        };
+        AttributeValue::find_for_context(ctx, context)
+            .await?
+            .ok_or(DriftError::AttributeValueNotFound(prop_id, component_id))
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct DiffValue {
+    diff: bool,
+    new_value: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DriftSummaryForComponent {
+    pub component_id: ComponentId,
+    pub component_name: String,
+    pub status: DriftStatus,
+    pub drifted_paths: Vec<String>,
+    pub checked_at: Option<DateTime<Utc>>,
+}
+
+/// Aggregates the [`DriftStatus`] of every [`Component`] in the workspace, similar to what
+/// [`QualificationSummary`](crate::qualification::QualificationSummary) does for qualifications.
+/// Components whose drift was never computed are counted as [`DriftStatus::Unknown`].
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DriftSummary {
+    pub total: i64,
+    pub drifted: i64,
+    pub in_sync: i64,
+    pub unknown: i64,
+    pub components: Vec<DriftSummaryForComponent>,
+}
+
+impl DriftSummary {
+    #[instrument(skip_all)]
+    pub async fn get_summary(ctx: &DalContext) -> DriftResult<DriftSummary> {
+        let mut drift_by_component: HashMap<ComponentId, ComponentDrift> =
+            ComponentDrift::list(ctx)
+                .await?
+                .into_iter()
+                .map(|drift| (drift.component_id, drift))
+                .collect();
+
+        let mut component_summaries = Vec::new();
+        let mut drifted = 0;
+        let mut in_sync = 0;
+        let mut unknown = 0;
+
+        for component in Component::list(ctx).await? {
+            let component_id = *component.id();
+            let (status, drifted_paths, checked_at) = match drift_by_component.remove(&component_id)
+            {
+                Some(drift) => (drift.status, drift.drifted_paths, Some(drift.checked_at)),
+                None => (DriftStatus::Unknown, Vec::new(), None),
+            };
+
+            match status {
+                DriftStatus::Drifted => drifted += 1,
+                DriftStatus::InSync => in_sync += 1,
+                DriftStatus::Unknown => unknown += 1,
+            }
+
+            component_summaries.push(DriftSummaryForComponent {
+                component_id,
+                component_name: component.name(ctx).await?,
+                status,
+                drifted_paths,
+                checked_at,
+            });
+        }
+
+        Ok(DriftSummary {
+            total: component_summaries.len() as i64,
+            drifted,
+            in_sync,
+            unknown,
+            components: component_summaries,
+        })
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentDriftChangedPayload {
+    component_id: ComponentId,
+    status: DriftStatus,
+}
+
+impl WsEvent {
+    pub async fn component_drift_changed(
+        ctx: &DalContext,
+        component_id: ComponentId,
+        status: DriftStatus,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(
+            ctx,
+            WsPayload::ComponentDriftChanged(ComponentDriftChangedPayload {
+                component_id,
+                status,
+            }),
+        )
+        .await
+    }
+}
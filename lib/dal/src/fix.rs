@@ -1,6 +1,6 @@
 //! This module contains the concept of "fixes".
 
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use postgres_types::{FromSql, ToSql};
 use serde::{Deserialize, Serialize};
 use si_data_pg::PgError;
@@ -9,20 +9,24 @@ use telemetry::prelude::*;
 use thiserror::Error;
 
 use crate::fix::batch::FixBatchId;
+use crate::fix::run::FixRun;
 use crate::func::binding_return_value::FuncBindingReturnValueError;
 use crate::schema::SchemaUiMenu;
 use crate::{
     func::backend::js_action::ActionRunResult, impl_standard_model, pk, standard_model,
     standard_model_accessor, standard_model_accessor_ro, standard_model_belongs_to, ActionKind,
     ActionPrototype, ActionPrototypeError, ActionPrototypeId, Component, ComponentError,
-    ComponentId, DalContext, FixBatch, FixResolverError, Func, FuncError, HistoryEventError,
-    ResourceView, SchemaError, StandardModel, StandardModelError, Tenancy, Timestamp,
-    TransactionsError, Visibility, WsEvent, WsEventError, WsEventResult, WsPayload,
+    ComponentId, DalContext, EdgeError, FixBatch, FixResolverError, Func, FuncError,
+    HistoryEventError, NodeError, ResourceView, SchemaError, StandardModel, StandardModelError,
+    Tenancy, Timestamp, TransactionsError, Visibility, WsEvent, WsEventError, WsEventResult,
+    WsPayload,
 };
 use veritech_client::ResourceStatus;
 
 pub mod batch;
+pub mod graph;
 pub mod resolver;
+pub mod run;
 
 /// The completion status of a [`Fix`] or [`FixBatch`](crate::FixBatch).
 #[remain::sorted]
@@ -69,12 +73,18 @@ pub enum FixError {
     AlreadyFinished,
     #[error("cannot stamp batch or fix as started since it already started")]
     AlreadyStarted,
+    #[error("retry backoff out of range: {0:?}")]
+    BackoffOutOfRange(std::time::Duration),
     #[error("cannot set batch for {0}: fix batch ({1}) already finished")]
     BatchAlreadyFinished(FixId, FixBatchId),
     #[error("cannot set batch for {0}: fix batch ({1}) already started")]
     BatchAlreadyStarted(FixId, FixBatchId),
+    #[error("cannot resume fix batch {0}: it has not finished yet")]
+    BatchNotFinished(FixBatchId),
     #[error(transparent)]
     Component(#[from] ComponentError),
+    #[error(transparent)]
+    Edge(#[from] EdgeError),
     #[error("completion status is empty")]
     EmptyCompletionStatus,
     #[error(transparent)]
@@ -87,18 +97,26 @@ pub enum FixError {
     HistoryEvent(#[from] HistoryEventError),
     #[error("action run status cannot be converted to fix completion status")]
     IncompatibleActionRunStatus,
+    #[error("invalid not before timestamp: {0}")]
+    InvalidNotBefore(#[from] chrono::ParseError),
     #[error("missing finished timestamp for fix: {0}")]
     MissingFinishedTimestampForFix(FixId),
     #[error("fix not found for id: {0}")]
     MissingFix(FixId),
     #[error("fix batch not found for id: {0}")]
     MissingFixBatch(FixBatchId),
+    #[error("fix not found in the fix batch dependency graph: {0}")]
+    MissingFixInGraph(FixId),
     #[error("missing started timestamp for fix: {0}")]
     MissingStartedTimestampForFix(FixId),
+    #[error(transparent)]
+    Node(#[from] NodeError),
     #[error("no fixes in batch: fix batch is empty")]
     NoFixesInBatch(FixBatchId),
     #[error("cannot stamp batch or fix as finished since it has not yet been started")]
     NotYetStarted,
+    #[error("no unsuccessful fixes to resume in batch: {0}")]
+    NoUnsuccessfulFixesInBatch(FixBatchId),
     #[error(transparent)]
     Pg(#[from] PgError),
     #[error(transparent)]
@@ -154,6 +172,28 @@ pub struct Fix {
 
     /// Contains a message related to the completion.
     completion_message: Option<String>,
+
+    /// The time before which the [`Fix`] must not run again, as it waits out the backoff of the
+    /// retry policy of its action.
+    #[serde(default)]
+    not_before: Option<String>,
+
+    /// The previous attempts at running this [`Fix`], oldest first. The current attempt is
+    /// tracked by the fields above.
+    #[serde(default)]
+    attempts: Vec<FixAttempt>,
+}
+
+/// A finished attempt at running a [`Fix`] that was later retried or resumed.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FixAttempt {
+    pub attempt: u32,
+    pub started_at: Option<String>,
+    pub finished_at: Option<String>,
+    pub completion_status: Option<FixCompletionStatus>,
+    pub completion_message: Option<String>,
+    pub resource: Option<JsonValue>,
 }
 
 impl_standard_model! {
@@ -205,6 +245,7 @@ impl Fix {
         FixResult
     );
     standard_model_accessor!(completion_message, Option<String>, FixResult);
+    standard_model_accessor!(not_before, Option<String>, FixResult);
     standard_model_accessor!(resource, OptionJson<JsonValue>, FixResult);
 
     standard_model_belongs_to!(
@@ -234,7 +275,8 @@ impl Fix {
         Ok(())
     }
 
-    /// Executes the [`fix`](Self). Returns true if some resource got updated, false if not
+    /// Executes the [`fix`](Self) and records the attempt as a [`FixRun`](run::FixRun). Returns
+    /// the result of the action, if it returned one.
     pub async fn run(
         &mut self,
         ctx: &DalContext,
@@ -242,36 +284,45 @@ impl Fix {
     ) -> FixResult<Option<ActionRunResult>> {
         // Stamp started and run the workflow.
         self.stamp_started(ctx).await?;
-
-        Ok(match action_prototype.run(ctx, self.component_id).await {
-            Ok(Some(run_result)) => {
-                let completion_status = match run_result.status {
-                    ResourceStatus::Ok | ResourceStatus::Warning => FixCompletionStatus::Success,
-                    ResourceStatus::Error => FixCompletionStatus::Failure,
-                };
-
-                self.stamp_finished(
-                    ctx,
-                    completion_status,
-                    run_result.message.clone(),
-                    Some(run_result.clone()),
-                )
-                .await?;
-
-                Some(run_result)
-            }
-            Ok(None) => {
-                error!("Fix did not return a value!");
-                self.stamp_finished(
-                    ctx,
-                    FixCompletionStatus::Error,
-                    Some("Fix did not return a value".into()),
-                    None,
-                )
-                .await?;
-
-                None
-            }
+        let started_at = Utc::now();
+        let resource_before = self.current_resource(ctx).await?;
+
+        let (run_result, details) = match action_prototype
+            .run_with_details(ctx, self.component_id)
+            .await
+        {
+            Ok(details) => match details.result.clone() {
+                Some(run_result) => {
+                    let completion_status = match run_result.status {
+                        ResourceStatus::Ok | ResourceStatus::Warning => {
+                            FixCompletionStatus::Success
+                        }
+                        ResourceStatus::Error => FixCompletionStatus::Failure,
+                    };
+
+                    self.stamp_finished(
+                        ctx,
+                        completion_status,
+                        run_result.message.clone(),
+                        Some(run_result.clone()),
+                    )
+                    .await?;
+
+                    (Some(run_result), Some(details))
+                }
+                None => {
+                    error!("Fix did not return a value!");
+                    self.stamp_finished(
+                        ctx,
+                        FixCompletionStatus::Error,
+                        Some("Fix did not return a value".into()),
+                        None,
+                    )
+                    .await?;
+
+                    (None, Some(details))
+                }
+            },
             Err(e) => {
                 error!("Unable to run fix: {e}");
                 self.stamp_finished(
@@ -282,8 +333,35 @@ impl Fix {
                 )
                 .await?;
 
-                None
+                (None, None)
             }
+        };
+
+        let resource_after = self.current_resource(ctx).await?;
+        FixRun::record(
+            ctx,
+            self,
+            started_at,
+            resource_before,
+            resource_after,
+            details.as_ref(),
+        )
+        .await?;
+
+        Ok(run_result)
+    }
+
+    /// The resource of the [`Fix's`](Self) [`Component`], as it currently is.
+    async fn current_resource(&self, ctx: &DalContext) -> FixResult<Option<JsonValue>> {
+        let ctx_with_deleted = &ctx.clone_with_delete_visibility();
+        let component = match Component::get_by_id(ctx_with_deleted, &self.component_id).await? {
+            Some(component) => component,
+            None => return Ok(None),
+        };
+        let resource = component.resource(ctx).await?;
+        Ok(match resource.payload {
+            Some(_) => Some(serde_json::to_value(resource)?),
+            None => None,
         })
     }
 
@@ -328,6 +406,98 @@ impl Fix {
         }
     }
 
+    /// Marks a [`fix`](Self) that will never run because one of the [`fixes`](Self) it depends on
+    /// did not succeed. The [`fix`](Self) stays [`unstarted`](FixCompletionStatus::Unstarted).
+    pub async fn stamp_skipped(
+        &mut self,
+        ctx: &DalContext,
+        completion_message: impl Into<String>,
+    ) -> FixResult<()> {
+        if self.started_at.is_some() {
+            Err(FixError::AlreadyStarted)
+        } else if self.finished_at.is_some() {
+            Err(FixError::AlreadyFinished)
+        } else {
+            self.set_completion_status(ctx, Some(FixCompletionStatus::Unstarted))
+                .await?;
+            self.set_completion_message(ctx, Some(completion_message.into()))
+                .await?;
+            Ok(())
+        }
+    }
+
+    /// The previous attempts at running this [`Fix`], oldest first.
+    pub fn attempts(&self) -> &[FixAttempt] {
+        &self.attempts
+    }
+
+    /// The (1-based) number of the current attempt at running this [`Fix`].
+    pub fn attempt(&self) -> u32 {
+        self.attempts.len() as u32 + 1
+    }
+
+    /// Moves the current attempt into the attempt history and clears the completion-related
+    /// columns so that the [`Fix`] can run again.
+    pub async fn reset_for_retry(&mut self, ctx: &DalContext) -> FixResult<()> {
+        if self.started_at.is_some() && self.finished_at.is_none() {
+            return Err(FixError::AlreadyStarted);
+        }
+
+        let mut attempts = self.attempts.clone();
+        attempts.push(FixAttempt {
+            attempt: self.attempt(),
+            started_at: self.started_at.clone(),
+            finished_at: self.finished_at.clone(),
+            completion_status: self.completion_status,
+            completion_message: self.completion_message.clone(),
+            resource: self.resource.clone(),
+        });
+        let updated_at = standard_model::update(
+            ctx,
+            Self::table_name(),
+            "attempts",
+            self.id(),
+            &serde_json::to_value(&attempts)?,
+            TypeHint::JsonB,
+        )
+        .await?;
+        self.timestamp.updated_at = updated_at;
+        self.attempts = attempts;
+
+        self.set_started_at(ctx, None::<String>).await?;
+        self.set_finished_at(ctx, None::<String>).await?;
+        self.set_completion_status(ctx, None::<FixCompletionStatus>)
+            .await?;
+        self.set_completion_message(ctx, None::<String>).await?;
+        self.set_resource(ctx, None::<JsonValue>).await?;
+
+        Ok(())
+    }
+
+    /// Holds the [`Fix`] back for the given backoff before it runs again.
+    pub async fn defer(&mut self, ctx: &DalContext, backoff: std::time::Duration) -> FixResult<()> {
+        let not_before = chrono::Duration::from_std(backoff)
+            .ok()
+            .and_then(|backoff| Utc::now().checked_add_signed(backoff))
+            .ok_or(FixError::BackoffOutOfRange(backoff))?;
+        self.set_not_before(ctx, Some(not_before.to_rfc3339()))
+            .await?;
+        Ok(())
+    }
+
+    /// How long the [`Fix`] must still wait before it runs again, if it was
+    /// [`deferred`](Self::defer).
+    pub fn remaining_backoff(&self) -> FixResult<std::time::Duration> {
+        let not_before = match &self.not_before {
+            Some(not_before) => DateTime::parse_from_rfc3339(not_before)?,
+            None => return Ok(std::time::Duration::ZERO),
+        };
+        // A time in the past does not convert, as there is nothing left to wait for.
+        Ok((not_before.with_timezone(&Utc) - Utc::now())
+            .to_std()
+            .unwrap_or_default())
+    }
+
     /// Generates a [`FixHistoryView`] based on [`self`](Fix).
     pub async fn history_view(
         &self,
@@ -384,6 +554,7 @@ impl Fix {
             resource: resource.map(ResourceView::new),
             started_at: self.started_at().map(|s| s.to_string()),
             finished_at: self.finished_at().map(|s| s.to_string()),
+            attempts: self.attempts.clone(),
         }))
     }
 
@@ -433,6 +604,7 @@ pub struct FixHistoryView {
     started_at: Option<String>,
     finished_at: Option<String>,
     resource: Option<ResourceView>,
+    attempts: Vec<FixAttempt>,
 }
 
 impl FixHistoryView {
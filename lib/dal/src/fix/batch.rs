@@ -6,7 +6,7 @@ use serde::{Deserialize, Serialize};
 use telemetry::prelude::*;
 
 use crate::{
-    fix::{FixCompletionStatus, FixError, FixResult},
+    fix::{graph::FixGraph, FixCompletionStatus, FixError, FixResult},
     impl_standard_model, pk, standard_model, standard_model_accessor, standard_model_has_many,
     DalContext, Fix, StandardModel, Tenancy, Timestamp, Visibility, WsEvent, WsEventResult,
     WsPayload,
@@ -15,6 +15,9 @@ use crate::{
 pk!(FixBatchPk);
 pk!(FixBatchId);
 
+// a type alias for satisfying the standard model macros
+type JsonValue = serde_json::Value;
+
 /// A batch of [`Fixs`](crate::Fix). Every [`Fix`](crate::Fix)
 /// must belong at one and only one [`batch`](Self).
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
@@ -41,6 +44,9 @@ pub struct FixBatch {
     finished_at: Option<String>,
     /// Indicates the state of the [`FixBatch`] when finished.
     completion_status: Option<FixCompletionStatus>,
+    /// The [`FixGraph`] used to schedule the [`Fixes`](crate::Fix), populated when the
+    /// [`FixBatch`] starts.
+    dependency_graph: Option<JsonValue>,
 }
 
 impl_standard_model! {
@@ -76,8 +82,8 @@ impl FixBatch {
         Option<Enum(FixCompletionStatus)>,
         FixResult
     );
+    standard_model_accessor!(dependency_graph, OptionJson<JsonValue>, FixResult);
 
-    // TODO(nick): store the order (and what's sequential, conditional, parallel, etc.) someday.
     standard_model_has_many!(
         lookup_fn: fixes,
         table: "fix_belongs_to_fix_batch",
@@ -135,6 +141,49 @@ impl FixBatch {
         }
     }
 
+    /// Prepares a finished [`FixBatch`] to run again, moving the current attempt of every
+    /// [`Fix`] that did not succeed into its attempt history. Returns those [`Fixes`](Fix), in the
+    /// order they originally ran in.
+    pub async fn reset_for_resume(&mut self, ctx: &DalContext) -> FixResult<Vec<Fix>> {
+        if self.finished_at.is_none() {
+            return Err(FixError::BatchNotFinished(self.id));
+        }
+
+        let mut fixes = self.fixes(ctx).await?;
+        fixes.retain(|fix| fix.completion_status() != Some(&FixCompletionStatus::Success));
+        if fixes.is_empty() {
+            return Err(FixError::NoUnsuccessfulFixesInBatch(self.id));
+        }
+        if let Some(graph) = self.graph()? {
+            let order: Vec<_> = graph.nodes().iter().map(|node| node.fix_id).collect();
+            fixes.sort_by_key(|fix| order.iter().position(|id| id == fix.id()));
+        }
+
+        for fix in &mut fixes {
+            fix.reset_for_retry(ctx).await?;
+        }
+
+        self.set_started_at(ctx, None::<String>).await?;
+        self.set_finished_at(ctx, None::<String>).await?;
+        self.set_completion_status(ctx, None::<FixCompletionStatus>)
+            .await?;
+
+        Ok(fixes)
+    }
+
+    /// Returns the [`FixGraph`] the [`FixBatch`] was scheduled with, if it has started.
+    pub fn graph(&self) -> FixResult<Option<FixGraph>> {
+        Ok(match &self.dependency_graph {
+            Some(graph) => Some(serde_json::from_value(graph.clone())?),
+            None => None,
+        })
+    }
+
+    pub async fn set_graph(&mut self, ctx: &DalContext, graph: &FixGraph) -> FixResult<()> {
+        self.set_dependency_graph(ctx, Some(serde_json::to_value(graph)?))
+            .await
+    }
+
     pub fn author(&self) -> String {
         self.author.clone()
     }
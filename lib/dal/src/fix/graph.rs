@@ -0,0 +1,358 @@
+//! This module contains [`FixGraph`], the dependency graph between the [`Fixes`](crate::Fix) of a
+//! [`FixBatch`](crate::FixBatch), and [`FixSchedule`], which walks that graph as the
+//! [`Fixes`](crate::Fix) finish.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use telemetry::prelude::*;
+
+use crate::edge::EdgeKind;
+use crate::fix::{FixError, FixResult};
+use crate::job::definition::FixItem;
+use crate::{ComponentId, DalContext, Edge, FixId, Node, NodeId};
+
+/// A directed acyclic graph of the [`Fixes`](crate::Fix) in a [`FixBatch`](crate::FixBatch).
+///
+/// A [`Fix`](crate::Fix) depends on every [`Fix`](crate::Fix) that comes before it in the batch and
+/// whose [`Component`](crate::Component) is the same as, upstream of or downstream of its own
+/// [`Component`](crate::Component) (following configuration edges). Everything else is
+/// independent and can run concurrently.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FixGraph {
+    /// The nodes of the graph, in the order the [`Fixes`](crate::Fix) were given to
+    /// [`Self::new()`]. Dependencies always point to an earlier node.
+    nodes: Vec<FixGraphNode>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FixGraphNode {
+    pub fix_id: FixId,
+    pub component_id: ComponentId,
+    pub depends_on: Vec<FixId>,
+}
+
+impl FixGraph {
+    /// Builds the graph for the given fixes. The fixes are expected to already be in a valid
+    /// sequential order (e.g. the one computed by
+    /// [`Action::sort_of_change_set()`](crate::Action::sort_of_change_set)): the graph only drops
+    /// the orderings that do not matter.
+    #[instrument(skip_all)]
+    pub async fn new(ctx: &DalContext, fixes: &[FixItem]) -> FixResult<Self> {
+        let ctx_with_deleted = &ctx.clone_with_delete_visibility();
+
+        let mut parents: HashMap<NodeId, HashSet<NodeId>> = HashMap::new();
+        let mut component_by_node: HashMap<NodeId, ComponentId> = HashMap::new();
+        for edge in Edge::list_for_kind(ctx_with_deleted, EdgeKind::Configuration).await? {
+            parents
+                .entry(edge.head_node_id())
+                .or_default()
+                .insert(edge.tail_node_id());
+            component_by_node.insert(edge.head_node_id(), (*edge.head_object_id()).into());
+            component_by_node.insert(edge.tail_node_id(), (*edge.tail_object_id()).into());
+        }
+
+        // Walking the nodes in topological order guarantees that the ancestors of every parent
+        // are known by the time we get to its children.
+        let mut ancestors: HashMap<NodeId, HashSet<NodeId>> = HashMap::new();
+        let sorted_node_ids =
+            Node::list_topologically_sorted_configuration_nodes_with_stable_ordering(ctx, false)
+                .await?;
+        for node_id in sorted_node_ids {
+            let mut node_ancestors = HashSet::new();
+            for parent_id in parents.get(&node_id).into_iter().flatten() {
+                node_ancestors.insert(*parent_id);
+                if let Some(parent_ancestors) = ancestors.get(parent_id) {
+                    node_ancestors.extend(parent_ancestors.iter().copied());
+                }
+            }
+            ancestors.insert(node_id, node_ancestors);
+        }
+
+        let mut component_ancestors: HashMap<ComponentId, HashSet<ComponentId>> = HashMap::new();
+        for (node_id, node_ancestors) in ancestors {
+            let component_id = match component_by_node.get(&node_id) {
+                Some(component_id) => *component_id,
+                // Nodes without edges have no ancestors to track.
+                None => continue,
+            };
+            component_ancestors.entry(component_id).or_default().extend(
+                node_ancestors
+                    .iter()
+                    .filter_map(|ancestor_id| component_by_node.get(ancestor_id).copied()),
+            );
+        }
+
+        let related = |a: ComponentId, b: ComponentId| {
+            a == b
+                || component_ancestors
+                    .get(&a)
+                    .map_or(false, |ancestors| ancestors.contains(&b))
+                || component_ancestors
+                    .get(&b)
+                    .map_or(false, |ancestors| ancestors.contains(&a))
+        };
+
+        let mut nodes: Vec<FixGraphNode> = Vec::with_capacity(fixes.len());
+        for fix in fixes {
+            let depends_on = nodes
+                .iter()
+                .filter(|earlier| related(earlier.component_id, fix.component_id))
+                .map(|earlier| earlier.fix_id)
+                .collect();
+            nodes.push(FixGraphNode {
+                fix_id: fix.id,
+                component_id: fix.component_id,
+                depends_on,
+            });
+        }
+
+        Ok(Self { nodes })
+    }
+
+    pub fn nodes(&self) -> &[FixGraphNode] {
+        &self.nodes
+    }
+
+    /// Returns the [`Fixes`](crate::Fix) that must finish successfully before the given
+    /// [`Fix`](crate::Fix) can run.
+    pub fn dependencies(&self, fix_id: FixId) -> FixResult<&[FixId]> {
+        self.nodes
+            .iter()
+            .find(|node| node.fix_id == fix_id)
+            .map(|node| node.depends_on.as_slice())
+            .ok_or(FixError::MissingFixInGraph(fix_id))
+    }
+}
+
+/// What to do next with a [`Fix`](crate::Fix) of a [`FixSchedule`].
+#[remain::sorted]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FixScheduleStep<'a> {
+    /// Every dependency of the [`Fix`](crate::Fix) succeeded, so it can run.
+    Run(&'a FixItem),
+    /// A dependency of the [`Fix`](crate::Fix) (the blocker) did not succeed, so it will not run.
+    Skip { fix: &'a FixItem, blocker: FixId },
+}
+
+/// Decides which [`Fixes`](crate::Fix) of a [`FixGraph`] run next, as the ones already running
+/// finish. A [`Fix`](crate::Fix) that does not succeed blocks everything downstream of it, which
+/// gets skipped.
+#[derive(Debug)]
+pub struct FixSchedule<'a> {
+    graph: &'a FixGraph,
+    pending: Vec<&'a FixItem>,
+    succeeded: HashSet<FixId>,
+    blocked: HashSet<FixId>,
+}
+
+impl<'a> FixSchedule<'a> {
+    /// Creates the schedule for the given fixes, which must all be in the graph. Fixes of the graph
+    /// that are not given are considered to have succeeded already, like the ones that are not
+    /// run again when a [`FixBatch`](crate::FixBatch) is resumed.
+    pub fn new(graph: &'a FixGraph, fixes: &'a [FixItem]) -> Self {
+        let scheduled: HashSet<FixId> = fixes.iter().map(|fix| fix.id).collect();
+        let succeeded = graph
+            .nodes
+            .iter()
+            .map(|node| node.fix_id)
+            .filter(|fix_id| !scheduled.contains(fix_id))
+            .collect();
+
+        Self {
+            graph,
+            pending: fixes.iter().collect(),
+            succeeded,
+            blocked: HashSet::new(),
+        }
+    }
+
+    /// Returns the fixes to skip and the fixes to run, given how many are already running. No more
+    /// than `max_concurrency` fixes are ever running at the same time.
+    pub fn next(
+        &mut self,
+        running: usize,
+        max_concurrency: usize,
+    ) -> FixResult<Vec<FixScheduleStep<'a>>> {
+        let mut steps = Vec::new();
+        let mut running = running;
+
+        // Dependencies always come earlier in the batch, so a single pass is enough to skip
+        // everything downstream of a fix that did not succeed.
+        let mut index = 0;
+        while index < self.pending.len() && running < max_concurrency {
+            let fix = self.pending[index];
+            let dependencies = self.graph.dependencies(fix.id)?;
+
+            if let Some(blocker) = dependencies.iter().find(|id| self.blocked.contains(id)) {
+                self.pending.remove(index);
+                self.blocked.insert(fix.id);
+                steps.push(FixScheduleStep::Skip {
+                    fix,
+                    blocker: *blocker,
+                });
+            } else if dependencies.iter().all(|id| self.succeeded.contains(id)) {
+                self.pending.remove(index);
+                running += 1;
+                steps.push(FixScheduleStep::Run(fix));
+            } else {
+                index += 1;
+            }
+        }
+
+        Ok(steps)
+    }
+
+    /// Records that a running fix finished, unblocking (or blocking) the fixes that depend on it.
+    pub fn finish(&mut self, fix_id: FixId, succeeded: bool) {
+        if succeeded {
+            self.succeeded.insert(fix_id);
+        } else {
+            self.blocked.insert(fix_id);
+        }
+    }
+
+    /// Puts a fix that ran, but will be retried, back in the schedule. The fixes that depend on it
+    /// keep waiting for it.
+    pub fn retry(&mut self, fix: &'a FixItem) {
+        self.pending.push(fix);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ActionPrototypeId;
+
+    fn fix_item() -> FixItem {
+        FixItem {
+            id: FixId::generate(),
+            action_prototype_id: ActionPrototypeId::NONE,
+            component_id: ComponentId::generate(),
+        }
+    }
+
+    /// Builds a graph where each fix depends on the fixes at the given indexes.
+    fn graph(fixes: &[FixItem], depends_on: &[&[usize]]) -> FixGraph {
+        FixGraph {
+            nodes: fixes
+                .iter()
+                .zip(depends_on)
+                .map(|(fix, depends_on)| FixGraphNode {
+                    fix_id: fix.id,
+                    component_id: fix.component_id,
+                    depends_on: depends_on.iter().map(|index| fixes[*index].id).collect(),
+                })
+                .collect(),
+        }
+    }
+
+    fn run_ids(steps: &[FixScheduleStep<'_>]) -> Vec<FixId> {
+        steps
+            .iter()
+            .filter_map(|step| match step {
+                FixScheduleStep::Run(fix) => Some(fix.id),
+                FixScheduleStep::Skip { .. } => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn independent_fixes_run_concurrently_up_to_the_limit() {
+        let fixes = vec![fix_item(), fix_item(), fix_item()];
+        let graph = graph(&fixes, &[&[], &[], &[]]);
+        let mut schedule = FixSchedule::new(&graph, &fixes);
+
+        let steps = schedule.next(0, 2).expect("could not schedule");
+        assert_eq!(run_ids(&steps), vec![fixes[0].id, fixes[1].id]);
+        assert!(schedule.next(2, 2).expect("could not schedule").is_empty());
+
+        schedule.finish(fixes[1].id, true);
+        let steps = schedule.next(1, 2).expect("could not schedule");
+        assert_eq!(run_ids(&steps), vec![fixes[2].id]);
+
+        schedule.finish(fixes[0].id, true);
+        schedule.finish(fixes[2].id, true);
+        assert!(schedule.next(0, 2).expect("could not schedule").is_empty());
+    }
+
+    #[test]
+    fn dependents_wait_for_their_dependencies() {
+        let fixes = vec![fix_item(), fix_item()];
+        let graph = graph(&fixes, &[&[], &[0]]);
+        let mut schedule = FixSchedule::new(&graph, &fixes);
+
+        let steps = schedule.next(0, 4).expect("could not schedule");
+        assert_eq!(run_ids(&steps), vec![fixes[0].id]);
+        assert!(schedule.next(1, 4).expect("could not schedule").is_empty());
+
+        schedule.finish(fixes[0].id, true);
+        let steps = schedule.next(0, 4).expect("could not schedule");
+        assert_eq!(run_ids(&steps), vec![fixes[1].id]);
+    }
+
+    #[test]
+    fn dependents_of_a_failed_fix_are_skipped() {
+        // 0 <- 1 <- 2, with 3 unrelated.
+        let fixes = vec![fix_item(), fix_item(), fix_item(), fix_item()];
+        let graph = graph(&fixes, &[&[], &[0], &[1], &[]]);
+        let mut schedule = FixSchedule::new(&graph, &fixes);
+
+        let steps = schedule.next(0, 4).expect("could not schedule");
+        assert_eq!(run_ids(&steps), vec![fixes[0].id, fixes[3].id]);
+
+        schedule.finish(fixes[0].id, false);
+        let steps = schedule.next(1, 4).expect("could not schedule");
+        assert_eq!(
+            steps,
+            vec![
+                FixScheduleStep::Skip {
+                    fix: &fixes[1],
+                    blocker: fixes[0].id,
+                },
+                FixScheduleStep::Skip {
+                    fix: &fixes[2],
+                    blocker: fixes[1].id,
+                },
+            ]
+        );
+
+        // The unrelated fix is not affected.
+        schedule.finish(fixes[3].id, true);
+        assert!(schedule.next(0, 4).expect("could not schedule").is_empty());
+    }
+
+    #[test]
+    fn fixes_outside_of_the_schedule_already_succeeded() {
+        // Resuming 0 <- 1 <- 2 after 0 succeeded and 1 failed.
+        let fixes = vec![fix_item(), fix_item(), fix_item()];
+        let graph = graph(&fixes, &[&[], &[0], &[1]]);
+        let mut schedule = FixSchedule::new(&graph, &fixes[1..]);
+
+        let steps = schedule.next(0, 4).expect("could not schedule");
+        assert_eq!(run_ids(&steps), vec![fixes[1].id]);
+
+        schedule.finish(fixes[1].id, true);
+        let steps = schedule.next(0, 4).expect("could not schedule");
+        assert_eq!(run_ids(&steps), vec![fixes[2].id]);
+    }
+
+    #[test]
+    fn retried_fixes_run_again_before_their_dependents() {
+        let fixes = vec![fix_item(), fix_item()];
+        let graph = graph(&fixes, &[&[], &[0]]);
+        let mut schedule = FixSchedule::new(&graph, &fixes);
+
+        let steps = schedule.next(0, 4).expect("could not schedule");
+        assert_eq!(run_ids(&steps), vec![fixes[0].id]);
+
+        schedule.retry(&fixes[0]);
+        let steps = schedule.next(0, 4).expect("could not schedule");
+        assert_eq!(run_ids(&steps), vec![fixes[0].id]);
+
+        schedule.finish(fixes[0].id, true);
+        let steps = schedule.next(0, 4).expect("could not schedule");
+        assert_eq!(run_ids(&steps), vec![fixes[1].id]);
+    }
+}
@@ -0,0 +1,153 @@
+//! This module contains [`FixRun`], the permanent record of a single attempt at running a
+//! [`Fix`].
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+use veritech_client::OutputStream;
+
+use crate::fix::{FixCompletionStatus, FixResult};
+use crate::standard_model::{object_option_from_row_option, objects_from_rows};
+use crate::{
+    pk, ActionKind, ActionPrototypeId, ActionRunDetails, ComponentId, DalContext, Fix, FixBatchId,
+    FixId, HistoryActor, Tenancy,
+};
+
+const GET_BY_PK: &str = include_str!("../queries/fix_run/get_by_pk.sql");
+const LIST: &str = include_str!("../queries/fix_run/list.sql");
+
+/// How many [`FixRuns`](FixRun) [`FixRun::list()`] returns when no limit is given.
+pub const DEFAULT_LIST_LIMIT: i64 = 100;
+/// The most [`FixRuns`](FixRun) [`FixRun::list()`] returns, whatever the limit given.
+pub const MAX_LIST_LIMIT: i64 = 1000;
+
+pk!(FixRunPk);
+
+/// Everything that went into and came out of an attempt at running a [`Fix`]: the arguments the
+/// action function was given, the resource before and after, every line of output it streamed
+/// and who ran it.
+///
+/// Unlike the [`Fix`] itself, which is reset when it is retried, a `FixRun` is never modified
+/// after it has been recorded.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct FixRun {
+    pub pk: FixRunPk,
+    pub fix_id: FixId,
+    pub fix_batch_id: Option<FixBatchId>,
+    pub component_id: ComponentId,
+    pub action_prototype_id: ActionPrototypeId,
+    pub action_kind: ActionKind,
+    pub attempt: i32,
+    pub actor: HistoryActor,
+    /// [`None`] if the run failed before the action function was executed.
+    pub arguments: Option<serde_json::Value>,
+    pub resource_before: Option<serde_json::Value>,
+    pub resource_after: Option<serde_json::Value>,
+    pub output_stream: Vec<OutputStream>,
+    pub completion_status: Option<FixCompletionStatus>,
+    pub completion_message: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub tenancy: Tenancy,
+}
+
+/// Filters for [`FixRun::list()`]. Every filter is optional.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct FixRunQuery {
+    pub component_id: Option<ComponentId>,
+    pub fix_batch_id: Option<FixBatchId>,
+    /// Only runs that started at or after this time.
+    pub started_after: Option<DateTime<Utc>>,
+    /// Only runs that started before this time.
+    pub started_before: Option<DateTime<Utc>>,
+    /// Defaults to [`DEFAULT_LIST_LIMIT`] and is clamped between 1 and [`MAX_LIST_LIMIT`].
+    pub limit: Option<i64>,
+}
+
+impl FixRun {
+    /// Records the attempt the given [`Fix`] just finished. Must be called after the [`Fix`] has
+    /// been [`stamped finished`](Fix::stamp_finished()). The resources are the ones of the
+    /// [`Component`](crate::Component) before and after the run.
+    #[instrument(skip_all, fields(fix_id = %fix.id()))]
+    pub async fn record(
+        ctx: &DalContext,
+        fix: &Fix,
+        started_at: DateTime<Utc>,
+        resource_before: Option<serde_json::Value>,
+        resource_after: Option<serde_json::Value>,
+        details: Option<&ActionRunDetails>,
+    ) -> FixResult<Self> {
+        let fix_batch_id = fix.fix_batch(ctx).await?.map(|batch| *batch.id());
+        let arguments = details.map(|details| &details.arguments);
+        let output_stream = details
+            .map(|details| serde_json::to_value(&details.output_stream))
+            .transpose()?
+            .unwrap_or_else(|| serde_json::json!([]));
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM fix_run_create_v1($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)",
+                &[
+                    ctx.tenancy(),
+                    fix.id(),
+                    &fix_batch_id,
+                    fix.component_id(),
+                    fix.action_prototype_id(),
+                    &fix.action_kind().as_ref(),
+                    &(fix.attempt() as i32),
+                    &serde_json::to_value(ctx.history_actor())?,
+                    &arguments,
+                    &resource_before,
+                    &resource_after,
+                    &output_stream,
+                    &fix.completion_status().map(|status| status.as_ref()),
+                    &fix.completion_message(),
+                    &started_at,
+                ],
+            )
+            .await?;
+        let json: serde_json::Value = row.try_get("object")?;
+        Ok(serde_json::from_value(json)?)
+    }
+
+    #[instrument(skip(ctx))]
+    pub async fn get_by_pk(ctx: &DalContext, pk: FixRunPk) -> FixResult<Option<Self>> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(GET_BY_PK, &[ctx.tenancy(), &pk])
+            .await?;
+        Ok(object_option_from_row_option(row)?)
+    }
+
+    /// Lists the [`FixRuns`](FixRun) matching the query, most recent first.
+    #[instrument(skip(ctx))]
+    pub async fn list(ctx: &DalContext, query: &FixRunQuery) -> FixResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                LIST,
+                &[
+                    ctx.tenancy(),
+                    &query.component_id,
+                    &query.fix_batch_id,
+                    &query.started_after,
+                    &query.started_before,
+                    &query
+                        .limit
+                        .unwrap_or(DEFAULT_LIST_LIMIT)
+                        .clamp(1, MAX_LIST_LIMIT),
+                ],
+            )
+            .await?;
+        Ok(objects_from_rows(rows)?)
+    }
+}
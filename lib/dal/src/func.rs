@@ -100,6 +100,9 @@ pub struct Func {
     link: Option<String>,
     hidden: bool,
     builtin: bool,
+    /// Whether the func may return different values for the same arguments. The executions of
+    /// pure funcs are memoized by [`FuncBinding::execute()`].
+    impure: bool,
     backend_kind: FuncBackendKind,
     backend_response_type: FuncBackendResponseType,
     handler: Option<String>,
@@ -176,6 +179,7 @@ impl Func {
         new_func.set_link(ctx, self.link()).await?;
         new_func.set_hidden(ctx, self.hidden).await?;
         new_func.set_builtin(ctx, self.builtin).await?;
+        new_func.set_impure(ctx, self.impure).await?;
         new_func.set_handler(ctx, self.handler()).await?;
         new_func.set_code_base64(ctx, self.code_base64()).await?;
 
@@ -261,6 +265,7 @@ impl Func {
     standard_model_accessor!(link, Option<String>, FuncResult);
     standard_model_accessor!(hidden, bool, FuncResult);
     standard_model_accessor!(builtin, bool, FuncResult);
+    standard_model_accessor!(impure, bool, FuncResult);
     standard_model_accessor!(backend_kind, Enum(FuncBackendKind), FuncResult);
     standard_model_accessor!(
         backend_response_type,
@@ -9,6 +9,7 @@ use veritech_client::{
     ResolverFunctionResponseType,
 };
 
+use crate::func::execution::FuncExecutionPk;
 use crate::{label_list::ToLabelList, DalContext, Func, FuncId, PropKind, StandardModel};
 
 pub mod array;
@@ -178,15 +179,22 @@ impl ToLabelList for FuncBackendKind {}
 pub struct FuncDispatchContext {
     pub veritech: VeritechClient,
     pub output_tx: mpsc::Sender<OutputStream>,
+    /// Identifies the execution to veritech, which is how it can be
+    /// [`cancelled`](crate::func::execution::FuncExecution::cancel()) while in flight.
+    pub execution_id: String,
 }
 
 impl FuncDispatchContext {
-    pub fn new(ctx: &DalContext) -> (Self, mpsc::Receiver<OutputStream>) {
+    pub fn new(
+        ctx: &DalContext,
+        execution_pk: FuncExecutionPk,
+    ) -> (Self, mpsc::Receiver<OutputStream>) {
         let (output_tx, rx) = mpsc::channel(64);
         (
             Self {
                 veritech: ctx.veritech().clone(),
                 output_tx,
+                execution_id: execution_pk.to_string(),
             },
             rx,
         )
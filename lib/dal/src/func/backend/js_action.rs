@@ -31,12 +31,11 @@ impl FuncDispatch for FuncBackendJsAction {
         args: Self::Args,
     ) -> Box<Self> {
         let request = ActionRunRequest {
-            // Once we start tracking the state of these executions, then this id will be useful,
-            // but for now it's passed along and back, and is opaue
-            execution_id: "ayrtonsennajscommand".to_string(),
+            execution_id: context.execution_id.clone(),
             handler: handler.into(),
             code_base64: code_base64.into(),
             args: serde_json::to_value(args).unwrap(),
+            timeout_secs: None,
         };
 
         Box::new(Self { context, request })
@@ -31,13 +31,12 @@ impl FuncDispatch for FuncBackendJsAttribute {
         args: Self::Args,
     ) -> Box<Self> {
         let request = ResolverFunctionRequest {
-            // Once we start tracking the state of these executions, then this id will be useful,
-            // but for now it's passed along and back, and is opaue
-            execution_id: "tomcruise".to_string(),
+            execution_id: context.execution_id.clone(),
             handler: handler.into(),
             component: args.component,
             response_type: args.response_type,
             code_base64: code_base64.into(),
+            timeout_secs: None,
         };
 
         Box::new(Self { context, request })
@@ -43,12 +43,11 @@ impl FuncDispatch for FuncBackendJsReconciliation {
         args: Self::Args,
     ) -> Box<Self> {
         let request = ReconciliationRequest {
-            // Once we start tracking the state of these executions, then this id will be useful,
-            // but for now it's passed along and back, and is opaue
-            execution_id: "freeronaldinhogauchojsreconciliation".to_string(),
+            execution_id: context.execution_id.clone(),
             handler: handler.into(),
             code_base64: code_base64.into(),
             args: serde_json::to_value(args).unwrap(),
+            timeout_secs: None,
         };
 
         Box::new(Self { context, request })
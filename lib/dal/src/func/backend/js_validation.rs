@@ -37,10 +37,11 @@ impl FuncDispatch for FuncBackendJsValidation {
         args: Self::Args,
     ) -> Box<Self> {
         let request = ValidationRequest {
-            execution_id: "johnwick".to_string(),
+            execution_id: context.execution_id.clone(),
             handler: handler.into(),
             code_base64: code_base64.to_owned(),
             value: args.value,
+            timeout_secs: None,
         };
 
         Box::new(Self { context, request })
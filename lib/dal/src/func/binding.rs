@@ -2,13 +2,13 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use si_data_nats::NatsError;
 use si_data_pg::PgError;
+use sodiumoxide::crypto::hash::sha256;
 use telemetry::prelude::*;
 use thiserror::Error;
 use tokio::sync::mpsc;
-use veritech_client::{OutputStream, ResolverFunctionComponent};
+use veritech_client::{OutputStream, ResolverFunctionComponent, SECRET_REFERENCE_MARKER};
 
 use crate::func::execution::FuncExecutionPk;
-use crate::FuncError;
 use crate::{
     func::backend::{
         array::FuncBackendArray,
@@ -35,6 +35,7 @@ use crate::{
     Timestamp, Visibility,
 };
 use crate::{DalContext, Tenancy};
+use crate::{FuncBackendResponseType, FuncError};
 
 use super::{
     binding_return_value::{FuncBindingReturnValue, FuncBindingReturnValueError},
@@ -85,6 +86,12 @@ pub enum FuncBindingError {
 
 pub type FuncBindingResult<T> = Result<T, FuncBindingError>;
 
+const ARGS_REFERENCE_SECRETS: &str =
+    include_str!("../queries/func_binding/args_reference_secrets.sql");
+
+/// The key of the objects [`ComponentView`](crate::ComponentView) wraps encrypted secrets in.
+const ENCRYPTED_DATA_MARKER: &str = "cycloneEncryptedDataMarker";
+
 pk!(FuncBindingPk);
 pk!(FuncBindingId);
 
@@ -206,10 +213,43 @@ impl FuncBinding {
         result: FuncBindingResult,
     );
 
-    // For a given [`FuncBinding`](Self), execute using veritech.
+    /// For a given [`FuncBinding`](Self), execute using veritech.
+    ///
+    /// Attribute functions are memoized (see [`Self::is_memoizable()`]): if a previous execution
+    /// in the workspace had the same input (see [`Self::input_sha256()`]), its value is reused
+    /// and veritech is skipped.
     pub async fn execute(&self, ctx: &DalContext) -> FuncBindingResult<FuncBindingReturnValue> {
-        let (func, execution, context, mut rx) = self.prepare_execution(ctx).await?;
+        let func: Func = self
+            .func(ctx)
+            .await?
+            .ok_or(FuncBindingError::FuncNotFound(self.pk))?;
+
+        let input_sha256 = if self.is_memoizable(ctx, &func).await? {
+            Some(self.input_sha256(&func)?)
+        } else {
+            None
+        };
+        if let Some(input_sha256) = &input_sha256 {
+            if let Some(execution) = FuncExecution::find_memoized(ctx, input_sha256).await? {
+                debug!(func_execution_pk = %execution.pk(), "reusing memoized func execution");
+                // A new return value, as the attribute values own theirs and may update them
+                return Ok(FuncBindingReturnValue::new(
+                    ctx,
+                    execution.unprocessed_value().cloned(),
+                    execution.value().cloned(),
+                    *func.id(),
+                    self.id,
+                    execution.pk(),
+                )
+                .await?);
+            }
+        }
+
+        let (func, mut execution, context, mut rx) = self.prepare_execution(ctx).await?;
         let value = self.execute_critical_section(func.clone(), context).await?;
+        if let Some(input_sha256) = input_sha256 {
+            execution.set_input_sha256(ctx, input_sha256).await?;
+        }
 
         let mut output = Vec::new();
         while let Some(output_stream) = rx.recv().await {
@@ -220,6 +260,63 @@ impl FuncBinding {
             .await
     }
 
+    /// Whether the value of an execution may be reused for the same input. Only attribute
+    /// functions are memoized, and not when:
+    ///
+    /// - the [`Func`](crate::Func) is [`impure`](crate::Func::impure())
+    /// - the [`Func`](crate::Func) is a qualification or code generation leaf function, whose
+    ///   result depends on more than its input, such as the resources and credentials it checks
+    /// - the arguments carry secrets, encrypted or referenced by id, as the secrets can be updated
+    ///   without the arguments changing
+    pub async fn is_memoizable(&self, ctx: &DalContext, func: &Func) -> FuncBindingResult<bool> {
+        if *self.backend_kind() != FuncBackendKind::JsAttribute
+            || func.impure()
+            || matches!(
+                func.backend_response_type(),
+                FuncBackendResponseType::Qualification | FuncBackendResponseType::CodeGeneration
+            )
+            || carries_secrets(&self.args)
+        {
+            return Ok(false);
+        }
+
+        let mut ids = Vec::new();
+        collect_ulids(&self.args, &mut ids);
+        if ids.is_empty() {
+            return Ok(true);
+        }
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(ARGS_REFERENCE_SECRETS, &[&ids])
+            .await?;
+        let references_secrets: bool = row.try_get("references_secrets")?;
+
+        Ok(!references_secrets)
+    }
+
+    /// Hashes everything the value of an execution of the [`Func`](crate::Func) depends on: its
+    /// code, handler, backend and response type, and the arguments of the binding. The keys of the
+    /// arguments are sorted first, so that their order does not change the hash.
+    pub fn input_sha256(&self, func: &Func) -> FuncBindingResult<String> {
+        let args = serde_json::to_vec(&sorted_keys(&self.args))?;
+
+        let mut state = sha256::State::new();
+        for part in [
+            func.code_sha256().as_str(),
+            func.handler().unwrap_or_default(),
+            func.backend_kind().as_ref(),
+            func.backend_response_type().as_ref(),
+        ] {
+            state.update(part.as_bytes());
+            state.update(b"\0");
+        }
+        state.update(&args);
+
+        Ok(hex::encode(state.finalize()))
+    }
+
     /// Perform function execution to veritech for a given [`Func`](crate::Func) and
     /// [`FuncDispatchContext`](crate::func::backend::FuncDispatchContext).
     pub async fn execute_critical_section(
@@ -371,11 +468,54 @@ impl FuncBinding {
             .set_state(ctx, super::execution::FuncExecutionState::Run)
             .await?;
 
-        let (context, rx) = FuncDispatchContext::new(ctx);
+        let (context, rx) = FuncDispatchContext::new(ctx, execution.pk());
         Ok((func, execution, context, rx))
     }
 }
 
+/// Returns a copy of the value whose objects have their keys sorted.
+fn sorted_keys(value: &JsonValue) -> JsonValue {
+    match value {
+        JsonValue::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            JsonValue::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key.clone(), sorted_keys(value)))
+                    .collect(),
+            )
+        }
+        JsonValue::Array(values) => JsonValue::Array(values.iter().map(sorted_keys).collect()),
+        value => value.clone(),
+    }
+}
+
+/// Whether the value holds an encrypted secret or a secret reference.
+fn carries_secrets(value: &JsonValue) -> bool {
+    match value {
+        JsonValue::Object(map) => {
+            map.contains_key(ENCRYPTED_DATA_MARKER)
+                || map.contains_key(SECRET_REFERENCE_MARKER)
+                || map.values().any(carries_secrets)
+        }
+        JsonValue::Array(values) => values.iter().any(carries_secrets),
+        _ => false,
+    }
+}
+
+/// Collects the strings of the value that are ulids, and so may be the ids of secrets.
+fn collect_ulids(value: &JsonValue, ids: &mut Vec<String>) {
+    match value {
+        JsonValue::Object(map) => map.values().for_each(|value| collect_ulids(value, ids)),
+        JsonValue::Array(values) => values.iter().for_each(|value| collect_ulids(value, ids)),
+        JsonValue::String(string) if ulid::Ulid::from_string(string).is_ok() => {
+            ids.push(string.clone())
+        }
+        _ => {}
+    }
+}
+
 #[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct LogLinePayload {
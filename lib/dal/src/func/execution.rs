@@ -7,7 +7,7 @@ use thiserror::Error;
 use tokio::sync::mpsc::Receiver;
 use veritech_client::{FunctionResultFailure, OutputStream};
 
-use crate::standard_model::object_from_row;
+use crate::standard_model::{object_from_row, object_option_from_row_option};
 use crate::{
     pk, DalContext, Func, FuncBackendKind, FuncBackendResponseType, HistoryEventError,
     StandardModel, StandardModelError, Timestamp,
@@ -36,10 +36,14 @@ pub enum FuncExecutionError {
     StandardModelError(#[from] StandardModelError),
     #[error("transactions error: {0}")]
     Transactions(#[from] TransactionsError),
+    #[error("veritech client error: {0}")]
+    VeritechClient(#[from] veritech_client::ClientError),
 }
 
 pub type FuncExecutionResult<T> = Result<T, FuncExecutionError>;
 
+const FIND_MEMOIZED: &str = include_str!("../queries/func_execution/find_memoized.sql");
+
 pk!(FuncExecutionPk);
 
 // Are these the right states? -- Adam
@@ -79,6 +83,9 @@ pub struct FuncExecution {
     value: Option<serde_json::Value>,
     output_stream: Option<Vec<OutputStream>>,
     function_failure: Option<FunctionResultFailure>,
+    /// The hash of everything the value of the execution depends on, set once a pure
+    /// [`Func`](crate::Func) executed successfully. See [`FuncBinding::execute()`].
+    input_sha256: Option<String>,
     #[serde(flatten)]
     tenancy: Tenancy,
     #[serde(flatten)]
@@ -127,6 +134,10 @@ impl FuncExecution {
         self.state
     }
 
+    pub fn tenancy(&self) -> &Tenancy {
+        &self.tenancy
+    }
+
     pub async fn set_state(
         &mut self,
         ctx: &DalContext,
@@ -203,6 +214,49 @@ impl FuncExecution {
         Ok(())
     }
 
+    /// Records the input hash of a successful execution of a pure [`Func`](crate::Func), so that
+    /// later executions with the same input can reuse its value.
+    pub async fn set_input_sha256(
+        &mut self,
+        ctx: &DalContext,
+        input_sha256: impl AsRef<str>,
+    ) -> FuncExecutionResult<()> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM func_execution_set_input_sha256_v1($1, $2)",
+                &[&self.pk, &input_sha256.as_ref()],
+            )
+            .await?;
+        let json: serde_json::Value = row.try_get("object")?;
+        ctx.txns()
+            .await?
+            .nats()
+            .publish("funcExecution", &json)
+            .await?;
+        let mut object: FuncExecution = serde_json::from_value(json)?;
+        std::mem::swap(self, &mut object);
+        Ok(())
+    }
+
+    /// Finds the most recent successful execution, in the workspace of the context's tenancy,
+    /// whose input hash is the given one.
+    #[instrument(skip(ctx))]
+    pub async fn find_memoized(
+        ctx: &DalContext,
+        input_sha256: &str,
+    ) -> FuncExecutionResult<Option<Self>> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(FIND_MEMOIZED, &[ctx.tenancy(), &input_sha256])
+            .await?;
+        Ok(object_option_from_row_option(row)?)
+    }
+
     /// Take the return value of a function binding, and store its results.
     pub async fn process_return_value(
         &mut self,
@@ -239,6 +293,20 @@ impl FuncExecution {
         self.pk
     }
 
+    /// Asks veritech to cancel the execution, if the function is still running. The function is
+    /// then killed and its result is a failure. Returns whether the execution was still running.
+    #[instrument(skip_all, fields(func_execution_pk = %self.pk))]
+    pub async fn cancel(&self, ctx: &DalContext) -> FuncExecutionResult<bool> {
+        if matches!(
+            self.state,
+            FuncExecutionState::Success | FuncExecutionState::Failure
+        ) {
+            return Ok(false);
+        }
+        ctx.veritech().cancel_execution(self.pk.to_string()).await?;
+        Ok(true)
+    }
+
     #[instrument(skip(ctx))]
     pub async fn get_by_pk(ctx: &DalContext, pk: &FuncExecutionPk) -> FuncExecutionResult<Self> {
         let row = ctx
@@ -290,4 +358,5 @@ impl FuncExecution {
     standard_model_accessor_ro!(backend_kind, FuncBackendKind);
     standard_model_accessor_ro!(backend_response_type, FuncBackendResponseType);
     standard_model_accessor_ro!(code_base64, Option<String>);
+    standard_model_accessor_ro!(input_sha256, Option<String>);
 }
@@ -1,4 +1,5 @@
 use crate::{Tenancy, TransactionsError};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use strum::Display as StrumDisplay;
 use thiserror::Error;
@@ -7,17 +8,32 @@ use si_data_nats::NatsError;
 use si_data_pg::PgError;
 use telemetry::prelude::*;
 
-use crate::{pk, DalContext, Timestamp, UserPk};
+use crate::standard_model::objects_from_rows;
+use crate::{pk, ChangeSetPk, DalContext, StandardModelError, Timestamp, UserPk};
+
+const CURSOR_EXISTS: &str = include_str!("queries/history_event/cursor_exists.sql");
+const LIST: &str = include_str!("queries/history_event/list.sql");
+
+/// How many [`HistoryEvents`](HistoryEvent) [`HistoryEvent::list()`] returns when no limit is
+/// given.
+pub const DEFAULT_LIST_LIMIT: i64 = 100;
+/// The most [`HistoryEvents`](HistoryEvent) [`HistoryEvent::list()`] returns, whatever the limit
+/// given.
+pub const MAX_LIST_LIMIT: i64 = 1000;
 
 #[remain::sorted]
 #[derive(Error, Debug)]
 pub enum HistoryEventError {
+    #[error("history event cursor not found: {0}")]
+    CursorNotFound(HistoryEventPk),
     #[error("nats txn error: {0}")]
     Nats(#[from] NatsError),
     #[error("pg error: {0}")]
     Pg(#[from] PgError),
     #[error("error serializing/deserializing json: {0}")]
     SerdeJson(#[from] serde_json::Error),
+    #[error("standard model error: {0}")]
+    StandardModel(#[from] StandardModelError),
     #[error("transactions error: {0}")]
     Transactions(#[from] TransactionsError),
 }
@@ -48,6 +64,36 @@ impl From<UserPk> for HistoryActor {
 
 pk!(HistoryEventPk);
 
+/// Filters for [`HistoryEvent::list()`]. Every filter is optional.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEventQuery {
+    pub actor: Option<HistoryActor>,
+    /// Only events whose label starts with this, e.g. `change_set.apply` or `secret`.
+    pub label_prefix: Option<String>,
+    /// Only events created at or after this time.
+    pub created_after: Option<DateTime<Utc>>,
+    /// Only events created before this time.
+    pub created_before: Option<DateTime<Utc>>,
+    /// Only events that happened in this [`ChangeSet`](crate::ChangeSet), or on head with
+    /// [`ChangeSetPk::NONE`].
+    pub change_set_pk: Option<ChangeSetPk>,
+    /// Only events older than this one, i.e. the [`next_cursor`](HistoryEventPage::next_cursor)
+    /// of the previous page.
+    pub cursor: Option<HistoryEventPk>,
+    /// Defaults to [`DEFAULT_LIST_LIMIT`] and is clamped between 1 and [`MAX_LIST_LIMIT`].
+    pub limit: Option<i64>,
+}
+
+/// A page of [`HistoryEvents`](HistoryEvent), most recent first.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEventPage {
+    pub events: Vec<HistoryEvent>,
+    /// The cursor to query the next page with, [`None`] if this is the last page.
+    pub next_cursor: Option<HistoryEventPk>,
+}
+
 /// HistoryEvents are the audit trail for things in SI. They track
 /// that a specific actor did something, and optionally store data
 /// associated with the activity for posterity.
@@ -58,6 +104,10 @@ pub struct HistoryEvent {
     pub actor: HistoryActor,
     pub message: String,
     pub data: serde_json::Value,
+    /// The [`ChangeSet`](crate::ChangeSet) the event happened in, [`ChangeSetPk::NONE`] for head.
+    /// [`None`] for events recorded before change sets were tracked.
+    #[serde(default)]
+    pub change_set_pk: Option<ChangeSetPk>,
     #[serde(flatten)]
     pub tenancy: Tenancy,
     #[serde(flatten)]
@@ -79,8 +129,15 @@ impl HistoryEvent {
         let row = txns
             .pg()
             .query_one(
-                "SELECT object FROM history_event_create_v1($1, $2, $3, $4, $5)",
-                &[&label.to_string(), &actor, &message, &data, ctx.tenancy()],
+                "SELECT object FROM history_event_create_v2($1, $2, $3, $4, $5, $6)",
+                &[
+                    &label.to_string(),
+                    &actor,
+                    &message,
+                    &data,
+                    ctx.tenancy(),
+                    &ctx.visibility().change_set_pk,
+                ],
             )
             .await?;
         let json: serde_json::Value = row.try_get("object")?;
@@ -89,4 +146,61 @@ impl HistoryEvent {
         let object: HistoryEvent = serde_json::from_value(json)?;
         Ok(object)
     }
+
+    /// Lists the events of the workspace matching the query, most recent first. The cursor of
+    /// the query, if any, must be an event of the workspace.
+    #[instrument(skip(ctx))]
+    pub async fn list(
+        ctx: &DalContext,
+        query: &HistoryEventQuery,
+    ) -> HistoryEventResult<HistoryEventPage> {
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_LIST_LIMIT)
+            .clamp(1, MAX_LIST_LIMIT);
+        let actor = query.actor.map(serde_json::to_value).transpose()?;
+        if let Some(cursor) = query.cursor {
+            let row = ctx
+                .txns()
+                .await?
+                .pg()
+                .query_one(CURSOR_EXISTS, &[ctx.tenancy(), &cursor])
+                .await?;
+            if !row.try_get::<_, bool>("exists")? {
+                return Err(HistoryEventError::CursorNotFound(cursor));
+            }
+        }
+        // Fetch one more event than asked for to know whether there is a next page
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                LIST,
+                &[
+                    ctx.tenancy(),
+                    &actor,
+                    &query.label_prefix,
+                    &query.created_after,
+                    &query.created_before,
+                    &query.change_set_pk,
+                    &query.cursor,
+                    &(limit + 1),
+                ],
+            )
+            .await?;
+        let mut events: Vec<Self> = objects_from_rows(rows)?;
+
+        let next_cursor = if events.len() as i64 > limit {
+            events.truncate(limit as usize);
+            events.last().map(|event| event.pk)
+        } else {
+            None
+        };
+
+        Ok(HistoryEventPage {
+            events,
+            next_cursor,
+        })
+    }
 }
@@ -11,8 +11,8 @@ use crate::{
     fix::FixError, func::binding_return_value::FuncBindingReturnValueError,
     job::producer::BlockingJobError, job::producer::JobProducerError, status::StatusUpdaterError,
     AccessBuilder, ActionPrototypeError, ActionPrototypeId, AttributeValueError, ComponentError,
-    ComponentId, DalContext, DalContextBuilder, FixBatchId, FixResolverError, StandardModelError,
-    TransactionsError, Visibility, WsEventError,
+    ComponentId, DalContext, DalContextBuilder, FixBatchId, FixResolverError, JobFailureError,
+    StandardModelError, TransactionsError, Visibility, WsEventError,
 };
 
 #[remain::sorted]
@@ -51,6 +51,8 @@ pub enum JobConsumerError {
     #[error(transparent)]
     Io(#[from] ::std::io::Error),
     #[error(transparent)]
+    JobFailure(#[from] JobFailureError),
+    #[error(transparent)]
     JobProducer(#[from] JobProducerError),
     #[error("missing fix execution batch for id: {0}")]
     MissingFixBatch(FixBatchId),
@@ -1,10 +1,15 @@
 use std::convert::TryFrom;
 
 use async_trait::async_trait;
+use futures::stream::{FuturesUnordered, StreamExt};
 use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
 
 use crate::{
-    fix::FixError,
+    fix::{
+        graph::{FixGraph, FixSchedule, FixScheduleStep},
+        FixError,
+    },
     job::{
         consumer::{
             JobConsumer, JobConsumerError, JobConsumerMetadata, JobConsumerResult, JobInfo,
@@ -16,7 +21,7 @@ use crate::{
     Visibility, WsEvent,
 };
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct FixItem {
     pub id: FixId,
     pub action_prototype_id: ActionPrototypeId,
@@ -27,7 +32,8 @@ pub struct FixItem {
 struct FixesJobArgs {
     fixes: Vec<FixItem>,
     batch_id: FixBatchId,
-    started: bool,
+    #[serde(default = "default_max_concurrency")]
+    max_concurrency: usize,
 }
 
 impl From<FixesJob> for FixesJobArgs {
@@ -35,44 +41,51 @@ impl From<FixesJob> for FixesJobArgs {
         Self {
             fixes: value.fixes,
             batch_id: value.batch_id,
-            started: value.started,
+            max_concurrency: value.max_concurrency,
         }
     }
 }
 
+fn default_max_concurrency() -> usize {
+    FixesJob::DEFAULT_MAX_CONCURRENCY
+}
+
+/// Runs every [`Fix`] of a [`FixBatch`]. [`Fixes`](Fix) that do not depend on each other (see
+/// [`FixGraph`]) run concurrently, up to a limit. A [`Fix`] that is retried does not count against
+/// that limit while it waits out its backoff. When a [`Fix`] does not succeed, the [`Fixes`](Fix)
+/// that depend on it are skipped, but the rest of the batch keeps running.
 #[derive(Clone, Debug, Serialize)]
 pub struct FixesJob {
     fixes: Vec<FixItem>,
-    started: bool,
     batch_id: FixBatchId,
+    max_concurrency: usize,
     access_builder: AccessBuilder,
     visibility: Visibility,
     job: Option<JobInfo>,
 }
 
 impl FixesJob {
-    pub fn new(ctx: &DalContext, fixes: Vec<FixItem>, batch_id: FixBatchId) -> Box<Self> {
-        Self::new_raw(ctx, fixes, batch_id, false)
-    }
+    /// The number of [`Fixes`](Fix) that run at the same time unless configured otherwise.
+    pub const DEFAULT_MAX_CONCURRENCY: usize = 4;
 
-    /// Used for creating another fix job in a "fixes" sequence.
-    fn new_iteration(ctx: &DalContext, fixes: Vec<FixItem>, batch_id: FixBatchId) -> Box<Self> {
-        Self::new_raw(ctx, fixes, batch_id, true)
+    pub fn new(ctx: &DalContext, fixes: Vec<FixItem>, batch_id: FixBatchId) -> Box<Self> {
+        Self::new_with_max_concurrency(ctx, fixes, batch_id, Self::DEFAULT_MAX_CONCURRENCY)
     }
 
-    fn new_raw(
+    pub fn new_with_max_concurrency(
         ctx: &DalContext,
         fixes: Vec<FixItem>,
         batch_id: FixBatchId,
-        started: bool,
+        max_concurrency: usize,
     ) -> Box<Self> {
         let access_builder = AccessBuilder::from(ctx.clone());
         let visibility = *ctx.visibility();
 
         Box::new(Self {
             fixes,
-            started,
             batch_id,
+            // A limit of zero would never run anything.
+            max_concurrency: max_concurrency.max(1),
             access_builder,
             visibility,
             job: None,
@@ -102,98 +115,280 @@ impl JobConsumerMetadata for FixesJob {
 
 #[async_trait]
 impl JobConsumer for FixesJob {
+    #[instrument(
+        name = "fixes_job.run",
+        skip_all,
+        level = "info",
+        fields(
+            batch_id = %self.batch_id,
+            max_concurrency = self.max_concurrency,
+        )
+    )]
     async fn run(&self, ctx: &mut DalContext) -> JobConsumerResult<()> {
-        // Mark the batch as started if it has not been yet.
-        if !self.started {
-            let mut batch = FixBatch::get_by_id(ctx, &self.batch_id)
-                .await?
-                .ok_or(JobConsumerError::MissingFixBatch(self.batch_id))?;
-            batch.stamp_started(ctx).await?;
+        let mut batch = FixBatch::get_by_id(ctx, &self.batch_id)
+            .await?
+            .ok_or(JobConsumerError::MissingFixBatch(self.batch_id))?;
+        batch.stamp_started(ctx).await?;
+
+        // A resumed batch keeps the graph it was first scheduled with, so that the edges to the
+        // fixes that already succeeded are not lost.
+        let graph = match batch.graph()? {
+            Some(graph) => graph,
+            None => {
+                let graph = FixGraph::new(ctx, &self.fixes).await?;
+                batch.set_graph(ctx, &graph).await?;
+                graph
+            }
+        };
+        ctx.commit().await?;
+
+        // Every fix runs with its own context (and therefore its own transactions) so that fixes
+        // can commit independently of each other.
+        let builder = ctx.services_context().into_builder(ctx.blocking());
+
+        let mut schedule = FixSchedule::new(&graph, &self.fixes);
+        let mut running = FuturesUnordered::new();
+        // The fixes waiting out the backoff of their retry policy, which do not take up a slot.
+        let mut deferred = FuturesUnordered::new();
+
+        loop {
+            for step in schedule.next(running.len(), self.max_concurrency)? {
+                match step {
+                    FixScheduleStep::Run(fix_item) => {
+                        let fix_ctx = builder
+                            .build(self.access_builder.build(self.visibility))
+                            .await?;
+                        running.push(async move {
+                            let result = run_fix(&fix_ctx, fix_item, self.batch_id).await;
+                            (fix_ctx, fix_item, result)
+                        });
+                    }
+                    FixScheduleStep::Skip { fix, blocker } => {
+                        skip_fix(ctx, fix, blocker, self.batch_id).await?;
+                    }
+                }
+            }
+            // Commit the skipped fixes, if any.
+            ctx.commit().await?;
+
+            tokio::select! {
+                Some((fix_ctx, fix_item, result)) = running.next() => match result {
+                    Ok(FixOutcome::Finished(completion_status)) => {
+                        schedule.finish(
+                            fix_item.id,
+                            completion_status == FixCompletionStatus::Success,
+                        );
+                    }
+                    Ok(FixOutcome::Retrying(backoff)) => deferred.push(async move {
+                        tokio::time::sleep(backoff).await;
+                        fix_item
+                    }),
+                    Err(err) => {
+                        schedule.finish(fix_item.id, false);
+                        error!(error = ?err, fix_id = %fix_item.id, "unable to run fix");
+                        fix_ctx.rollback().await?;
+                        fail_fix(&fix_ctx, fix_item, self.batch_id, err.to_string()).await?;
+                    }
+                },
+                Some(fix_item) = deferred.next() => schedule.retry(fix_item),
+                else => break,
+            }
         }
 
-        if self.fixes.is_empty() {
-            return finish_batch(ctx, self.batch_id).await;
+        finish_batch(ctx, self.batch_id).await
+    }
+}
+
+/// What came out of running a [`Fix`] once.
+enum FixOutcome {
+    /// The [`Fix`] is done, and its [`Component`] was refreshed.
+    Finished(FixCompletionStatus),
+    /// The [`Fix`] did not succeed and must run again, once the backoff has elapsed.
+    Retrying(std::time::Duration),
+}
+
+/// Runs a single attempt of a [`Fix`], refreshes its [`Component`] and commits.
+async fn run_fix(
+    ctx: &DalContext,
+    fix_item: &FixItem,
+    batch_id: FixBatchId,
+) -> JobConsumerResult<FixOutcome> {
+    let deleted_ctx = &ctx.clone_with_delete_visibility();
+    // Get the workflow for the action we need to run.
+    let component = Component::get_by_id(deleted_ctx, &fix_item.component_id)
+        .await?
+        .ok_or(JobConsumerError::ComponentNotFound(fix_item.component_id))?;
+    if component.is_destroyed() {
+        return Err(JobConsumerError::ComponentIsDestroyed(*component.id()));
+    }
+
+    let action = ActionPrototype::get_by_id(ctx, &fix_item.action_prototype_id)
+        .await?
+        .ok_or_else(|| JobConsumerError::ActionPrototypeNotFound(fix_item.action_prototype_id))?;
+
+    let mut fix = Fix::get_by_id(ctx, &fix_item.id)
+        .await?
+        .ok_or(FixError::MissingFix(fix_item.id))?;
+    // The job may have been restarted while the fix was waiting out its backoff.
+    let remaining_backoff = fix.remaining_backoff()?;
+    if !remaining_backoff.is_zero() {
+        return Ok(FixOutcome::Retrying(remaining_backoff));
+    }
+
+    // Run the fix (via the action prototype).
+    let resource = match fix.run(ctx, &action).await {
+        Ok(resource) => resource,
+        Err(err) => {
+            // The run may have failed halfway through, so start over from what was committed
+            // and, if the retry policy allows it, record the error as this attempt's outcome.
+            ctx.rollback().await?;
+            fix = Fix::get_by_id(ctx, &fix_item.id)
+                .await?
+                .ok_or(FixError::MissingFix(fix_item.id))?;
+            let retryable = action
+                .retry_policy()
+                .map_or(false, |policy| policy.should_retry(fix.attempt(), None));
+            if !retryable || fix.finished_at().is_some() {
+                return Err(err.into());
+            }
+            if fix.started_at().is_none() {
+                fix.stamp_started(ctx).await?;
+            }
+            fix.stamp_finished(ctx, FixCompletionStatus::Error, Some(err.to_string()), None)
+                .await?;
+            None
         }
-        let fix_item = &self.fixes[0];
+    };
+    let status = resource.as_ref().map(|r| r.status);
 
-        let deleted_ctx = &ctx.clone_with_delete_visibility();
-        // Get the workflow for the action we need to run.
-        let component = Component::get_by_id(deleted_ctx, &fix_item.component_id)
-            .await?
-            .ok_or(JobConsumerError::ComponentNotFound(fix_item.component_id))?;
-        if component.is_destroyed() {
-            return Err(JobConsumerError::ComponentIsDestroyed(*component.id()));
+    // Hand the fix back to the job if the retry policy of the action allows another attempt.
+    if let Some(policy) = action.retry_policy() {
+        if policy.should_retry(fix.attempt(), status) {
+            let backoff = policy.backoff(fix.attempt());
+            info!(
+                fix_id = %fix.id(),
+                attempt = fix.attempt(),
+                ?backoff,
+                "fix did not succeed, retrying"
+            );
+            fix.reset_for_retry(ctx).await?;
+            fix.defer(ctx, backoff).await?;
+            ctx.commit().await?;
+            return Ok(FixOutcome::Retrying(backoff));
         }
+    }
+    let completion_status: FixCompletionStatus = *fix
+        .completion_status()
+        .ok_or(FixError::EmptyCompletionStatus)?;
 
-        let action = ActionPrototype::get_by_id(ctx, &fix_item.action_prototype_id)
-            .await?
-            .ok_or_else(|| {
-                JobConsumerError::ActionPrototypeNotFound(fix_item.action_prototype_id)
-            })?;
+    // Upsert the fix resolver.
+    FixResolver::upsert(
+        ctx,
+        *action.id(),
+        Some(matches!(completion_status, FixCompletionStatus::Success)),
+        *fix.id(),
+    )
+    .await?;
 
-        // Run the fix (via the action prototype).
-        let mut fix = Fix::get_by_id(ctx, &fix_item.id)
-            .await?
-            .ok_or(FixError::MissingFix(fix_item.id))?;
-        let resource = fix.run(ctx, &action).await?;
-        let completion_status: FixCompletionStatus = *fix
-            .completion_status()
-            .ok_or(FixError::EmptyCompletionStatus)?;
-
-        // Upsert the fix resolver.
-        FixResolver::upsert(
-            ctx,
-            *action.id(),
-            Some(matches!(completion_status, FixCompletionStatus::Success)),
-            *fix.id(),
-        )
-        .await?;
+    let logs: Vec<_> = match resource {
+        Some(r) => r
+            .logs
+            .iter()
+            .flat_map(|l| l.split('\n'))
+            .map(|l| l.to_owned())
+            .collect(),
+        None => vec![],
+    };
 
-        let logs: Vec<_> = match resource {
-            Some(r) => r
-                .logs
-                .iter()
-                .flat_map(|l| l.split('\n'))
-                .map(|l| l.to_owned())
-                .collect(),
-            None => vec![],
-        };
+    // Commit progress so far, and wait for dependent values propagation so we can run
+    // dependent fixes that rely on the /root/resource from this fix.
+    // `blocking_commit()` will wait for any jobs that have ben created through
+    // `enqueue_job(...)` to finish before moving on.
+    ctx.blocking_commit().await?;
 
-        // Commit progress so far, and wait for dependent values propagation so we can run
-        // consecutive fixes that depend on the /root/resource from the previous fix.
-        // `blocking_commit()` will wait for any jobs that have ben created through
-        // `enqueue_job(...)` to finish before moving on.
-        ctx.blocking_commit().await?;
+    component.act(ctx, ActionKind::Refresh).await?;
 
-        component.act(ctx, ActionKind::Refresh).await?;
+    ctx.blocking_commit().await?;
 
-        ctx.blocking_commit().await?;
+    WsEvent::fix_return(
+        ctx,
+        *fix.id(),
+        batch_id,
+        *action.kind(),
+        completion_status,
+        logs,
+    )
+    .await?
+    .publish_on_commit(ctx)
+    .await?;
+    ctx.commit().await?;
 
-        WsEvent::fix_return(
-            ctx,
-            *fix.id(),
-            self.batch_id,
-            *action.kind(),
-            completion_status,
-            logs,
-        )
-        .await?
-        .publish_on_commit(ctx)
-        .await?;
+    Ok(FixOutcome::Finished(completion_status))
+}
 
-        if self.fixes.len() == 1 {
-            finish_batch(ctx, self.batch_id).await?;
-        } else {
-            ctx.enqueue_job(FixesJob::new_iteration(
-                ctx,
-                self.fixes.iter().skip(1).cloned().collect(),
-                self.batch_id,
-            ))
-            .await?;
+/// Records a [`Fix`] that could not be run at all as errored.
+async fn fail_fix(
+    ctx: &DalContext,
+    fix_item: &FixItem,
+    batch_id: FixBatchId,
+    message: String,
+) -> JobConsumerResult<()> {
+    let mut fix = Fix::get_by_id(ctx, &fix_item.id)
+        .await?
+        .ok_or(FixError::MissingFix(fix_item.id))?;
+    if fix.finished_at().is_none() {
+        if fix.started_at().is_none() {
+            fix.stamp_started(ctx).await?;
         }
-
-        Ok(())
+        fix.stamp_finished(ctx, FixCompletionStatus::Error, Some(message), None)
+            .await?;
     }
+
+    WsEvent::fix_return(
+        ctx,
+        *fix.id(),
+        batch_id,
+        *fix.action_kind(),
+        FixCompletionStatus::Error,
+        vec![],
+    )
+    .await?
+    .publish_on_commit(ctx)
+    .await?;
+    ctx.commit().await?;
+
+    Ok(())
+}
+
+/// Records a [`Fix`] that will not run because one of its dependencies did not succeed.
+async fn skip_fix(
+    ctx: &DalContext,
+    fix_item: &FixItem,
+    blocker: FixId,
+    batch_id: FixBatchId,
+) -> JobConsumerResult<()> {
+    let mut fix = Fix::get_by_id(ctx, &fix_item.id)
+        .await?
+        .ok_or(FixError::MissingFix(fix_item.id))?;
+    fix.stamp_skipped(
+        ctx,
+        format!("skipped: depends on fix {blocker}, which did not succeed"),
+    )
+    .await?;
+
+    WsEvent::fix_return(
+        ctx,
+        *fix.id(),
+        batch_id,
+        *fix.action_kind(),
+        FixCompletionStatus::Unstarted,
+        vec![],
+    )
+    .await?
+    .publish_on_commit(ctx)
+    .await?;
+
+    Ok(())
 }
 
 impl TryFrom<JobInfo> for FixesJob {
@@ -205,7 +400,7 @@ impl TryFrom<JobInfo> for FixesJob {
         Ok(Self {
             fixes: args.fixes,
             batch_id: args.batch_id,
-            started: args.started,
+            max_concurrency: args.max_concurrency.max(1),
             access_builder: job.access_builder,
             visibility: job.visibility,
             job: Some(job),
@@ -11,8 +11,8 @@ use crate::{
         },
         producer::{JobProducer, JobProducerResult},
     },
-    AccessBuilder, ActionKind, Component, ComponentId, DalContext, StandardModel, Visibility,
-    WsEvent,
+    AccessBuilder, ActionKind, Component, ComponentDrift, ComponentId, DalContext, JobFailure,
+    StandardModel, Visibility, WsEvent,
 };
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -86,24 +86,64 @@ impl JobConsumer for RefreshJob {
         ctx.update_with_deleted_visibility();
 
         for component_id in &self.component_ids {
-            let component = Component::get_by_id(ctx, component_id)
-                .await?
-                .ok_or(JobConsumerError::ComponentNotFound(*component_id))?;
-            component.act(ctx, ActionKind::Refresh).await?;
-
-            WsEvent::resource_refreshed(ctx, *component.id())
+            // A failure to refresh one component should not prevent the rest of the batch from
+            // being refreshed, so we record it and move on.
+            let refreshed = match Self::refresh_component(ctx, *component_id).await {
+                Ok(()) => true,
+                Err(err) => {
+                    warn!(error = ?err, %component_id, "failed to refresh resource");
+                    ctx.rollback().await?;
+
+                    JobFailure::new(
+                        ctx,
+                        self.type_name(),
+                        format!("failed to refresh component {component_id}: {err}"),
+                    )
+                    .await?;
+                    false
+                }
+            };
+
+            // Let listeners know the refresh for this component is over, whether or not it
+            // succeeded.
+            WsEvent::resource_refreshed(ctx, *component_id)
                 .await?
                 .publish_on_commit(ctx)
                 .await?;
 
-            // Save the refreshed resource for the component
+            // Save the refreshed resource (or the failure) for the component
             ctx.commit().await?;
+
+            // Now that the resource is fresh, check whether it drifted away from the domain.
+            // Drift is informational, so failing to compute it must not fail the refresh.
+            if refreshed {
+                match ComponentDrift::detect(ctx, *component_id).await {
+                    Ok(_) => ctx.commit().await?,
+                    Err(err) => {
+                        warn!(error = ?err, %component_id, "failed to detect resource drift");
+                        ctx.rollback().await?;
+                    }
+                }
+            }
         }
 
         Ok(())
     }
 }
 
+impl RefreshJob {
+    async fn refresh_component(
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> JobConsumerResult<()> {
+        let component = Component::get_by_id(ctx, &component_id)
+            .await?
+            .ok_or(JobConsumerError::ComponentNotFound(component_id))?;
+        component.act(ctx, ActionKind::Refresh).await?;
+        Ok(())
+    }
+}
+
 impl TryFrom<JobInfo> for RefreshJob {
     type Error = JobConsumerError;
 
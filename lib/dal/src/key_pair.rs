@@ -7,8 +7,9 @@ use telemetry::prelude::*;
 use thiserror::Error;
 
 use crate::{
-    pk, standard_model_accessor_ro, DalContext, HistoryEvent, HistoryEventError, Timestamp,
-    TransactionsError, Workspace, WorkspaceError, WorkspacePk,
+    pk, standard_model_accessor_ro, DalContext, EncryptedSecret, HistoryEvent, HistoryEventError,
+    SecretError, Timestamp, TransactionsError, User, UserError, Workspace, WorkspaceError,
+    WorkspacePermission, WorkspacePk,
 };
 
 mod key_pair_box_public_key_serde;
@@ -16,6 +17,7 @@ mod key_pair_box_secret_key_serde;
 
 const PUBLIC_KEY_GET_CURRENT: &str = include_str!("./queries/public_key_get_current.sql");
 const KEY_PAIR_GET_BY_PK: &str = include_str!("queries/key_pair_get_by_pk.sql");
+const KEY_PAIR_LIST_FOR_WORKSPACE: &str = include_str!("queries/key_pair_list_for_workspace.sql");
 
 #[remain::sorted]
 #[derive(Error, Debug)]
@@ -28,13 +30,19 @@ pub enum KeyPairError {
     Nats(#[from] NatsError),
     #[error("no current key pair found when one was expected")]
     NoCurrentKeyPair,
+    #[error("no workspace in tenancy")]
+    NoWorkspaceInTenancy,
     #[error("pg error: {0}")]
     Pg(#[from] PgError),
+    #[error(transparent)]
+    Secret(#[from] Box<SecretError>),
     #[error("error serializing/deserializing json: {0}")]
     SerdeJson(#[from] serde_json::Error),
     #[error("transactions error: {0}")]
     Transactions(#[from] TransactionsError),
     #[error(transparent)]
+    User(#[from] UserError),
+    #[error(transparent)]
     Workspace(#[from] Box<WorkspaceError>),
 }
 
@@ -42,6 +50,16 @@ pub type KeyPairResult<T> = Result<T, KeyPairError>;
 
 pk!(KeyPairPk);
 
+/// What [`KeyPair::rotate()`] did.
+#[derive(Debug, Clone)]
+pub struct KeyPairRotation {
+    /// The new current key pair of the workspace.
+    pub key_pair: KeyPair,
+    pub reencrypted_secret_count: usize,
+    /// The key pairs that were deleted as no secret uses them anymore.
+    pub deleted_key_pair_pks: Vec<KeyPairPk>,
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
 pub struct KeyPair {
     pk: KeyPairPk,
@@ -126,6 +144,74 @@ impl KeyPair {
     standard_model_accessor_ro!(secret_key, BoxSecretKey);
     standard_model_accessor_ro!(created_lamport_clock, u64);
 
+    /// Rotates the key pair of the workspace: creates a new [`KeyPair`], which becomes the
+    /// current one, and re-encrypts every [`EncryptedSecret`] of the workspace with it. The
+    /// previous key pairs are kept only as long as a secret still uses them.
+    ///
+    /// Everything happens in the transaction of the given context, so either every secret is
+    /// re-encrypted or none is. The caller is responsible for committing.
+    pub async fn rotate(ctx: &DalContext) -> KeyPairResult<KeyPairRotation> {
+        User::ensure_authorized(ctx, WorkspacePermission::ManageWorkspace).await?;
+        let workspace_pk = ctx
+            .tenancy()
+            .workspace_pk()
+            .ok_or(KeyPairError::NoWorkspaceInTenancy)?;
+
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(KEY_PAIR_LIST_FOR_WORKSPACE, &[&workspace_pk])
+            .await?;
+        let mut previous_key_pairs = Vec::with_capacity(rows.len());
+        for row in rows {
+            let json: serde_json::Value = row.try_get("object")?;
+            previous_key_pairs.push(serde_json::from_value::<Self>(json)?);
+        }
+
+        let key_pair = Self::new(ctx, "default").await?;
+
+        let mut reencrypted_secret_count = 0;
+        for previous_key_pair in &previous_key_pairs {
+            reencrypted_secret_count +=
+                EncryptedSecret::reencrypt_all(ctx, previous_key_pair, &key_pair)
+                    .await
+                    .map_err(Box::new)?;
+        }
+
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT pk FROM key_pair_delete_unused_v1($1, $2) AS pk",
+                &[&workspace_pk, &key_pair.pk],
+            )
+            .await?;
+        let mut deleted_key_pair_pks = Vec::with_capacity(rows.len());
+        for row in rows {
+            deleted_key_pair_pks.push(row.try_get("pk")?);
+        }
+
+        let _history_event = HistoryEvent::new(
+            ctx,
+            "key_pair.rotate".to_owned(),
+            "Key Pair rotated".to_owned(),
+            &serde_json::json![{
+                "pk": key_pair.pk,
+                "reencryptedSecretCount": reencrypted_secret_count,
+                "deletedKeyPairPks": &deleted_key_pair_pks,
+            }],
+        )
+        .await?;
+
+        Ok(KeyPairRotation {
+            key_pair,
+            reencrypted_secret_count,
+            deleted_key_pair_pks,
+        })
+    }
+
     pub async fn workspace(&self, ctx: &DalContext) -> KeyPairResult<Workspace> {
         Workspace::get_by_pk(ctx, &self.workspace_pk)
             .await
@@ -19,7 +19,9 @@ use crate::builtins::SelectedTestBuiltinSchemas;
 
 pub mod action;
 pub mod action_prototype;
+pub mod action_schedule;
 pub mod actor_view;
+pub mod api_token;
 pub mod attribute;
 pub mod builtins;
 pub mod change_set;
@@ -29,6 +31,7 @@ pub mod component;
 pub mod context;
 pub mod cyclone_key_pair;
 pub mod diagram;
+pub mod drift;
 pub mod edge;
 pub mod fix;
 pub mod func;
@@ -70,9 +73,14 @@ pub mod ws_event;
 pub use action::{Action, ActionError, ActionId};
 pub use action_prototype::{
     ActionKind, ActionPrototype, ActionPrototypeContext, ActionPrototypeError, ActionPrototypeId,
-    ActionPrototypeView,
+    ActionPrototypeView, ActionRetryPolicy, ActionRunDetails,
+};
+pub use action_schedule::{
+    ActionSchedule, ActionScheduleError, ActionScheduleId, ActionScheduleRecurrence,
+    ActionScheduleResult,
 };
 pub use actor_view::ActorView;
+pub use api_token::{ApiToken, ApiTokenError, ApiTokenPk, ApiTokenResult};
 pub use attribute::value::view::AttributeView;
 pub use attribute::{
     context::{
@@ -92,7 +100,10 @@ pub use attribute::{
     },
 };
 pub use builtins::{BuiltinsError, BuiltinsResult};
-pub use change_set::{ChangeSet, ChangeSetError, ChangeSetPk, ChangeSetStatus};
+pub use change_set::{
+    AttributeValueConflict, ChangeSet, ChangeSetApproval, ChangeSetApprovalDecision,
+    ChangeSetApprovalStatus, ChangeSetError, ChangeSetPk, ChangeSetStatus,
+};
 pub use code_view::{CodeLanguage, CodeView};
 pub use component::{
     resource::ResourceView, status::ComponentStatus, status::HistoryActorTimestamp, Component,
@@ -106,10 +117,12 @@ pub use cyclone_key_pair::CycloneKeyPair;
 pub use diagram::{
     connection::Connection, connection::DiagramEdgeView, Diagram, DiagramError, DiagramKind,
 };
+pub use drift::{ComponentDrift, DriftError, DriftResult, DriftStatus, DriftSummary};
 pub use edge::{Edge, EdgeError, EdgeResult};
 pub use fix::batch::{FixBatch, FixBatchId};
 pub use fix::resolver::{FixResolver, FixResolverError, FixResolverId};
-pub use fix::{Fix, FixCompletionStatus, FixError, FixId};
+pub use fix::run::{FixRun, FixRunPk, FixRunQuery};
+pub use fix::{Fix, FixAttempt, FixCompletionStatus, FixError, FixId};
 pub use func::argument::FuncArgument;
 pub use func::binding_return_value::{FuncBindingReturnValue, FuncBindingReturnValueError};
 pub use func::{
@@ -117,13 +130,16 @@ pub use func::{
     binding::{FuncBinding, FuncBindingError, FuncBindingId},
     Func, FuncError, FuncId, FuncResult,
 };
-pub use history_event::{HistoryActor, HistoryEvent, HistoryEventError};
+pub use history_event::{
+    HistoryActor, HistoryEvent, HistoryEventError, HistoryEventPage, HistoryEventPk,
+    HistoryEventQuery,
+};
 pub use index_map::IndexMap;
 pub use job::definition::DependentValuesUpdate;
 pub use job::processor::{JobQueueProcessor, NatsProcessor};
 pub use job_failure::{JobFailure, JobFailureError, JobFailureResult};
 pub use jwt_key::JwtPublicSigningKey;
-pub use key_pair::{KeyPair, KeyPairError, KeyPairResult, PublicKey};
+pub use key_pair::{KeyPair, KeyPairError, KeyPairResult, KeyPairRotation, PublicKey};
 pub use label_list::{LabelEntry, LabelList, LabelListError};
 pub use node::NodeId;
 pub use node::{Node, NodeError, NodeKind};
@@ -159,7 +175,9 @@ pub use status::{
 };
 pub use tenancy::{Tenancy, TenancyError};
 pub use timestamp::{Timestamp, TimestampError};
-pub use user::{User, UserClaim, UserError, UserPk, UserResult};
+pub use user::{
+    User, UserClaim, UserError, UserPk, UserResult, WorkspacePermission, WorkspaceRole,
+};
 pub use validation::prototype::{
     context::ValidationPrototypeContext, ValidationPrototype, ValidationPrototypeError,
     ValidationPrototypeId,
@@ -168,7 +186,11 @@ pub use validation::resolver::{
     ValidationResolver, ValidationResolverError, ValidationResolverId, ValidationStatus,
 };
 pub use visibility::{Visibility, VisibilityError};
-pub use workspace::{Workspace, WorkspaceError, WorkspacePk, WorkspaceResult, WorkspaceSignup};
+pub use workspace::{
+    UserWorkspace, Workspace, WorkspaceBackup, WorkspaceBackupPk, WorkspaceBackupRetention,
+    WorkspaceError, WorkspaceForkProgressPayload, WorkspaceForkStep, WorkspaceMember, WorkspacePk,
+    WorkspaceRestoreTarget, WorkspaceResult, WorkspaceSignup,
+};
 pub use ws_event::{WsEvent, WsEventError, WsEventResult, WsPayload};
 
 #[remain::sorted]
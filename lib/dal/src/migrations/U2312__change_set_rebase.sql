@@ -0,0 +1,15 @@
+ALTER TABLE change_sets ADD COLUMN rebased_at timestamp with time zone;
+
+CREATE OR REPLACE FUNCTION change_set_rebase_v1(this_change_set_pk ident,
+                                                this_tenancy jsonb,
+                                                OUT rebased_at timestamp with time zone) AS
+$$
+BEGIN
+    UPDATE change_sets
+    SET rebased_at = clock_timestamp(),
+        updated_at = clock_timestamp()
+    WHERE pk = this_change_set_pk
+      AND in_tenancy_v1(this_tenancy, change_sets.tenancy_workspace_pk)
+    RETURNING change_sets.rebased_at INTO rebased_at;
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1,14 @@
+CREATE OR REPLACE FUNCTION change_set_update_status_v1(this_change_set_pk ident,
+                                                       this_status text,
+                                                       this_tenancy jsonb,
+                                                       OUT timestamp_updated_at timestamp with time zone) AS
+$$
+BEGIN
+    UPDATE change_sets
+    SET status     = this_status,
+        updated_at = clock_timestamp()
+    WHERE pk = this_change_set_pk
+      AND in_tenancy_v1(this_tenancy, change_sets.tenancy_workspace_pk)
+    RETURNING updated_at INTO timestamp_updated_at;
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1,38 @@
+CREATE TABLE component_drift_statuses
+(
+    pk                          ident primary key default ident_create_v1(),
+    tenancy_workspace_pk        ident,
+    component_id                ident                    NOT NULL,
+    status                      text                     NOT NULL,
+    drifted_paths               jsonb                    NOT NULL DEFAULT '[]'::jsonb,
+    checked_at                  timestamp with time zone NOT NULL DEFAULT NOW(),
+    created_at                  timestamp with time zone NOT NULL DEFAULT NOW(),
+    updated_at                  timestamp with time zone NOT NULL DEFAULT NOW()
+);
+CREATE UNIQUE INDEX ON component_drift_statuses (component_id, tenancy_workspace_pk);
+
+CREATE OR REPLACE FUNCTION component_drift_upsert_v1(this_tenancy jsonb,
+                                                     this_component_id ident,
+                                                     this_status text,
+                                                     this_drifted_paths jsonb,
+                                                     OUT object json) AS
+$$
+DECLARE
+    this_tenancy_record    tenancy_record_v1;
+    this_new_row           component_drift_statuses%ROWTYPE;
+BEGIN
+    this_tenancy_record := tenancy_json_to_columns_v1(this_tenancy);
+
+    INSERT INTO component_drift_statuses (tenancy_workspace_pk, component_id, status, drifted_paths)
+    VALUES (this_tenancy_record.tenancy_workspace_pk, this_component_id, this_status,
+            this_drifted_paths)
+    ON CONFLICT (component_id, tenancy_workspace_pk)
+        DO UPDATE SET status        = this_status,
+                      drifted_paths = this_drifted_paths,
+                      checked_at    = NOW(),
+                      updated_at    = NOW()
+    RETURNING * INTO this_new_row;
+
+    object := row_to_json(this_new_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
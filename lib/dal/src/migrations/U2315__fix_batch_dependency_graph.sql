@@ -0,0 +1 @@
+ALTER TABLE fix_batches ADD COLUMN dependency_graph jsonb;
@@ -0,0 +1,2 @@
+ALTER TABLE action_prototypes ADD COLUMN retry_policy jsonb;
+ALTER TABLE fixes ADD COLUMN attempts jsonb NOT NULL DEFAULT '[]'::jsonb;
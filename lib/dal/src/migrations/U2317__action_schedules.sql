@@ -0,0 +1,65 @@
+CREATE TABLE action_schedules
+(
+    pk                          ident primary key default ident_create_v1(),
+    id                          ident not null default ident_create_v1(),
+    tenancy_workspace_pk        ident,
+    visibility_change_set_pk    ident                    NOT NULL DEFAULT ident_nil_v1(),
+    visibility_deleted_at       timestamp with time zone,
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    updated_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    action_prototype_id         ident                    NOT NULL,
+    component_id                ident                    NOT NULL,
+    author                      text                     NOT NULL,
+    recurrence                  text,
+    next_run_at                 timestamp with time zone,
+    last_run_at                 timestamp with time zone,
+    last_fix_batch_id           ident,
+    paused                      bool                     NOT NULL DEFAULT false
+);
+SELECT standard_model_table_constraints_v1('action_schedules');
+
+-- Used by the action scheduler to find everything that is due, across workspaces.
+CREATE INDEX action_schedules_due
+    ON action_schedules (next_run_at)
+    WHERE visibility_deleted_at IS NULL AND NOT paused;
+
+INSERT INTO standard_models (table_name, table_type, history_event_label_base, history_event_message_name)
+VALUES ('action_schedules', 'model', 'action_schedule', 'Action Schedule');
+
+CREATE OR REPLACE FUNCTION action_schedule_create_v1(
+    this_tenancy jsonb,
+    this_visibility jsonb,
+    this_action_prototype_id ident,
+    this_component_id ident,
+    this_author text,
+    this_recurrence text,
+    this_next_run_at timestamp with time zone,
+    OUT object json) AS
+$$
+DECLARE
+    this_tenancy_record    tenancy_record_v1;
+    this_visibility_record visibility_record_v1;
+    this_new_row           action_schedules%ROWTYPE;
+BEGIN
+    this_tenancy_record := tenancy_json_to_columns_v1(this_tenancy);
+    this_visibility_record := visibility_json_to_columns_v1(this_visibility);
+
+    INSERT INTO action_schedules (tenancy_workspace_pk,
+                                  visibility_change_set_pk,
+                                  action_prototype_id,
+                                  component_id,
+                                  author,
+                                  recurrence,
+                                  next_run_at)
+    VALUES (this_tenancy_record.tenancy_workspace_pk,
+            this_visibility_record.visibility_change_set_pk,
+            this_action_prototype_id,
+            this_component_id,
+            this_author,
+            this_recurrence,
+            this_next_run_at)
+    RETURNING * INTO this_new_row;
+
+    object := row_to_json(this_new_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1,61 @@
+CREATE TABLE fix_runs
+(
+    pk                          ident primary key default ident_create_v1(),
+    tenancy_workspace_pk        ident,
+    fix_id                      ident                    NOT NULL,
+    fix_batch_id                ident,
+    component_id                ident                    NOT NULL,
+    action_prototype_id         ident                    NOT NULL,
+    action_kind                 text                     NOT NULL,
+    attempt                     integer                  NOT NULL,
+    actor                       jsonb                    NOT NULL,
+    arguments                   jsonb,
+    resource_before             jsonb,
+    resource_after              jsonb,
+    output_stream               jsonb                    NOT NULL DEFAULT '[]'::jsonb,
+    completion_status           text,
+    completion_message          text,
+    started_at                  timestamp with time zone NOT NULL,
+    finished_at                 timestamp with time zone NOT NULL DEFAULT NOW(),
+    created_at                  timestamp with time zone NOT NULL DEFAULT NOW()
+);
+CREATE INDEX ON fix_runs (tenancy_workspace_pk, started_at);
+CREATE INDEX ON fix_runs (tenancy_workspace_pk, component_id, started_at);
+CREATE INDEX ON fix_runs (tenancy_workspace_pk, fix_batch_id);
+
+CREATE OR REPLACE FUNCTION fix_run_create_v1(this_tenancy jsonb,
+                                             this_fix_id ident,
+                                             this_fix_batch_id ident,
+                                             this_component_id ident,
+                                             this_action_prototype_id ident,
+                                             this_action_kind text,
+                                             this_attempt integer,
+                                             this_actor jsonb,
+                                             this_arguments jsonb,
+                                             this_resource_before jsonb,
+                                             this_resource_after jsonb,
+                                             this_output_stream jsonb,
+                                             this_completion_status text,
+                                             this_completion_message text,
+                                             this_started_at timestamp with time zone,
+                                             OUT object json) AS
+$$
+DECLARE
+    this_tenancy_record tenancy_record_v1;
+    this_new_row        fix_runs%ROWTYPE;
+BEGIN
+    this_tenancy_record := tenancy_json_to_columns_v1(this_tenancy);
+
+    INSERT INTO fix_runs (tenancy_workspace_pk, fix_id, fix_batch_id, component_id,
+                          action_prototype_id, action_kind, attempt, actor, arguments,
+                          resource_before, resource_after, output_stream, completion_status,
+                          completion_message, started_at)
+    VALUES (this_tenancy_record.tenancy_workspace_pk, this_fix_id, this_fix_batch_id,
+            this_component_id, this_action_prototype_id, this_action_kind, this_attempt,
+            this_actor, this_arguments, this_resource_before, this_resource_after,
+            this_output_stream, this_completion_status, this_completion_message, this_started_at)
+    RETURNING * INTO this_new_row;
+
+    object := row_to_json(this_new_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
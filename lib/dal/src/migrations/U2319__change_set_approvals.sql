@@ -0,0 +1,67 @@
+ALTER TABLE workspaces ADD COLUMN required_change_set_approvals integer NOT NULL DEFAULT 0;
+ALTER TABLE change_sets ADD COLUMN approval_requested_at timestamp with time zone;
+ALTER TABLE change_sets ADD COLUMN approval_requested_by ident;
+
+CREATE TABLE change_set_approvals
+(
+    pk                          ident primary key default ident_create_v1(),
+    tenancy_workspace_pk        ident,
+    change_set_pk               ident                    NOT NULL,
+    user_pk                     ident                    NOT NULL,
+    decision                    text                     NOT NULL,
+    comment                     text,
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP()
+);
+CREATE INDEX ON change_set_approvals (tenancy_workspace_pk, change_set_pk, created_at);
+
+CREATE OR REPLACE FUNCTION workspace_update_required_change_set_approvals_v1(this_pk ident,
+                                                                             this_required integer,
+                                                                             OUT timestamp_updated_at timestamp with time zone) AS
+$$
+BEGIN
+    UPDATE workspaces
+    SET required_change_set_approvals = this_required,
+        updated_at                    = clock_timestamp()
+    WHERE pk = this_pk
+    RETURNING updated_at INTO timestamp_updated_at;
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+CREATE OR REPLACE FUNCTION change_set_request_approval_v1(this_change_set_pk ident,
+                                                          this_user_pk ident,
+                                                          this_tenancy jsonb,
+                                                          OUT approval_requested_at timestamp with time zone) AS
+$$
+BEGIN
+    UPDATE change_sets
+    SET status                = 'PendingApproval',
+        approval_requested_at = clock_timestamp(),
+        approval_requested_by = this_user_pk,
+        updated_at            = clock_timestamp()
+    WHERE pk = this_change_set_pk
+      AND in_tenancy_v1(this_tenancy, change_sets.tenancy_workspace_pk)
+    RETURNING change_sets.approval_requested_at INTO approval_requested_at;
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+CREATE OR REPLACE FUNCTION change_set_approval_create_v1(this_tenancy jsonb,
+                                                         this_change_set_pk ident,
+                                                         this_user_pk ident,
+                                                         this_decision text,
+                                                         this_comment text,
+                                                         OUT object json) AS
+$$
+DECLARE
+    this_tenancy_record tenancy_record_v1;
+    this_new_row        change_set_approvals%ROWTYPE;
+BEGIN
+    this_tenancy_record := tenancy_json_to_columns_v1(this_tenancy);
+
+    INSERT INTO change_set_approvals (tenancy_workspace_pk, change_set_pk, user_pk, decision, comment)
+    VALUES (this_tenancy_record.tenancy_workspace_pk, this_change_set_pk, this_user_pk,
+            this_decision, this_comment)
+    RETURNING * INTO this_new_row;
+
+    object := row_to_json(this_new_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
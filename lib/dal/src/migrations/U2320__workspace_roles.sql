@@ -0,0 +1,33 @@
+-- Everyone who already belongs to a workspace could do anything in it, so they keep doing so.
+ALTER TABLE user_belongs_to_workspaces ADD COLUMN role text NOT NULL DEFAULT 'Admin';
+ALTER TABLE user_belongs_to_workspaces ALTER COLUMN role DROP DEFAULT;
+
+CREATE OR REPLACE FUNCTION user_associate_workspace_v2(
+    this_user_pk ident,
+    this_workspace_pk ident,
+    this_role text
+    ) RETURNS void AS
+$$
+BEGIN
+    INSERT INTO user_belongs_to_workspaces (user_pk, workspace_pk, role)
+        VALUES (this_user_pk, this_workspace_pk, this_role)
+        ON CONFLICT DO NOTHING;
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+CREATE OR REPLACE FUNCTION user_set_workspace_role_v1(
+    this_user_pk ident,
+    this_workspace_pk ident,
+    this_role text,
+    OUT timestamp_updated_at timestamp with time zone) AS
+$$
+BEGIN
+    UPDATE user_belongs_to_workspaces
+    SET role       = this_role,
+        updated_at = clock_timestamp()
+    WHERE user_pk = this_user_pk
+      AND workspace_pk = this_workspace_pk
+      AND visibility_deleted_at IS NULL
+    RETURNING updated_at INTO timestamp_updated_at;
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1,34 @@
+-- Re-encrypting a secret does not change what it holds, so every row is updated in place,
+-- whatever its visibility, and without touching its timestamps.
+CREATE OR REPLACE FUNCTION encrypted_secret_reencrypt_v1(
+    this_pk ident,
+    this_crypted text,
+    this_key_pair_pk ident
+) RETURNS void AS
+$$
+BEGIN
+    UPDATE encrypted_secrets
+    SET crypted     = this_crypted,
+        key_pair_pk = this_key_pair_pk
+    WHERE pk = this_pk;
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+-- Soft deletes the key pairs of the workspace, other than the current one, that no encrypted
+-- secret references anymore.
+CREATE OR REPLACE FUNCTION key_pair_delete_unused_v1(
+    this_workspace_pk ident,
+    this_current_key_pair_pk ident
+) RETURNS SETOF ident AS
+$$
+    UPDATE key_pairs
+    SET visibility_deleted_at = clock_timestamp(),
+        updated_at            = clock_timestamp()
+    WHERE workspace_pk = this_workspace_pk
+      AND pk != this_current_key_pair_pk
+      AND visibility_deleted_at IS NULL
+      AND NOT EXISTS(SELECT 1
+                     FROM encrypted_secrets
+                     WHERE encrypted_secrets.key_pair_pk = key_pairs.pk)
+    RETURNING pk
+$$ LANGUAGE SQL VOLATILE;
@@ -0,0 +1,27 @@
+ALTER TABLE history_events
+    ADD COLUMN change_set_pk ident;
+
+-- Used by the audit log, which lists a workspace's events most recent first.
+CREATE INDEX history_events_audit_log
+    ON history_events (tenancy_workspace_pk, created_at DESC, pk DESC);
+
+CREATE OR REPLACE FUNCTION history_event_create_v2(this_label text,
+                                                   this_actor jsonb,
+                                                   this_message text,
+                                                   this_data jsonb,
+                                                   this_tenancy jsonb,
+                                                   this_change_set_pk ident,
+                                                   OUT object json) AS
+$$
+DECLARE
+    this_tenancy_record tenancy_record_v1;
+    this_new_row        history_events%ROWTYPE;
+BEGIN
+    SELECT * FROM tenancy_json_to_columns_v1(this_tenancy) INTO this_tenancy_record;
+    INSERT INTO history_events (label, actor, message, data, tenancy_workspace_pk, change_set_pk)
+    VALUES (this_label, this_actor, this_message, this_data, this_tenancy_record.tenancy_workspace_pk,
+            this_change_set_pk)
+    RETURNING * INTO this_new_row;
+    object := row_to_json(this_new_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
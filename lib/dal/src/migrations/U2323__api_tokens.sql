@@ -0,0 +1,62 @@
+CREATE TABLE api_tokens
+(
+    pk                          ident primary key default ident_create_v1(),
+    tenancy_workspace_pk        ident                    NOT NULL,
+    user_pk                     ident                    NOT NULL,
+    name                        text                     NOT NULL,
+    -- Only the SHA-256 hash of the token is stored, the token itself is shown once on creation.
+    token_hash                  text                     NOT NULL UNIQUE,
+    max_role                    text,
+    expires_at                  timestamp with time zone,
+    last_used_at                timestamp with time zone,
+    revoked_at                  timestamp with time zone,
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP()
+);
+CREATE INDEX ON api_tokens (tenancy_workspace_pk, user_pk);
+
+CREATE OR REPLACE FUNCTION api_token_create_v1(this_tenancy jsonb,
+                                               this_user_pk ident,
+                                               this_name text,
+                                               this_token_hash text,
+                                               this_max_role text,
+                                               this_expires_at timestamp with time zone,
+                                               OUT object json) AS
+$$
+DECLARE
+    this_tenancy_record tenancy_record_v1;
+    this_new_row        api_tokens%ROWTYPE;
+BEGIN
+    this_tenancy_record := tenancy_json_to_columns_v1(this_tenancy);
+
+    INSERT INTO api_tokens (tenancy_workspace_pk, user_pk, name, token_hash, max_role, expires_at)
+    VALUES (this_tenancy_record.tenancy_workspace_pk, this_user_pk, this_name, this_token_hash,
+            this_max_role, this_expires_at)
+    RETURNING * INTO this_new_row;
+
+    object := row_to_json(this_new_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+CREATE OR REPLACE FUNCTION api_token_revoke_v1(this_pk ident,
+                                               this_tenancy jsonb,
+                                               OUT revoked_at timestamp with time zone) AS
+$$
+BEGIN
+    UPDATE api_tokens
+    SET revoked_at = COALESCE(api_tokens.revoked_at, clock_timestamp())
+    WHERE pk = this_pk
+      AND in_tenancy_v1(this_tenancy, api_tokens.tenancy_workspace_pk)
+    RETURNING api_tokens.revoked_at INTO revoked_at;
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+CREATE OR REPLACE FUNCTION api_token_record_use_v1(this_pk ident,
+                                                   OUT last_used_at timestamp with time zone) AS
+$$
+BEGIN
+    UPDATE api_tokens
+    SET last_used_at = clock_timestamp()
+    WHERE pk = this_pk
+    RETURNING api_tokens.last_used_at INTO last_used_at;
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1,50 @@
+-- Copies an encrypted secret into another workspace, keeping its id so that the attribute values
+-- referencing it keep resolving. The crypted payload is expected to already be encrypted with a
+-- key pair of that workspace.
+CREATE OR REPLACE FUNCTION encrypted_secret_copy_v1(
+    this_tenancy jsonb,
+    this_visibility jsonb,
+    this_id ident,
+    this_name text,
+    this_definition text,
+    this_description text,
+    this_crypted text,
+    this_version text,
+    this_algorithm text,
+    this_key_pair_pk ident,
+    this_created_by ident
+) RETURNS void AS
+$$
+DECLARE
+    this_tenancy_record    tenancy_record_v1;
+    this_visibility_record visibility_record_v1;
+BEGIN
+    this_tenancy_record := tenancy_json_to_columns_v1(this_tenancy);
+    this_visibility_record := visibility_json_to_columns_v1(this_visibility);
+
+    INSERT INTO encrypted_secrets (id,
+                                   tenancy_workspace_pk,
+                                   visibility_change_set_pk,
+                                   name,
+                                   definition,
+                                   description,
+                                   crypted,
+                                   version,
+                                   algorithm,
+                                   key_pair_pk,
+                                   created_by,
+                                   updated_by)
+    VALUES (this_id,
+            this_tenancy_record.tenancy_workspace_pk,
+            this_visibility_record.visibility_change_set_pk,
+            this_name,
+            this_definition,
+            this_description,
+            this_crypted,
+            this_version,
+            this_algorithm,
+            this_key_pair_pk,
+            this_created_by,
+            this_created_by);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1,53 @@
+-- Workspace backups live in the module index, this keeps track of which modules hold the backups
+-- of each workspace, so they can be listed, pruned and restored.
+CREATE TABLE workspace_backups
+(
+    pk                   ident primary key default ident_create_v1(),
+    tenancy_workspace_pk ident                    NOT NULL,
+    module_id            text                     NOT NULL,
+    name                 text                     NOT NULL,
+    version              text                     NOT NULL,
+    hash                 text                     NOT NULL,
+    -- Taken by the backup scheduler, as opposed to exported by a user. Only scheduled backups
+    -- are subject to the retention policy.
+    scheduled            bool                     NOT NULL,
+    pruned_at            timestamp with time zone,
+    created_at           timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP()
+);
+CREATE INDEX ON workspace_backups (tenancy_workspace_pk, created_at);
+
+CREATE OR REPLACE FUNCTION workspace_backup_create_v1(this_tenancy jsonb,
+                                                      this_module_id text,
+                                                      this_name text,
+                                                      this_version text,
+                                                      this_hash text,
+                                                      this_scheduled bool,
+                                                      OUT object json) AS
+$$
+DECLARE
+    this_tenancy_record tenancy_record_v1;
+    this_new_row        workspace_backups%ROWTYPE;
+BEGIN
+    this_tenancy_record := tenancy_json_to_columns_v1(this_tenancy);
+
+    INSERT INTO workspace_backups (tenancy_workspace_pk, module_id, name, version, hash, scheduled)
+    VALUES (this_tenancy_record.tenancy_workspace_pk, this_module_id, this_name, this_version,
+            this_hash, this_scheduled)
+    RETURNING * INTO this_new_row;
+
+    object := row_to_json(this_new_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+CREATE OR REPLACE FUNCTION workspace_backup_prune_v1(this_pk ident,
+                                                     this_tenancy jsonb,
+                                                     OUT pruned_at timestamp with time zone) AS
+$$
+BEGIN
+    UPDATE workspace_backups
+    SET pruned_at = COALESCE(workspace_backups.pruned_at, clock_timestamp())
+    WHERE pk = this_pk
+      AND in_tenancy_v1(this_tenancy, workspace_backups.tenancy_workspace_pk)
+    RETURNING workspace_backups.pruned_at INTO pruned_at;
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1,20 @@
+-- Pure funcs return the same value for the same code, handler and arguments, so the value of a
+-- previous execution with the same input hash can be reused instead of running the func again.
+ALTER TABLE funcs ADD COLUMN impure bool NOT NULL DEFAULT FALSE;
+
+ALTER TABLE func_executions ADD COLUMN input_sha256 text;
+CREATE INDEX ON func_executions (input_sha256, tenancy_workspace_pk) WHERE input_sha256 IS NOT NULL;
+
+CREATE OR REPLACE FUNCTION func_execution_set_input_sha256_v1(
+    this_pk ident,
+    this_input_sha256 text,
+    OUT object json) AS
+$$
+BEGIN
+    UPDATE func_executions
+    SET input_sha256 = this_input_sha256,
+        updated_at   = clock_timestamp()
+    WHERE pk = this_pk
+    RETURNING row_to_json(func_executions.*) INTO object;
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1,48 @@
+-- Approvals are given for the contents of a change set at the time they were requested, so any
+-- write to a change set that is pending approval sends it back to being open. The approvals it
+-- collected so far no longer count, and the approval has to be requested again.
+CREATE OR REPLACE FUNCTION change_set_invalidate_approvals_v1() RETURNS trigger AS
+$$
+BEGIN
+    UPDATE change_sets
+    SET status                = 'Open',
+        approval_requested_at = NULL,
+        approval_requested_by = NULL,
+        updated_at            = clock_timestamp()
+    WHERE pk = NEW.visibility_change_set_pk
+      AND status = 'PendingApproval';
+    RETURN NULL;
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+CREATE OR REPLACE FUNCTION change_set_invalidate_approvals_trigger_create_v1(this_table_name text) RETURNS void AS
+$$
+BEGIN
+    EXECUTE format('CREATE TRIGGER %1$I '
+                   '  AFTER INSERT OR UPDATE ON %2$I '
+                   '  FOR EACH ROW '
+                   '  WHEN (NEW.visibility_change_set_pk <> ident_nil_v1()) '
+                   '  EXECUTE FUNCTION change_set_invalidate_approvals_v1()',
+                   this_table_name || '_invalidate_approvals', this_table_name);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+SELECT change_set_invalidate_approvals_trigger_create_v1(table_name)
+FROM (SELECT DISTINCT table_name FROM standard_models) AS standard_model_tables
+WHERE to_regclass(table_name) IS NOT NULL;
+
+-- Tables created by later migrations get the trigger as soon as they are registered as
+-- standard models.
+CREATE OR REPLACE FUNCTION standard_model_invalidate_approvals_v1() RETURNS trigger AS
+$$
+BEGIN
+    PERFORM change_set_invalidate_approvals_trigger_create_v1(NEW.table_name);
+    RETURN NULL;
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
+
+CREATE TRIGGER standard_models_invalidate_approvals
+    AFTER INSERT
+    ON standard_models
+    FOR EACH ROW
+EXECUTE FUNCTION standard_model_invalidate_approvals_v1();
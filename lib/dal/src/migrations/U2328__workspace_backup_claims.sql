@@ -0,0 +1,7 @@
+-- Every replica of sdf runs the backup scheduler. Before backing up a workspace, a scheduler
+-- claims it here, so that a workspace is backed up once per interval whatever the replica count.
+CREATE TABLE workspace_backup_claims
+(
+    tenancy_workspace_pk ident primary key,
+    claimed_at           timestamp with time zone NOT NULL
+);
@@ -0,0 +1,12 @@
+-- Restoring a workspace backup creates new objects for the items it restores. Their origin, the
+-- unique id the item has in the backup, is recorded here so that restoring the same item again
+-- finds the object it was restored to.
+CREATE TABLE restore_origins
+(
+    tenancy_workspace_pk ident                    NOT NULL,
+    kind                 text                     NOT NULL,
+    origin_unique_id     text                     NOT NULL,
+    object_id            ident                    NOT NULL,
+    created_at           timestamp with time zone NOT NULL DEFAULT clock_timestamp(),
+    PRIMARY KEY (tenancy_workspace_pk, kind, origin_unique_id, object_id)
+);
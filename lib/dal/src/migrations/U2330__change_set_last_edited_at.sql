@@ -0,0 +1,72 @@
+-- Approvals are no longer invalidated by a trigger on every write: jobs finishing after the
+-- approval was requested, such as dependent values updates, qualifications and code generation,
+-- sent change sets back to being open. Edits are now compared with the approval request when the
+-- change set is approved or applied.
+DROP TRIGGER IF EXISTS standard_models_invalidate_approvals ON standard_models;
+DROP FUNCTION IF EXISTS standard_model_invalidate_approvals_v1();
+
+DO
+$$
+    DECLARE
+        this_table_name text;
+    BEGIN
+        FOR this_table_name IN SELECT DISTINCT table_name
+                               FROM standard_models
+                               WHERE to_regclass(table_name) IS NOT NULL
+            LOOP
+                EXECUTE format('DROP TRIGGER IF EXISTS %1$I ON %2$I',
+                               this_table_name || '_invalidate_approvals', this_table_name);
+            END LOOP;
+    END;
+$$;
+
+DROP FUNCTION IF EXISTS change_set_invalidate_approvals_trigger_create_v1(text);
+DROP FUNCTION IF EXISTS change_set_invalidate_approvals_v1();
+
+-- When a change set was last edited, which is when a row of one of its models was last written.
+-- The values computed by functions are left out: they are written by the jobs following an edit,
+-- while an edit of a value always writes its attribute prototype too.
+CREATE OR REPLACE FUNCTION change_set_last_edited_at_v1(this_tenancy jsonb,
+                                                        this_change_set_pk ident,
+                                                        OUT last_edited_at timestamp with time zone) AS
+$$
+DECLARE
+    this_table_name           text;
+    this_table_last_edited_at timestamp with time zone;
+BEGIN
+    FOR this_table_name IN SELECT DISTINCT table_name
+                           FROM standard_models
+                           WHERE table_type = 'model'
+                             AND table_name NOT IN ('attribute_values',
+                                                    'func_bindings',
+                                                    'func_binding_return_values')
+                             AND to_regclass(table_name) IS NOT NULL
+        LOOP
+            EXECUTE format('SELECT max(updated_at) FROM %1$I '
+                           'WHERE visibility_change_set_pk = $1 '
+                           '  AND in_tenancy_v1($2, tenancy_workspace_pk)', this_table_name)
+                INTO this_table_last_edited_at
+                USING this_change_set_pk, this_tenancy;
+            last_edited_at := greatest(last_edited_at, this_table_last_edited_at);
+        END LOOP;
+END;
+$$ LANGUAGE PLPGSQL STABLE;
+
+-- Changes the status of a change set and discards its approval request, which the approvals
+-- collected so far count against.
+CREATE OR REPLACE FUNCTION change_set_discard_approval_request_v1(this_change_set_pk ident,
+                                                                  this_status text,
+                                                                  this_tenancy jsonb,
+                                                                  OUT timestamp_updated_at timestamp with time zone) AS
+$$
+BEGIN
+    UPDATE change_sets
+    SET status                = this_status,
+        approval_requested_at = NULL,
+        approval_requested_by = NULL,
+        updated_at            = clock_timestamp()
+    WHERE pk = this_change_set_pk
+      AND in_tenancy_v1(this_tenancy, change_sets.tenancy_workspace_pk)
+    RETURNING updated_at INTO timestamp_updated_at;
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1,8 @@
+-- Every replica of sdf runs the resource scheduler. Before refreshing the resources of a
+-- workspace, a scheduler claims it here, so that they are refreshed once per interval whatever
+-- the replica count.
+CREATE TABLE resource_refresh_claims
+(
+    tenancy_workspace_pk ident primary key,
+    claimed_at           timestamp with time zone NOT NULL
+);
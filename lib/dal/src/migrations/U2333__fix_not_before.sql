@@ -0,0 +1,4 @@
+-- Retried fixes used to wait out the backoff of their retry policy by sleeping in the fixes job,
+-- holding on to one of its concurrency slots. The time before which a fix may not run again is
+-- now recorded instead, so that a resumed or restarted job keeps honoring it.
+ALTER TABLE fixes ADD COLUMN not_before text;
@@ -7,10 +7,11 @@ mod import;
 
 pub use export::{get_component_type, PkgExporter};
 pub use import::{
-    import_pkg, import_pkg_from_pkg, ImportAttributeSkip, ImportEdgeSkip, ImportOptions,
-    ImportSkips,
+    import_pkg, import_pkg_from_pkg, ImportAttributeSkip, ImportConflict, ImportEdgeSkip,
+    ImportOptions, ImportSelection, ImportSkips,
 };
 
+use si_data_pg::PgError;
 use si_pkg::{FuncSpecBackendKind, FuncSpecBackendResponseType, SiPkgError, SpecError};
 
 use crate::{
@@ -29,8 +30,8 @@ use crate::{
     ComponentId, EdgeError, ExternalProviderError, ExternalProviderId, FuncBackendKind,
     FuncBackendResponseType, FuncBindingReturnValueError, FuncError, FuncId, InternalProviderError,
     InternalProviderId, NodeError, PropError, PropId, PropKind, SchemaError, SchemaId,
-    SchemaVariantError, SchemaVariantId, StandardModelError, ValidationPrototypeError,
-    WorkspaceError, WorkspacePk,
+    SchemaVariantError, SchemaVariantId, StandardModelError, TransactionsError, UserError,
+    ValidationPrototypeError, WorkspaceError, WorkspacePk,
 };
 
 #[remain::sorted]
@@ -175,6 +176,8 @@ pub enum PkgError {
     #[error("Package with that hash already installed: {0}")]
     PackageAlreadyInstalled(String),
     #[error(transparent)]
+    Pg(#[from] PgError),
+    #[error(transparent)]
     Pkg(#[from] SiPkgError),
     #[error(transparent)]
     PkgSpec(#[from] SpecError),
@@ -194,6 +197,10 @@ pub enum PkgError {
     SchemaVariantDefinition(#[from] SchemaVariantDefinitionError),
     #[error("schema variant not found: {0}")]
     SchemaVariantNotFound(SchemaVariantId),
+    #[error("selected {0} not found in workspace backup")]
+    SelectedItemNotInBackup(String),
+    #[error("only workspace backups can be restored selectively")]
+    SelectionWithoutWorkspaceBackup,
     #[error("json serialization error: {0}")]
     SerdeJson(#[from] serde_json::Error),
     #[error(transparent)]
@@ -205,9 +212,13 @@ pub enum PkgError {
     #[error("standard model relationship {0} found multiple belongs_to for {1} with id {2}")]
     StandardModelMultipleBelongsTo(&'static str, &'static str, String),
     #[error(transparent)]
+    Transactions(#[from] TransactionsError),
+    #[error(transparent)]
     UlidDecode(#[from] ulid::DecodeError),
     #[error(transparent)]
     UrlParse(#[from] ParseError),
+    #[error(transparent)]
+    User(#[from] UserError),
     #[error("Validation creation error: {0}")]
     Validation(#[from] ValidationPrototypeError),
     #[error(transparent)]
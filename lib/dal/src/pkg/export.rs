@@ -3,11 +3,11 @@ use strum::IntoEnumIterator;
 use telemetry::prelude::*;
 
 use si_pkg::{
-    ActionFuncSpec, AttrFuncInputSpec, AttrFuncInputSpecKind, AttributeValuePath,
-    AttributeValueSpec, ChangeSetSpec, ComponentSpec, ComponentSpecVariant, EdgeSpec, EdgeSpecKind,
-    FuncArgumentSpec, FuncSpec, FuncSpecData, LeafFunctionSpec, MapKeyFuncSpec, PkgSpec,
-    PositionSpec, PropSpec, PropSpecBuilder, PropSpecKind, SchemaSpec, SchemaSpecData,
-    SchemaVariantSpec, SchemaVariantSpecBuilder, SchemaVariantSpecComponentType,
+    ActionFuncRetryPolicySpec, ActionFuncSpec, AttrFuncInputSpec, AttrFuncInputSpecKind,
+    AttributeValuePath, AttributeValueSpec, ChangeSetSpec, ComponentSpec, ComponentSpecVariant,
+    EdgeSpec, EdgeSpecKind, FuncArgumentSpec, FuncSpec, FuncSpecData, LeafFunctionSpec,
+    MapKeyFuncSpec, PkgSpec, PositionSpec, PropSpec, PropSpecBuilder, PropSpecKind, SchemaSpec,
+    SchemaSpecData, SchemaVariantSpec, SchemaVariantSpecBuilder, SchemaVariantSpecComponentType,
     SchemaVariantSpecData, SchemaVariantSpecPropRoot, SiPkg, SiPkgKind, SiPropFuncSpec,
     SiPropFuncSpecKind, SocketSpec, SocketSpecData, SocketSpecKind, SpecError, ValidationSpec,
     ValidationSpecKind,
@@ -581,6 +581,10 @@ impl PkgExporter {
                 builder.unique_id(action_proto.id().to_string());
             }
 
+            if let Some(retry_policy) = action_proto.retry_policy() {
+                builder.retry_policy(ActionFuncRetryPolicySpec::from(retry_policy));
+            }
+
             specs.push(
                 builder
                     .kind(action_proto.kind())
@@ -1088,6 +1092,7 @@ impl PkgExporter {
             data_builder.backend_kind(*func.backend_kind());
 
             data_builder.hidden(func.hidden());
+            data_builder.impure(func.impure());
 
             func_spec_builder.data(data_builder.build()?);
         }
@@ -1576,6 +1581,7 @@ impl PkgExporter {
                         ChangeSetSpec::builder()
                             .name(&change_set.name)
                             .based_on_change_set("head")
+                            .source_pk(change_set.pk.to_string())
                             .funcs(remove_duplicate_func_specs(&funcs))
                             .schemas(schemas)
                             .components(components)
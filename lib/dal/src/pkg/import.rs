@@ -40,18 +40,22 @@ use crate::{
     },
     socket::SocketEdgeKind,
     validation::{Validation, ValidationKind},
-    ActionKind, ActionPrototype, ActionPrototypeContext, AttributeContext, AttributeContextBuilder,
-    AttributePrototype, AttributePrototypeArgument, AttributePrototypeId, AttributeReadContext,
-    AttributeValue, AttributeValueError, ChangeSet, ChangeSetPk, Component, ComponentId,
-    DalContext, Edge, ExternalProvider, ExternalProviderId, Func, FuncArgument, FuncBindingError,
-    FuncBindingReturnValueError, FuncError, FuncId, InternalProvider, InternalProviderId, LeafKind,
-    Node, Prop, PropId, PropKind, Schema, SchemaId, SchemaVariant, SchemaVariantError,
-    SchemaVariantId, Socket, StandardModel, Tenancy, UserPk, ValidationPrototype,
-    ValidationPrototypeContext, Workspace, WorkspacePk,
+    ActionKind, ActionPrototype, ActionPrototypeContext, ActionRetryPolicy, AttributeContext,
+    AttributeContextBuilder, AttributePrototype, AttributePrototypeArgument, AttributePrototypeId,
+    AttributeReadContext, AttributeValue, AttributeValueError, ChangeSet, ChangeSetPk, Component,
+    ComponentId, DalContext, Edge, ExternalProvider, ExternalProviderId, Func, FuncArgument,
+    FuncBindingError, FuncBindingReturnValueError, FuncError, FuncId, InternalProvider,
+    InternalProviderId, LeafKind, Node, Prop, PropId, PropKind, Schema, SchemaId, SchemaVariant,
+    SchemaVariantError, SchemaVariantId, Socket, StandardModel, Tenancy, User, UserPk,
+    ValidationPrototype, ValidationPrototypeContext, Workspace, WorkspacePermission, WorkspacePk,
 };
 
 use super::{PkgError, PkgResult};
 
+mod selection;
+
+pub use selection::{ImportConflict, ImportSelection};
+
 #[derive(Clone, Debug)]
 enum Thing {
     ActionPrototype(ActionPrototype),
@@ -75,9 +79,30 @@ pub struct ImportOptions {
     /// If set to `true`, the importer will install the assets from the module
     /// but will not make a record of the install as an "installed module".
     pub no_record: bool,
+    /// The permission the current user needs in the workspace to import the
+    /// module. Defaults to [`WorkspacePermission::InstallPackages`].
+    pub permission: Option<WorkspacePermission>,
     /// If set to `true` then we will set the functions to a builtin
     /// in the UI. They will be marked as such.
     pub is_builtin: bool,
+    /// If set, a workspace backup is restored into this workspace instead of
+    /// the one it was taken from.
+    pub workspace_pk: Option<WorkspacePk>,
+    /// If set, only the items of a workspace backup this picks are restored,
+    /// into the workspace and change set of the context, and nothing else in
+    /// there is touched.
+    pub selection: Option<ImportSelection>,
+}
+
+/// Intrinsic and builtin funcs are looked up by name instead of being imported.
+fn is_builtin_func_spec(func_spec: &SiPkgFunc<'_>) -> bool {
+    // This is a hack because the hash of the intrinsics has changed from the version in the
+    // packages. We also apply this to si:resourcePayloadToValue since it should be an
+    // intrinsic but is only in our packages
+    let special_case_funcs = ["si:resourcePayloadToValue", "si:normalizeToArray"];
+    func::is_intrinsic(func_spec.name())
+        || special_case_funcs.contains(&func_spec.name())
+        || func_spec.is_from_builtin().unwrap_or(false)
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -100,14 +125,7 @@ async fn import_change_set(
     for func_spec in funcs {
         let unique_id = func_spec.unique_id().to_string();
 
-        // This is a hack because the hash of the intrinsics has changed from the version in the
-        // packages. We also apply this to si:resourcePayloadToValue since it should be an
-        // intrinsic but is only in our packages
-        let special_case_funcs = ["si:resourcePayloadToValue", "si:normalizeToArray"];
-        if func::is_intrinsic(func_spec.name())
-            || special_case_funcs.contains(&func_spec.name())
-            || func_spec.is_from_builtin().unwrap_or(false)
-        {
+        if is_builtin_func_spec(func_spec) {
             if let Some(func) = Func::find_by_name(ctx, func_spec.name()).await? {
                 thing_map.insert(
                     change_set_pk,
@@ -1343,8 +1361,31 @@ async fn update_attribute_value(
 #[serde(rename_all = "camelCase")]
 pub struct ImportSkips {
     change_set_pk: ChangeSetPk,
+    /// The pk the change set had in the workspace the package was exported from, if the
+    /// package recorded it.
+    source_change_set_pk: Option<ChangeSetPk>,
     edge_skips: Vec<ImportEdgeSkip>,
     attribute_skips: Vec<(String, Vec<ImportAttributeSkip>)>,
+    /// Only reported when restoring a [`selection`](ImportOptions::selection).
+    conflicts: Vec<ImportConflict>,
+}
+
+impl ImportSkips {
+    /// The change set the skips happened in, in the workspace the package was imported into.
+    pub fn change_set_pk(&self) -> ChangeSetPk {
+        self.change_set_pk
+    }
+
+    /// The change set the skips happened in, in the workspace the package was exported from, if
+    /// the package recorded it.
+    pub fn source_change_set_pk(&self) -> Option<ChangeSetPk> {
+        self.source_change_set_pk
+    }
+
+    /// The selected items that were not restored because they already exist.
+    pub fn conflicts(&self) -> &[ImportConflict] {
+        &self.conflicts
+    }
 }
 
 #[remain::sorted]
@@ -1384,6 +1425,14 @@ pub async fn import_pkg_from_pkg(
 
     let options = options.unwrap_or_default();
 
+    User::ensure_authorized(
+        ctx,
+        options
+            .permission
+            .unwrap_or(WorkspacePermission::InstallPackages),
+    )
+    .await?;
+
     if InstalledPkg::find_by_hash(ctx, &root_hash).await?.is_some() {
         return Err(PkgError::PackageAlreadyInstalled(root_hash));
     }
@@ -1404,6 +1453,10 @@ pub async fn import_pkg_from_pkg(
 
     match metadata.kind() {
         SiPkgKind::Module => {
+            if options.selection.is_some() {
+                return Err(PkgError::SelectionWithoutWorkspaceBackup);
+            }
+
             let (installed_schema_variant_ids, _, _) = import_change_set(
                 ctx,
                 None,
@@ -1421,50 +1474,82 @@ pub async fn import_pkg_from_pkg(
             Ok((installed_pkg_id, installed_schema_variant_ids, None))
         }
         SiPkgKind::WorkspaceBackup => {
+            let change_sets = pkg.change_sets()?;
+            let default_change_set_name = metadata.default_change_set().unwrap_or("head");
+            let default_change_set = change_sets
+                .iter()
+                .find(|cs| cs.name() == default_change_set_name)
+                .ok_or(PkgError::WorkspaceBackupNoDefaultChangeSet(
+                    default_change_set_name.into(),
+                ))?;
+
+            if let Some(selection) = &options.selection {
+                let import_skips = selection::import_selection(
+                    ctx,
+                    &metadata,
+                    default_change_set,
+                    selection,
+                    &options,
+                )
+                .await?;
+
+                return Ok((None, vec![], Some(vec![import_skips])));
+            }
+
             let mut ctx = ctx.clone_with_new_visibility(ctx.visibility().to_head());
 
             let mut import_skips = vec![];
 
-            let workspace_pk = WorkspacePk::from_str(
-                metadata
-                    .workspace_pk()
-                    .ok_or(PkgError::WorkspacePkNotInBackup)?,
-            )?;
+            let workspace_pk = match options.workspace_pk {
+                Some(workspace_pk) => workspace_pk,
+                None => WorkspacePk::from_str(
+                    metadata
+                        .workspace_pk()
+                        .ok_or(PkgError::WorkspacePkNotInBackup)?,
+                )?,
+            };
             let workspace_name = metadata
                 .workspace_name()
                 .ok_or(PkgError::WorkspaceNameNotInBackup)?;
-            let default_change_set_name = metadata.default_change_set().unwrap_or("head");
 
             Workspace::clear_or_create_workspace(&mut ctx, workspace_pk, workspace_name).await?;
 
             ctx.update_tenancy(Tenancy::new(workspace_pk));
 
-            let change_sets = pkg.change_sets()?;
-            let default_change_set = change_sets
-                .iter()
-                .find(|cs| cs.name() == default_change_set_name)
-                .ok_or(PkgError::WorkspaceBackupNoDefaultChangeSet(
-                    default_change_set_name.into(),
-                ))?;
-
+            let funcs = default_change_set.funcs()?;
+            let schemas = default_change_set.schemas()?;
+            let components = default_change_set.components()?;
+            let edges = default_change_set.edges()?;
             let (_, attribute_skips, edge_skips) = import_change_set(
                 &ctx,
                 Some(ChangeSetPk::NONE),
                 &metadata,
-                &default_change_set.funcs()?,
-                &default_change_set.schemas()?,
-                &default_change_set.components()?,
-                &default_change_set.edges()?,
+                &funcs,
+                &schemas,
+                &components,
+                &edges,
                 installed_pkg_id,
                 &mut change_set_things,
                 &options,
             )
             .await?;
+            selection::record_origins(
+                &ctx,
+                Some(ChangeSetPk::NONE),
+                &change_set_things,
+                &funcs,
+                &schemas,
+                &components,
+                &edges,
+            )
+            .await?;
 
             import_skips.push(ImportSkips {
                 change_set_pk: ChangeSetPk::NONE,
+                source_change_set_pk: Some(ChangeSetPk::NONE),
                 attribute_skips,
                 edge_skips,
+                conflicts: vec![],
             });
 
             for change_set in change_sets {
@@ -1478,24 +1563,40 @@ pub async fn import_pkg_from_pkg(
                 // Switch to new change set visibility
                 let ctx = ctx.clone_with_new_visibility(ctx.visibility().to_change_set(new_cs.pk));
 
+                let funcs = change_set.funcs()?;
+                let schemas = change_set.schemas()?;
+                let components = change_set.components()?;
+                let edges = change_set.edges()?;
                 let (_, attribute_skips, edge_skips) = import_change_set(
                     &ctx,
                     Some(new_cs.pk),
                     &metadata,
-                    &change_set.funcs()?,
-                    &change_set.schemas()?,
-                    &change_set.components()?,
-                    &change_set.edges()?,
+                    &funcs,
+                    &schemas,
+                    &components,
+                    &edges,
                     installed_pkg_id,
                     &mut change_set_things,
                     &options,
                 )
                 .await?;
+                selection::record_origins(
+                    &ctx,
+                    Some(new_cs.pk),
+                    &change_set_things,
+                    &funcs,
+                    &schemas,
+                    &components,
+                    &edges,
+                )
+                .await?;
 
                 import_skips.push(ImportSkips {
                     change_set_pk: new_cs.pk,
+                    source_change_set_pk: change_set.source_pk().map(str::parse).transpose()?,
                     attribute_skips,
                     edge_skips,
+                    conflicts: vec![],
                 });
             }
 
@@ -1545,6 +1646,7 @@ async fn create_func(ctx: &DalContext, func_spec: &SiPkgFunc<'_>) -> PkgResult<F
     func.set_handler(ctx, Some(func_spec_data.handler()))
         .await?;
     func.set_hidden(ctx, func_spec_data.hidden()).await?;
+    func.set_impure(ctx, func_spec_data.impure()).await?;
     func.set_link(ctx, func_spec_data.link().map(|l| l.to_string()))
         .await?;
 
@@ -1570,6 +1672,7 @@ async fn update_func(
     func.set_handler(ctx, Some(func_spec_data.handler()))
         .await?;
     func.set_hidden(ctx, func_spec_data.hidden()).await?;
+    func.set_impure(ctx, func_spec_data.impure()).await?;
     func.set_link(ctx, func_spec_data.link().map(|l| l.to_string()))
         .await?;
 
@@ -2309,6 +2412,12 @@ async fn create_action_protoype(
         proto.set_name(ctx, Some(name)).await?;
     }
 
+    if let Some(retry_policy) = action_func_spec.retry_policy() {
+        proto
+            .set_retry_policy(ctx, Some(ActionRetryPolicy::from(retry_policy)))
+            .await?;
+    }
+
     Ok(proto)
 }
 
@@ -2338,6 +2447,11 @@ async fn update_action_prototype(
         prototype.set_kind(ctx, kind).await?;
     }
 
+    let retry_policy = action_func_spec.retry_policy().map(ActionRetryPolicy::from);
+    if prototype.retry_policy() != retry_policy.as_ref() {
+        prototype.set_retry_policy(ctx, retry_policy).await?;
+    }
+
     Ok(())
 }
 
@@ -0,0 +1,532 @@
+//! Selective restores of workspace backups, see [`ImportOptions::selection`].
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::str::FromStr;
+
+use postgres_types::{FromSql, ToSql};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use si_pkg::{
+    ComponentSpecVariant, PropSpec, SchemaSpec, SchemaVariantSpec, SiPkgChangeSet, SiPkgComponent,
+    SiPkgEdge, SiPkgFunc, SiPkgMetadata, SiPkgSchema, ValidationSpec,
+};
+use strum::AsRefStr;
+
+use crate::{
+    ChangeSetPk, Component, DalContext, Edge, Func, Schema, SchemaVariant, StandardModel,
+    WorkspaceError, WorkspacePk,
+};
+
+use super::{
+    import_change_set, is_builtin_func_spec, ImportOptions, ImportSkips, PkgError, PkgResult,
+    Thing, ThingMap,
+};
+
+const FIND_RESTORED_OBJECT_IDS: &str =
+    include_str!("../../queries/restore_origin/find_object_ids.sql");
+const RECORD_RESTORE_ORIGIN: &str = include_str!("../../queries/restore_origin/record.sql");
+
+/// The name of the sockets connecting frames to the components inside of them.
+const FRAME_SOCKET_NAME: &str = "Frame";
+
+/// The items of a workspace backup to restore. Each of them is restored along with what it
+/// depends on: the schemas components are made of, the funcs schemas and components use, and
+/// the components inside of frames.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportSelection {
+    /// The unique ids of the components to restore.
+    #[serde(default)]
+    pub components: Vec<String>,
+    /// The names of the schemas to restore.
+    #[serde(default)]
+    pub schemas: Vec<String>,
+    /// The names of the funcs to restore.
+    #[serde(default)]
+    pub funcs: Vec<String>,
+}
+
+/// An item of a selective restore that already exists where it is restored, either under the
+/// id it was backed up with or as the object an earlier restore created from it. It is left as
+/// it is, and the restored items depending on it use it as it is.
+#[remain::sorted]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ImportConflict {
+    #[serde(rename_all = "camelCase")]
+    Component { unique_id: String, name: String },
+    #[serde(rename_all = "camelCase")]
+    Edge { unique_id: String },
+    #[serde(rename_all = "camelCase")]
+    Func { unique_id: String, name: String },
+    #[serde(rename_all = "camelCase")]
+    Schema { unique_id: String, name: String },
+}
+
+/// The unique ids of the items of a change set a selection picks, dependencies included.
+#[derive(Debug, Default)]
+struct Picked {
+    funcs: HashSet<String>,
+    schemas: HashSet<String>,
+    components: HashSet<String>,
+    edges: HashSet<String>,
+}
+
+/// Restores the items of the change set that the selection picks into the workspace and change
+/// set of the context.
+pub(super) async fn import_selection(
+    ctx: &DalContext,
+    metadata: &SiPkgMetadata,
+    change_set: &SiPkgChangeSet<'_>,
+    selection: &ImportSelection,
+    options: &ImportOptions,
+) -> PkgResult<ImportSkips> {
+    let funcs = change_set.funcs()?;
+    let schemas = change_set.schemas()?;
+    let components = change_set.components()?;
+    let edges = change_set.edges()?;
+    let picked = pick(selection, &funcs, &schemas, &components, &edges).await?;
+
+    let change_set_pk = ctx.visibility().change_set_pk;
+    let mut thing_map = ThingMap::new();
+    let mut conflicts = vec![];
+
+    let mut import_funcs = vec![];
+    for func_spec in funcs {
+        let unique_id = func_spec.unique_id().to_owned();
+        if !picked.funcs.contains(&unique_id) {
+            continue;
+        }
+        let existing = if is_builtin_func_spec(&func_spec) {
+            None
+        } else {
+            find_existing::<Func>(ctx, OriginKind::Func, &unique_id).await?
+        };
+        match existing {
+            Some(func) => {
+                conflicts.push(ImportConflict::Func {
+                    unique_id: unique_id.to_owned(),
+                    name: func_spec.name().to_owned(),
+                });
+                thing_map.insert(Some(change_set_pk), unique_id, Thing::Func(func));
+            }
+            None => import_funcs.push(func_spec),
+        }
+    }
+
+    let mut import_schemas = vec![];
+    for schema_spec in schemas {
+        let unique_id = match schema_spec.unique_id() {
+            Some(unique_id) if picked.schemas.contains(unique_id) => unique_id.to_owned(),
+            _ => continue,
+        };
+        match find_existing::<Schema>(ctx, OriginKind::Schema, &unique_id).await? {
+            Some(schema) => {
+                for variant_spec in schema_spec.variants()? {
+                    let variant_unique_id = match variant_spec.unique_id() {
+                        Some(variant_unique_id) => variant_unique_id,
+                        None => continue,
+                    };
+                    if let Some(variant) = find_existing::<SchemaVariant>(
+                        ctx,
+                        OriginKind::SchemaVariant,
+                        variant_unique_id,
+                    )
+                    .await?
+                    {
+                        thing_map.insert(
+                            Some(change_set_pk),
+                            variant_unique_id.to_owned(),
+                            Thing::SchemaVariant(variant),
+                        );
+                    }
+                }
+                conflicts.push(ImportConflict::Schema {
+                    unique_id: unique_id.to_owned(),
+                    name: schema_spec.name().to_owned(),
+                });
+                thing_map.insert(Some(change_set_pk), unique_id, Thing::Schema(schema));
+            }
+            None => import_schemas.push(schema_spec),
+        }
+    }
+
+    let mut import_components = vec![];
+    for component_spec in components {
+        let unique_id = component_spec.unique_id().to_owned();
+        if !picked.components.contains(&unique_id) {
+            continue;
+        }
+        match find_existing::<Component>(ctx, OriginKind::Component, &unique_id).await? {
+            Some(component) => {
+                let node = component
+                    .node(ctx)
+                    .await?
+                    .pop()
+                    .ok_or(PkgError::ComponentMissingNode(*component.id()))?;
+                conflicts.push(ImportConflict::Component {
+                    unique_id: unique_id.to_owned(),
+                    name: component_spec.name().to_owned(),
+                });
+                thing_map.insert(
+                    Some(change_set_pk),
+                    unique_id,
+                    Thing::Component((component, node)),
+                );
+            }
+            None => import_components.push(component_spec),
+        }
+    }
+
+    let mut import_edges = vec![];
+    for edge_spec in edges {
+        let unique_id = edge_spec.unique_id().to_owned();
+        if !picked.edges.contains(&unique_id) {
+            continue;
+        }
+        match find_existing::<Edge>(ctx, OriginKind::Edge, &unique_id).await? {
+            Some(_) => conflicts.push(ImportConflict::Edge { unique_id }),
+            None => import_edges.push(edge_spec),
+        }
+    }
+
+    let (_, attribute_skips, edge_skips) = import_change_set(
+        ctx,
+        Some(change_set_pk),
+        metadata,
+        &import_funcs,
+        &import_schemas,
+        &import_components,
+        &import_edges,
+        None,
+        &mut thing_map,
+        options,
+    )
+    .await?;
+    record_origins(
+        ctx,
+        Some(change_set_pk),
+        &thing_map,
+        &import_funcs,
+        &import_schemas,
+        &import_components,
+        &import_edges,
+    )
+    .await?;
+
+    Ok(ImportSkips {
+        change_set_pk,
+        source_change_set_pk: change_set.source_pk().map(str::parse).transpose()?,
+        edge_skips,
+        attribute_skips,
+        conflicts,
+    })
+}
+
+/// The kinds of objects a restore records the origin of, see [`record_origins`].
+#[derive(AsRefStr, Clone, Copy, Debug)]
+#[strum(serialize_all = "snake_case")]
+enum OriginKind {
+    Component,
+    Edge,
+    Func,
+    Schema,
+    SchemaVariant,
+}
+
+/// Finds the object an item of a workspace backup exists as in the workspace and change set of
+/// the context: either the object the unique id of the item is the id of, or the most recent
+/// object a restore created from the item.
+async fn find_existing<T>(
+    ctx: &DalContext,
+    kind: OriginKind,
+    unique_id: &str,
+) -> PkgResult<Option<T>>
+where
+    T: StandardModel + DeserializeOwned + Send,
+    T::Id: FromStr + for<'a> FromSql<'a>,
+{
+    let mut ids: Vec<T::Id> = unique_id.parse().ok().into_iter().collect();
+    let rows = ctx
+        .txns()
+        .await?
+        .pg()
+        .query(
+            FIND_RESTORED_OBJECT_IDS,
+            &[&workspace_pk(ctx)?, &kind.as_ref(), &unique_id],
+        )
+        .await?;
+    for row in rows {
+        ids.push(row.try_get("object_id")?);
+    }
+
+    for id in ids {
+        if let Some(object) = T::get_by_id(ctx, &id).await? {
+            return Ok(Some(object));
+        }
+    }
+    Ok(None)
+}
+
+/// Records the unique ids of the imported items as the origin of the objects they were imported
+/// as, so that restoring them again finds these objects, see [`find_existing`].
+pub(super) async fn record_origins(
+    ctx: &DalContext,
+    change_set_pk: Option<ChangeSetPk>,
+    thing_map: &ThingMap,
+    funcs: &[SiPkgFunc<'_>],
+    schemas: &[SiPkgSchema<'_>],
+    components: &[SiPkgComponent<'_>],
+    edges: &[SiPkgEdge<'_>],
+) -> PkgResult<()> {
+    let mut unique_ids = vec![];
+    unique_ids.extend(
+        funcs
+            .iter()
+            .filter(|func| !is_builtin_func_spec(func))
+            .map(|func| func.unique_id().to_owned()),
+    );
+    for schema in schemas {
+        unique_ids.extend(schema.unique_id().map(ToOwned::to_owned));
+        for variant in schema.variants()? {
+            unique_ids.extend(variant.unique_id().map(ToOwned::to_owned));
+        }
+    }
+    unique_ids.extend(
+        components
+            .iter()
+            .map(|component| component.unique_id().to_owned()),
+    );
+    unique_ids.extend(edges.iter().map(|edge| edge.unique_id().to_owned()));
+
+    let workspace_pk = workspace_pk(ctx)?;
+    for unique_id in unique_ids {
+        let (kind, object_id): (OriginKind, &(dyn ToSql + Sync)) =
+            match thing_map.get(change_set_pk, &unique_id) {
+                Some(Thing::Func(func)) => (OriginKind::Func, func.id()),
+                Some(Thing::Schema(schema)) => (OriginKind::Schema, schema.id()),
+                Some(Thing::SchemaVariant(variant)) => (OriginKind::SchemaVariant, variant.id()),
+                Some(Thing::Component((component, _))) => (OriginKind::Component, component.id()),
+                Some(Thing::Edge(edge)) => (OriginKind::Edge, edge.id()),
+                _ => continue,
+            };
+        ctx.txns()
+            .await?
+            .pg()
+            .execute(
+                RECORD_RESTORE_ORIGIN,
+                &[&workspace_pk, &kind.as_ref(), &unique_id, object_id],
+            )
+            .await?;
+    }
+
+    Ok(())
+}
+
+fn workspace_pk(ctx: &DalContext) -> PkgResult<WorkspacePk> {
+    Ok(ctx
+        .tenancy()
+        .workspace_pk()
+        .ok_or(WorkspaceError::NoWorkspaceInTenancy)?)
+}
+
+/// Picks the items of a change set the selection asks for, along with what they depend on.
+async fn pick(
+    selection: &ImportSelection,
+    funcs: &[SiPkgFunc<'_>],
+    schemas: &[SiPkgSchema<'_>],
+    components: &[SiPkgComponent<'_>],
+    edges: &[SiPkgEdge<'_>],
+) -> PkgResult<Picked> {
+    let mut picked = Picked::default();
+
+    let mut children: HashMap<&str, Vec<&str>> = HashMap::new();
+    for edge in edges.iter().filter(|edge| {
+        !edge.deleted()
+            && edge.to_socket_name() == FRAME_SOCKET_NAME
+            && edge.from_socket_name() == FRAME_SOCKET_NAME
+    }) {
+        children
+            .entry(edge.to_component_unique_id())
+            .or_default()
+            .push(edge.from_component_unique_id());
+    }
+    let mut queue = VecDeque::new();
+    for unique_id in &selection.components {
+        if !components
+            .iter()
+            .any(|component| component.unique_id() == unique_id)
+        {
+            return Err(PkgError::SelectedItemNotInBackup(format!(
+                "component {unique_id}"
+            )));
+        }
+        queue.push_back(unique_id.as_str());
+    }
+    while let Some(unique_id) = queue.pop_front() {
+        if picked.components.insert(unique_id.to_owned()) {
+            queue.extend(children.get(unique_id).into_iter().flatten());
+        }
+    }
+    picked.edges = edges
+        .iter()
+        .filter(|edge| {
+            picked.components.contains(edge.from_component_unique_id())
+                && picked.components.contains(edge.to_component_unique_id())
+        })
+        .map(|edge| edge.unique_id().to_owned())
+        .collect();
+
+    let mut schema_specs = Vec::with_capacity(schemas.len());
+    for schema in schemas {
+        schema_specs.push(schema.to_spec().await?);
+    }
+    let mut picked_schema_specs = vec![];
+    for name in &selection.schemas {
+        let schema_spec = schema_specs
+            .iter()
+            .find(|schema_spec| schema_spec.name == *name)
+            .ok_or_else(|| PkgError::SelectedItemNotInBackup(format!("schema {name}")))?;
+        picked_schema_specs.push(schema_spec);
+    }
+    for component in components
+        .iter()
+        .filter(|component| picked.components.contains(component.unique_id()))
+    {
+        if let ComponentSpecVariant::WorkspaceVariant { variant_unique_id } = component.variant() {
+            let schema_spec = schema_specs
+                .iter()
+                .find(|schema_spec| {
+                    schema_spec.variants.iter().any(|variant_spec| {
+                        variant_spec.unique_id.as_deref() == Some(variant_unique_id.as_str())
+                    })
+                })
+                .ok_or_else(|| {
+                    PkgError::ComponentMissingSchemaVariant(
+                        variant_unique_id.to_owned(),
+                        component.name().to_owned(),
+                    )
+                })?;
+            picked_schema_specs.push(schema_spec);
+        }
+
+        let attributes = [
+            component.attributes()?,
+            component.input_sockets()?,
+            component.output_sockets()?,
+        ];
+        picked.funcs.extend(
+            attributes
+                .iter()
+                .flatten()
+                .map(|attribute| attribute.func_unique_id().to_owned()),
+        );
+    }
+    for schema_spec in picked_schema_specs {
+        let unique_id = schema_spec.unique_id.as_ref().ok_or_else(|| {
+            PkgError::MissingUniqueIdForNode(format!("schema {}", schema_spec.name))
+        })?;
+        picked.schemas.insert(unique_id.to_owned());
+        schema_func_unique_ids(schema_spec, &mut picked.funcs);
+    }
+
+    for name in &selection.funcs {
+        let func_spec = funcs
+            .iter()
+            .find(|func_spec| func_spec.name() == name)
+            .ok_or_else(|| PkgError::SelectedItemNotInBackup(format!("func {name}")))?;
+        picked.funcs.insert(func_spec.unique_id().to_owned());
+    }
+
+    Ok(picked)
+}
+
+/// Collects the unique ids of the funcs the schema uses.
+fn schema_func_unique_ids(schema_spec: &SchemaSpec, func_unique_ids: &mut HashSet<String>) {
+    for variant_spec in &schema_spec.variants {
+        variant_func_unique_ids(variant_spec, func_unique_ids);
+    }
+}
+
+fn variant_func_unique_ids(
+    variant_spec: &SchemaVariantSpec,
+    func_unique_ids: &mut HashSet<String>,
+) {
+    if let Some(data) = &variant_spec.data {
+        func_unique_ids.insert(data.func_unique_id.to_owned());
+    }
+    func_unique_ids.extend(
+        variant_spec
+            .action_funcs
+            .iter()
+            .map(|action_func| action_func.func_unique_id.to_owned()),
+    );
+    func_unique_ids.extend(
+        variant_spec
+            .leaf_functions
+            .iter()
+            .map(|leaf_function| leaf_function.func_unique_id.to_owned()),
+    );
+    func_unique_ids.extend(
+        variant_spec
+            .si_prop_funcs
+            .iter()
+            .map(|si_prop_func| si_prop_func.func_unique_id.to_owned()),
+    );
+    func_unique_ids.extend(
+        variant_spec
+            .sockets
+            .iter()
+            .filter_map(|socket| socket.data.as_ref()?.func_unique_id.to_owned()),
+    );
+
+    let mut props = vec![
+        &variant_spec.domain,
+        &variant_spec.secrets,
+        &variant_spec.resource_value,
+    ];
+    props.extend(&variant_spec.secret_definition);
+    while let Some(prop) = props.pop() {
+        let data = match prop {
+            PropSpec::Array {
+                data, type_prop, ..
+            } => {
+                props.push(type_prop);
+                data
+            }
+            PropSpec::Map {
+                data,
+                type_prop,
+                map_key_funcs,
+                ..
+            } => {
+                props.push(type_prop);
+                func_unique_ids.extend(
+                    map_key_funcs
+                        .iter()
+                        .flatten()
+                        .map(|map_key_func| map_key_func.func_unique_id.to_owned()),
+                );
+                data
+            }
+            PropSpec::Object { data, entries, .. } => {
+                props.extend(entries);
+                data
+            }
+            PropSpec::Boolean { data, .. }
+            | PropSpec::Number { data, .. }
+            | PropSpec::String { data, .. } => data,
+        };
+
+        if let Some(data) = data {
+            func_unique_ids.extend(data.func_unique_id.to_owned());
+            func_unique_ids.extend(data.validations.iter().flatten().filter_map(|validation| {
+                match validation {
+                    ValidationSpec::CustomValidation { func_unique_id, .. } => {
+                        Some(func_unique_id.to_owned())
+                    }
+                    _ => None,
+                }
+            }));
+        }
+    }
+}
@@ -0,0 +1,10 @@
+SELECT row_to_json(action_schedules.*) AS object
+FROM action_schedules
+-- Like list_due, this bypasses tenancy checks on purpose. The row lock keeps concurrent
+-- schedulers from enqueueing the same run twice
+WHERE action_schedules.id = $1
+  AND action_schedules.visibility_change_set_pk = ident_nil_v1()
+  AND action_schedules.visibility_deleted_at IS NULL
+  AND NOT action_schedules.paused
+  AND action_schedules.next_run_at <= $2
+FOR UPDATE SKIP LOCKED
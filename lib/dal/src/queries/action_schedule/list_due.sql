@@ -0,0 +1,9 @@
+SELECT row_to_json(action_schedules.*) AS object
+FROM action_schedules
+-- We bypass tenancy checks on purpose: every workspace's schedules run from the same task
+WHERE action_schedules.visibility_change_set_pk = ident_nil_v1()
+  AND action_schedules.visibility_deleted_at IS NULL
+  AND action_schedules.tenancy_workspace_pk IS NOT NULL
+  AND NOT action_schedules.paused
+  AND action_schedules.next_run_at <= $1
+ORDER BY action_schedules.next_run_at, action_schedules.id
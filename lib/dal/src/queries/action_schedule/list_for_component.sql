@@ -0,0 +1,4 @@
+SELECT row_to_json(action_schedules.*) AS object
+FROM action_schedules_v1($1, $2) AS action_schedules
+WHERE action_schedules.component_id = $3
+ORDER BY action_schedules.next_run_at, action_schedules.id
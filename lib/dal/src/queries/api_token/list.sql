@@ -0,0 +1,5 @@
+SELECT row_to_json(api_tokens.*) AS object
+FROM api_tokens
+WHERE in_tenancy_v1($1, api_tokens.tenancy_workspace_pk)
+  AND ($2::ident IS NULL OR api_tokens.user_pk = $2)
+ORDER BY api_tokens.created_at DESC, api_tokens.pk DESC
@@ -0,0 +1,6 @@
+SELECT row_to_json(change_sets.*) AS object
+FROM change_sets
+WHERE
+    status = 'Closed'
+    AND in_tenancy_v1($1, change_sets.tenancy_workspace_pk)
+ORDER BY change_sets.pk
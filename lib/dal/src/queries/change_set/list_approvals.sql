@@ -0,0 +1,6 @@
+SELECT row_to_json(change_set_approvals.*) AS object
+FROM change_set_approvals
+WHERE in_tenancy_v1($1, change_set_approvals.tenancy_workspace_pk)
+  AND change_set_approvals.change_set_pk = $2
+  AND change_set_approvals.created_at >= $3
+ORDER BY change_set_approvals.created_at ASC
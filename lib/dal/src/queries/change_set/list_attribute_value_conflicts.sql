@@ -0,0 +1,34 @@
+SELECT change_set_values.id                             AS attribute_value_id,
+       change_set_values.attribute_context_component_id AS component_id,
+       change_set_values.attribute_context_prop_id      AS prop_id,
+       head_fbrvs.value                                 AS head_value,
+       change_set_fbrvs.value                           AS change_set_value,
+       head_values.updated_at                           AS head_updated_at,
+       change_set_values.updated_at                     AS change_set_updated_at
+FROM attribute_values AS change_set_values
+
+         -- Only look at values that exist on HEAD and that HEAD has touched since the change set
+         -- was last based on it
+         INNER JOIN attribute_values AS head_values
+                    ON head_values.id = change_set_values.id
+                        AND head_values.visibility_change_set_pk = ident_nil_v1()
+                        AND head_values.visibility_deleted_at IS NULL
+                        AND in_tenancy_v1($1, head_values.tenancy_workspace_pk)
+                        AND head_values.updated_at > $3
+
+         LEFT JOIN func_binding_return_values_v1($1, $4) AS head_fbrvs
+                   ON head_fbrvs.id = head_values.func_binding_return_value_id
+
+         LEFT JOIN func_binding_return_values_v1($1, $5) AS change_set_fbrvs
+                   ON change_set_fbrvs.id = change_set_values.func_binding_return_value_id
+
+WHERE change_set_values.visibility_change_set_pk = $2
+  AND change_set_values.visibility_deleted_at IS NULL
+  AND in_tenancy_v1($1, change_set_values.tenancy_workspace_pk)
+
+  -- If both sides landed on the same value, there is nothing to resolve
+  AND head_fbrvs.value IS DISTINCT FROM change_set_fbrvs.value
+
+ORDER BY change_set_values.attribute_context_component_id,
+         change_set_values.attribute_context_prop_id,
+         change_set_values.id
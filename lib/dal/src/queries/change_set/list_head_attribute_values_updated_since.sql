@@ -0,0 +1,6 @@
+SELECT DISTINCT attribute_values.id
+FROM attribute_values
+WHERE attribute_values.visibility_change_set_pk = ident_nil_v1()
+  AND attribute_values.visibility_deleted_at IS NULL
+  AND in_tenancy_v1($1, attribute_values.tenancy_workspace_pk)
+  AND attribute_values.updated_at > $2
@@ -1,5 +1,6 @@
 SELECT row_to_json(change_sets.*) AS object
 FROM change_sets
 WHERE
-    status = 'Open'
+    status IN ('Open', 'PendingApproval')
     AND in_tenancy_v1($1, change_sets.tenancy_workspace_pk)
+ORDER BY change_sets.pk
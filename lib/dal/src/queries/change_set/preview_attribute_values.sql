@@ -0,0 +1,31 @@
+SELECT change_set_values.id                             AS attribute_value_id,
+       change_set_values.attribute_context_component_id AS component_id,
+       change_set_values.attribute_context_prop_id      AS prop_id,
+       CASE
+           WHEN change_set_values.visibility_deleted_at IS NOT NULL THEN 'deleted'
+           WHEN head_values.id IS NULL THEN 'added'
+           ELSE 'modified'
+           END                                          AS change_status,
+       COALESCE(head_values.updated_at > $3
+                    AND head_fbrvs.value IS DISTINCT FROM change_set_fbrvs.value,
+                FALSE)                                  AS conflict
+FROM attribute_values AS change_set_values
+
+         LEFT JOIN attribute_values AS head_values
+                   ON head_values.id = change_set_values.id
+                       AND head_values.visibility_change_set_pk = ident_nil_v1()
+                       AND in_tenancy_v1($1, head_values.tenancy_workspace_pk)
+
+         LEFT JOIN func_binding_return_values_v1($1, $4) AS head_fbrvs
+                   ON head_fbrvs.id = head_values.func_binding_return_value_id
+
+         LEFT JOIN func_binding_return_values_v1($1, $5) AS change_set_fbrvs
+                   ON change_set_fbrvs.id = change_set_values.func_binding_return_value_id
+
+WHERE change_set_values.visibility_change_set_pk = $2
+  AND in_tenancy_v1($1, change_set_values.tenancy_workspace_pk)
+  AND NOT (change_set_values.visibility_deleted_at IS NOT NULL AND head_values.id IS NULL)
+
+ORDER BY change_set_values.attribute_context_component_id,
+         change_set_values.attribute_context_prop_id,
+         change_set_values.id
@@ -0,0 +1,34 @@
+SELECT change_set_rows.id,
+       CASE
+           WHEN change_set_rows.visibility_deleted_at IS NOT NULL THEN 'deleted'
+           WHEN head_rows.id IS NULL THEN 'added'
+           ELSE 'modified'
+           END                             AS change_status,
+       -- HEAD touching a row is only a conflict if it no longer matches the change set's row,
+       -- leaving out the columns that differ between any two rows of the same object
+       COALESCE(head_rows.updated_at > $3
+                    AND ((head_rows.visibility_deleted_at IS NULL)
+                             IS DISTINCT FROM (change_set_rows.visibility_deleted_at IS NULL)
+                        OR to_jsonb(head_rows) - ARRAY ['pk', 'visibility_change_set_pk',
+                                                        'visibility_deleted_at', 'created_at',
+                                                        'updated_at']
+                             IS DISTINCT FROM to_jsonb(change_set_rows)
+                                 - ARRAY ['pk', 'visibility_change_set_pk',
+                                          'visibility_deleted_at', 'created_at', 'updated_at']),
+                FALSE)                     AS conflict
+FROM components AS change_set_rows
+
+         -- HEAD rows are matched regardless of deletion, since HEAD deleting a row after the
+         -- change set was based on it is a conflict as well
+         LEFT JOIN components AS head_rows
+                   ON head_rows.id = change_set_rows.id
+                       AND head_rows.visibility_change_set_pk = ident_nil_v1()
+                       AND in_tenancy_v1($1, head_rows.tenancy_workspace_pk)
+
+WHERE change_set_rows.visibility_change_set_pk = $2
+  AND in_tenancy_v1($1, change_set_rows.tenancy_workspace_pk)
+
+  -- Rows both created and deleted within the change set never reach HEAD
+  AND NOT (change_set_rows.visibility_deleted_at IS NOT NULL AND head_rows.id IS NULL)
+
+ORDER BY change_set_rows.id
@@ -0,0 +1,7 @@
+INSERT INTO resource_refresh_claims (tenancy_workspace_pk, claimed_at)
+VALUES ($1, clock_timestamp())
+-- Only one of the concurrent claims of the row wins, the others find it claimed too recently
+ON CONFLICT (tenancy_workspace_pk) DO UPDATE
+    SET claimed_at = EXCLUDED.claimed_at
+WHERE resource_refresh_claims.claimed_at <= EXCLUDED.claimed_at - make_interval(secs => $2)
+RETURNING claimed_at
@@ -0,0 +1,24 @@
+SELECT DISTINCT ON (components.id) components.id                   AS component_id,
+                                   components.tenancy_workspace_pk AS workspace_pk
+FROM components
+
+         INNER JOIN component_belongs_to_schema_variant
+                    ON component_belongs_to_schema_variant.object_id = components.id
+                        AND component_belongs_to_schema_variant.tenancy_workspace_pk =
+                            components.tenancy_workspace_pk
+                        AND component_belongs_to_schema_variant.visibility_change_set_pk = ident_nil_v1()
+                        AND component_belongs_to_schema_variant.visibility_deleted_at IS NULL
+
+         INNER JOIN action_prototypes
+                    ON action_prototypes.schema_variant_id = component_belongs_to_schema_variant.belongs_to_id
+                        AND action_prototypes.tenancy_workspace_pk = components.tenancy_workspace_pk
+                        AND action_prototypes.kind = 'refresh'
+                        AND action_prototypes.visibility_change_set_pk = ident_nil_v1()
+                        AND action_prototypes.visibility_deleted_at IS NULL
+
+-- We bypass tenancy checks on purpose: every workspace's head components are refreshed
+WHERE components.visibility_change_set_pk = ident_nil_v1()
+  AND components.tenancy_workspace_pk IS NOT NULL
+  AND (components.visibility_deleted_at IS NULL OR components.needs_destroy)
+
+ORDER BY components.id
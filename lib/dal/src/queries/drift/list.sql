@@ -0,0 +1,4 @@
+SELECT row_to_json(component_drift_statuses.*) AS object
+FROM component_drift_statuses
+WHERE in_tenancy_v1($1, component_drift_statuses.tenancy_workspace_pk)
+ORDER BY component_drift_statuses.component_id
@@ -0,0 +1,4 @@
+SELECT row_to_json(fix_runs.*) AS object
+FROM fix_runs
+WHERE in_tenancy_v1($1, fix_runs.tenancy_workspace_pk)
+  AND fix_runs.pk = $2
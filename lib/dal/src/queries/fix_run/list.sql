@@ -0,0 +1,9 @@
+SELECT row_to_json(fix_runs.*) AS object
+FROM fix_runs
+WHERE in_tenancy_v1($1, fix_runs.tenancy_workspace_pk)
+  AND ($2::ident IS NULL OR fix_runs.component_id = $2)
+  AND ($3::ident IS NULL OR fix_runs.fix_batch_id = $3)
+  AND ($4::timestamp with time zone IS NULL OR fix_runs.started_at >= $4)
+  AND ($5::timestamp with time zone IS NULL OR fix_runs.started_at < $5)
+ORDER BY fix_runs.started_at DESC, fix_runs.pk DESC
+LIMIT $6
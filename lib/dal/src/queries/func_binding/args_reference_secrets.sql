@@ -0,0 +1,3 @@
+SELECT EXISTS(SELECT 1
+              FROM encrypted_secrets
+              WHERE id::text = ANY ($1)) AS references_secrets
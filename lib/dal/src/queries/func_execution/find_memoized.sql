@@ -0,0 +1,7 @@
+SELECT row_to_json(func_executions.*) AS object
+FROM func_executions
+WHERE in_tenancy_v1($1, func_executions.tenancy_workspace_pk)
+  AND func_executions.input_sha256 = $2
+  AND func_executions.state = 'Success'
+ORDER BY func_executions.updated_at DESC
+LIMIT 1
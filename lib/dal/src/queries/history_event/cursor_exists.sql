@@ -0,0 +1,4 @@
+SELECT EXISTS(SELECT 1
+              FROM history_events
+              WHERE history_events.pk = $2
+                AND in_tenancy_v1($1, history_events.tenancy_workspace_pk)) AS exists
@@ -0,0 +1,15 @@
+SELECT row_to_json(history_events.*) AS object
+FROM history_events
+WHERE in_tenancy_v1($1, history_events.tenancy_workspace_pk)
+  AND ($2::jsonb IS NULL OR history_events.actor = $2)
+  AND ($3::text IS NULL OR left(history_events.label, length($3)) = $3)
+  AND ($4::timestamp with time zone IS NULL OR history_events.created_at >= $4)
+  AND ($5::timestamp with time zone IS NULL OR history_events.created_at < $5)
+  AND ($6::ident IS NULL OR history_events.change_set_pk = $6)
+  AND ($7::ident IS NULL OR (history_events.created_at, history_events.pk) <
+                            (SELECT cursor_events.created_at, cursor_events.pk
+                             FROM history_events AS cursor_events
+                             WHERE cursor_events.pk = $7
+                               AND in_tenancy_v1($1, cursor_events.tenancy_workspace_pk)))
+ORDER BY history_events.created_at DESC, history_events.pk DESC
+LIMIT $8
@@ -0,0 +1,4 @@
+SELECT row_to_json(key_pairs.*) AS object
+FROM key_pairs
+WHERE key_pairs.workspace_pk = $1 AND key_pairs.visibility_deleted_at IS NULL
+ORDER BY key_pairs.created_lamport_clock
@@ -0,0 +1,6 @@
+SELECT object_id
+FROM restore_origins
+WHERE tenancy_workspace_pk = $1
+  AND kind = $2
+  AND origin_unique_id = $3
+ORDER BY created_at DESC
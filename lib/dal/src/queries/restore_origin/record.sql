@@ -0,0 +1,3 @@
+INSERT INTO restore_origins (tenancy_workspace_pk, kind, origin_unique_id, object_id)
+VALUES ($1, $2, $3, $4)
+ON CONFLICT DO NOTHING
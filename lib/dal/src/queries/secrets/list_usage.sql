@@ -0,0 +1,13 @@
+SELECT attribute_values.attribute_context_component_id AS component_id,
+       attribute_values.id                             AS attribute_value_id,
+       attribute_values.visibility_change_set_pk
+FROM attribute_values_v1($1, $2) AS attribute_values
+         JOIN props_v1($1, $2) AS props
+              ON props.id = attribute_values.attribute_context_prop_id
+                  AND props.path LIKE E'root\x0Bsecrets\x0B%'
+         JOIN func_binding_return_values_v1($1, $2) AS func_binding_return_values
+              ON func_binding_return_values.id = attribute_values.func_binding_return_value_id
+WHERE attribute_values.attribute_context_component_id != ident_nil_v1()
+  AND func_binding_return_values.value = to_jsonb($3::text)
+ORDER BY attribute_values.attribute_context_component_id,
+         attribute_values.id
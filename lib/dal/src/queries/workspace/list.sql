@@ -0,0 +1,5 @@
+SELECT row_to_json(workspaces.*) AS object
+FROM workspaces
+WHERE workspaces.pk != ident_nil_v1()
+  AND workspaces.visibility_deleted_at IS NULL
+ORDER BY workspaces.pk
@@ -0,0 +1,8 @@
+SELECT row_to_json(workspaces.*) AS object, user_belongs_to_workspaces.role AS role
+FROM workspaces
+         INNER JOIN user_belongs_to_workspaces
+                    ON user_belongs_to_workspaces.workspace_pk = workspaces.pk
+                        AND user_belongs_to_workspaces.visibility_deleted_at IS NULL
+WHERE user_belongs_to_workspaces.user_pk = $1
+  AND workspaces.visibility_deleted_at IS NULL
+ORDER BY workspaces.name, workspaces.pk
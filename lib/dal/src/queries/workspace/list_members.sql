@@ -0,0 +1,8 @@
+SELECT row_to_json(users.*) AS object, user_belongs_to_workspaces.role AS role
+FROM users
+         INNER JOIN user_belongs_to_workspaces
+                    ON user_belongs_to_workspaces.user_pk = users.pk
+                        AND user_belongs_to_workspaces.visibility_deleted_at IS NULL
+WHERE user_belongs_to_workspaces.workspace_pk = $1
+  AND users.visibility_deleted_at IS NULL
+ORDER BY users.name
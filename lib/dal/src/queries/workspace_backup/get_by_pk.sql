@@ -0,0 +1,5 @@
+SELECT row_to_json(workspace_backups.*) AS object
+FROM workspace_backups
+WHERE in_tenancy_v1($1, workspace_backups.tenancy_workspace_pk)
+  AND workspace_backups.pk = $2
+  AND workspace_backups.pruned_at IS NULL
@@ -0,0 +1,6 @@
+SELECT row_to_json(workspace_backups.*) AS object
+FROM workspace_backups
+WHERE in_tenancy_v1($1, workspace_backups.tenancy_workspace_pk)
+  AND workspace_backups.pruned_at IS NULL
+  AND ($2::bool IS NULL OR workspace_backups.scheduled = $2)
+ORDER BY workspace_backups.created_at DESC, workspace_backups.pk DESC
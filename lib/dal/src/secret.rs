@@ -1,3 +1,5 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
 use std::fmt;
 
 use base64::{engine::general_purpose, Engine};
@@ -14,6 +16,7 @@ use thiserror::Error;
 use veritech_client::SensitiveContainer;
 
 use crate::diagram::node::HistoryEventMetadata;
+use crate::job::definition::DependentValuesUpdate;
 use crate::standard_model::objects_from_rows;
 use crate::{
     impl_standard_model,
@@ -24,30 +27,52 @@ use crate::{
     HistoryEventError, KeyPair, KeyPairError, StandardModel, StandardModelError, Timestamp,
     Visibility,
 };
-use crate::{ActorView, HistoryActor, Tenancy, TransactionsError, UserPk};
+use crate::{
+    ActorView, AttributeValueId, ChangeSet, ChangeSetError, ChangeSetPk, ComponentId, HistoryActor,
+    Tenancy, TransactionsError, User, UserError, UserPk, WorkspacePermission, WorkspacePk,
+};
+
+pub mod backend;
+
+pub use backend::{FileSecretBackend, SecretBackend, SecretReference, FILE_SECRET_BACKEND_NAME};
 
 const LIST_SECRET_DEFINITIONS: &str = include_str!("queries/secrets/list_secret_definitions.sql");
+const LIST_ENCRYPTED_FOR_KEY_PAIR: &str =
+    include_str!("queries/secrets/list_encrypted_for_key_pair.sql");
+const LIST_ENCRYPTED_FOR_WORKSPACE: &str =
+    include_str!("queries/secrets/list_encrypted_for_workspace.sql");
+const LIST_USAGE: &str = include_str!("queries/secrets/list_usage.sql");
 
 /// Error type for Secrets.
 #[remain::sorted]
 #[derive(Error, Debug)]
 pub enum SecretError {
+    #[error("change set error: {0}")]
+    ChangeSet(#[from] Box<ChangeSetError>),
     #[error("error when decrypting crypted secret")]
     DecryptionFailed,
     #[error("error deserializing message: {0}")]
     DeserializeMessage(#[source] serde_json::Error),
     #[error("history event error: {0}")]
     HistoryEvent(#[from] HistoryEventError),
+    #[error("secret {0} is still used by {1} component(s)")]
+    InUse(SecretId, usize),
     #[error("key pair error: {0}")]
     KeyPair(#[from] KeyPairError),
     #[error("key pair not found for secret")]
     KeyPairNotFound,
+    #[error("secret must be encrypted with the current key pair of the workspace, found: {0}")]
+    NotCurrentKeyPair(KeyPairPk),
+    #[error("no workspace in tenancy")]
+    NoWorkspaceInTenancy,
     #[error("pg error: {0}")]
     Pg(#[from] PgError),
     #[error("standard model error: {0}")]
     StandardModelError(#[from] StandardModelError),
     #[error("transactions error: {0}")]
     Transactions(#[from] TransactionsError),
+    #[error(transparent)]
+    User(#[from] UserError),
 }
 
 /// Result type for Secrets.
@@ -97,6 +122,8 @@ impl Secret {
         ctx: &DalContext,
         value: impl Into<String>,
     ) -> SecretResult<()> {
+        User::ensure_authorized(ctx, WorkspacePermission::ManageSecrets).await?;
+
         let value = value.into();
         let updated_at = standard_model::update(
             ctx,
@@ -120,13 +147,221 @@ impl Secret {
         Ok(())
     }
 
-    // Once created, these object fields are to be considered immutable
+    // Once created, this object field is to be considered immutable
     standard_model_accessor_ro!(definition, String);
+
     standard_model_accessor_ro!(description, Option<String>);
 
+    pub async fn set_description(
+        &mut self,
+        ctx: &DalContext,
+        value: Option<String>,
+    ) -> SecretResult<()> {
+        User::ensure_authorized(ctx, WorkspacePermission::ManageSecrets).await?;
+
+        self.update_encrypted_secrets_column(ctx, "description", &value, TypeHint::Text)
+            .await?;
+        let _history_event = HistoryEvent::new(
+            ctx,
+            Self::history_event_label(vec!["updated"]),
+            Self::history_event_message("updated"),
+            &serde_json::json!({"pk": self.pk, "field": "description", "value": &value}),
+        )
+        .await?;
+        self.description = value;
+
+        Ok(())
+    }
+
     pub async fn key_pair(&self, ctx: &DalContext) -> SecretResult<KeyPair> {
         Ok(KeyPair::get_by_pk(ctx, self.key_pair_pk).await?)
     }
+
+    /// Replaces the encrypted message of the secret, i.e. rotates it. The new message must have
+    /// been encrypted with the current [`KeyPair`] of the workspace.
+    ///
+    /// The values of the [`Components`](crate::Component) using the secret are not modified, as
+    /// they only reference it, but a [`DependentValuesUpdate`] is enqueued for them, on head and
+    /// in every open [`ChangeSet`], so everything computed from the secret picks up the new
+    /// message.
+    pub async fn update_encrypted_contents(
+        &mut self,
+        ctx: &DalContext,
+        crypted: &[u8],
+        key_pair_pk: KeyPairPk,
+        version: SecretVersion,
+        algorithm: SecretAlgorithm,
+    ) -> SecretResult<()> {
+        User::ensure_authorized(ctx, WorkspacePermission::ManageSecrets).await?;
+
+        let key_pair = KeyPair::get_current(ctx).await?;
+        if key_pair.pk() != key_pair_pk {
+            return Err(SecretError::NotCurrentKeyPair(key_pair_pk));
+        }
+        // Refuse a message we would not be able to decrypt when the secret gets used
+        match (version, algorithm) {
+            (SecretVersion::V1, SecretAlgorithm::Sealedbox) => {
+                sealedbox::open(crypted, key_pair.public_key(), key_pair.secret_key())
+                    .map_err(|_| SecretError::DecryptionFailed)?;
+            }
+        }
+
+        self.update_encrypted_secrets_column(
+            ctx,
+            "crypted",
+            &encode_crypted(crypted),
+            TypeHint::Text,
+        )
+        .await?;
+        self.update_encrypted_secrets_column(ctx, "key_pair_pk", &key_pair_pk, TypeHint::Ident)
+            .await?;
+        self.update_encrypted_secrets_column(ctx, "version", &version.as_ref(), TypeHint::Text)
+            .await?;
+        self.update_encrypted_secrets_column(ctx, "algorithm", &algorithm.as_ref(), TypeHint::Text)
+            .await?;
+        // A new message references the backend material of this workspace
+        self.update_encrypted_secrets_column(
+            ctx,
+            "backend_namespace",
+            &None::<WorkspacePk>,
+            TypeHint::Ident,
+        )
+        .await?;
+        self.key_pair_pk = key_pair_pk;
+
+        let _history_event = HistoryEvent::new(
+            ctx,
+            Self::history_event_label(vec!["rotated"]),
+            Self::history_event_message("rotated"),
+            &serde_json::json!({"pk": self.pk, "keyPairPk": key_pair_pk}),
+        )
+        .await?;
+
+        // The usage is ordered by visibility, so the values of a visibility are adjacent
+        let mut attribute_value_ids_by_visibility: Vec<(Visibility, Vec<AttributeValueId>)> =
+            Vec::new();
+        for (visibility, _, attribute_value_id) in self.usage(ctx).await? {
+            match attribute_value_ids_by_visibility.last_mut() {
+                Some((last, attribute_value_ids)) if *last == visibility => {
+                    attribute_value_ids.push(attribute_value_id)
+                }
+                _ => attribute_value_ids_by_visibility.push((visibility, vec![attribute_value_id])),
+            }
+        }
+        for (visibility, attribute_value_ids) in attribute_value_ids_by_visibility {
+            ctx.enqueue_job(DependentValuesUpdate::new(
+                ctx.access_builder(),
+                visibility,
+                attribute_value_ids,
+            ))
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Soft deletes the secret. A secret that is still used by a [`Component`](crate::Component),
+    /// on head or in any open [`ChangeSet`], cannot be deleted.
+    pub async fn delete(&mut self, ctx: &DalContext) -> SecretResult<()> {
+        User::ensure_authorized(ctx, WorkspacePermission::ManageSecrets).await?;
+
+        let used_by = self.used_by(ctx).await?;
+        if !used_by.is_empty() {
+            return Err(SecretError::InUse(self.id, used_by.len()));
+        }
+
+        let deleted_at = standard_model::delete_by_id(ctx, "encrypted_secrets", self.id).await?;
+        self.visibility.deleted_at = Some(deleted_at);
+        self.timestamp.updated_at = deleted_at;
+
+        let _history_event = HistoryEvent::new(
+            ctx,
+            Self::history_event_label(vec!["deleted"]),
+            Self::history_event_message("deleted"),
+            &serde_json::json!({"pk": self.pk, "id": self.id}),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Lists the [`Components`](crate::Component) with a "/root/secrets" value referencing the
+    /// secret, on head or in any open [`ChangeSet`].
+    pub async fn used_by(&self, ctx: &DalContext) -> SecretResult<Vec<ComponentId>> {
+        let mut component_ids: Vec<ComponentId> = self
+            .usage(ctx)
+            .await?
+            .into_iter()
+            .map(|(_, component_id, _)| component_id)
+            .collect();
+        component_ids.sort();
+        component_ids.dedup();
+        Ok(component_ids)
+    }
+
+    /// The attribute values referencing the secret on head and in every open [`ChangeSet`],
+    /// along with the visibility each of them lives in. Values a change set inherits from head
+    /// are only listed for head.
+    async fn usage(
+        &self,
+        ctx: &DalContext,
+    ) -> SecretResult<Vec<(Visibility, ComponentId, AttributeValueId)>> {
+        let mut visibilities = vec![Visibility::new_head(false)];
+        for change_set in ChangeSet::list_open(ctx).await.map_err(Box::new)? {
+            visibilities.push(Visibility::new_change_set(change_set.pk, false));
+        }
+
+        let mut usage = Vec::new();
+        for visibility in visibilities {
+            let rows = ctx
+                .txns()
+                .await?
+                .pg()
+                .query(LIST_USAGE, &[ctx.tenancy(), &visibility, &self.id])
+                .await?;
+            for row in rows {
+                let change_set_pk: ChangeSetPk = row.try_get("visibility_change_set_pk")?;
+                if change_set_pk != visibility.change_set_pk {
+                    continue;
+                }
+                usage.push((
+                    visibility,
+                    row.try_get("component_id")?,
+                    row.try_get("attribute_value_id")?,
+                ));
+            }
+        }
+        Ok(usage)
+    }
+
+    // Like `set_name`, this updates the underlying `encrypted_secrets` table. It also records who
+    // made the change.
+    async fn update_encrypted_secrets_column<V: Send + Sync + postgres_types::ToSql>(
+        &mut self,
+        ctx: &DalContext,
+        column: &str,
+        value: &V,
+        hint: TypeHint,
+    ) -> SecretResult<()> {
+        standard_model::update(ctx, "encrypted_secrets", column, self.id(), value, hint).await?;
+
+        let updated_by = match ctx.history_actor() {
+            HistoryActor::SystemInit => None,
+            HistoryActor::User(user_pk) => Some(*user_pk),
+        };
+        self.timestamp.updated_at = standard_model::update(
+            ctx,
+            "encrypted_secrets",
+            "updated_by",
+            self.id(),
+            &updated_by,
+            TypeHint::Ident,
+        )
+        .await?;
+        self.updated_by = updated_by;
+
+        Ok(())
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -204,6 +439,8 @@ pub struct EncryptedSecret {
     crypted: Vec<u8>,
     version: SecretVersion,
     algorithm: SecretAlgorithm,
+    /// Only set for the secrets copied by a [`fork`](crate::Workspace::fork).
+    backend_namespace: Option<WorkspacePk>,
     #[serde(flatten)]
     tenancy: Tenancy,
     #[serde(flatten)]
@@ -224,6 +461,7 @@ impl fmt::Debug for EncryptedSecret {
             .field("description", &self.description)
             .field("version", &self.version)
             .field("algorithm", &self.algorithm)
+            .field("backend_namespace", &self.backend_namespace)
             .field("tenancy", &self.tenancy)
             .field("timestamp", &self.timestamp)
             .field("visibility", &self.visibility)
@@ -241,7 +479,8 @@ impl_standard_model! {
 }
 
 impl EncryptedSecret {
-    /// Creates a new encrypted secret and returns a corresponding [`Secret`] representation.
+    /// Creates a new encrypted secret and returns a corresponding [`Secret`] representation. The
+    /// message must have been encrypted with the current [`KeyPair`] of the workspace.
     #[allow(clippy::too_many_arguments, clippy::new_ret_no_self)]
     pub async fn new(
         ctx: &DalContext,
@@ -254,6 +493,13 @@ impl EncryptedSecret {
         algorithm: SecretAlgorithm,
     ) -> SecretResult<Secret> {
         let name = name.as_ref();
+        User::ensure_authorized(ctx, WorkspacePermission::ManageSecrets).await?;
+
+        // Secrets encrypted with a rotated out key pair would keep it alive forever
+        let key_pair = KeyPair::get_current(ctx).await?;
+        if key_pair.pk() != key_pair_pk {
+            return Err(SecretError::NotCurrentKeyPair(key_pair_pk));
+        }
 
         let maybe_actor = match ctx.history_actor() {
             HistoryActor::SystemInit => None,
@@ -293,6 +539,15 @@ impl EncryptedSecret {
     standard_model_accessor_ro!(version, SecretVersion);
     standard_model_accessor_ro!(algorithm, SecretAlgorithm);
 
+    /// The workspace whose namespace the [`SecretReference`] the message may hold is resolved
+    /// in. It is the workspace of the secret, unless a [`fork`](crate::Workspace::fork) copied
+    /// the secret from another workspace: the backend material stays where it was, so the
+    /// reference keeps resolving in the namespace of that workspace until the secret is rotated.
+    pub fn backend_namespace(&self) -> Option<WorkspacePk> {
+        self.backend_namespace
+            .or_else(|| self.tenancy.workspace_pk())
+    }
+
     /// Decrypts the encrypted secret with its associated [`KeyPair`] and returns a
     /// [`DecryptedSecret`].
     pub async fn decrypt(self, ctx: &DalContext) -> SecretResult<DecryptedSecret> {
@@ -319,6 +574,133 @@ impl EncryptedSecret {
     pub async fn key_pair(&self, ctx: &DalContext) -> SecretResult<KeyPair> {
         Ok(KeyPair::get_by_pk(ctx, self.key_pair_pk).await?)
     }
+
+    /// Re-encrypts every encrypted secret using the `from` [`KeyPair`] with the `to` one, in every
+    /// change set and including the deleted ones. Returns how many were re-encrypted.
+    pub(crate) async fn reencrypt_all(
+        ctx: &DalContext,
+        from: &KeyPair,
+        to: &KeyPair,
+    ) -> SecretResult<usize> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(LIST_ENCRYPTED_FOR_KEY_PAIR, &[&from.pk()])
+            .await?;
+        let encrypted_secrets: Vec<Self> = objects_from_rows(rows)?;
+
+        for encrypted_secret in &encrypted_secrets {
+            // Explicitly match on (version, algorithm) tuple to ensure that any new
+            // versions/algorithms will trigger a compilation failure
+            let crypted = match (encrypted_secret.version, encrypted_secret.algorithm) {
+                (SecretVersion::V1, SecretAlgorithm::Sealedbox) => {
+                    let message = sealedbox::open(
+                        &encrypted_secret.crypted,
+                        from.public_key(),
+                        from.secret_key(),
+                    )
+                    .map_err(|_| SecretError::DecryptionFailed)?;
+                    sealedbox::seal(&message, to.public_key())
+                }
+            };
+
+            ctx.txns()
+                .await?
+                .pg()
+                .execute(
+                    "SELECT encrypted_secret_reencrypt_v1($1, $2, $3)",
+                    &[&encrypted_secret.pk, &encode_crypted(&crypted), &to.pk()],
+                )
+                .await?;
+        }
+
+        Ok(encrypted_secrets.len())
+    }
+
+    /// Copies the encrypted secrets of the context's workspace that are on head, or in one of the
+    /// given change sets, into another workspace, re-encrypting them with the `to` [`KeyPair`] of
+    /// that workspace. Secrets keep their ids, and land in the change set `change_set_pks` maps
+    /// their change set to. They keep their [`backend namespace`](Self::backend_namespace), so
+    /// the references to a [`SecretBackend`] they hold keep resolving. Returns how many were
+    /// copied.
+    pub(crate) async fn copy_all_to_workspace(
+        ctx: &DalContext,
+        to: &KeyPair,
+        change_set_pks: &HashMap<ChangeSetPk, ChangeSetPk>,
+    ) -> SecretResult<usize> {
+        let workspace_pk = ctx
+            .tenancy()
+            .workspace_pk()
+            .ok_or(SecretError::NoWorkspaceInTenancy)?;
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(LIST_ENCRYPTED_FOR_WORKSPACE, &[&workspace_pk])
+            .await?;
+        let encrypted_secrets: Vec<Self> = objects_from_rows(rows)?;
+
+        let tenancy = Tenancy::new(*to.workspace_pk());
+        let mut key_pairs: HashMap<KeyPairPk, KeyPair> = HashMap::new();
+        let mut copied = 0;
+        for encrypted_secret in &encrypted_secrets {
+            let change_set_pk = if encrypted_secret.visibility.is_head() {
+                ChangeSetPk::NONE
+            } else {
+                match change_set_pks.get(&encrypted_secret.visibility.change_set_pk) {
+                    Some(change_set_pk) => *change_set_pk,
+                    // The secret belongs to a change set that was not copied
+                    None => continue,
+                }
+            };
+
+            let from = match key_pairs.entry(encrypted_secret.key_pair_pk) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    entry.insert(KeyPair::get_by_pk(ctx, encrypted_secret.key_pair_pk).await?)
+                }
+            };
+            // Explicitly match on (version, algorithm) tuple to ensure that any new
+            // versions/algorithms will trigger a compilation failure
+            let crypted = match (encrypted_secret.version, encrypted_secret.algorithm) {
+                (SecretVersion::V1, SecretAlgorithm::Sealedbox) => {
+                    let message = sealedbox::open(
+                        &encrypted_secret.crypted,
+                        from.public_key(),
+                        from.secret_key(),
+                    )
+                    .map_err(|_| SecretError::DecryptionFailed)?;
+                    sealedbox::seal(&message, to.public_key())
+                }
+            };
+
+            ctx.txns()
+                .await?
+                .pg()
+                .execute(
+                    "SELECT encrypted_secret_copy_v2($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+                    &[
+                        &tenancy,
+                        &Visibility::new(change_set_pk, None),
+                        &encrypted_secret.id,
+                        &encrypted_secret.name,
+                        &encrypted_secret.definition,
+                        &encrypted_secret.description,
+                        &encode_crypted(&crypted),
+                        &encrypted_secret.version.as_ref(),
+                        &encrypted_secret.algorithm.as_ref(),
+                        &to.pk(),
+                        &encrypted_secret.created_by,
+                        &encrypted_secret.backend_namespace(),
+                    ],
+                )
+                .await?;
+            copied += 1;
+        }
+
+        Ok(copied)
+    }
 }
 
 /// A secret that has been decrypted.
@@ -350,6 +732,12 @@ impl DecryptedSecret {
     pub fn definition(&self) -> &str {
         self.definition.as_ref()
     }
+
+    /// Returns the reference the message holds if the secret lives in an external
+    /// [`SecretBackend`].
+    pub fn reference(&self) -> Option<SecretReference> {
+        SecretReference::from_message(&self.message)
+    }
 }
 
 impl fmt::Debug for DecryptedSecret {
@@ -0,0 +1,39 @@
+//! This module contains [`SecretBackend`], which lets a [`Secret`](crate::Secret) reference a
+//! secret living in an external store instead of holding the secret itself.
+//!
+//! The message of such a secret holds a [`SecretReference`]. It is encrypted and passed along
+//! like any other secret message, and Cyclone resolves it with its backend of the same name when
+//! a function is executed, so the secret itself never goes through SI. Before it is sent to
+//! Cyclone, the key of the reference is [`namespaced`](SecretReference::namespaced()) with the
+//! [`backend namespace`](crate::EncryptedSecret::backend_namespace()) of the secret: its
+//! workspace, or the workspace it was forked from.
+
+use serde_json::Value;
+
+pub use veritech_client::{SecretReference, FILE_SECRET_BACKEND_NAME};
+
+/// An external store secrets can be referenced in. Cyclone must have a backend of the same name
+/// configured to resolve the references.
+pub trait SecretBackend {
+    /// The name Cyclone knows the backend by.
+    fn name(&self) -> &'static str;
+
+    /// Builds the message of a secret referencing the given key of the backend.
+    fn reference_message(&self, key: impl Into<String>) -> Value
+    where
+        Self: Sized,
+    {
+        SecretReference::new(self.name(), key).to_message()
+    }
+}
+
+/// The stand-in backend of Cyclone, which reads every secret from a JSON file named after its key,
+/// in the directory of the workspace of the secret. Meant for tests and local development.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FileSecretBackend;
+
+impl SecretBackend for FileSecretBackend {
+    fn name(&self) -> &'static str {
+        FILE_SECRET_BACKEND_NAME
+    }
+}
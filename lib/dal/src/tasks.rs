@@ -2,9 +2,15 @@
 //! SI binaries that are dependent on the [`dal`](crate).
 
 // This modules should remain private! Add "pub use" statements to use their contents.
+mod action_scheduler;
+mod backup_scheduler;
 mod resource_scheduler;
 mod status_receiver;
 
-pub use resource_scheduler::{ResourceScheduler, ResourceSchedulerError};
+pub use action_scheduler::{ActionScheduler, ActionSchedulerConfig, ActionSchedulerError};
+pub use backup_scheduler::{
+    scheduled_backup_name, BackupScheduler, BackupSchedulerConfig, BackupSchedulerError,
+};
+pub use resource_scheduler::{ResourceScheduler, ResourceSchedulerConfig, ResourceSchedulerError};
 pub use status_receiver::client::StatusReceiverClient;
 pub use status_receiver::{StatusReceiver, StatusReceiverError, StatusReceiverRequest};
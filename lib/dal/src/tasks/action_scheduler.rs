@@ -0,0 +1,180 @@
+//! This module contains [`ActionScheduler`], which is a "long-running" task that enqueues the
+//! [`ActionSchedules`](crate::ActionSchedule) that are due.
+
+use std::time::Duration;
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use si_data_pg::{PgError, PgPoolError};
+use telemetry::prelude::*;
+use thiserror::Error;
+use tokio::{sync::broadcast, time};
+
+use crate::{
+    ActionSchedule, ActionScheduleError, ServicesContext, StandardModel, TransactionsError,
+};
+
+const DEFAULT_INTERVAL_SECS: u64 = 60;
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum ActionSchedulerError {
+    #[error(transparent)]
+    ActionSchedule(#[from] ActionScheduleError),
+    #[error(transparent)]
+    Pg(#[from] PgError),
+    #[error(transparent)]
+    PgPool(#[from] PgPoolError),
+    #[error(transparent)]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type ActionSchedulerResult<T> = Result<T, ActionSchedulerError>;
+
+/// Configuration for the [`ActionScheduler`].
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ActionSchedulerConfig {
+    /// If `false`, the scheduler is never started.
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// How often, in seconds, the scheduler looks for due
+    /// [`ActionSchedules`](crate::ActionSchedule). This is also the worst case delay of a run.
+    #[serde(default = "default_interval_secs")]
+    pub interval_secs: u64,
+}
+
+impl Default for ActionSchedulerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            interval_secs: default_interval_secs(),
+        }
+    }
+}
+
+impl ActionSchedulerConfig {
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_secs)
+    }
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_interval_secs() -> u64 {
+    DEFAULT_INTERVAL_SECS
+}
+
+/// The action scheduler looks up every unpaused [`ActionSchedule`] on head that is due, across
+/// all workspaces, and enqueues a [`FixesJob`](crate::job::definition::FixesJob) for each of
+/// them. Every schedule is claimed and enqueued in its own transaction, so one failing schedule
+/// does not hold back the others and replicas running the scheduler never enqueue a run twice.
+#[derive(Debug, Clone)]
+pub struct ActionScheduler {
+    services_context: ServicesContext,
+    config: ActionSchedulerConfig,
+}
+
+impl ActionScheduler {
+    pub fn new(services_context: ServicesContext, config: ActionSchedulerConfig) -> Self {
+        Self {
+            services_context,
+            config,
+        }
+    }
+
+    /// Starts the scheduler, consuming itself. It stops when the shutdown broadcast is received.
+    pub fn start(self, mut shutdown_broadcast_rx: broadcast::Receiver<()>) {
+        if !self.config.enabled {
+            info!("Action Scheduler is disabled, not starting");
+            return;
+        }
+
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = shutdown_broadcast_rx.recv() => {
+                    info!("Action Scheduler received shutdown request, bailing out");
+                },
+                _ = self.start_task() => {}
+            }
+            info!("Action Scheduler stopped");
+        });
+    }
+
+    /// The internal task spawned by `start`. On every configured interval, it enqueues all the
+    /// schedules that are due.
+    #[instrument(name = "action_scheduler.start_task", skip_all, level = "debug")]
+    async fn start_task(&self) {
+        let mut interval = time::interval(self.config.interval());
+        interval.set_missed_tick_behavior(time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            if let Err(err) = self.run().await {
+                error!("{err}");
+            }
+        }
+    }
+
+    #[instrument(name = "action_scheduler.run", skip_all, level = "debug")]
+    async fn run(&self) -> ActionSchedulerResult<()> {
+        let due = {
+            let ctx = self
+                .services_context
+                .clone()
+                .into_builder(false)
+                .build_default()
+                .await?;
+            let due = ActionSchedule::list_due(&ctx, Utc::now()).await?;
+            ctx.commit().await?;
+            due
+        };
+        if !due.is_empty() {
+            info!(schedules = due.len(), "enqueueing scheduled actions");
+        }
+
+        for schedule in due {
+            let id = *schedule.id();
+            if let Err(err) = self.enqueue(schedule).await {
+                error!(error = ?err, action_schedule_id = %id, "failed to enqueue scheduled action");
+            }
+        }
+
+        Ok(())
+    }
+
+    #[instrument(name = "action_scheduler.enqueue", skip_all, level = "debug")]
+    async fn enqueue(&self, schedule: ActionSchedule) -> ActionSchedulerResult<()> {
+        let mut ctx = self
+            .services_context
+            .clone()
+            .into_builder(false)
+            .build_default()
+            .await?;
+        ctx.update_tenancy(*schedule.tenancy());
+
+        // Another replica may be enqueueing the same schedule, or may have already done it since
+        // we listed it.
+        let mut schedule = match ActionSchedule::claim_due(&ctx, *schedule.id(), Utc::now()).await {
+            Ok(Some(schedule)) => schedule,
+            Ok(None) => {
+                debug!(action_schedule_id = %schedule.id(), "action schedule already claimed, skipping");
+                ctx.rollback().await?;
+                return Ok(());
+            }
+            Err(err) => {
+                ctx.rollback().await?;
+                return Err(err.into());
+            }
+        };
+
+        match schedule.enqueue(&ctx).await {
+            Ok(_) => ctx.commit().await?,
+            Err(err) => {
+                ctx.rollback().await?;
+                return Err(err.into());
+            }
+        }
+        Ok(())
+    }
+}
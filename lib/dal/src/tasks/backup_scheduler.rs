@@ -0,0 +1,315 @@
+//! This module contains [`BackupScheduler`], which is a "long-running" task that periodically
+//! uploads a backup of every [`Workspace`] to the module index, and prunes the backups the
+//! [`WorkspaceBackupRetention`] policy does not keep.
+
+use std::str::FromStr;
+use std::time::Duration;
+
+use chrono::Utc;
+use module_index_client::{IndexClient, IndexClientError};
+use serde::{Deserialize, Serialize};
+use si_std::SensitiveString;
+use telemetry::prelude::*;
+use thiserror::Error;
+use tokio::{sync::broadcast, time};
+use ulid::Ulid;
+
+use crate::pkg::{PkgError, PkgExporter};
+use crate::{
+    DalContext, ServicesContext, Tenancy, TransactionsError, Workspace, WorkspaceBackup,
+    WorkspaceBackupRetention, WorkspaceError,
+};
+
+const DEFAULT_INTERVAL_SECS: u64 = 24 * 60 * 60;
+
+/// The name the backups of the given workspace are uploaded under.
+pub fn scheduled_backup_name(workspace: &Workspace) -> String {
+    format!("workspace-backup-{}", workspace.pk())
+}
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum BackupSchedulerError {
+    #[error(transparent)]
+    IndexClient(#[from] IndexClientError),
+    #[error("invalid module id: {0}")]
+    InvalidModuleId(#[from] ulid::DecodeError),
+    #[error(transparent)]
+    Pkg(#[from] PkgError),
+    #[error(transparent)]
+    Transactions(#[from] TransactionsError),
+    #[error(transparent)]
+    Url(#[from] url::ParseError),
+    #[error(transparent)]
+    Workspace(#[from] WorkspaceError),
+}
+
+pub type BackupSchedulerResult<T> = Result<T, BackupSchedulerError>;
+
+/// Configuration for the [`BackupScheduler`].
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BackupSchedulerConfig {
+    /// If `false`, the scheduler is never started. Backups are opt-in.
+    #[serde(default)]
+    pub enabled: bool,
+    /// How often, in seconds, every workspace is backed up.
+    #[serde(default = "default_interval_secs")]
+    pub interval_secs: u64,
+    /// The auth token the backups are uploaded to the module index with. The scheduler does not
+    /// start without one.
+    #[serde(default)]
+    pub module_index_token: Option<SensitiveString>,
+    #[serde(default)]
+    pub retention: WorkspaceBackupRetention,
+}
+
+impl Default for BackupSchedulerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_secs: default_interval_secs(),
+            module_index_token: None,
+            retention: Default::default(),
+        }
+    }
+}
+
+impl BackupSchedulerConfig {
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_secs)
+    }
+
+    /// How long ago a workspace must have been claimed to be backed up again. This is a bit
+    /// shorter than the interval, so that replicas whose timers drift apart don't skip a run.
+    fn claim_min_age(&self) -> Duration {
+        let interval = self.interval();
+        interval - interval / 10
+    }
+}
+
+fn default_interval_secs() -> u64 {
+    DEFAULT_INTERVAL_SECS
+}
+
+/// The backup scheduler exports every workspace as a workspace backup module, uploads it to the
+/// module index and records it as a [`WorkspaceBackup`]. It then prunes the scheduled backups of
+/// the workspace its [`retention`](BackupSchedulerConfig::retention) policy does not keep.
+///
+/// Every replica runs the scheduler: a workspace is only backed up by the one that
+/// [claims](WorkspaceBackup::claim_scheduled) it first in an interval. Every workspace is handled
+/// on its own, so one failing workspace does not hold back the others, and no transaction is
+/// held open while the module index is called.
+#[derive(Debug, Clone)]
+pub struct BackupScheduler {
+    services_context: ServicesContext,
+    module_index_url: String,
+    config: BackupSchedulerConfig,
+}
+
+impl BackupScheduler {
+    pub fn new(
+        services_context: ServicesContext,
+        module_index_url: String,
+        config: BackupSchedulerConfig,
+    ) -> Self {
+        Self {
+            services_context,
+            module_index_url,
+            config,
+        }
+    }
+
+    /// Starts the scheduler, consuming itself. It stops when the shutdown broadcast is received.
+    pub fn start(self, mut shutdown_broadcast_rx: broadcast::Receiver<()>) {
+        if !self.config.enabled {
+            info!("Backup Scheduler is disabled, not starting");
+            return;
+        }
+        if self.config.interval_secs == 0 {
+            error!("Backup Scheduler has a zero interval, not starting");
+            return;
+        }
+        let index_client = match self.index_client() {
+            Ok(Some(index_client)) => index_client,
+            Ok(None) => {
+                warn!("Backup Scheduler has no module index token, not starting");
+                return;
+            }
+            Err(err) => {
+                error!(error = ?err, "Backup Scheduler has an invalid module index url, not starting");
+                return;
+            }
+        };
+
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = shutdown_broadcast_rx.recv() => {
+                    info!("Backup Scheduler received shutdown request, bailing out");
+                },
+                _ = self.start_task(&index_client) => {}
+            }
+            info!("Backup Scheduler stopped");
+        });
+    }
+
+    fn index_client(&self) -> BackupSchedulerResult<Option<IndexClient>> {
+        let token = match &self.config.module_index_token {
+            Some(token) => token,
+            None => return Ok(None),
+        };
+        Ok(Some(IndexClient::new(
+            self.module_index_url.as_str().try_into()?,
+            token,
+        )))
+    }
+
+    /// The internal task spawned by `start`. On every configured interval, it backs up all the
+    /// workspaces.
+    #[instrument(name = "backup_scheduler.start_task", skip_all, level = "debug")]
+    async fn start_task(&self, index_client: &IndexClient) {
+        let mut interval = time::interval(self.config.interval());
+        interval.set_missed_tick_behavior(time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            if let Err(err) = self.run(index_client).await {
+                error!("{err}");
+            }
+        }
+    }
+
+    #[instrument(name = "backup_scheduler.run", skip_all, level = "debug")]
+    async fn run(&self, index_client: &IndexClient) -> BackupSchedulerResult<()> {
+        let workspaces = {
+            let ctx = self.build_ctx().await?;
+            let workspaces = Workspace::list(&ctx).await?;
+            ctx.commit().await?;
+            workspaces
+        };
+        info!(workspaces = workspaces.len(), "backing up workspaces");
+
+        for workspace in workspaces {
+            if let Err(err) = self.back_up(index_client, &workspace).await {
+                error!(error = ?err, workspace_pk = %workspace.pk(), "failed to back up workspace");
+            }
+        }
+
+        Ok(())
+    }
+
+    #[instrument(name = "backup_scheduler.back_up", skip_all, level = "debug")]
+    async fn back_up(
+        &self,
+        index_client: &IndexClient,
+        workspace: &Workspace,
+    ) -> BackupSchedulerResult<()> {
+        let mut ctx = self.build_ctx().await?;
+        ctx.update_tenancy(Tenancy::new(*workspace.pk()));
+
+        // The export is read in the transaction of the claim, which commits before uploading
+        if !WorkspaceBackup::claim_scheduled(&ctx, self.config.claim_min_age()).await? {
+            debug!(workspace_pk = %workspace.pk(), "workspace backup already claimed, skipping");
+            ctx.rollback().await?;
+            return Ok(());
+        }
+        let name = scheduled_backup_name(workspace);
+        let version = Utc::now().format("%Y-%m-%d_%H:%M:%S").to_string();
+        let module_payload = PkgExporter::new_workspace_exporter(
+            &name,
+            "backup scheduler",
+            &version,
+            format!("scheduled backup of workspace {}", workspace.name()),
+        )
+        .export_as_bytes(&ctx)
+        .await?;
+        ctx.commit().await?;
+
+        let response = index_client
+            .upload_module(&name, &version, module_payload)
+            .await?;
+        let recorded = async {
+            WorkspaceBackup::record(
+                &ctx,
+                &response.id,
+                &name,
+                &version,
+                &response.latest_hash,
+                true,
+            )
+            .await?;
+            ctx.commit().await?;
+            Ok::<_, BackupSchedulerError>(())
+        }
+        .await;
+        if let Err(err) = recorded {
+            // An unrecorded backup would never be pruned, so take it back out of the index
+            if let Err(reject_err) = index_client
+                .reject_module(Ulid::from_str(&response.id)?, "backup scheduler".to_owned())
+                .await
+            {
+                error!(error = ?reject_err, module_id = %response.id, "failed to remove unrecorded workspace backup");
+            }
+            return Err(err);
+        }
+
+        let backups = WorkspaceBackup::list(&ctx, Some(true)).await?;
+        ctx.rollback().await?;
+        for backup in self
+            .config
+            .retention
+            .prunable(&backups, |backup| backup.created_at)
+        {
+            let mut backup = backup.clone();
+            index_client
+                .reject_module(
+                    Ulid::from_str(&backup.module_id)?,
+                    "backup retention".to_owned(),
+                )
+                .await?;
+            backup.mark_pruned(&ctx).await?;
+            ctx.commit().await?;
+        }
+
+        Ok(())
+    }
+
+    async fn build_ctx(&self) -> BackupSchedulerResult<DalContext> {
+        Ok(self
+            .services_context
+            .clone()
+            .into_builder(false)
+            .build_default()
+            .await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn claims_expire_a_bit_before_the_next_run() {
+        let config = BackupSchedulerConfig {
+            interval_secs: 24 * 60 * 60,
+            ..Default::default()
+        };
+
+        assert!(config.claim_min_age() < config.interval());
+        assert_eq!(
+            Duration::from_secs(21 * 60 * 60 + 36 * 60),
+            config.claim_min_age()
+        );
+    }
+
+    #[test]
+    fn scheduled_backups_are_disabled_by_default() {
+        let config: BackupSchedulerConfig =
+            serde_json::from_value(serde_json::json!({})).expect("cannot deserialize config");
+
+        assert!(!config.enabled);
+        assert_eq!(
+            Duration::from_secs(DEFAULT_INTERVAL_SECS),
+            config.interval()
+        );
+        assert_eq!(WorkspaceBackupRetention::default(), config.retention);
+    }
+}
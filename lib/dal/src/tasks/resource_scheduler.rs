@@ -1,21 +1,39 @@
 //! This module contains [`ResourceScheduler`], which is a "long-running" tasks that performs
 //! [`resource`](crate::component::resource) syncing on a cadence.
 
+use std::collections::HashMap;
 use std::time::Duration;
 
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
 use si_data_nats::NatsError;
 use si_data_pg::{PgError, PgPoolError};
 use telemetry::prelude::*;
 use thiserror::Error;
 use tokio::{sync::broadcast, time};
 
-use crate::{standard_model, Component, ServicesContext, StandardModelError, TransactionsError};
+use crate::job::definition::RefreshJob;
+use crate::{
+    standard_model, Component, ComponentId, DalContext, ServicesContext, StandardModelError,
+    Tenancy, TransactionsError, WorkspacePk,
+};
+
+const CLAIM_SCHEDULED_REFRESH: &str =
+    include_str!("../queries/component/claim_scheduled_refresh.sql");
+const LIST_HEAD_WITH_REFRESH_ACTION: &str =
+    include_str!("../queries/component/list_head_with_refresh_action.sql");
+
+const DEFAULT_INTERVAL_SECS: u64 = 300;
+const DEFAULT_MAX_CONCURRENT_JOBS: usize = 4;
+const DEFAULT_BATCH_SIZE: usize = 25;
 
 #[remain::sorted]
 #[derive(Error, Debug)]
 pub enum ResourceSchedulerError {
     #[error(transparent)]
     Nats(#[from] NatsError),
+    #[error("no workspace in tenancy")]
+    NoWorkspaceInTenancy,
     #[error(transparent)]
     Pg(#[from] PgError),
     #[error(transparent)]
@@ -30,25 +48,99 @@ pub enum ResourceSchedulerError {
 
 pub type ResourceSchedulerResult<T> = Result<T, ResourceSchedulerError>;
 
-/// The resource scheduler handles looking up all the components, and scheduling
-/// their resources to refresh. Eventually, it should become smart enough to parallelize,
-/// it might be extracted to a fully separate service, etc etc. For now,
-/// it is the dumbest thing that could possibly work - no more often than every 30
-/// seconds, it will ask a resource to refresh
+/// Configuration for the [`ResourceScheduler`].
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ResourceSchedulerConfig {
+    /// If `false`, the scheduler is never started.
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// How often, in seconds, every refreshable [`Component`] on head is scheduled to refresh.
+    #[serde(default = "default_interval_secs")]
+    pub interval_secs: u64,
+    /// How many [`RefreshJobs`](crate::job::definition::RefreshJob) a scheduler runs at the
+    /// same time. Every replica runs its own scheduler.
+    #[serde(default = "default_max_concurrent_jobs")]
+    pub max_concurrent_jobs: usize,
+    /// How many [`Components`](Component) are refreshed by a single
+    /// [`RefreshJob`](crate::job::definition::RefreshJob).
+    #[serde(default = "default_batch_size")]
+    pub batch_size: usize,
+}
+
+impl Default for ResourceSchedulerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            interval_secs: default_interval_secs(),
+            max_concurrent_jobs: default_max_concurrent_jobs(),
+            batch_size: default_batch_size(),
+        }
+    }
+}
+
+impl ResourceSchedulerConfig {
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_secs)
+    }
+
+    /// How long ago a workspace must have been claimed to be refreshed again. This is a bit
+    /// shorter than the interval, so that replicas whose timers drift apart don't skip a run.
+    fn claim_min_age(&self) -> Duration {
+        let interval = self.interval();
+        interval - interval / 10
+    }
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_interval_secs() -> u64 {
+    DEFAULT_INTERVAL_SECS
+}
+
+fn default_max_concurrent_jobs() -> usize {
+    DEFAULT_MAX_CONCURRENT_JOBS
+}
+
+fn default_batch_size() -> usize {
+    DEFAULT_BATCH_SIZE
+}
+
+/// The resource scheduler handles looking up all the components on head that have a
+/// [`Refresh`](crate::ActionKind::Refresh) action, and scheduling their resources to refresh.
+/// Components are grouped per workspace and refreshed by batched
+/// [`RefreshJobs`](crate::job::definition::RefreshJob), with a limited number of jobs running at
+/// the same time.
+///
+/// Every replica runs the scheduler: the resources of a workspace are only refreshed by the one
+/// that [claims](Self::claim_workspace) it first in an interval.
 #[derive(Debug, Clone)]
 pub struct ResourceScheduler {
     services_context: ServicesContext,
+    config: ResourceSchedulerConfig,
 }
 
 impl ResourceScheduler {
-    pub fn new(services_context: ServicesContext) -> ResourceScheduler {
-        ResourceScheduler { services_context }
+    pub fn new(
+        services_context: ServicesContext,
+        config: ResourceSchedulerConfig,
+    ) -> ResourceScheduler {
+        ResourceScheduler {
+            services_context,
+            config,
+        }
     }
 
     /// Starts the scheduler. It returns the join handle to the spawned scheduler, and
     /// consumes itself. The caller should check for errors and restart the scheduler if
     /// it ever returns an error.
     pub fn start(self, mut shutdown_broadcast_rx: broadcast::Receiver<()>) {
+        if !self.config.enabled {
+            info!("Resource Refreshing Scheduler is disabled, not starting");
+            return;
+        }
+
         tokio::spawn(async move {
             tokio::select! {
                 _ = shutdown_broadcast_rx.recv() => {
@@ -61,33 +153,115 @@ impl ResourceScheduler {
     }
 
     #[instrument(name = "resource_scheduler.run", skip_all, level = "debug")]
-    async fn run(&self) -> Result<(), Box<dyn std::error::Error>> {
-        /*
-        let components = self.components().await?;
-        info!("Refresh {} resources", components.len());
+    async fn run(&self) -> ResourceSchedulerResult<()> {
+        let components_by_workspace = self.refreshable_components_by_workspace().await?;
+        info!(
+            workspaces = components_by_workspace.len(),
+            "scheduling resource refreshes"
+        );
 
-        for component in components {
-            // First we're building a ctx with no tenancy at head, then updating it with a
-            // workspace head request context
+        let mut claimed = Vec::new();
+        for (workspace_pk, component_ids) in components_by_workspace {
+            match self.claim(workspace_pk).await {
+                Ok(true) => claimed.push((workspace_pk, component_ids)),
+                Ok(false) => {
+                    debug!(%workspace_pk, "resource refresh already claimed, skipping");
+                }
+                Err(err) => error!(
+                    error = ?err,
+                    %workspace_pk,
+                    "failed to claim resource refreshes for workspace"
+                ),
+            }
+        }
 
-            let builder = self.services_context.clone().into_builder(false);
-            let mut ctx = builder.build_default().await?;
+        let jobs = claimed.iter().flat_map(|(workspace_pk, component_ids)| {
+            batches(component_ids, self.config.batch_size).map(move |batch| (*workspace_pk, batch))
+        });
+        futures::stream::iter(jobs)
+            .for_each_concurrent(
+                self.config.max_concurrent_jobs.max(1),
+                |(workspace_pk, component_ids)| async move {
+                    if let Err(err) = self.refresh(workspace_pk, component_ids).await {
+                        error!(
+                            error = ?err,
+                            %workspace_pk,
+                            "failed to refresh resources for workspace"
+                        );
+                    }
+                },
+            )
+            .await;
 
-            ctx.update_tenancy(*component.tenancy());
+        Ok(())
+    }
 
-            component.act(&ctx, "refresh").await?;
-            ctx.commit().await?;
-        }
-        */
+    /// Claims the resource refreshes of a workspace for this interval, committing the claim
+    /// right away so that the other replicas see it.
+    async fn claim(&self, workspace_pk: WorkspacePk) -> ResourceSchedulerResult<bool> {
+        let ctx = self.build_ctx(workspace_pk).await?;
+        let claimed = Self::claim_workspace(&ctx, self.config.claim_min_age()).await?;
+        ctx.commit().await?;
+        Ok(claimed)
+    }
+
+    /// Runs a [`RefreshJob`] for a batch of [`Components`](Component) of a single workspace. It
+    /// waits for the job to finish, which is what bounds how many jobs run at the same time.
+    #[instrument(name = "resource_scheduler.refresh", skip_all, level = "debug")]
+    async fn refresh(
+        &self,
+        workspace_pk: WorkspacePk,
+        component_ids: &[ComponentId],
+    ) -> ResourceSchedulerResult<()> {
+        let ctx = self.build_ctx(workspace_pk).await?;
+        ctx.enqueue_job(RefreshJob::new(
+            ctx.access_builder(),
+            *ctx.visibility(),
+            component_ids.to_vec(),
+        ))
+        .await?;
+        ctx.blocking_commit().await?;
         Ok(())
     }
 
-    /// The internal task spawned by `start`. No more frequently than every 30
-    /// seconds, it will iterate over all the components on head in the database and
-    /// schedule them to refresh.
+    async fn build_ctx(&self, workspace_pk: WorkspacePk) -> ResourceSchedulerResult<DalContext> {
+        let builder = self.services_context.clone().into_builder(false);
+        let mut ctx = builder.build_default().await?;
+        ctx.update_tenancy(Tenancy::new(workspace_pk));
+        Ok(ctx)
+    }
+
+    /// Claims the scheduled resource refreshes of the workspace of the context's tenancy, unless
+    /// it was claimed less than `min_age` ago. Only one of the schedulers running concurrently
+    /// gets the claim, which holds whether or not the refreshes then succeed. The claim is only
+    /// taken once the transaction commits.
+    #[instrument(skip(ctx))]
+    pub async fn claim_workspace(
+        ctx: &DalContext,
+        min_age: Duration,
+    ) -> ResourceSchedulerResult<bool> {
+        let workspace_pk = ctx
+            .tenancy()
+            .workspace_pk()
+            .ok_or(ResourceSchedulerError::NoWorkspaceInTenancy)?;
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                CLAIM_SCHEDULED_REFRESH,
+                &[&workspace_pk, &min_age.as_secs_f64()],
+            )
+            .await?;
+        Ok(row.is_some())
+    }
+
+    /// The internal task spawned by `start`. On every configured interval, it will iterate over
+    /// all the refreshable components on head in the database and schedule them to refresh.
     #[instrument(name = "resource_scheduler.start_task", skip_all, level = "debug")]
     async fn start_task(&self) {
-        let mut interval = time::interval(Duration::from_secs(300));
+        let mut interval = time::interval(self.config.interval());
+        interval.set_missed_tick_behavior(time::MissedTickBehavior::Delay);
         loop {
             interval.tick().await;
             match self.run().await {
@@ -97,6 +271,33 @@ impl ResourceScheduler {
         }
     }
 
+    /// Gets the ids of all components on head whose schema variant has a
+    /// [`Refresh`](crate::ActionKind::Refresh) action, grouped by workspace.
+    #[instrument(skip_all, level = "debug")]
+    pub async fn refreshable_components_by_workspace(
+        &self,
+    ) -> ResourceSchedulerResult<HashMap<WorkspacePk, Vec<ComponentId>>> {
+        let builder = self.services_context.clone().into_builder(false);
+        let ctx = builder.build_default().await?;
+
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(LIST_HEAD_WITH_REFRESH_ACTION, &[])
+            .await?;
+
+        let mut components = Vec::with_capacity(rows.len());
+        for row in rows {
+            let workspace_pk: WorkspacePk = row.try_get("workspace_pk")?;
+            let component_id: ComponentId = row.try_get("component_id")?;
+            components.push((workspace_pk, component_id));
+        }
+
+        ctx.commit().await?;
+        Ok(group_by_workspace(components))
+    }
+
     /// Gets a list of all the resources in the database.
     #[instrument(skip_all, level = "debug")]
     pub async fn components(&self) -> ResourceSchedulerResult<Vec<Component>> {
@@ -124,3 +325,66 @@ impl ResourceScheduler {
         Ok(components)
     }
 }
+
+fn group_by_workspace(
+    components: impl IntoIterator<Item = (WorkspacePk, ComponentId)>,
+) -> HashMap<WorkspacePk, Vec<ComponentId>> {
+    let mut components_by_workspace: HashMap<WorkspacePk, Vec<ComponentId>> = HashMap::new();
+    for (workspace_pk, component_id) in components {
+        components_by_workspace
+            .entry(workspace_pk)
+            .or_default()
+            .push(component_id);
+    }
+    components_by_workspace
+}
+
+/// Splits a workspace's [`Components`](Component) into the batches refreshed by a single
+/// [`RefreshJob`]. A batch size of zero is treated as one.
+fn batches(
+    component_ids: &[ComponentId],
+    batch_size: usize,
+) -> std::slice::Chunks<'_, ComponentId> {
+    component_ids.chunks(batch_size.max(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn components_are_grouped_per_workspace() {
+        let (workspace_a, workspace_b) = (WorkspacePk::generate(), WorkspacePk::generate());
+        let (component_1, component_2, component_3) = (
+            ComponentId::generate(),
+            ComponentId::generate(),
+            ComponentId::generate(),
+        );
+
+        let grouped = group_by_workspace([
+            (workspace_a, component_1),
+            (workspace_b, component_2),
+            (workspace_a, component_3),
+        ]);
+
+        assert_eq!(grouped.len(), 2);
+        assert_eq!(grouped[&workspace_a], vec![component_1, component_3]);
+        assert_eq!(grouped[&workspace_b], vec![component_2]);
+    }
+
+    #[test]
+    fn workspace_components_are_batched() {
+        let component_ids: Vec<ComponentId> = (0..5).map(|_| ComponentId::generate()).collect();
+
+        let sizes: Vec<usize> = batches(&component_ids, 2).map(<[_]>::len).collect();
+        assert_eq!(sizes, vec![2, 2, 1]);
+        assert_eq!(
+            batches(&component_ids, 2).flatten().collect::<Vec<_>>(),
+            component_ids.iter().collect::<Vec<_>>()
+        );
+
+        assert_eq!(batches(&component_ids, 0).count(), 5);
+        assert_eq!(batches(&component_ids, 25).count(), 1);
+        assert_eq!(batches(&[], 25).count(), 0);
+    }
+}
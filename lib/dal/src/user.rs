@@ -6,11 +6,16 @@ use thiserror::Error;
 use tokio::task::JoinError;
 
 use crate::{
-    jwt_key::JwtKeyError, pk, standard_model_accessor_ro, DalContext, HistoryEvent,
+    jwt_key::JwtKeyError, pk, standard_model_accessor_ro, DalContext, HistoryActor, HistoryEvent,
     HistoryEventError, JwtPublicSigningKey, Tenancy, Timestamp, TransactionsError, WorkspacePk,
 };
 
+pub mod role;
+
+pub use role::{WorkspacePermission, WorkspaceRole};
+
 const USER_GET_BY_PK: &str = include_str!("queries/user/get_by_pk.sql");
+const USER_GET_WORKSPACE_ROLE: &str = include_str!("queries/user/get_workspace_role.sql");
 
 #[remain::sorted]
 #[derive(Error, Debug)]
@@ -23,18 +28,32 @@ pub enum UserError {
     JwtKey(#[from] JwtKeyError),
     #[error("nats txn error: {0}")]
     Nats(#[from] NatsError),
+    #[error("user {0} is not a member of workspace {1}")]
+    NotAMember(UserPk, WorkspacePk),
     #[error("user not found in tenancy: {0} {1:?}")]
     NotFoundInTenancy(UserPk, Tenancy),
     #[error("no workspace in tenancy")]
     NoWorkspaceInTenancy,
+    #[error("user {0} has the {1} role in workspace {2}, which does not allow them to {3}")]
+    PermissionDenied(UserPk, WorkspaceRole, WorkspacePk, WorkspacePermission),
     #[error("pg error: {0}")]
     Pg(#[from] PgError),
     #[error("error serializing/deserializing json: {0}")]
     SerdeJson(#[from] serde_json::Error),
     #[error(transparent)]
+    StrumParse(#[from] strum::ParseError),
+    #[error(transparent)]
     Transactions(#[from] TransactionsError),
 }
 
+impl UserError {
+    /// Returns `true` if the error is the result of a failed permission check, as opposed to
+    /// something going wrong.
+    pub fn is_permission_denied(&self) -> bool {
+        matches!(self, Self::NotAMember(..) | Self::PermissionDenied(..))
+    }
+}
+
 pub type UserResult<T> = Result<T, UserError>;
 
 pk!(UserPk);
@@ -117,22 +136,102 @@ impl User {
         }
     }
 
-    pub async fn authorize(_ctx: &DalContext, _user_pk: &UserPk) -> UserResult<bool> {
-        // TODO(paulo,theo): implement capabilities through auth0
-        Ok(true)
+    /// Returns whether the user's [`WorkspaceRole`] in the workspace of the context's tenancy
+    /// grants the permission. Users that are not a member of the workspace have no permissions.
+    pub async fn authorize(
+        ctx: &DalContext,
+        user_pk: &UserPk,
+        permission: WorkspacePermission,
+    ) -> UserResult<bool> {
+        let workspace_pk = ctx
+            .tenancy()
+            .workspace_pk()
+            .ok_or(UserError::NoWorkspaceInTenancy)?;
+        Ok(Self::role_in_workspace(ctx, *user_pk, workspace_pk)
+            .await?
+            .map_or(false, |role| role.allows(permission)))
+    }
+
+    /// Fails with a permission error unless the context's [`HistoryActor`] may perform the
+    /// action in the workspace of the context's tenancy, within the context's
+    /// [`max_role`](DalContext::max_role()) if it has one. The system is allowed everything.
+    pub async fn ensure_authorized(
+        ctx: &DalContext,
+        permission: WorkspacePermission,
+    ) -> UserResult<()> {
+        let workspace_pk = ctx
+            .tenancy()
+            .workspace_pk()
+            .ok_or(UserError::NoWorkspaceInTenancy)?;
+        Self::ensure_authorized_in_workspace(ctx, workspace_pk, permission).await
+    }
+
+    /// Like [`Self::ensure_authorized()`], but for the given workspace rather than the one of the
+    /// context's tenancy.
+    pub async fn ensure_authorized_in_workspace(
+        ctx: &DalContext,
+        workspace_pk: WorkspacePk,
+        permission: WorkspacePermission,
+    ) -> UserResult<()> {
+        let user_pk = match ctx.history_actor() {
+            HistoryActor::SystemInit => return Ok(()),
+            HistoryActor::User(user_pk) => *user_pk,
+        };
+
+        let role = Self::role_in_workspace(ctx, user_pk, workspace_pk)
+            .await?
+            .ok_or(UserError::NotAMember(user_pk, workspace_pk))?;
+        // The cap of the context, e.g. the one of a restricted API token, applies on top of the
+        // role of the user
+        for role in std::iter::once(role).chain(ctx.max_role()) {
+            if !role.allows(permission) {
+                return Err(UserError::PermissionDenied(
+                    user_pk,
+                    role,
+                    workspace_pk,
+                    permission,
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the user's [`WorkspaceRole`] in the workspace, or [`None`] if they are not a
+    /// member of it.
+    pub async fn role_in_workspace(
+        ctx: &DalContext,
+        user_pk: UserPk,
+        workspace_pk: WorkspacePk,
+    ) -> UserResult<Option<WorkspaceRole>> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(USER_GET_WORKSPACE_ROLE, &[&user_pk, &workspace_pk])
+            .await?;
+        match row {
+            Some(row) => {
+                let role: String = row.try_get("role")?;
+                Ok(Some(role.parse()?))
+            }
+            None => Ok(None),
+        }
     }
 
+    /// Makes the user a member of the workspace with the given role. Does nothing if they
+    /// already are a member, whatever their role.
     pub async fn associate_workspace(
         &self,
         ctx: &DalContext,
         workspace_pk: WorkspacePk,
+        role: WorkspaceRole,
     ) -> UserResult<()> {
         ctx.txns()
             .await?
             .pg()
             .execute(
-                "SELECT user_associate_workspace_v1($1, $2)",
-                &[&self.pk, &workspace_pk],
+                "SELECT user_associate_workspace_v2($1, $2, $3)",
+                &[&self.pk, &workspace_pk, &role.to_string()],
             )
             .await?;
         Ok(())
@@ -160,4 +259,18 @@ impl UserClaim {
         let claims = crate::jwt_key::validate_bearer_token(public_key, &token).await?;
         Ok(claims.custom)
     }
+
+    /// Makes another workspace the user belongs to the active one, without a new token. Returns
+    /// the user's role in it.
+    pub async fn switch_workspace(
+        &mut self,
+        ctx: &DalContext,
+        workspace_pk: WorkspacePk,
+    ) -> UserResult<WorkspaceRole> {
+        let role = User::role_in_workspace(ctx, self.user_pk, workspace_pk)
+            .await?
+            .ok_or(UserError::NotAMember(self.user_pk, workspace_pk))?;
+        self.workspace_pk = workspace_pk;
+        Ok(role)
+    }
 }
@@ -0,0 +1,81 @@
+//! This module contains [`WorkspaceRole`], which decides what a [`User`](crate::User) may do in a
+//! [`Workspace`](crate::Workspace) they belong to, and the [`WorkspacePermissions`](WorkspacePermission)
+//! it grants.
+
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+
+/// The role a [`User`](crate::User) has in a [`Workspace`](crate::Workspace). Roles are
+/// cumulative: every role has all the permissions of the ones below it.
+///
+/// | Role    | Permissions                                                      |
+/// |---------|------------------------------------------------------------------|
+/// | Viewer  | read                                                             |
+/// | Editor  | + edit change sets, manage secrets                               |
+/// | Applier | + apply (and approve or reject) change sets, install packages    |
+/// | Admin   | + manage the workspace (member roles, required approvals, ...)   |
+#[remain::sorted]
+#[derive(Deserialize, Serialize, Debug, Display, EnumString, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum WorkspaceRole {
+    Admin,
+    Applier,
+    Editor,
+    Viewer,
+}
+
+impl WorkspaceRole {
+    pub fn permissions(&self) -> &'static [WorkspacePermission] {
+        use WorkspacePermission::*;
+        match self {
+            Self::Viewer => &[Read],
+            Self::Editor => &[Read, EditChangeSets, ManageSecrets],
+            Self::Applier => &[
+                Read,
+                EditChangeSets,
+                ManageSecrets,
+                ApplyChangeSets,
+                InstallPackages,
+            ],
+            Self::Admin => &[
+                Read,
+                EditChangeSets,
+                ManageSecrets,
+                ApplyChangeSets,
+                InstallPackages,
+                ManageWorkspace,
+            ],
+        }
+    }
+
+    pub fn allows(&self, permission: WorkspacePermission) -> bool {
+        self.permissions().contains(&permission)
+    }
+
+    /// Returns `true` if the role has every permission of the other role.
+    pub fn includes(&self, other: WorkspaceRole) -> bool {
+        other
+            .permissions()
+            .iter()
+            .all(|permission| self.allows(*permission))
+    }
+}
+
+/// Something a [`User`](crate::User) may or may not do in a [`Workspace`](crate::Workspace),
+/// depending on their [`WorkspaceRole`]. Displays as the action it permits, so that it reads well
+/// in permission errors.
+#[remain::sorted]
+#[derive(Deserialize, Serialize, Debug, Display, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum WorkspacePermission {
+    #[strum(serialize = "apply change sets")]
+    ApplyChangeSets,
+    #[strum(serialize = "edit change sets")]
+    EditChangeSets,
+    #[strum(serialize = "install packages")]
+    InstallPackages,
+    #[strum(serialize = "manage secrets")]
+    ManageSecrets,
+    #[strum(serialize = "manage the workspace")]
+    ManageWorkspace,
+    #[strum(serialize = "read the workspace")]
+    Read,
+}
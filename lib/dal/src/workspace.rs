@@ -1,37 +1,78 @@
 use serde::{Deserialize, Serialize};
 use si_data_nats::NatsError;
 use si_data_pg::PgError;
+use si_pkg::SiPkgError;
 use telemetry::prelude::*;
 use thiserror::Error;
 
 use crate::{
-    pk, standard_model, standard_model_accessor_ro, DalContext, HistoryActor, HistoryEvent,
-    HistoryEventError, KeyPair, KeyPairError, StandardModelError, Tenancy, Timestamp,
-    TransactionsError, User, UserError, UserPk,
+    pk, pkg::PkgError, standard_model, standard_model_accessor_ro, ChangeSetError, DalContext,
+    HistoryActor, HistoryEvent, HistoryEventError, KeyPair, KeyPairError, SecretError,
+    StandardModelError, Tenancy, Timestamp, TransactionsError, User, UserError, UserPk,
+    WorkspacePermission, WorkspaceRole, WsEventError,
 };
 
+pub mod backup;
+pub mod fork;
+
+pub use backup::{
+    WorkspaceBackup, WorkspaceBackupPk, WorkspaceBackupRetention, WorkspaceRestoreTarget,
+};
+pub use fork::{WorkspaceForkProgressPayload, WorkspaceForkStep};
+
 const WORKSPACE_GET_BY_PK: &str = include_str!("queries/workspace/get_by_pk.sql");
 const WORKSPACE_FIND_BY_NAME: &str = include_str!("queries/workspace/find_by_name.sql");
+const WORKSPACE_LIST: &str = include_str!("queries/workspace/list.sql");
+const WORKSPACE_LIST_FOR_USER: &str = include_str!("queries/workspace/list_for_user.sql");
+const WORKSPACE_LIST_MEMBERS: &str = include_str!("queries/workspace/list_members.sql");
 
 #[remain::sorted]
 #[derive(Error, Debug)]
 pub enum WorkspaceError {
+    #[error("workspace backup not found: {0}")]
+    BackupNotFound(WorkspaceBackupPk),
+    #[error("{0} is not a backup of this workspace")]
+    BackupOfAnotherWorkspace(String),
+    #[error(transparent)]
+    ChangeSet(#[from] Box<ChangeSetError>),
     #[error(transparent)]
     HistoryEvent(#[from] HistoryEventError),
+    #[error("invalid number of required change set approvals: {0}")]
+    InvalidRequiredChangeSetApprovals(i32),
     #[error(transparent)]
     KeyPair(#[from] KeyPairError),
+    #[error("user {0} is the last admin of workspace {1}")]
+    LastAdmin(UserPk, WorkspacePk),
     #[error(transparent)]
     Nats(#[from] NatsError),
+    #[error("user {0} is not a member of workspace {1}")]
+    NotAMember(UserPk, WorkspacePk),
+    #[error("{0} is not a workspace backup")]
+    NotAWorkspaceBackup(String),
+    #[error("workspace not found: {0}")]
+    NotFound(WorkspacePk),
+    #[error("no workspace in tenancy")]
+    NoWorkspaceInTenancy,
     #[error(transparent)]
     Pg(#[from] PgError),
     #[error(transparent)]
+    Pkg(#[from] Box<PkgError>),
+    #[error(transparent)]
+    Secret(#[from] SecretError),
+    #[error(transparent)]
     SerdeJson(#[from] serde_json::Error),
     #[error(transparent)]
+    SiPkg(#[from] SiPkgError),
+    #[error(transparent)]
     StandardModel(#[from] StandardModelError),
     #[error(transparent)]
+    StrumParse(#[from] strum::ParseError),
+    #[error(transparent)]
     Transactions(#[from] TransactionsError),
     #[error(transparent)]
     User(#[from] UserError),
+    #[error(transparent)]
+    WsEvent(#[from] WsEventError),
 }
 
 pub type WorkspaceResult<T> = Result<T, WorkspaceError>;
@@ -45,10 +86,30 @@ pub struct WorkspaceSignup {
     pub workspace: Workspace,
 }
 
+/// A [`User`] that belongs to a [`Workspace`], along with their [`WorkspaceRole`] in it.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceMember {
+    pub user: User,
+    pub role: WorkspaceRole,
+}
+
+/// A [`Workspace`] a [`User`] belongs to, along with their [`WorkspaceRole`] in it.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UserWorkspace {
+    pub workspace: Workspace,
+    pub role: WorkspaceRole,
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
 pub struct Workspace {
     pk: WorkspacePk,
     name: String,
+    /// How many users have to approve a [`ChangeSet`](crate::ChangeSet) before it can be
+    /// applied. Zero means change sets can be applied without review.
+    #[serde(default)]
+    required_change_set_approvals: i32,
     #[serde(flatten)]
     timestamp: Timestamp,
 }
@@ -74,6 +135,8 @@ impl Workspace {
         Ok(object)
     }
 
+    /// Picks the oldest workspace that is not the builtin one, whoever it belongs to. Users can
+    /// belong to several workspaces: use [`Self::list_for_user()`] to find theirs.
     pub async fn find_first_user_workspace(ctx: &DalContext) -> WorkspaceResult<Option<Self>> {
         let row = ctx.txns().await?.pg().query_opt(
             "SELECT row_to_json(w.*) AS object FROM workspaces AS w WHERE pk != $1 ORDER BY created_at ASC LIMIT 1", &[&WorkspacePk::NONE],
@@ -116,6 +179,32 @@ impl Workspace {
         Ok(object)
     }
 
+    /// Creates a workspace with a [`KeyPair`], making the acting [`User`], if there is one, its
+    /// admin. The context keeps its tenancy.
+    pub(crate) async fn new_for_history_actor(
+        ctx: &DalContext,
+        pk: WorkspacePk,
+        name: impl AsRef<str>,
+    ) -> WorkspaceResult<(Self, KeyPair)> {
+        let mut new_ctx = ctx.clone();
+        let workspace = Workspace::new(&mut new_ctx, pk, name).await?;
+        if let HistoryActor::User(user_pk) = ctx.history_actor() {
+            let user = User::get_by_pk(ctx, *user_pk)
+                .await?
+                .ok_or(UserError::NotFoundInTenancy(*user_pk, *ctx.tenancy()))?;
+            user.associate_workspace(&new_ctx, pk, WorkspaceRole::Admin)
+                .await?;
+        }
+        let key_pair = KeyPair::new(&new_ctx, "default").await?;
+        Ok((workspace, key_pair))
+    }
+
+    /// Lists every workspace but the builtin one.
+    pub async fn list(ctx: &DalContext) -> WorkspaceResult<Vec<Self>> {
+        let rows = ctx.txns().await?.pg().query(WORKSPACE_LIST, &[]).await?;
+        Ok(standard_model::objects_from_rows(rows)?)
+    }
+
     pub async fn clear(&self, ctx: &DalContext) -> WorkspaceResult<()> {
         let tenancy = Tenancy::new(self.pk);
 
@@ -163,6 +252,8 @@ impl Workspace {
             None::<&str>,
         )
         .await?;
+        user.associate_workspace(ctx, workspace.pk, WorkspaceRole::Admin)
+            .await?;
         ctx.update_history_actor(HistoryActor::User(user.pk()));
 
         ctx.import_builtins().await?;
@@ -204,4 +295,132 @@ impl Workspace {
     }
 
     standard_model_accessor_ro!(name, String);
+
+    pub fn required_change_set_approvals(&self) -> i32 {
+        self.required_change_set_approvals
+    }
+
+    /// Sets how many users have to approve a [`ChangeSet`](crate::ChangeSet) before it can be
+    /// applied in this workspace.
+    #[instrument(skip(ctx))]
+    pub async fn set_required_change_set_approvals(
+        &mut self,
+        ctx: &DalContext,
+        required: i32,
+    ) -> WorkspaceResult<()> {
+        User::ensure_authorized_in_workspace(ctx, self.pk, WorkspacePermission::ManageWorkspace)
+            .await?;
+        if required < 0 {
+            return Err(WorkspaceError::InvalidRequiredChangeSetApprovals(required));
+        }
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT timestamp_updated_at FROM workspace_update_required_change_set_approvals_v1($1, $2)",
+                &[&self.pk, &required],
+            )
+            .await?;
+        self.timestamp.updated_at = row.try_get("timestamp_updated_at")?;
+        self.required_change_set_approvals = required;
+
+        let _history_event = HistoryEvent::new(
+            ctx,
+            "workspace.update_required_change_set_approvals",
+            "Workspace required change set approvals updated",
+            &serde_json::json![{ "pk": &self.pk, "required": required }],
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Lists the [`Users`](User) that belong to the workspace, with their roles.
+    pub async fn members(&self, ctx: &DalContext) -> WorkspaceResult<Vec<WorkspaceMember>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(WORKSPACE_LIST_MEMBERS, &[&self.pk])
+            .await?;
+        let mut members = Vec::with_capacity(rows.len());
+        for row in rows {
+            let json: serde_json::Value = row.try_get("object")?;
+            let role: String = row.try_get("role")?;
+            members.push(WorkspaceMember {
+                user: serde_json::from_value(json)?,
+                role: role.parse()?,
+            });
+        }
+        Ok(members)
+    }
+
+    /// Lists the workspaces the [`User`] belongs to, with their role in each, ordered by name.
+    pub async fn list_for_user(
+        ctx: &DalContext,
+        user_pk: UserPk,
+    ) -> WorkspaceResult<Vec<UserWorkspace>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(WORKSPACE_LIST_FOR_USER, &[&user_pk])
+            .await?;
+        let mut workspaces = Vec::with_capacity(rows.len());
+        for row in rows {
+            let json: serde_json::Value = row.try_get("object")?;
+            let role: String = row.try_get("role")?;
+            workspaces.push(UserWorkspace {
+                workspace: serde_json::from_value(json)?,
+                role: role.parse()?,
+            });
+        }
+        Ok(workspaces)
+    }
+
+    /// Changes the role of a member of the workspace. The workspace always keeps at least one
+    /// [`admin`](WorkspaceRole::Admin).
+    #[instrument(skip(ctx))]
+    pub async fn set_member_role(
+        &self,
+        ctx: &DalContext,
+        user_pk: UserPk,
+        role: WorkspaceRole,
+    ) -> WorkspaceResult<()> {
+        User::ensure_authorized_in_workspace(ctx, self.pk, WorkspacePermission::ManageWorkspace)
+            .await?;
+
+        let members = self.members(ctx).await?;
+        let member = members
+            .iter()
+            .find(|member| member.user.pk() == user_pk)
+            .ok_or(WorkspaceError::NotAMember(user_pk, self.pk))?;
+        if member.role == WorkspaceRole::Admin
+            && role != WorkspaceRole::Admin
+            && !members
+                .iter()
+                .any(|other| other.role == WorkspaceRole::Admin && other.user.pk() != user_pk)
+        {
+            return Err(WorkspaceError::LastAdmin(user_pk, self.pk));
+        }
+
+        ctx.txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT timestamp_updated_at FROM user_set_workspace_role_v1($1, $2, $3)",
+                &[&user_pk, &self.pk, &role.to_string()],
+            )
+            .await?;
+
+        let _history_event = HistoryEvent::new(
+            ctx,
+            "workspace.set_member_role",
+            "Workspace member role updated",
+            &serde_json::json![{ "pk": &self.pk, "user_pk": user_pk, "role": role }],
+        )
+        .await?;
+        Ok(())
+    }
 }
@@ -0,0 +1,395 @@
+//! This module contains [`WorkspaceBackup`], the record of a backup of a [`Workspace`] uploaded
+//! to the module index, and [`Workspace::restore_backup()`].
+
+use std::collections::HashSet;
+use std::str::FromStr;
+use std::time::Duration;
+
+use chrono::{DateTime, Datelike, Utc};
+use serde::{Deserialize, Serialize};
+use si_pkg::{SiPkg, SiPkgKind, SiPkgMetadata};
+use telemetry::prelude::*;
+
+use crate::pkg::{import_pkg_from_pkg, ImportOptions, ImportSelection, ImportSkips};
+use crate::standard_model::{object_option_from_row_option, objects_from_rows};
+use crate::{pk, DalContext, HistoryEvent, Tenancy, User, WorkspacePermission};
+
+use super::{Workspace, WorkspaceError, WorkspacePk, WorkspaceResult};
+
+const CLAIM_SCHEDULED: &str = include_str!("../queries/workspace_backup/claim_scheduled.sql");
+const GET_BY_PK: &str = include_str!("../queries/workspace_backup/get_by_pk.sql");
+const LIST: &str = include_str!("../queries/workspace_backup/list.sql");
+
+pk!(WorkspaceBackupPk);
+
+/// A backup of a [`Workspace`], stored in the module index as a workspace backup module.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct WorkspaceBackup {
+    pub pk: WorkspaceBackupPk,
+    /// The id of the module holding the backup in the module index.
+    pub module_id: String,
+    pub name: String,
+    pub version: String,
+    pub hash: String,
+    /// Whether the backup was taken by the backup scheduler rather than exported by a user.
+    pub scheduled: bool,
+    /// Set once the retention policy removed the backup from the module index.
+    pub pruned_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub tenancy: Tenancy,
+}
+
+impl WorkspaceBackup {
+    /// Records that a backup of the workspace of the context's tenancy was uploaded to the
+    /// module index.
+    #[instrument(skip_all)]
+    pub async fn record(
+        ctx: &DalContext,
+        module_id: impl AsRef<str>,
+        name: impl AsRef<str>,
+        version: impl AsRef<str>,
+        hash: impl AsRef<str>,
+        scheduled: bool,
+    ) -> WorkspaceResult<Self> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM workspace_backup_create_v1($1, $2, $3, $4, $5, $6)",
+                &[
+                    ctx.tenancy(),
+                    &module_id.as_ref(),
+                    &name.as_ref(),
+                    &version.as_ref(),
+                    &hash.as_ref(),
+                    &scheduled,
+                ],
+            )
+            .await?;
+        let json: serde_json::Value = row.try_get("object")?;
+        Ok(serde_json::from_value(json)?)
+    }
+
+    #[instrument(skip(ctx))]
+    pub async fn get_by_pk(
+        ctx: &DalContext,
+        pk: WorkspaceBackupPk,
+    ) -> WorkspaceResult<Option<Self>> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(GET_BY_PK, &[ctx.tenancy(), &pk])
+            .await?;
+        Ok(object_option_from_row_option(row)?)
+    }
+
+    /// Lists the backups of the workspace of the context's tenancy that were not pruned, most
+    /// recent first. If `scheduled` is given, only the backups taken (or not) by the backup
+    /// scheduler are listed.
+    #[instrument(skip(ctx))]
+    pub async fn list(ctx: &DalContext, scheduled: Option<bool>) -> WorkspaceResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(LIST, &[ctx.tenancy(), &scheduled])
+            .await?;
+        Ok(objects_from_rows(rows)?)
+    }
+
+    /// Claims the scheduled backup of the workspace of the context's tenancy, unless it was
+    /// claimed less than `min_age` ago. Only one of the backup schedulers running concurrently
+    /// gets the claim, which holds whether or not the backup then succeeds. The claim is only
+    /// taken once the transaction commits.
+    #[instrument(skip(ctx))]
+    pub async fn claim_scheduled(ctx: &DalContext, min_age: Duration) -> WorkspaceResult<bool> {
+        let workspace_pk = ctx
+            .tenancy()
+            .workspace_pk()
+            .ok_or(WorkspaceError::NoWorkspaceInTenancy)?;
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(CLAIM_SCHEDULED, &[&workspace_pk, &min_age.as_secs_f64()])
+            .await?;
+        Ok(row.is_some())
+    }
+
+    /// Marks the backup as pruned. Removing it from the module index is up to the caller.
+    #[instrument(skip_all, fields(workspace_backup_pk = %self.pk))]
+    pub async fn mark_pruned(&mut self, ctx: &DalContext) -> WorkspaceResult<()> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT pruned_at FROM workspace_backup_prune_v1($1, $2)",
+                &[&self.pk, ctx.tenancy()],
+            )
+            .await?;
+        let pruned_at: Option<DateTime<Utc>> = row.try_get("pruned_at")?;
+        self.pruned_at = Some(pruned_at.ok_or(WorkspaceError::BackupNotFound(self.pk))?);
+        Ok(())
+    }
+}
+
+/// Which scheduled [`WorkspaceBackups`](WorkspaceBackup) to keep. A backup is kept as soon as
+/// one of the rules keeps it, the others are pruned.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WorkspaceBackupRetention {
+    /// Keep the most recent backups, whenever they were taken.
+    #[serde(default = "default_keep_last")]
+    pub keep_last: usize,
+    /// Keep the most recent backup of each of the last days that have one.
+    #[serde(default = "default_keep_daily")]
+    pub keep_daily: usize,
+    /// Keep the most recent backup of each of the last ISO weeks that have one.
+    #[serde(default = "default_keep_weekly")]
+    pub keep_weekly: usize,
+}
+
+impl Default for WorkspaceBackupRetention {
+    fn default() -> Self {
+        Self {
+            keep_last: default_keep_last(),
+            keep_daily: default_keep_daily(),
+            keep_weekly: default_keep_weekly(),
+        }
+    }
+}
+
+fn default_keep_last() -> usize {
+    3
+}
+
+fn default_keep_daily() -> usize {
+    7
+}
+
+fn default_keep_weekly() -> usize {
+    4
+}
+
+impl WorkspaceBackupRetention {
+    /// Picks the backups the policy does not keep, given when each of them was taken. The
+    /// backups can be in any order.
+    pub fn prunable<'a, T>(
+        &self,
+        backups: &'a [T],
+        created_at: impl Fn(&T) -> DateTime<Utc>,
+    ) -> Vec<&'a T> {
+        let mut newest_first: Vec<&T> = backups.iter().collect();
+        newest_first.sort_by_key(|backup| std::cmp::Reverse(created_at(backup)));
+
+        let mut kept = HashSet::new();
+        let mut days = HashSet::new();
+        let mut weeks = HashSet::new();
+        for (index, backup) in newest_first.iter().enumerate() {
+            let taken_at = created_at(backup);
+            if index < self.keep_last {
+                kept.insert(index);
+            }
+            if days.len() < self.keep_daily && days.insert(taken_at.date_naive()) {
+                kept.insert(index);
+            }
+            let week = taken_at.iso_week();
+            if weeks.len() < self.keep_weekly && weeks.insert((week.year(), week.week())) {
+                kept.insert(index);
+            }
+        }
+
+        newest_first
+            .into_iter()
+            .enumerate()
+            .filter(|(index, _)| !kept.contains(index))
+            .map(|(_, backup)| backup)
+            .collect()
+    }
+}
+
+/// Where [`Workspace::restore_backup()`] restores a backup to.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum WorkspaceRestoreTarget {
+    /// Clears the workspace the backup was taken from, then restores the backup into it.
+    Current,
+    /// Restores the backup into a new workspace with the given name.
+    New { name: String },
+}
+
+impl Workspace {
+    /// Restores a backup of the workspace of the context's tenancy. Backups hold the schemas,
+    /// funcs, components and edges of head and of the change sets that were open, but no
+    /// secrets: restoring into the [`current`](WorkspaceRestoreTarget::Current) workspace
+    /// deletes its secrets. The caller is responsible for committing.
+    #[instrument(skip_all)]
+    pub async fn restore_backup(
+        ctx: &DalContext,
+        pkg: &SiPkg,
+        target: WorkspaceRestoreTarget,
+    ) -> WorkspaceResult<Self> {
+        User::ensure_authorized(ctx, WorkspacePermission::ManageWorkspace).await?;
+
+        let (workspace_pk, metadata) = backup_of_current_workspace(ctx, pkg)?;
+
+        let ctx = ctx.clone_with_head();
+        let workspace = match &target {
+            WorkspaceRestoreTarget::Current => Workspace::get_by_pk(&ctx, &workspace_pk)
+                .await?
+                .ok_or(WorkspaceError::NotFound(workspace_pk))?,
+            WorkspaceRestoreTarget::New { name } => {
+                let (workspace, _key_pair) =
+                    Workspace::new_for_history_actor(&ctx, WorkspacePk::generate(), name).await?;
+                workspace
+            }
+        };
+
+        import_pkg_from_pkg(
+            &ctx,
+            pkg,
+            Some(ImportOptions {
+                no_record: true,
+                permission: Some(WorkspacePermission::ManageWorkspace),
+                workspace_pk: Some(workspace.pk),
+                ..Default::default()
+            }),
+        )
+        .await
+        .map_err(Box::new)?;
+
+        let _history_event = HistoryEvent::new(
+            &ctx,
+            "workspace.restore_backup",
+            "Workspace backup restored",
+            &serde_json::json![{
+                "backup_name": metadata.name(),
+                "backup_version": metadata.version(),
+                "workspace_pk": workspace.pk,
+            }],
+        )
+        .await?;
+
+        Ok(workspace)
+    }
+
+    /// Restores the items of a backup of the workspace of the context's tenancy that the
+    /// selection picks, along with what they depend on, into the change set of the context.
+    /// Nothing else is touched: the picked items that already exist are left as they are and
+    /// reported as conflicts. The caller is responsible for committing.
+    #[instrument(skip_all)]
+    pub async fn restore_backup_selection(
+        ctx: &DalContext,
+        pkg: &SiPkg,
+        selection: ImportSelection,
+    ) -> WorkspaceResult<ImportSkips> {
+        User::ensure_authorized(ctx, WorkspacePermission::EditChangeSets).await?;
+
+        let (workspace_pk, metadata) = backup_of_current_workspace(ctx, pkg)?;
+
+        let (_, _, import_skips) = import_pkg_from_pkg(
+            ctx,
+            pkg,
+            Some(ImportOptions {
+                no_record: true,
+                permission: Some(WorkspacePermission::EditChangeSets),
+                selection: Some(selection),
+                ..Default::default()
+            }),
+        )
+        .await
+        .map_err(Box::new)?;
+        let import_skips = import_skips
+            .and_then(|import_skips| import_skips.into_iter().next())
+            .ok_or(WorkspaceError::NotAWorkspaceBackup(
+                metadata.name().to_owned(),
+            ))?;
+
+        let _history_event = HistoryEvent::new(
+            ctx,
+            "workspace.restore_backup_selection",
+            "Workspace backup partially restored",
+            &serde_json::json![{
+                "backup_name": metadata.name(),
+                "backup_version": metadata.version(),
+                "workspace_pk": workspace_pk,
+                "conflict_count": import_skips.conflicts().len(),
+            }],
+        )
+        .await?;
+
+        Ok(import_skips)
+    }
+}
+
+/// Checks the package is a backup of the workspace of the context's tenancy.
+fn backup_of_current_workspace(
+    ctx: &DalContext,
+    pkg: &SiPkg,
+) -> WorkspaceResult<(WorkspacePk, SiPkgMetadata)> {
+    let workspace_pk = ctx
+        .tenancy()
+        .workspace_pk()
+        .ok_or(WorkspaceError::NoWorkspaceInTenancy)?;
+    let metadata = pkg.metadata()?;
+    if metadata.kind() != SiPkgKind::WorkspaceBackup {
+        return Err(WorkspaceError::NotAWorkspaceBackup(
+            metadata.name().to_owned(),
+        ));
+    }
+    let backup_workspace_pk = metadata
+        .workspace_pk()
+        .and_then(|pk| WorkspacePk::from_str(pk).ok());
+    if backup_workspace_pk != Some(workspace_pk) {
+        return Err(WorkspaceError::BackupOfAnotherWorkspace(
+            metadata.name().to_owned(),
+        ));
+    }
+
+    Ok((workspace_pk, metadata))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn retention_keeps_last_daily_and_weekly_backups() {
+        let retention = WorkspaceBackupRetention {
+            keep_last: 2,
+            keep_daily: 3,
+            keep_weekly: 2,
+        };
+        let at = |day: u32, hour: u32| {
+            Utc.with_ymd_and_hms(2023, 9, day, hour, 0, 0)
+                .single()
+                .expect("invalid date")
+        };
+        // Weeks run from monday to sunday, e.g. from the 11th to the 17th
+        let backups = vec![
+            at(20, 12),
+            at(20, 6),
+            at(19, 6),
+            at(18, 6),
+            at(17, 6),
+            at(11, 6),
+            at(10, 6),
+            at(1, 6),
+        ];
+
+        let mut prunable: Vec<_> = retention
+            .prunable(&backups, |created_at| *created_at)
+            .into_iter()
+            .copied()
+            .collect();
+        prunable.sort();
+        // Kept: the last two, the last of the 20th, 19th and 18th, and the last of the weeks of
+        // the 18th and the 11th, which is the 17th.
+        assert_eq!(vec![at(1, 6), at(10, 6), at(11, 6)], prunable);
+    }
+}
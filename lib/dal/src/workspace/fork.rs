@@ -0,0 +1,186 @@
+//! This module contains [`Workspace::fork()`], which copies a [`Workspace`] into a new one.
+
+use std::collections::HashMap;
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+
+use crate::pkg::{import_pkg_from_pkg, ImportOptions, PkgExporter};
+use crate::{
+    ChangeSetPk, DalContext, EncryptedSecret, HistoryActor, HistoryEvent, User,
+    WorkspacePermission, WsEvent, WsEventResult, WsPayload,
+};
+
+use super::{Workspace, WorkspacePk, WorkspaceResult};
+
+/// The steps of a [`Workspace::fork()`], in the order they happen.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum WorkspaceForkStep {
+    Started,
+    Exporting,
+    Importing,
+    CopyingSecrets,
+    Finished,
+    Failed,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceForkProgressPayload {
+    fork_workspace_pk: WorkspacePk,
+    step: WorkspaceForkStep,
+    /// Only set when the fork [`failed`](WorkspaceForkStep::Failed).
+    error: Option<String>,
+}
+
+impl Workspace {
+    /// Forks the workspace of the context's tenancy into a new workspace named `name`. Its
+    /// schemas, funcs, components and edges are copied, on head and in every open
+    /// [`ChangeSet`](crate::ChangeSet), and so are its secrets, re-encrypted with the
+    /// [`KeyPair`](crate::KeyPair) of the new workspace. Secrets referencing a
+    /// [`SecretBackend`](crate::secret::SecretBackend) keep resolving in the namespace of this
+    /// workspace, as the backend material cannot be copied. The acting [`User`] becomes the only
+    /// admin of the new workspace. Approvals are not copied: every change set of the fork starts
+    /// out [`Open`](crate::ChangeSetStatus::Open), including those pending approval here.
+    ///
+    /// Progress is published to the source workspace as
+    /// [`WorkspaceForkProgress`](WsPayload::WorkspaceForkProgress) events: right away while the
+    /// fork runs, on commit once it has [`finished`](WorkspaceForkStep::Finished). The caller is
+    /// responsible for committing.
+    #[instrument(skip_all)]
+    pub async fn fork(ctx: &DalContext, name: impl AsRef<str>) -> WorkspaceResult<Self> {
+        User::ensure_authorized(ctx, WorkspacePermission::ManageWorkspace).await?;
+
+        let ctx = ctx.clone_with_head();
+        let fork_pk = WorkspacePk::generate();
+        match Self::fork_into(&ctx, fork_pk, name.as_ref()).await {
+            Ok(fork) => {
+                WsEvent::workspace_fork_progress(&ctx, fork_pk, WorkspaceForkStep::Finished, None)
+                    .await?
+                    .publish_on_commit(&ctx)
+                    .await?;
+                Ok(fork)
+            }
+            Err(err) => {
+                // The transaction is going to be rolled back, so this cannot wait for the commit
+                let published = match WsEvent::workspace_fork_progress(
+                    &ctx,
+                    fork_pk,
+                    WorkspaceForkStep::Failed,
+                    Some(err.to_string()),
+                )
+                .await
+                {
+                    Ok(ws_event) => ws_event.publish_immediately(&ctx).await,
+                    Err(publish_err) => Err(publish_err),
+                };
+                if let Err(publish_err) = published {
+                    warn!(error = ?publish_err, "failed to publish workspace fork failure");
+                }
+                Err(err)
+            }
+        }
+    }
+
+    async fn fork_into(
+        ctx: &DalContext,
+        fork_pk: WorkspacePk,
+        name: &str,
+    ) -> WorkspaceResult<Self> {
+        publish_fork_progress(ctx, fork_pk, WorkspaceForkStep::Started).await?;
+
+        let user = match ctx.history_actor() {
+            HistoryActor::User(user_pk) => User::get_by_pk(ctx, *user_pk).await?,
+            HistoryActor::SystemInit => None,
+        };
+
+        let (fork, key_pair) = Workspace::new_for_history_actor(ctx, fork_pk, name).await?;
+
+        publish_fork_progress(ctx, fork_pk, WorkspaceForkStep::Exporting).await?;
+        let mut exporter = PkgExporter::new_workspace_exporter(
+            name,
+            user.as_ref().map_or("system", |user| user.email().as_str()),
+            Utc::now().format("%Y-%m-%d_%H:%M:%S").to_string(),
+            "workspace fork",
+        );
+        let pkg = exporter.export(ctx).await.map_err(Box::new)?;
+
+        publish_fork_progress(ctx, fork_pk, WorkspaceForkStep::Importing).await?;
+        let (_, _, import_skips) = import_pkg_from_pkg(
+            ctx,
+            &pkg,
+            Some(ImportOptions {
+                no_record: true,
+                permission: Some(WorkspacePermission::ManageWorkspace),
+                workspace_pk: Some(fork_pk),
+                ..Default::default()
+            }),
+        )
+        .await
+        .map_err(Box::new)?;
+
+        // The backup records the pk every open change set had here, which maps it to the change
+        // set it was imported as
+        let change_set_pks: HashMap<_, _> = import_skips
+            .iter()
+            .flatten()
+            .filter_map(|skips| {
+                skips
+                    .source_change_set_pk()
+                    .filter(|source_pk| *source_pk != ChangeSetPk::NONE)
+                    .map(|source_pk| (source_pk, skips.change_set_pk()))
+            })
+            .collect();
+
+        publish_fork_progress(ctx, fork_pk, WorkspaceForkStep::CopyingSecrets).await?;
+        let copied_secret_count =
+            EncryptedSecret::copy_all_to_workspace(ctx, &key_pair, &change_set_pks).await?;
+
+        let _history_event = HistoryEvent::new(
+            ctx,
+            "workspace.fork",
+            "Workspace forked",
+            &serde_json::json![{
+                "fork_workspace_pk": fork_pk,
+                "change_set_count": change_set_pks.len(),
+                "secret_count": copied_secret_count,
+            }],
+        )
+        .await?;
+
+        Ok(fork)
+    }
+}
+
+async fn publish_fork_progress(
+    ctx: &DalContext,
+    fork_workspace_pk: WorkspacePk,
+    step: WorkspaceForkStep,
+) -> WorkspaceResult<()> {
+    WsEvent::workspace_fork_progress(ctx, fork_workspace_pk, step, None)
+        .await?
+        .publish_immediately(ctx)
+        .await?;
+    Ok(())
+}
+
+impl WsEvent {
+    pub async fn workspace_fork_progress(
+        ctx: &DalContext,
+        fork_workspace_pk: WorkspacePk,
+        step: WorkspaceForkStep,
+        error: Option<String>,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(
+            ctx,
+            WsPayload::WorkspaceForkProgress(WorkspaceForkProgressPayload {
+                fork_workspace_pk,
+                step,
+                error,
+            }),
+        )
+        .await
+    }
+}
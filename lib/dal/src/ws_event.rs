@@ -3,13 +3,16 @@ use si_data_nats::NatsError;
 use si_data_pg::PgError;
 use thiserror::Error;
 
+use crate::change_set::ChangeSetApprovalPayload;
 use crate::component::ComponentCreatedPayload;
+use crate::drift::ComponentDriftChangedPayload;
 use crate::{
     component::{code::CodeGeneratedPayload, resource::ResourceRefreshedPayload},
     fix::{batch::FixBatchReturn, FixReturn},
     func::binding::LogLinePayload,
     qualification::QualificationCheckPayload,
     status::StatusMessage,
+    workspace::fork::WorkspaceForkProgressPayload,
     AttributeValueId, ChangeSetPk, ComponentId, DalContext, PropId, SchemaPk, SocketId,
     StandardModelError, TransactionsError, WorkspacePk,
 };
@@ -39,18 +42,26 @@ pub type WsEventResult<T> = Result<T, WsEventError>;
 #[allow(clippy::large_enum_variant)]
 pub enum WsPayload {
     ChangeSetApplied(ChangeSetPk),
+    ChangeSetApprovalRequested(ChangeSetPk),
+    ChangeSetApproved(ChangeSetApprovalPayload),
     ChangeSetCanceled(ChangeSetPk),
+    ChangeSetClosed(ChangeSetPk),
     ChangeSetCreated(ChangeSetPk),
+    ChangeSetRebased(ChangeSetPk),
+    ChangeSetRejected(ChangeSetApprovalPayload),
+    ChangeSetReopened(ChangeSetPk),
     ChangeSetWritten(ChangeSetPk),
     CheckedQualifications(QualificationCheckPayload),
     CodeGenerated(CodeGeneratedPayload),
     ComponentCreated(ComponentCreatedPayload),
+    ComponentDriftChanged(ComponentDriftChangedPayload),
     FixBatchReturn(FixBatchReturn),
     FixReturn(FixReturn),
     LogLine(LogLinePayload),
     ResourceRefreshed(ResourceRefreshedPayload),
     SchemaCreated(SchemaPk),
     StatusUpdate(StatusMessage),
+    WorkspaceForkProgress(WorkspaceForkProgressPayload),
 }
 
 #[remain::sorted]
@@ -124,4 +135,13 @@ impl WsEvent {
         ctx.txns().await?.nats().publish(subject, &self).await?;
         Ok(())
     }
+
+    /// Publishes the [`event`](Self) right away, regardless of whether the transaction is ever
+    /// committed. Meant for reporting the progress of long running work.
+    pub async fn publish_immediately(&self, ctx: &DalContext) -> WsEventResult<()> {
+        let subject = format!("si.workspace_pk.{}.event", self.workspace_pk);
+        let msg_bytes = serde_json::to_vec(self)?;
+        ctx.nats_conn().publish(subject, msg_bytes).await?;
+        Ok(())
+    }
 }
@@ -0,0 +1,128 @@
+use chrono::{Duration, Utc};
+use dal::{
+    ActionKind, ActionPrototype, ActionPrototypeContext, ActionSchedule, ActionScheduleError,
+    Component, FixBatch, FuncId, StandardModel,
+};
+use dal_test::{test, test_harness::create_component_and_schema, DalContextHeadRef};
+use pretty_assertions_sorted::assert_eq;
+
+#[test]
+async fn lifecycle(DalContextHeadRef(ctx): DalContextHeadRef<'_>) {
+    let mut component = create_component_and_schema(ctx).await;
+    let context = ActionPrototypeContext {
+        schema_variant_id: Component::schema_variant_id(ctx, *component.id())
+            .await
+            .expect("could not get schema variant id"),
+    };
+    let rotate = ActionPrototype::new(ctx, FuncId::NONE, ActionKind::Other, context)
+        .await
+        .expect("could not create action prototype");
+    let create = ActionPrototype::new(ctx, FuncId::NONE, ActionKind::Create, context)
+        .await
+        .expect("could not create action prototype");
+
+    assert!(matches!(
+        ActionSchedule::new(
+            ctx,
+            *create.id(),
+            *component.id(),
+            "toddhoward@bethesda.com",
+            Some(Utc::now()),
+            None,
+        )
+        .await,
+        Err(ActionScheduleError::NotSchedulable(ActionKind::Create))
+    ));
+    assert!(matches!(
+        ActionSchedule::new(
+            ctx,
+            *rotate.id(),
+            *component.id(),
+            "toddhoward@bethesda.com",
+            None,
+            None,
+        )
+        .await,
+        Err(ActionScheduleError::RunAtOrRecurrenceRequired)
+    ));
+
+    let mut once = ActionSchedule::new(
+        ctx,
+        *rotate.id(),
+        *component.id(),
+        "toddhoward@bethesda.com",
+        Some(Utc::now() - Duration::minutes(1)),
+        None,
+    )
+    .await
+    .expect("could not create action schedule");
+    let mut nightly = ActionSchedule::new(
+        ctx,
+        *rotate.id(),
+        *component.id(),
+        "toddhoward@bethesda.com",
+        None,
+        Some("0 2 * * *".parse().expect("could not parse recurrence")),
+    )
+    .await
+    .expect("could not create action schedule");
+    assert!(nightly.next_run_at().expect("no next run") > Utc::now());
+
+    let due_ids = |due: Vec<ActionSchedule>| due.iter().map(|s| *s.id()).collect::<Vec<_>>();
+    let due = ActionSchedule::list_due(ctx, Utc::now())
+        .await
+        .expect("could not list due schedules");
+    assert_eq!(vec![*once.id()], due_ids(due));
+
+    // Paused schedules are never due.
+    once.pause(ctx).await.expect("could not pause");
+    let due = ActionSchedule::list_due(ctx, Utc::now())
+        .await
+        .expect("could not list due schedules");
+    assert!(due_ids(due).is_empty());
+    once.resume(ctx).await.expect("could not resume");
+
+    let claimed = ActionSchedule::claim_due(ctx, *once.id(), Utc::now())
+        .await
+        .expect("could not claim schedule");
+    assert_eq!(Some(*once.id()), claimed.map(|s| *s.id()));
+    let batch_id = once.enqueue(ctx).await.expect("could not enqueue");
+    assert_eq!(Some(batch_id), once.last_fix_batch_id());
+    assert!(once.last_run_at().is_some());
+    // One-off schedules are done after their run.
+    assert_eq!(None, once.next_run_at());
+    let due = ActionSchedule::list_due(ctx, Utc::now())
+        .await
+        .expect("could not list due schedules");
+    assert!(due_ids(due).is_empty());
+    // Nor can it be claimed anymore.
+    let claimed = ActionSchedule::claim_due(ctx, *once.id(), Utc::now())
+        .await
+        .expect("could not claim schedule");
+    assert!(claimed.is_none());
+
+    let batch = FixBatch::get_by_id(ctx, &batch_id)
+        .await
+        .expect("could not get fix batch")
+        .expect("fix batch not found");
+    assert_eq!("toddhoward@bethesda.com", batch.author());
+    let fixes = batch.fixes(ctx).await.expect("could not list fixes");
+    assert_eq!(1, fixes.len());
+    assert_eq!(rotate.id(), fixes[0].action_prototype_id());
+
+    let schedules = ActionSchedule::list_for_component(ctx, *component.id())
+        .await
+        .expect("could not list schedules");
+    assert_eq!(2, schedules.len());
+
+    // Schedules of deleted components are not enqueued.
+    component
+        .delete_and_propagate(ctx)
+        .await
+        .expect("could not delete component");
+    let component_id = *component.id();
+    assert!(matches!(
+        nightly.enqueue(ctx).await,
+        Err(ActionScheduleError::ComponentNotFound(id)) if id == component_id
+    ));
+}
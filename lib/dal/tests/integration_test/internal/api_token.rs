@@ -0,0 +1,141 @@
+use dal::{
+    ApiToken, ApiTokenError, DalContext, HistoryActor, HistoryEvent, HistoryEventQuery, User,
+    UserError, WorkspacePermission, WorkspaceRole, WorkspaceSignup,
+};
+use dal_test::test;
+
+#[test]
+async fn create_authenticate_and_revoke(ctx: &mut DalContext, nw: &WorkspaceSignup) {
+    let result = ApiToken::new(ctx, "ci", None, None).await;
+    assert!(matches!(result, Err(ApiTokenError::RequiresUser)));
+
+    ctx.update_history_actor(HistoryActor::User(nw.user.pk()));
+    let (api_token, token) = ApiToken::new(ctx, "ci", Some(WorkspaceRole::Viewer), None)
+        .await
+        .expect("cannot create api token");
+    assert_eq!(api_token.user_pk, nw.user.pk());
+    assert_eq!(api_token.max_role, Some(WorkspaceRole::Viewer));
+    assert_eq!(api_token.last_used_at, None);
+
+    let (expired, expired_token) = ApiToken::new(
+        ctx,
+        "expired",
+        None,
+        Some(chrono::Utc::now() - chrono::Duration::minutes(1)),
+    )
+    .await
+    .expect("cannot create api token");
+
+    let authenticated = ApiToken::authenticate(ctx, &token)
+        .await
+        .expect("cannot authenticate api token");
+    assert_eq!(authenticated.pk, api_token.pk);
+    assert!(authenticated.last_used_at.is_some());
+    let uses = HistoryEvent::list(
+        ctx,
+        &HistoryEventQuery {
+            label_prefix: Some("api_token.use".to_string()),
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("cannot list history events");
+    assert_eq!(uses.events.len(), 1);
+    assert_eq!(uses.events[0].actor, HistoryActor::User(nw.user.pk()));
+
+    let result = ApiToken::authenticate(ctx, &expired_token).await;
+    assert!(matches!(result, Err(ApiTokenError::Expired(pk)) if pk == expired.pk));
+    let result = ApiToken::authenticate(ctx, format!("{token}0")).await;
+    assert!(matches!(result, Err(ApiTokenError::Invalid)));
+
+    let tokens = ApiToken::list(ctx).await.expect("cannot list api tokens");
+    assert_eq!(
+        tokens.iter().map(|token| token.pk).collect::<Vec<_>>(),
+        vec![expired.pk, api_token.pk]
+    );
+
+    let mut api_token = authenticated;
+    api_token
+        .revoke(ctx)
+        .await
+        .expect("cannot revoke api token");
+    assert!(api_token.revoked_at.is_some());
+    let result = ApiToken::authenticate(ctx, &token).await;
+    assert!(matches!(result, Err(ApiTokenError::Revoked(pk)) if pk == api_token.pk));
+}
+
+#[test]
+async fn max_role(ctx: &mut DalContext, nw: &WorkspaceSignup) {
+    ctx.update_history_actor(HistoryActor::User(nw.user.pk()));
+    User::ensure_authorized(ctx, WorkspacePermission::ManageWorkspace)
+        .await
+        .expect("the user that signed up administers the workspace");
+
+    ctx.update_access_builder(ctx.access_builder().with_max_role(WorkspaceRole::Editor));
+    User::ensure_authorized(ctx, WorkspacePermission::ManageSecrets)
+        .await
+        .expect("editors can manage secrets");
+    let result = User::ensure_authorized(ctx, WorkspacePermission::ManageWorkspace).await;
+    assert!(matches!(
+        result,
+        Err(UserError::PermissionDenied(
+            _,
+            WorkspaceRole::Editor,
+            _,
+            WorkspacePermission::ManageWorkspace
+        ))
+    ));
+}
+
+#[test]
+async fn token_cannot_create_broader_token(ctx: &mut DalContext, nw: &WorkspaceSignup) {
+    ctx.update_history_actor(HistoryActor::User(nw.user.pk()));
+    let (caller, _) = ApiToken::new(
+        ctx,
+        "ci",
+        Some(WorkspaceRole::Editor),
+        Some(chrono::Utc::now() + chrono::Duration::hours(1)),
+    )
+    .await
+    .expect("cannot create api token");
+    let caller_expires_at = caller.expires_at.expect("api token has no expiry");
+
+    // Requests authenticated with the token are capped to its role
+    ctx.update_access_builder(
+        ctx.access_builder()
+            .with_max_role(WorkspaceRole::Editor)
+            .with_api_token_pk(caller.pk),
+    );
+
+    let result = ApiToken::new(ctx, "admin", Some(WorkspaceRole::Admin), None).await;
+    assert!(matches!(
+        result,
+        Err(ApiTokenError::RoleAboveCaller(
+            WorkspaceRole::Admin,
+            WorkspaceRole::Editor
+        ))
+    ));
+    let result = ApiToken::new(
+        ctx,
+        "later",
+        Some(WorkspaceRole::Viewer),
+        Some(caller_expires_at + chrono::Duration::hours(1)),
+    )
+    .await;
+    assert!(matches!(
+        result,
+        Err(ApiTokenError::ExpiresAfterCaller(_, expires_at)) if expires_at == caller_expires_at
+    ));
+
+    let (inherited, _) = ApiToken::new(ctx, "inherited", None, None)
+        .await
+        .expect("cannot create api token");
+    assert_eq!(inherited.max_role, Some(WorkspaceRole::Editor));
+    assert_eq!(inherited.expires_at, Some(caller_expires_at));
+
+    let (narrower, _) = ApiToken::new(ctx, "narrower", Some(WorkspaceRole::Viewer), None)
+        .await
+        .expect("cannot create api token");
+    assert_eq!(narrower.max_role, Some(WorkspaceRole::Viewer));
+    assert_eq!(narrower.expires_at, Some(caller_expires_at));
+}
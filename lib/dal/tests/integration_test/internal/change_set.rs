@@ -1,5 +1,15 @@
-use dal::{ChangeSet, ChangeSetStatus, DalContext, Visibility};
-use dal_test::{helpers::create_change_set, test, DalContextHeadMutRef, DalContextHeadRef};
+use dal::{
+    attribute::context::AttributeContextBuilder, job::definition::DependentValuesUpdate,
+    AttributeReadContext, AttributeValue, ChangeSet, ChangeSetApprovalDecision, ChangeSetError,
+    ChangeSetPk, ChangeSetStatus, Component, ComponentId, DalContext, HistoryActor, Prop, PropId,
+    PropKind, RootPropChild, StandardModel, User, UserPk, Visibility, Workspace, WorkspaceRole,
+};
+use dal_test::{
+    helpers::create_change_set,
+    test,
+    test_harness::{create_component_and_schema, create_schema, create_schema_variant_with_root},
+    DalContextHeadMutRef, DalContextHeadRef,
+};
 
 #[test]
 async fn new(DalContextHeadRef(ctx): DalContextHeadRef<'_>) {
@@ -84,3 +94,679 @@ async fn get_by_pk(DalContextHeadRef(ctx): DalContextHeadRef<'_>) {
         .expect("change set pk should exist");
     assert_eq!(&change_set, &result);
 }
+
+#[test]
+async fn rebase(DalContextHeadMutRef(ctx): DalContextHeadMutRef<'_>) {
+    let mut change_set = create_change_set(ctx).await;
+    assert!(change_set.rebased_at.is_none());
+
+    let conflicts = change_set
+        .rebase(ctx)
+        .await
+        .expect("cannot rebase change set");
+    assert!(conflicts.is_empty());
+    let rebased_at = change_set.rebased_at.expect("change set should be rebased");
+    assert_eq!(change_set.based_on_head_at(), rebased_at);
+
+    let fetched = ChangeSet::get_by_pk(ctx, &change_set.pk)
+        .await
+        .expect("cannot get change set by pk")
+        .expect("change set pk should exist");
+    assert_eq!(fetched.rebased_at, Some(rebased_at));
+
+    change_set
+        .apply(ctx)
+        .await
+        .expect("cannot apply change set");
+    let result = change_set.rebase(ctx).await;
+    assert!(matches!(result, Err(ChangeSetError::NotOpen(_, _))));
+}
+
+/// Creates a component whose "domain" has a single "name" string prop, returning the ids of the
+/// component and of the prop.
+async fn create_named_component(ctx: &DalContext) -> (ComponentId, PropId) {
+    let mut schema = create_schema(ctx).await;
+    let (mut schema_variant, root) = create_schema_variant_with_root(ctx, *schema.id()).await;
+    schema
+        .set_default_schema_variant_id(ctx, Some(*schema_variant.id()))
+        .await
+        .expect("cannot set default schema variant");
+    let name_prop = Prop::new(
+        ctx,
+        "name",
+        PropKind::String,
+        None,
+        *schema_variant.id(),
+        Some(root.domain_prop_id),
+    )
+    .await
+    .expect("could not create prop");
+    schema_variant
+        .finalize(ctx, None)
+        .await
+        .expect("cannot finalize schema variant");
+    let (component, _) =
+        Component::new_for_default_variant_from_schema(ctx, "rebased", *schema.id())
+            .await
+            .expect("cannot create component");
+
+    (*component.id(), *name_prop.id())
+}
+
+/// Sets the value of the prop of the component, in the visibility of the context.
+async fn set_value(
+    ctx: &DalContext,
+    component_id: ComponentId,
+    prop_id: PropId,
+    value: &str,
+) -> AttributeValue {
+    let read_context = AttributeReadContext {
+        prop_id: Some(prop_id),
+        component_id: Some(component_id),
+        ..AttributeReadContext::default()
+    };
+    let attribute_value = AttributeValue::find_for_context(ctx, read_context)
+        .await
+        .expect("cannot get attribute value")
+        .expect("attribute value not found");
+    let parent_value = attribute_value
+        .parent_attribute_value(ctx)
+        .await
+        .expect("cannot get parent attribute value")
+        .expect("parent attribute value not found");
+    let update_context = AttributeContextBuilder::from(read_context)
+        .to_context()
+        .expect("cannot build attribute context");
+    AttributeValue::update_for_context(
+        ctx,
+        *attribute_value.id(),
+        Some(*parent_value.id()),
+        update_context,
+        Some(serde_json::json!(value)),
+        None,
+    )
+    .await
+    .expect("cannot update attribute value");
+
+    AttributeValue::find_for_context(ctx, read_context)
+        .await
+        .expect("cannot get attribute value")
+        .expect("attribute value not found")
+}
+
+/// Counts the dependent values updates that ran in the change set so far: each of them tracks
+/// its progress with a status update of its own.
+async fn count_dependent_values_updates(ctx: &DalContext, change_set_pk: ChangeSetPk) -> i64 {
+    ctx.txns()
+        .await
+        .expect("cannot get transactions")
+        .pg()
+        .query_one(
+            "SELECT count(*) AS count FROM status_updates WHERE change_set_pk = $1",
+            &[&change_set_pk],
+        )
+        .await
+        .expect("cannot count status updates")
+        .try_get("count")
+        .expect("cannot get status update count")
+}
+
+#[test]
+async fn rebase_with_conflict(DalContextHeadMutRef(ctx): DalContextHeadMutRef<'_>) {
+    let (component_id, name_prop_id) = create_named_component(ctx).await;
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let mut change_set = create_change_set(ctx).await;
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+    let change_set_ctx =
+        ctx.clone_with_new_visibility(Visibility::new_change_set(change_set.pk, false));
+    set_value(&change_set_ctx, component_id, name_prop_id, "change set").await;
+    change_set_ctx
+        .blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    // Head changes the same value after the change set was opened.
+    let head_value = set_value(ctx, component_id, name_prop_id, "head").await;
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+    let updates_before = count_dependent_values_updates(ctx, change_set.pk).await;
+
+    let conflicts = change_set
+        .rebase(ctx)
+        .await
+        .expect("cannot rebase change set");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    assert_eq!(conflicts.len(), 1);
+    let conflict = &conflicts[0];
+    assert_eq!(conflict.attribute_value_id, *head_value.id());
+    assert_eq!(conflict.component_id, component_id);
+    assert_eq!(conflict.prop_id, name_prop_id);
+    assert_eq!(conflict.head_value, Some(serde_json::json!("head")));
+    assert_eq!(
+        conflict.change_set_value,
+        Some(serde_json::json!("change set"))
+    );
+
+    // The change set keeps its own value, head keeps its own.
+    let read_context = AttributeReadContext {
+        prop_id: Some(name_prop_id),
+        component_id: Some(component_id),
+        ..AttributeReadContext::default()
+    };
+    for (ctx, expected) in [(&change_set_ctx, "change set"), (&*ctx, "head")] {
+        let value = AttributeValue::find_for_context(ctx, read_context)
+            .await
+            .expect("cannot get attribute value")
+            .expect("attribute value not found")
+            .get_value(ctx)
+            .await
+            .expect("cannot get value");
+        assert_eq!(value, Some(serde_json::json!(expected)));
+    }
+
+    // The values head changed are rerun within the change set.
+    assert_eq!(
+        count_dependent_values_updates(ctx, change_set.pk).await,
+        updates_before + 1
+    );
+
+    // Nothing changed on head since, so rebasing again finds no conflict.
+    let conflicts = change_set
+        .rebase(ctx)
+        .await
+        .expect("cannot rebase change set");
+    assert!(conflicts.is_empty());
+}
+
+/// Sets the same value differently in a new change set and on head afterwards, committing in
+/// between so that head's change lands after the change set was based on it.
+async fn create_conflicting_change_set(ctx: &DalContext) -> (ChangeSet, ComponentId, PropId) {
+    let (component_id, name_prop_id) = create_named_component(ctx).await;
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let change_set = create_change_set(ctx).await;
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+    let change_set_ctx =
+        ctx.clone_with_new_visibility(Visibility::new_change_set(change_set.pk, false));
+    set_value(&change_set_ctx, component_id, name_prop_id, "change set").await;
+    change_set_ctx
+        .blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    set_value(ctx, component_id, name_prop_id, "head").await;
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    (change_set, component_id, name_prop_id)
+}
+
+#[test]
+async fn apply_with_conflict(DalContextHeadMutRef(ctx): DalContextHeadMutRef<'_>) {
+    let (mut change_set, component_id, _) = create_conflicting_change_set(ctx).await;
+
+    // The component and its conflicting value are a single conflict.
+    let preview = change_set
+        .apply_preview(ctx)
+        .await
+        .expect("cannot preview change set apply");
+    assert!(preview
+        .components
+        .iter()
+        .any(|c| c.component_id == component_id && c.conflict));
+    assert_eq!(preview.conflict_count(), 1);
+
+    let result = change_set.apply(ctx).await;
+    assert!(matches!(
+        result,
+        Err(ChangeSetError::ApplyConflicts(pk, 1)) if pk == change_set.pk
+    ));
+    assert_eq!(&change_set.status, &ChangeSetStatus::Open);
+}
+
+#[test]
+async fn apply_overriding_conflicts(DalContextHeadMutRef(ctx): DalContextHeadMutRef<'_>) {
+    let (mut change_set, component_id, name_prop_id) = create_conflicting_change_set(ctx).await;
+
+    change_set
+        .apply_overriding_conflicts(ctx)
+        .await
+        .expect("cannot apply change set");
+    assert_eq!(&change_set.status, &ChangeSetStatus::Applied);
+    ctx.update_visibility(Visibility::new_head(false));
+
+    let value = AttributeValue::find_for_context(
+        ctx,
+        AttributeReadContext {
+            prop_id: Some(name_prop_id),
+            component_id: Some(component_id),
+            ..AttributeReadContext::default()
+        },
+    )
+    .await
+    .expect("cannot get attribute value")
+    .expect("attribute value not found")
+    .get_value(ctx)
+    .await
+    .expect("cannot get value");
+    assert_eq!(value, Some(serde_json::json!("change set")));
+}
+
+#[test]
+async fn apply_preview(DalContextHeadRef(ctx): DalContextHeadRef<'_>) {
+    let change_set = create_change_set(ctx).await;
+
+    let preview = change_set
+        .apply_preview(ctx)
+        .await
+        .expect("cannot preview change set apply");
+    assert_eq!(preview.change_set_pk, change_set.pk);
+    assert!(preview.components.is_empty());
+    assert!(preview.edges.is_empty());
+    assert!(preview.attribute_values.is_empty());
+    assert!(!preview.has_conflicts());
+}
+
+#[test]
+async fn abandon(DalContextHeadRef(ctx): DalContextHeadRef<'_>) {
+    let mut change_set = create_change_set(ctx).await;
+    change_set
+        .abandon(ctx)
+        .await
+        .expect("cannot abandon change set");
+    assert_eq!(&change_set.status, &ChangeSetStatus::Abandoned);
+
+    let open = ChangeSet::list_open(ctx)
+        .await
+        .expect("cannot get list of open change sets");
+    assert!(!open.iter().any(|c| c.pk == change_set.pk));
+
+    let result = change_set.reopen(ctx).await;
+    assert!(matches!(result, Err(ChangeSetError::NotClosed(_, _))));
+}
+
+#[test]
+async fn apply_requires_open(DalContextHeadMutRef(ctx): DalContextHeadMutRef<'_>) {
+    let mut abandoned = create_change_set(ctx).await;
+    abandoned
+        .abandon(ctx)
+        .await
+        .expect("cannot abandon change set");
+    let mut closed = create_change_set(ctx).await;
+    closed.close(ctx).await.expect("cannot close change set");
+
+    for change_set in [&mut abandoned, &mut closed] {
+        let result = change_set.apply(ctx).await;
+        assert!(matches!(result, Err(ChangeSetError::NotOpen(_, _))));
+        let result = change_set.apply_overriding_conflicts(ctx).await;
+        assert!(matches!(result, Err(ChangeSetError::NotOpen(_, _))));
+    }
+    assert_eq!(&abandoned.status, &ChangeSetStatus::Abandoned);
+    assert_eq!(&closed.status, &ChangeSetStatus::Closed);
+}
+
+#[test]
+async fn close_and_reopen(DalContextHeadRef(ctx): DalContextHeadRef<'_>) {
+    let mut change_set = create_change_set(ctx).await;
+    change_set
+        .close(ctx)
+        .await
+        .expect("cannot close change set");
+    assert_eq!(&change_set.status, &ChangeSetStatus::Closed);
+
+    let result = change_set.close(ctx).await;
+    assert!(matches!(result, Err(ChangeSetError::NotOpen(_, _))));
+
+    let closed = ChangeSet::list_closed(ctx)
+        .await
+        .expect("cannot get list of closed change sets");
+    assert!(closed.iter().any(|c| c.pk == change_set.pk));
+
+    change_set
+        .reopen(ctx)
+        .await
+        .expect("cannot reopen change set");
+    assert_eq!(&change_set.status, &ChangeSetStatus::Open);
+
+    let fetched = ChangeSet::get_by_pk(ctx, &change_set.pk)
+        .await
+        .expect("cannot get change set by pk")
+        .expect("change set pk should exist");
+    assert_eq!(&fetched.status, &ChangeSetStatus::Open);
+}
+
+#[test]
+async fn abandon_and_close_pending_approval(DalContextHeadRef(ctx): DalContextHeadRef<'_>) {
+    let mut abandoned = create_change_set(ctx).await;
+    let mut closed = create_change_set(ctx).await;
+    for change_set in [&mut abandoned, &mut closed] {
+        change_set
+            .request_approval(ctx)
+            .await
+            .expect("cannot request approval");
+    }
+
+    abandoned
+        .abandon(ctx)
+        .await
+        .expect("cannot abandon change set");
+    closed.close(ctx).await.expect("cannot close change set");
+    assert_eq!(&abandoned.status, &ChangeSetStatus::Abandoned);
+    assert_eq!(&closed.status, &ChangeSetStatus::Closed);
+
+    // The approval request does not outlive the change set being abandoned or closed
+    for change_set in [&abandoned, &closed] {
+        let fetched = ChangeSet::get_by_pk(ctx, &change_set.pk)
+            .await
+            .expect("cannot get change set by pk")
+            .expect("change set pk should exist");
+        assert_eq!(&fetched.status, &change_set.status);
+        assert_eq!(fetched.approval_requested_at, None);
+        assert_eq!(fetched.approval_requested_by, None);
+    }
+
+    closed.reopen(ctx).await.expect("cannot reopen change set");
+    assert_eq!(&closed.status, &ChangeSetStatus::Open);
+    assert!(closed
+        .approvals(ctx)
+        .await
+        .expect("cannot list approvals")
+        .is_empty());
+}
+
+#[test]
+async fn approval_gate(DalContextHeadMutRef(ctx): DalContextHeadMutRef<'_>) {
+    let workspace_pk = ctx
+        .tenancy()
+        .workspace_pk()
+        .expect("no workspace in tenancy");
+    let mut users = Vec::new();
+    for (name, role) in [
+        ("requester", WorkspaceRole::Editor),
+        ("first approver", WorkspaceRole::Applier),
+        ("second approver", WorkspaceRole::Applier),
+    ] {
+        let user = User::new(
+            ctx,
+            UserPk::generate(),
+            name,
+            format!("{}@systeminit.com", name.replace(' ', "-")),
+            None::<String>,
+        )
+        .await
+        .expect("cannot create user");
+        user.associate_workspace(ctx, workspace_pk, role)
+            .await
+            .expect("cannot associate user with workspace");
+        users.push(user.pk());
+    }
+
+    let mut workspace = Workspace::get_by_pk(ctx, &workspace_pk)
+        .await
+        .expect("cannot get workspace")
+        .expect("workspace should exist");
+    workspace
+        .set_required_change_set_approvals(ctx, 2)
+        .await
+        .expect("cannot require approvals");
+
+    let mut change_set = create_change_set(ctx).await;
+    let result = change_set.apply(ctx).await;
+    assert!(matches!(
+        result,
+        Err(ChangeSetError::NotEnoughApprovals(_, 0, 2))
+    ));
+
+    ctx.update_history_actor(HistoryActor::User(users[0]));
+    change_set
+        .request_approval(ctx)
+        .await
+        .expect("cannot request approval");
+    assert_eq!(&change_set.status, &ChangeSetStatus::PendingApproval);
+    // Editors can ask for approval but cannot apply.
+    let result = change_set.apply(ctx).await;
+    assert!(matches!(result, Err(ChangeSetError::User(_))));
+    let result = change_set.approve(ctx, None).await;
+    assert!(matches!(result, Err(ChangeSetError::SelfApproval(_))));
+
+    // A rejection sends the change set back and resets the approvals.
+    ctx.update_history_actor(HistoryActor::User(users[1]));
+    change_set
+        .approve(ctx, Some("looks good".to_owned()))
+        .await
+        .expect("cannot approve change set");
+    ctx.update_history_actor(HistoryActor::User(users[2]));
+    let rejection = change_set
+        .reject(ctx, Some("not yet".to_owned()))
+        .await
+        .expect("cannot reject change set");
+    assert_eq!(rejection.decision, ChangeSetApprovalDecision::Rejected);
+    assert_eq!(&change_set.status, &ChangeSetStatus::Open);
+    let result = change_set.approve(ctx, None).await;
+    assert!(matches!(
+        result,
+        Err(ChangeSetError::NotPendingApproval(_, _))
+    ));
+
+    ctx.update_history_actor(HistoryActor::User(users[0]));
+    change_set
+        .request_approval(ctx)
+        .await
+        .expect("cannot request approval");
+    ctx.update_history_actor(HistoryActor::User(users[1]));
+    change_set
+        .approve(ctx, None)
+        .await
+        .expect("cannot approve change set");
+    // Approving twice does not count twice.
+    change_set
+        .approve(ctx, None)
+        .await
+        .expect("cannot approve change set");
+    let status = change_set
+        .approval_status(ctx)
+        .await
+        .expect("cannot get approval status");
+    assert_eq!(status.approval_count, 1);
+    assert_eq!(status.approvals.len(), 2);
+    let result = change_set.apply_overriding_conflicts(ctx).await;
+    assert!(matches!(
+        result,
+        Err(ChangeSetError::NotEnoughApprovals(_, 1, 2))
+    ));
+
+    ctx.update_history_actor(HistoryActor::User(users[2]));
+    change_set
+        .approve(ctx, None)
+        .await
+        .expect("cannot approve change set");
+    change_set
+        .apply(ctx)
+        .await
+        .expect("cannot apply approved change set");
+    assert_eq!(&change_set.status, &ChangeSetStatus::Applied);
+}
+
+#[test]
+async fn edits_discard_approvals(DalContextHeadMutRef(ctx): DalContextHeadMutRef<'_>) {
+    let workspace_pk = ctx
+        .tenancy()
+        .workspace_pk()
+        .expect("no workspace in tenancy");
+    let mut users = Vec::new();
+    for (name, role) in [
+        ("requester", WorkspaceRole::Editor),
+        ("approver", WorkspaceRole::Applier),
+    ] {
+        let user = User::new(
+            ctx,
+            UserPk::generate(),
+            name,
+            format!("{name}@systeminit.com"),
+            None::<String>,
+        )
+        .await
+        .expect("cannot create user");
+        user.associate_workspace(ctx, workspace_pk, role)
+            .await
+            .expect("cannot associate user with workspace");
+        users.push(user.pk());
+    }
+    let mut workspace = Workspace::get_by_pk(ctx, &workspace_pk)
+        .await
+        .expect("cannot get workspace")
+        .expect("workspace should exist");
+    workspace
+        .set_required_change_set_approvals(ctx, 1)
+        .await
+        .expect("cannot require approvals");
+
+    let mut change_set = create_change_set(ctx).await;
+    ctx.update_history_actor(HistoryActor::User(users[0]));
+    change_set
+        .request_approval(ctx)
+        .await
+        .expect("cannot request approval");
+    ctx.update_history_actor(HistoryActor::User(users[1]));
+    change_set
+        .approve(ctx, None)
+        .await
+        .expect("cannot approve change set");
+
+    // Editing the change set after it was approved sends it back to being open once it is
+    // applied or approved again.
+    let change_set_ctx =
+        ctx.clone_with_new_visibility(Visibility::new_change_set(change_set.pk, false));
+    create_schema(&change_set_ctx).await;
+    let last_edited_at = change_set
+        .last_edited_at(ctx)
+        .await
+        .expect("cannot get when the change set was last edited")
+        .expect("change set should have been edited");
+    assert!(
+        last_edited_at
+            > change_set
+                .approval_requested_at
+                .expect("approval was requested")
+    );
+
+    let result = change_set.apply(ctx).await;
+    assert!(matches!(
+        result,
+        Err(ChangeSetError::NotEnoughApprovals(_, 0, 1))
+    ));
+    assert_eq!(&change_set.status, &ChangeSetStatus::Open);
+    assert!(change_set
+        .approvals(ctx)
+        .await
+        .expect("cannot list approvals")
+        .is_empty());
+    let fetched = ChangeSet::get_by_pk(ctx, &change_set.pk)
+        .await
+        .expect("cannot get change set by pk")
+        .expect("change set pk should exist");
+    assert_eq!(&fetched.status, &ChangeSetStatus::Open);
+    assert_eq!(fetched.approval_requested_at, None);
+    let result = change_set.approve(ctx, None).await;
+    assert!(matches!(
+        result,
+        Err(ChangeSetError::NotPendingApproval(_, _))
+    ));
+}
+
+#[test]
+async fn job_writes_keep_approvals(DalContextHeadMutRef(ctx): DalContextHeadMutRef<'_>) {
+    let workspace_pk = ctx
+        .tenancy()
+        .workspace_pk()
+        .expect("no workspace in tenancy");
+    let mut users = Vec::new();
+    for (name, role) in [
+        ("requester", WorkspaceRole::Editor),
+        ("approver", WorkspaceRole::Applier),
+    ] {
+        let user = User::new(
+            ctx,
+            UserPk::generate(),
+            name,
+            format!("{name}@systeminit.com"),
+            None::<String>,
+        )
+        .await
+        .expect("cannot create user");
+        user.associate_workspace(ctx, workspace_pk, role)
+            .await
+            .expect("cannot associate user with workspace");
+        users.push(user.pk());
+    }
+    let mut workspace = Workspace::get_by_pk(ctx, &workspace_pk)
+        .await
+        .expect("cannot get workspace")
+        .expect("workspace should exist");
+    workspace
+        .set_required_change_set_approvals(ctx, 1)
+        .await
+        .expect("cannot require approvals");
+
+    let mut change_set = create_change_set(ctx).await;
+    let change_set_ctx =
+        ctx.clone_with_new_visibility(Visibility::new_change_set(change_set.pk, false));
+    let component = create_component_and_schema(&change_set_ctx).await;
+    change_set_ctx
+        .blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    ctx.update_history_actor(HistoryActor::User(users[0]));
+    change_set
+        .request_approval(ctx)
+        .await
+        .expect("cannot request approval");
+
+    // Values computed by a job finishing after the approval was requested are not edits.
+    let domain_value = Component::root_prop_child_attribute_value_for_component(
+        &change_set_ctx,
+        *component.id(),
+        RootPropChild::Domain,
+    )
+    .await
+    .expect("cannot get domain attribute value");
+    change_set_ctx
+        .enqueue_job(DependentValuesUpdate::new(
+            change_set_ctx.access_builder(),
+            *change_set_ctx.visibility(),
+            vec![*domain_value.id()],
+        ))
+        .await
+        .expect("failed to enqueue job");
+    change_set_ctx
+        .blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    ctx.update_history_actor(HistoryActor::User(users[1]));
+    change_set
+        .approve(ctx, None)
+        .await
+        .expect("cannot approve change set");
+    assert_eq!(&change_set.status, &ChangeSetStatus::PendingApproval);
+    change_set
+        .apply(ctx)
+        .await
+        .expect("cannot apply change set");
+    assert_eq!(&change_set.status, &ChangeSetStatus::Applied);
+}
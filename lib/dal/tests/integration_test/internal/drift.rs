@@ -0,0 +1,221 @@
+use dal::attribute::context::AttributeContextBuilder;
+use dal::func::backend::js_action::ActionRunResult;
+use dal::{
+    AttributeReadContext, AttributeValue, Component, ComponentDrift, ComponentId, DalContext,
+    DriftStatus, DriftSummary, Prop, PropId, PropKind, StandardModel,
+};
+use dal_test::{
+    test,
+    test_harness::{create_component_and_schema, create_schema, create_schema_variant_with_root},
+};
+use veritech_client::ResourceStatus;
+
+#[test]
+async fn detect_without_resource(ctx: &DalContext) {
+    let component = create_component_and_schema(ctx).await;
+
+    assert!(ComponentDrift::get_for_component(ctx, *component.id())
+        .await
+        .expect("could not get drift")
+        .is_none());
+
+    let drift = ComponentDrift::detect(ctx, *component.id())
+        .await
+        .expect("could not detect drift");
+    assert_eq!(DriftStatus::Unknown, drift.status);
+    assert!(drift.drifted_paths.is_empty());
+
+    let stored = ComponentDrift::get_for_component(ctx, *component.id())
+        .await
+        .expect("could not get drift")
+        .expect("drift not stored");
+    assert_eq!(drift, stored);
+
+    // Detecting again overwrites the stored status instead of adding a new one.
+    ComponentDrift::detect(ctx, *component.id())
+        .await
+        .expect("could not detect drift");
+    assert_eq!(
+        1,
+        ComponentDrift::list(ctx)
+            .await
+            .expect("could not list drift")
+            .len()
+    );
+}
+
+#[test]
+async fn summary(ctx: &DalContext) {
+    let checked = create_component_and_schema(ctx).await;
+    let unchecked = create_component_and_schema(ctx).await;
+
+    ComponentDrift::detect(ctx, *checked.id())
+        .await
+        .expect("could not detect drift");
+
+    let summary = DriftSummary::get_summary(ctx)
+        .await
+        .expect("could not get drift summary");
+    assert_eq!(2, summary.total);
+    assert_eq!(0, summary.drifted);
+    assert_eq!(0, summary.in_sync);
+    assert_eq!(2, summary.unknown);
+
+    let unchecked_summary = summary
+        .components
+        .iter()
+        .find(|c| c.component_id == *unchecked.id())
+        .expect("unchecked component missing from summary");
+    assert!(unchecked_summary.checked_at.is_none());
+
+    let checked_summary = summary
+        .components
+        .iter()
+        .find(|c| c.component_id == *checked.id())
+        .expect("checked component missing from summary");
+    assert!(checked_summary.checked_at.is_some());
+}
+
+/// Creates a component whose "/root/resource_value/name" refers to its "/root/domain/name",
+/// returning the component and the id of the domain prop.
+async fn create_component_with_referring_resource_value(ctx: &DalContext) -> (Component, PropId) {
+    let schema = create_schema(ctx).await;
+    let (mut schema_variant, root) = create_schema_variant_with_root(ctx, *schema.id()).await;
+    let domain_name_prop = Prop::new(
+        ctx,
+        "name",
+        PropKind::String,
+        None,
+        *schema_variant.id(),
+        Some(root.domain_prop_id),
+    )
+    .await
+    .expect("could not create prop");
+    let mut resource_name_prop = Prop::new(
+        ctx,
+        "name",
+        PropKind::String,
+        None,
+        *schema_variant.id(),
+        Some(root.resource_value_prop_id),
+    )
+    .await
+    .expect("could not create prop");
+    resource_name_prop
+        .set_refers_to_prop_id(ctx, Some(*domain_name_prop.id()))
+        .await
+        .expect("could not set referred prop");
+    resource_name_prop
+        .set_default_diff(ctx)
+        .await
+        .expect("could not set diff func");
+    schema_variant
+        .finalize(ctx, None)
+        .await
+        .expect("unable to finalize schema variant");
+
+    let (component, _) = Component::new(ctx, "drifty", *schema_variant.id())
+        .await
+        .expect("cannot create component");
+
+    (component, *domain_name_prop.id())
+}
+
+async fn set_domain_name(
+    ctx: &DalContext,
+    component_id: ComponentId,
+    domain_name_prop_id: PropId,
+    value: &str,
+) {
+    let read_context = AttributeReadContext {
+        prop_id: Some(domain_name_prop_id),
+        component_id: Some(component_id),
+        ..AttributeReadContext::default()
+    };
+    let attribute_value = AttributeValue::find_for_context(ctx, read_context)
+        .await
+        .expect("cannot get attribute value")
+        .expect("attribute value not found");
+    let parent_value = attribute_value
+        .parent_attribute_value(ctx)
+        .await
+        .expect("cannot get parent attribute value")
+        .expect("parent attribute value not found");
+    AttributeValue::update_for_context(
+        ctx,
+        *attribute_value.id(),
+        Some(*parent_value.id()),
+        AttributeContextBuilder::from(read_context)
+            .to_context()
+            .expect("cannot build attribute context"),
+        Some(serde_json::json!(value)),
+        None,
+    )
+    .await
+    .expect("cannot update attribute value");
+}
+
+async fn set_resource_name(ctx: &DalContext, component: &Component, value: &str) {
+    component
+        .set_resource(
+            ctx,
+            ActionRunResult {
+                status: ResourceStatus::Ok,
+                payload: Some(serde_json::json!({ "name": value })),
+                message: None,
+                logs: vec![],
+                last_synced: Default::default(),
+            },
+        )
+        .await
+        .expect("could not set resource");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+}
+
+#[test]
+async fn detect_in_sync(ctx: &DalContext) {
+    let (component, domain_name_prop_id) =
+        create_component_with_referring_resource_value(ctx).await;
+    set_domain_name(ctx, *component.id(), domain_name_prop_id, "pipboy").await;
+    set_resource_name(ctx, &component, "pipboy").await;
+
+    let drift = ComponentDrift::detect(ctx, *component.id())
+        .await
+        .expect("could not detect drift");
+    assert_eq!(DriftStatus::InSync, drift.status);
+    assert!(drift.drifted_paths.is_empty());
+}
+
+#[test]
+async fn detect_drifted(ctx: &DalContext) {
+    let (component, domain_name_prop_id) =
+        create_component_with_referring_resource_value(ctx).await;
+    set_domain_name(ctx, *component.id(), domain_name_prop_id, "pipboy").await;
+    set_resource_name(ctx, &component, "vault boy").await;
+
+    let drift = ComponentDrift::detect(ctx, *component.id())
+        .await
+        .expect("could not detect drift");
+    assert_eq!(DriftStatus::Drifted, drift.status);
+    assert_eq!(
+        vec!["root/resource_value/name".to_owned()],
+        drift.drifted_paths
+    );
+
+    // Bringing the domain back in line with the resource clears the drift.
+    set_domain_name(ctx, *component.id(), domain_name_prop_id, "vault boy").await;
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+    let drift = ComponentDrift::detect(ctx, *component.id())
+        .await
+        .expect("could not detect drift");
+    assert_eq!(DriftStatus::InSync, drift.status);
+
+    let summary = DriftSummary::get_summary(ctx)
+        .await
+        .expect("could not get drift summary");
+    assert_eq!(1, summary.in_sync);
+}
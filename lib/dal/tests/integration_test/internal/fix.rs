@@ -0,0 +1,489 @@
+use chrono::{Duration, Utc};
+use dal::{
+    edge::EdgeKind,
+    fix::graph::FixGraph,
+    job::{
+        consumer::JobConsumer,
+        definition::{FixItem, FixesJob},
+    },
+    socket::SocketEdgeKind,
+    ActionKind, ActionPrototype, ActionPrototypeContext, ActionPrototypeId, Connection, DalContext,
+    Fix, FixBatch, FixCompletionStatus, FixId, FixRun, FixRunQuery, FuncId, HistoryActor, Socket,
+    StandardModel,
+};
+use dal_test::helpers::component_bag::{ComponentBag, ComponentBagger};
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+
+/// Connects the "bethesda" output socket of the tail to the "bethesda" input socket of the head.
+async fn connect(ctx: &DalContext, tail_bag: &ComponentBag, head_bag: &ComponentBag) {
+    let output_socket = Socket::find_by_name_for_edge_kind_and_node(
+        ctx,
+        "bethesda",
+        SocketEdgeKind::ConfigurationOutput,
+        tail_bag.node_id,
+    )
+    .await
+    .expect("could not perform socket find'")
+    .expect("could not find socket");
+    let input_socket = Socket::find_by_name_for_edge_kind_and_node(
+        ctx,
+        "bethesda",
+        SocketEdgeKind::ConfigurationInput,
+        head_bag.node_id,
+    )
+    .await
+    .expect("could not perform socket find'")
+    .expect("could not find socket");
+    Connection::new(
+        ctx,
+        tail_bag.node_id,
+        *output_socket.id(),
+        head_bag.node_id,
+        *input_socket.id(),
+        EdgeKind::Configuration,
+    )
+    .await
+    .expect("could not create connection");
+}
+
+#[test]
+async fn graph(ctx: &DalContext) {
+    let mut bagger = ComponentBagger::new();
+    let fallout_bag = bagger.create_component(ctx, "tail", "fallout").await;
+    let starfield_bag = bagger.create_component(ctx, "head", "starfield").await;
+    let loner_bag = bagger.create_component(ctx, "loner", "fallout").await;
+
+    connect(ctx, &fallout_bag, &starfield_bag).await;
+
+    let fix_item = |component_id| FixItem {
+        id: FixId::generate(),
+        action_prototype_id: ActionPrototypeId::NONE,
+        component_id,
+    };
+    let fallout_fix = fix_item(fallout_bag.component_id);
+    let loner_fix = fix_item(loner_bag.component_id);
+    let starfield_fix = fix_item(starfield_bag.component_id);
+    let second_fallout_fix = fix_item(fallout_bag.component_id);
+
+    let graph = FixGraph::new(
+        ctx,
+        &[
+            fallout_fix.clone(),
+            loner_fix.clone(),
+            starfield_fix.clone(),
+            second_fallout_fix.clone(),
+        ],
+    )
+    .await
+    .expect("could not build fix graph");
+
+    // Nothing depends on unrelated components.
+    assert!(graph
+        .dependencies(fallout_fix.id)
+        .expect("fix not in graph")
+        .is_empty());
+    assert!(graph
+        .dependencies(loner_fix.id)
+        .expect("fix not in graph")
+        .is_empty());
+    // Downstream components wait for upstream ones...
+    assert_eq!(
+        &[fallout_fix.id],
+        graph
+            .dependencies(starfield_fix.id)
+            .expect("fix not in graph")
+    );
+    // ...and the order of fixes between related components is preserved either way.
+    assert_eq!(
+        &[fallout_fix.id, starfield_fix.id],
+        graph
+            .dependencies(second_fallout_fix.id)
+            .expect("fix not in graph")
+    );
+}
+
+#[test]
+async fn resume(ctx: &DalContext) {
+    let mut bagger = ComponentBagger::new();
+    let fallout_bag = bagger.create_component(ctx, "fallout", "fallout").await;
+    let starfield_bag = bagger.create_component(ctx, "starfield", "starfield").await;
+    let fallout_prototype = ActionPrototype::new(
+        ctx,
+        FuncId::NONE,
+        ActionKind::Other,
+        ActionPrototypeContext {
+            schema_variant_id: fallout_bag.schema_variant_id,
+        },
+    )
+    .await
+    .expect("could not create action prototype");
+    let starfield_prototype = ActionPrototype::new(
+        ctx,
+        FuncId::NONE,
+        ActionKind::Other,
+        ActionPrototypeContext {
+            schema_variant_id: starfield_bag.schema_variant_id,
+        },
+    )
+    .await
+    .expect("could not create action prototype");
+
+    let mut batch = FixBatch::new(ctx, "toddhoward@bethesda.com")
+        .await
+        .expect("could not create fix batch");
+    let mut succeeded = Fix::new(
+        ctx,
+        *batch.id(),
+        fallout_bag.component_id,
+        *fallout_prototype.id(),
+    )
+    .await
+    .expect("could not create fix");
+    let mut failed = Fix::new(
+        ctx,
+        *batch.id(),
+        starfield_bag.component_id,
+        *starfield_prototype.id(),
+    )
+    .await
+    .expect("could not create fix");
+
+    // Only finished batches can be resumed.
+    assert!(batch.reset_for_resume(ctx).await.is_err());
+
+    batch
+        .stamp_started(ctx)
+        .await
+        .expect("could not start batch");
+    succeeded
+        .stamp_started(ctx)
+        .await
+        .expect("could not start fix");
+    succeeded
+        .stamp_finished(ctx, FixCompletionStatus::Success, None, None)
+        .await
+        .expect("could not finish fix");
+    failed
+        .stamp_started(ctx)
+        .await
+        .expect("could not start fix");
+    failed
+        .stamp_finished(
+            ctx,
+            FixCompletionStatus::Failure,
+            Some("it just works".to_owned()),
+            None,
+        )
+        .await
+        .expect("could not finish fix");
+    batch
+        .stamp_finished(ctx)
+        .await
+        .expect("could not finish batch");
+
+    let resumed = batch
+        .reset_for_resume(ctx)
+        .await
+        .expect("could not resume batch");
+    assert_eq!(
+        vec![*failed.id()],
+        resumed.iter().map(|fix| *fix.id()).collect::<Vec<_>>()
+    );
+    assert!(batch.started_at().is_none());
+    assert!(batch.finished_at().is_none());
+
+    let failed = Fix::get_by_id(ctx, failed.id())
+        .await
+        .expect("could not get fix")
+        .expect("fix not found");
+    assert_eq!(2, failed.attempt());
+    assert_eq!(1, failed.attempts().len());
+    assert_eq!(
+        Some(FixCompletionStatus::Failure),
+        failed.attempts()[0].completion_status
+    );
+    assert_eq!(
+        Some("it just works"),
+        failed.attempts()[0].completion_message.as_deref()
+    );
+    assert!(failed.started_at().is_none());
+    assert!(failed.completion_status().is_none());
+
+    // The successful fix is left alone.
+    let succeeded = Fix::get_by_id(ctx, succeeded.id())
+        .await
+        .expect("could not get fix")
+        .expect("fix not found");
+    assert!(succeeded.attempts().is_empty());
+    assert_eq!(
+        Some(&FixCompletionStatus::Success),
+        succeeded.completion_status()
+    );
+}
+
+#[test]
+async fn defer(ctx: &DalContext) {
+    let mut bagger = ComponentBagger::new();
+    let fallout_bag = bagger.create_component(ctx, "fallout", "fallout").await;
+    let prototype = ActionPrototype::new(
+        ctx,
+        FuncId::NONE,
+        ActionKind::Other,
+        ActionPrototypeContext {
+            schema_variant_id: fallout_bag.schema_variant_id,
+        },
+    )
+    .await
+    .expect("could not create action prototype");
+    let batch = FixBatch::new(ctx, "toddhoward@bethesda.com")
+        .await
+        .expect("could not create fix batch");
+    let mut fix = Fix::new(ctx, *batch.id(), fallout_bag.component_id, *prototype.id())
+        .await
+        .expect("could not create fix");
+    assert!(fix
+        .remaining_backoff()
+        .expect("could not get remaining backoff")
+        .is_zero());
+
+    fix.defer(ctx, std::time::Duration::from_secs(3600))
+        .await
+        .expect("could not defer fix");
+
+    // The backoff survives reloading the fix, as a restarted job would.
+    let fix = Fix::get_by_id(ctx, fix.id())
+        .await
+        .expect("could not get fix")
+        .expect("fix not found");
+    let remaining = fix
+        .remaining_backoff()
+        .expect("could not get remaining backoff");
+    assert!(remaining > std::time::Duration::from_secs(3500));
+    assert!(remaining <= std::time::Duration::from_secs(3600));
+}
+
+#[test]
+async fn run_history(ctx: &DalContext) {
+    let mut bagger = ComponentBagger::new();
+    let fallout_bag = bagger.create_component(ctx, "fallout", "fallout").await;
+    let starfield_bag = bagger.create_component(ctx, "starfield", "starfield").await;
+
+    let record_run = |bag_schema_variant_id, component_id| async move {
+        let prototype = ActionPrototype::new(
+            ctx,
+            FuncId::NONE,
+            ActionKind::Other,
+            ActionPrototypeContext {
+                schema_variant_id: bag_schema_variant_id,
+            },
+        )
+        .await
+        .expect("could not create action prototype");
+        let batch = FixBatch::new(ctx, "toddhoward@bethesda.com")
+            .await
+            .expect("could not create fix batch");
+        let mut fix = Fix::new(ctx, *batch.id(), component_id, *prototype.id())
+            .await
+            .expect("could not create fix");
+
+        let started_at = Utc::now();
+        fix.stamp_started(ctx).await.expect("could not start fix");
+        fix.stamp_finished(
+            ctx,
+            FixCompletionStatus::Failure,
+            Some("it just works".to_owned()),
+            None,
+        )
+        .await
+        .expect("could not finish fix");
+        FixRun::record(
+            ctx,
+            &fix,
+            started_at,
+            Some(serde_json::json!({ "before": true })),
+            Some(serde_json::json!({ "after": true })),
+            None,
+        )
+        .await
+        .expect("could not record fix run")
+    };
+    let fallout_run = record_run(fallout_bag.schema_variant_id, fallout_bag.component_id).await;
+    let starfield_run =
+        record_run(starfield_bag.schema_variant_id, starfield_bag.component_id).await;
+
+    assert_eq!(1, fallout_run.attempt);
+    assert_eq!(HistoryActor::SystemInit, fallout_run.actor);
+    assert_eq!(
+        Some(FixCompletionStatus::Failure),
+        fallout_run.completion_status
+    );
+    assert_eq!(
+        Some("it just works"),
+        fallout_run.completion_message.as_deref()
+    );
+    assert_eq!(
+        Some(serde_json::json!({ "before": true })),
+        fallout_run.resource_before
+    );
+    assert_eq!(
+        Some(serde_json::json!({ "after": true })),
+        fallout_run.resource_after
+    );
+    assert!(fallout_run.output_stream.is_empty());
+
+    let fetched = FixRun::get_by_pk(ctx, fallout_run.pk)
+        .await
+        .expect("could not get fix run")
+        .expect("fix run not found");
+    assert_eq!(fallout_run, fetched);
+
+    // Most recent first.
+    let all = FixRun::list(ctx, &FixRunQuery::default())
+        .await
+        .expect("could not list fix runs");
+    assert_eq!(
+        vec![starfield_run.pk, fallout_run.pk],
+        all.iter().map(|run| run.pk).collect::<Vec<_>>()
+    );
+
+    let by_component = FixRun::list(
+        ctx,
+        &FixRunQuery {
+            component_id: Some(fallout_bag.component_id),
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("could not list fix runs");
+    assert_eq!(vec![fallout_run.clone()], by_component);
+
+    let by_batch = FixRun::list(
+        ctx,
+        &FixRunQuery {
+            fix_batch_id: starfield_run.fix_batch_id,
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("could not list fix runs");
+    assert_eq!(vec![starfield_run.clone()], by_batch);
+
+    let in_the_future = FixRun::list(
+        ctx,
+        &FixRunQuery {
+            started_after: Some(Utc::now() + Duration::hours(1)),
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("could not list fix runs");
+    assert!(in_the_future.is_empty());
+
+    let limited = FixRun::list(
+        ctx,
+        &FixRunQuery {
+            limit: Some(1),
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("could not list fix runs");
+    assert_eq!(vec![starfield_run.clone()], limited);
+
+    // Out of range limits are clamped rather than rejected by the database.
+    let clamped = FixRun::list(
+        ctx,
+        &FixRunQuery {
+            limit: Some(-1),
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("could not list fix runs");
+    assert_eq!(vec![starfield_run], clamped);
+}
+
+#[test]
+async fn failed_fix_skips_dependents(ctx: &mut DalContext) {
+    let mut bagger = ComponentBagger::new();
+    let fallout_bag = bagger.create_component(ctx, "tail", "fallout").await;
+    let starfield_bag = bagger.create_component(ctx, "head", "starfield").await;
+    let loner_bag = bagger.create_component(ctx, "loner", "fallout").await;
+    connect(ctx, &fallout_bag, &starfield_bag).await;
+
+    // Actions without a func can never succeed.
+    let mut fix_items = Vec::new();
+    let batch = FixBatch::new(ctx, "toddhoward@bethesda.com")
+        .await
+        .expect("could not create fix batch");
+    for bag in [&fallout_bag, &starfield_bag, &loner_bag] {
+        let prototype = ActionPrototype::new(
+            ctx,
+            FuncId::NONE,
+            ActionKind::Other,
+            ActionPrototypeContext {
+                schema_variant_id: bag.schema_variant_id,
+            },
+        )
+        .await
+        .expect("could not create action prototype");
+        let fix = Fix::new(ctx, *batch.id(), bag.component_id, *prototype.id())
+            .await
+            .expect("could not create fix");
+        fix_items.push(FixItem {
+            id: *fix.id(),
+            action_prototype_id: *prototype.id(),
+            component_id: bag.component_id,
+        });
+    }
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    FixesJob::new(ctx, fix_items.clone(), *batch.id())
+        .run(ctx)
+        .await
+        .expect("could not run fixes job");
+
+    let get_fix = |fix_id: FixId| {
+        let ctx = &*ctx;
+        async move {
+            Fix::get_by_id(ctx, &fix_id)
+                .await
+                .expect("could not get fix")
+                .expect("fix not found")
+        }
+    };
+    let fallout_fix = get_fix(fix_items[0].id).await;
+    assert_eq!(
+        Some(&FixCompletionStatus::Error),
+        fallout_fix.completion_status()
+    );
+
+    // The downstream component is never touched...
+    let starfield_fix = get_fix(fix_items[1].id).await;
+    assert_eq!(
+        Some(&FixCompletionStatus::Unstarted),
+        starfield_fix.completion_status()
+    );
+    assert!(starfield_fix.started_at().is_none());
+    assert!(starfield_fix
+        .completion_message()
+        .expect("no completion message")
+        .contains(&fallout_fix.id().to_string()));
+
+    // ...while the unrelated one still runs.
+    let loner_fix = get_fix(fix_items[2].id).await;
+    assert!(loner_fix.started_at().is_some());
+    assert_eq!(
+        Some(&FixCompletionStatus::Error),
+        loner_fix.completion_status()
+    );
+
+    let batch = FixBatch::get_by_id(ctx, batch.id())
+        .await
+        .expect("could not get fix batch")
+        .expect("fix batch not found");
+    assert!(batch.finished_at().is_some());
+}
@@ -7,11 +7,11 @@ use dal::{
         execution::FuncExecution,
     },
     generate_name, ChangeSetPk, DalContext, Func, FuncBackendKind, FuncBackendResponseType, FuncId,
-    StandardModel, Visibility,
+    StandardModel, Visibility, WorkspaceSignup,
 };
 use dal_test::{
     test,
-    test_harness::{create_func, create_func_binding},
+    test_harness::{create_func, create_func_binding, create_secret},
 };
 use strum::IntoEnumIterator;
 
@@ -227,3 +227,141 @@ async fn duplicate(ctx: &DalContext) {
         new_func.handler()  // actual
     );
 }
+
+#[test]
+async fn func_binding_execute_memoized(ctx: &DalContext) {
+    let mut func = Func::new(
+        ctx,
+        generate_name(),
+        FuncBackendKind::JsAttribute,
+        FuncBackendResponseType::Integer,
+    )
+    .await
+    .expect("cannot create func");
+    func.set_code_plaintext(
+        ctx,
+        Some("function double(input) { return input.count * 2; }"),
+    )
+    .await
+    .expect("set code");
+    func.set_handler(ctx, Some("double"))
+        .await
+        .expect("set handler");
+
+    let (_, first) = FuncBinding::create_and_execute(
+        ctx,
+        serde_json::json!({ "count": 21, "unused": "poop" }),
+        *func.id(),
+    )
+    .await
+    .expect("failed to execute func binding");
+    assert_eq!(first.value(), Some(&serde_json::json![42]));
+
+    // Same arguments in another order: the first execution is reused
+    let (_, second) = FuncBinding::create_and_execute(
+        ctx,
+        serde_json::json!({ "unused": "poop", "count": 21 }),
+        *func.id(),
+    )
+    .await
+    .expect("failed to execute func binding");
+    assert_ne!(first.id(), second.id());
+    assert_eq!(first.func_execution_pk(), second.func_execution_pk());
+    assert_eq!(second.value(), Some(&serde_json::json![42]));
+
+    // Other arguments: the func runs again
+    let (_, other) = FuncBinding::create_and_execute(
+        ctx,
+        serde_json::json!({ "count": 2, "unused": "poop" }),
+        *func.id(),
+    )
+    .await
+    .expect("failed to execute func binding");
+    assert_ne!(first.func_execution_pk(), other.func_execution_pk());
+    assert_eq!(other.value(), Some(&serde_json::json![4]));
+
+    // Impure funcs always run
+    func.set_impure(ctx, true)
+        .await
+        .expect("could not set impure");
+    let (_, impure) = FuncBinding::create_and_execute(
+        ctx,
+        serde_json::json!({ "count": 21, "unused": "poop" }),
+        *func.id(),
+    )
+    .await
+    .expect("failed to execute func binding");
+    assert_ne!(first.func_execution_pk(), impure.func_execution_pk());
+    assert_eq!(impure.value(), Some(&serde_json::json![42]));
+}
+
+#[test]
+async fn func_binding_execute_qualification_not_memoized(ctx: &DalContext) {
+    let mut func = Func::new(
+        ctx,
+        generate_name(),
+        FuncBackendKind::JsAttribute,
+        FuncBackendResponseType::Qualification,
+    )
+    .await
+    .expect("cannot create func");
+    func.set_code_plaintext(
+        ctx,
+        Some("function qualify(input) { return { result: 'success', message: input.name }; }"),
+    )
+    .await
+    .expect("set code");
+    func.set_handler(ctx, Some("qualify"))
+        .await
+        .expect("set handler");
+
+    let args = serde_json::json!({ "name": "poop" });
+    let (_, first) = FuncBinding::create_and_execute(ctx, args.clone(), *func.id())
+        .await
+        .expect("failed to execute func binding");
+    let (_, second) = FuncBinding::create_and_execute(ctx, args, *func.id())
+        .await
+        .expect("failed to execute func binding");
+
+    // Qualifications check more than their input, so they always run
+    assert_ne!(first.func_execution_pk(), second.func_execution_pk());
+    assert_eq!(first.value(), second.value());
+}
+
+#[test]
+async fn func_binding_execute_with_secrets_not_memoized(ctx: &DalContext, nw: &WorkspaceSignup) {
+    let mut func = Func::new(
+        ctx,
+        generate_name(),
+        FuncBackendKind::JsAttribute,
+        FuncBackendResponseType::String,
+    )
+    .await
+    .expect("cannot create func");
+    func.set_code_plaintext(ctx, Some("function name(input) { return input.name; }"))
+        .await
+        .expect("set code");
+    func.set_handler(ctx, Some("name"))
+        .await
+        .expect("set handler");
+    let secret = create_secret(ctx, nw.key_pair.pk()).await;
+
+    for args in [
+        serde_json::json!({ "name": "poop", "secret": secret.id().to_string() }),
+        serde_json::json!({
+            "name": "poop",
+            "secret": { "cycloneSecretReference": { "backend": "file", "key": "docker-hub" } },
+        }),
+    ] {
+        let (_, first) = FuncBinding::create_and_execute(ctx, args.clone(), *func.id())
+            .await
+            .expect("failed to execute func binding");
+        let (_, second) = FuncBinding::create_and_execute(ctx, args, *func.id())
+            .await
+            .expect("failed to execute func binding");
+
+        // The secret may be updated without the arguments changing, so the func always runs
+        assert_ne!(first.func_execution_pk(), second.func_execution_pk());
+        assert_eq!(second.value(), Some(&serde_json::json!["poop"]));
+    }
+}
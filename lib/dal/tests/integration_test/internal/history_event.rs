@@ -1,4 +1,7 @@
-use dal::{DalContext, HistoryActor, HistoryEvent};
+use dal::{
+    DalContext, HistoryActor, HistoryEvent, HistoryEventError, HistoryEventPk, HistoryEventQuery,
+    Tenancy, WorkspacePk,
+};
 use dal_test::test;
 
 #[test]
@@ -17,3 +20,126 @@ async fn new(ctx: &DalContext) {
     assert_eq!(&history_event.data, &serde_json::json!({}));
     assert_eq!(&history_event.tenancy, ctx.tenancy());
 }
+
+#[test]
+async fn list(ctx: &DalContext) {
+    for label in [
+        "audit.secret.create",
+        "audit.secret.update",
+        "audit.change_set.apply",
+    ] {
+        HistoryEvent::new(ctx, label, "audited", &serde_json::json!({}))
+            .await
+            .expect("cannot create a new history event");
+    }
+
+    let page = HistoryEvent::list(
+        ctx,
+        &HistoryEventQuery {
+            label_prefix: Some("audit.secret".to_string()),
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("cannot list history events");
+    let labels: Vec<&str> = page.events.iter().map(|e| e.label.as_str()).collect();
+    assert_eq!(labels, vec!["audit.secret.update", "audit.secret.create"]);
+    assert_eq!(page.next_cursor, None);
+    assert!(page.events.iter().all(|event| {
+        event.actor == HistoryActor::SystemInit
+            && event.change_set_pk == Some(ctx.visibility().change_set_pk)
+    }));
+
+    let query = HistoryEventQuery {
+        actor: Some(HistoryActor::SystemInit),
+        label_prefix: Some("audit.".to_string()),
+        change_set_pk: Some(ctx.visibility().change_set_pk),
+        limit: Some(2),
+        ..Default::default()
+    };
+    let first_page = HistoryEvent::list(ctx, &query)
+        .await
+        .expect("cannot list history events");
+    assert_eq!(first_page.events.len(), 2);
+    assert_eq!(first_page.events[0].label, "audit.change_set.apply");
+    let cursor = first_page.next_cursor.expect("a cursor to the next page");
+    assert_eq!(cursor, first_page.events[1].pk);
+
+    let second_page = HistoryEvent::list(
+        ctx,
+        &HistoryEventQuery {
+            cursor: Some(cursor),
+            ..query.clone()
+        },
+    )
+    .await
+    .expect("cannot list history events");
+    let labels: Vec<&str> = second_page
+        .events
+        .iter()
+        .map(|e| e.label.as_str())
+        .collect();
+    assert_eq!(labels, vec!["audit.secret.create"]);
+    assert_eq!(second_page.next_cursor, None);
+
+    // Limits are clamped, rather than overflowing or failing the query
+    let unbounded = HistoryEvent::list(
+        ctx,
+        &HistoryEventQuery {
+            limit: Some(i64::MAX),
+            ..query.clone()
+        },
+    )
+    .await
+    .expect("cannot list history events");
+    assert_eq!(unbounded.events.len(), 3);
+    assert_eq!(unbounded.next_cursor, None);
+    let smallest = HistoryEvent::list(
+        ctx,
+        &HistoryEventQuery {
+            limit: Some(0),
+            ..query.clone()
+        },
+    )
+    .await
+    .expect("cannot list history events");
+    assert_eq!(smallest.events.len(), 1);
+    assert!(smallest.next_cursor.is_some());
+
+    let after_now = HistoryEvent::list(
+        ctx,
+        &HistoryEventQuery {
+            created_after: Some(chrono::Utc::now() + chrono::Duration::minutes(1)),
+            ..query
+        },
+    )
+    .await
+    .expect("cannot list history events");
+    assert!(after_now.events.is_empty());
+}
+
+#[test]
+async fn list_with_unknown_cursor(ctx: &DalContext) {
+    let other_ctx = ctx.clone_with_new_tenancy(Tenancy::new(WorkspacePk::generate()));
+    let other_event = HistoryEvent::new(
+        &other_ctx,
+        "audit.secret.create",
+        "audited",
+        &serde_json::json!({}),
+    )
+    .await
+    .expect("cannot create a new history event");
+
+    // The events of other workspaces are not cursors into this one
+    for cursor in [other_event.pk, HistoryEventPk::generate()] {
+        let result = HistoryEvent::list(
+            ctx,
+            &HistoryEventQuery {
+                cursor: Some(cursor),
+                ..Default::default()
+            },
+        )
+        .await;
+        assert!(matches!(result, Err(HistoryEventError::CursorNotFound(pk)) if pk == cursor));
+    }
+}
@@ -1,7 +1,10 @@
-use dal::{key_pair::PublicKey, DalContext, KeyPair, Tenancy};
+use dal::{
+    key_pair::PublicKey, DalContext, EncryptedSecret, KeyPair, Secret, StandardModel, Tenancy,
+    WorkspaceSignup,
+};
 use dal_test::{
     test,
-    test_harness::{create_key_pair, create_workspace},
+    test_harness::{create_key_pair, create_secret_with_message, create_workspace},
 };
 
 #[test]
@@ -44,3 +47,65 @@ async fn public_key_get_current(ctx: &mut DalContext) {
     assert_eq!(second_key_pair.pk(), *pk.pk());
     assert_eq!(second_key_pair.public_key(), pk.public_key());
 }
+
+async fn decrypt_all(ctx: &DalContext, secrets: &[Secret]) -> Vec<serde_json::Value> {
+    let mut messages = Vec::new();
+    for secret in secrets {
+        let decrypted = EncryptedSecret::get_by_id(ctx, secret.id())
+            .await
+            .expect("failed to fetch encrypted secret")
+            .expect("failed to find encrypted secret")
+            .decrypt(ctx)
+            .await
+            .expect("failed to decrypt secret");
+        let decrypted_value =
+            serde_json::to_value(&decrypted).expect("failed to serialize decrypted into Value");
+        messages.push(decrypted_value["message"].clone());
+    }
+    messages
+}
+
+#[test]
+async fn rotate(ctx: &DalContext, nw: &WorkspaceSignup) {
+    let mut secrets = Vec::new();
+    for song in ["Slow Rollin", "Bar Round Here", "The South"] {
+        secrets.push(
+            create_secret_with_message(ctx, nw.key_pair.pk(), &serde_json::json!({ "song": song }))
+                .await,
+        );
+    }
+    let messages_before = decrypt_all(ctx, &secrets).await;
+    assert_eq!(
+        vec![
+            serde_json::json!({"song": "Slow Rollin"}),
+            serde_json::json!({"song": "Bar Round Here"}),
+            serde_json::json!({"song": "The South"}),
+        ],
+        messages_before
+    );
+
+    let rotation = KeyPair::rotate(ctx)
+        .await
+        .expect("could not rotate key pair");
+    assert_ne!(nw.key_pair.pk(), rotation.key_pair.pk());
+    assert_eq!(secrets.len(), rotation.reencrypted_secret_count);
+    assert_eq!(vec![nw.key_pair.pk()], rotation.deleted_key_pair_pks);
+
+    let current = PublicKey::get_current(ctx)
+        .await
+        .expect("cannot get public key");
+    assert_eq!(rotation.key_pair.pk(), *current.pk());
+
+    for secret in &secrets {
+        let secret = Secret::get_by_id(ctx, secret.id())
+            .await
+            .expect("could not get secret")
+            .expect("could not find secret");
+        let key_pair = secret
+            .key_pair(ctx)
+            .await
+            .expect("could not get key pair of secret");
+        assert_eq!(rotation.key_pair.pk(), key_pair.pk());
+    }
+    assert_eq!(messages_before, decrypt_all(ctx, &secrets).await);
+}
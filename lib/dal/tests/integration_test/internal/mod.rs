@@ -1,9 +1,13 @@
 mod action_prototype;
+mod action_schedule;
+mod api_token;
 mod attribute;
 mod change_set;
 mod component;
 mod diagram;
+mod drift;
 mod edge;
+mod fix;
 mod func;
 mod func_execution;
 mod graph;
@@ -16,6 +20,7 @@ mod prop;
 mod prop_tree;
 mod property_editor;
 mod provider;
+mod refresh;
 mod schema;
 mod secret;
 mod socket;
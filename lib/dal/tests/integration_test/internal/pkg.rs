@@ -1,6 +1,7 @@
 use base64::{engine::general_purpose, Engine};
 use dal::BuiltinsResult;
 use dal::{
+    edge::EdgeKind,
     func::{
         argument::FuncArgumentKind, backend::validation::FuncBackendValidationArgs,
         intrinsics::IntrinsicFunc,
@@ -9,9 +10,11 @@ use dal::{
     pkg::*,
     prop::PropPath,
     schema::variant::leaves::LeafKind,
+    socket::SocketEdgeKind,
     validation::Validation,
-    ActionKind, ChangeSet, ChangeSetPk, DalContext, ExternalProvider, Func, InternalProvider,
-    PropKind, Schema, SchemaVariant, StandardModel, ValidationPrototype,
+    ActionKind, ChangeSet, ChangeSetPk, Component, Connection, DalContext, Edge, ExternalProvider,
+    Func, InternalProvider, PropKind, Schema, SchemaVariant, Socket, StandardModel,
+    ValidationPrototype,
 };
 use dal_test::{test, DalContextHeadRef};
 use si_pkg::{
@@ -876,3 +879,268 @@ async fn test_install_pkg(ctx: &DalContext) {
         .expect("func is there");
     assert_eq!(func.name(), "groucho");
 }
+
+#[test]
+async fn test_workspace_pkg_selective_import(DalContextHeadRef(ctx): DalContextHeadRef<'_>) {
+    let starfield = Schema::find_by_name(ctx, "starfield")
+        .await
+        .expect("get starfield");
+    let (mut component, _) =
+        Component::new_for_default_variant_from_schema(ctx, "new vegas", *starfield.id())
+            .await
+            .expect("able to create component");
+    let unique_id = component.id().to_string();
+
+    let pkg =
+        PkgExporter::new_workspace_exporter("workspace", "sally@systeminit.com", "foo", "bar")
+            .export(ctx)
+            .await
+            .expect("able to export");
+    let options = |components: Vec<String>| ImportOptions {
+        no_record: true,
+        selection: Some(ImportSelection {
+            components,
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    let result =
+        import_pkg_from_pkg(ctx, &pkg, Some(options(vec!["not a component".to_owned()]))).await;
+    assert!(matches!(result, Err(PkgError::SelectedItemNotInBackup(_))));
+
+    let (_, _, import_skips) =
+        import_pkg_from_pkg(ctx, &pkg, Some(options(vec![unique_id.clone()])))
+            .await
+            .expect("able to import selection");
+    let import_skips = import_skips.expect("import skips are reported");
+    assert_eq!(
+        &[ImportConflict::Component {
+            unique_id: unique_id.clone(),
+            name: "new vegas".to_owned(),
+        }],
+        import_skips[0].conflicts()
+    );
+
+    component
+        .delete_and_propagate(ctx)
+        .await
+        .expect("able to delete component");
+    let (_, _, import_skips) = import_pkg_from_pkg(ctx, &pkg, Some(options(vec![unique_id])))
+        .await
+        .expect("able to import selection");
+    let import_skips = import_skips.expect("import skips are reported");
+    assert!(import_skips[0].conflicts().is_empty());
+    assert_eq!(1, components_named(ctx, "new vegas").await.len());
+}
+
+async fn components_named(ctx: &DalContext, name: &str) -> Vec<Component> {
+    let mut components = vec![];
+    for component in Component::list(ctx).await.expect("able to list components") {
+        if component.name(ctx).await.expect("able to get name") == name {
+            components.push(component);
+        }
+    }
+    components
+}
+
+fn selection_options(selection: ImportSelection) -> ImportOptions {
+    ImportOptions {
+        no_record: true,
+        selection: Some(selection),
+        ..Default::default()
+    }
+}
+
+#[test]
+async fn test_workspace_pkg_selective_import_frame(DalContextHeadRef(ctx): DalContextHeadRef<'_>) {
+    let generic_frame = Schema::find_by_name(ctx, "Generic Frame")
+        .await
+        .expect("get generic frame");
+    let starfield = Schema::find_by_name(ctx, "starfield")
+        .await
+        .expect("get starfield");
+    let (mut frame, frame_node) =
+        Component::new_for_default_variant_from_schema(ctx, "mojave", *generic_frame.id())
+            .await
+            .expect("able to create frame");
+    let (mut child, child_node) =
+        Component::new_for_default_variant_from_schema(ctx, "new vegas", *starfield.id())
+            .await
+            .expect("able to create component");
+    let from_socket = Socket::find_frame_socket_for_node(
+        ctx,
+        *child_node.id(),
+        SocketEdgeKind::ConfigurationOutput,
+    )
+    .await
+    .expect("able to find child frame socket");
+    let to_socket = Socket::find_frame_socket_for_node(
+        ctx,
+        *frame_node.id(),
+        SocketEdgeKind::ConfigurationInput,
+    )
+    .await
+    .expect("able to find frame socket");
+    let connection = Connection::new(
+        ctx,
+        *child_node.id(),
+        *from_socket.id(),
+        *frame_node.id(),
+        *to_socket.id(),
+        EdgeKind::Symbolic,
+    )
+    .await
+    .expect("able to put component in frame");
+    let frame_unique_id = frame.id().to_string();
+    let child_unique_id = child.id().to_string();
+
+    let pkg =
+        PkgExporter::new_workspace_exporter("workspace", "sally@systeminit.com", "foo", "bar")
+            .export(ctx)
+            .await
+            .expect("able to export");
+    let options = || {
+        selection_options(ImportSelection {
+            components: vec![frame_unique_id.clone()],
+            ..Default::default()
+        })
+    };
+
+    child
+        .delete_and_propagate(ctx)
+        .await
+        .expect("able to delete component");
+    frame
+        .delete_and_propagate(ctx)
+        .await
+        .expect("able to delete frame");
+
+    // Selecting the frame restores the components inside of it along with it
+    let (_, _, import_skips) = import_pkg_from_pkg(ctx, &pkg, Some(options()))
+        .await
+        .expect("able to import selection");
+    let import_skips = import_skips.expect("import skips are reported");
+    assert!(import_skips[0].conflicts().is_empty());
+    let restored_frame = components_named(ctx, "mojave")
+        .await
+        .pop()
+        .expect("frame is restored");
+    let restored_child = components_named(ctx, "new vegas")
+        .await
+        .pop()
+        .expect("component in frame is restored");
+    assert!(Edge::list_for_component(ctx, *restored_frame.id())
+        .await
+        .expect("able to list edges")
+        .iter()
+        .any(|edge| edge.tail_object_id() == (*restored_child.id()).into()));
+
+    // The restored objects have new ids, they are found by the unique ids they were restored from
+    let (_, _, import_skips) = import_pkg_from_pkg(ctx, &pkg, Some(options()))
+        .await
+        .expect("able to import selection");
+    let import_skips = import_skips.expect("import skips are reported");
+    let conflicts = import_skips[0].conflicts();
+    assert_eq!(3, conflicts.len());
+    assert!(conflicts.contains(&ImportConflict::Component {
+        unique_id: frame_unique_id,
+        name: "mojave".to_owned(),
+    }));
+    assert!(conflicts.contains(&ImportConflict::Component {
+        unique_id: child_unique_id,
+        name: "new vegas".to_owned(),
+    }));
+    assert!(conflicts.contains(&ImportConflict::Edge {
+        unique_id: connection.id.to_string(),
+    }));
+    assert_eq!(1, components_named(ctx, "mojave").await.len());
+    assert_eq!(1, components_named(ctx, "new vegas").await.len());
+}
+
+#[test]
+async fn test_workspace_pkg_selective_import_schema_and_func(
+    DalContextHeadRef(ctx): DalContextHeadRef<'_>,
+) {
+    make_stellarfield(ctx)
+        .await
+        .expect("able to make stellarfield");
+    let stellarfield = Schema::find_by_name(ctx, "stellarfield")
+        .await
+        .expect("get stellarfield");
+    let mut func = Func::find_by_name(ctx, "test:createActionStellarfield")
+        .await
+        .expect("able to find func")
+        .expect("func exists");
+    let func_unique_id = func.id().to_string();
+
+    let pkg =
+        PkgExporter::new_workspace_exporter("workspace", "sally@systeminit.com", "foo", "bar")
+            .export(ctx)
+            .await
+            .expect("able to export");
+
+    // Builtin schemas are not part of workspace backups
+    let result = import_pkg_from_pkg(
+        ctx,
+        &pkg,
+        Some(selection_options(ImportSelection {
+            schemas: vec!["starfield".to_owned()],
+            ..Default::default()
+        })),
+    )
+    .await;
+    assert!(matches!(result, Err(PkgError::SelectedItemNotInBackup(_))));
+
+    let (_, _, import_skips) = import_pkg_from_pkg(
+        ctx,
+        &pkg,
+        Some(selection_options(ImportSelection {
+            schemas: vec!["stellarfield".to_owned()],
+            ..Default::default()
+        })),
+    )
+    .await
+    .expect("able to import selection");
+    let import_skips = import_skips.expect("import skips are reported");
+    let conflicts = import_skips[0].conflicts();
+    assert!(conflicts.contains(&ImportConflict::Schema {
+        unique_id: stellarfield.id().to_string(),
+        name: "stellarfield".to_owned(),
+    }));
+    // The funcs of the schema are picked along with it
+    assert!(conflicts.contains(&ImportConflict::Func {
+        unique_id: func_unique_id.clone(),
+        name: "test:createActionStellarfield".to_owned(),
+    }));
+
+    let func_options = || {
+        selection_options(ImportSelection {
+            funcs: vec!["test:createActionStellarfield".to_owned()],
+            ..Default::default()
+        })
+    };
+    func.delete_by_id(ctx).await.expect("able to delete func");
+    let (_, _, import_skips) = import_pkg_from_pkg(ctx, &pkg, Some(func_options()))
+        .await
+        .expect("able to import selection");
+    let import_skips = import_skips.expect("import skips are reported");
+    assert!(import_skips[0].conflicts().is_empty());
+    let restored = Func::find_by_name(ctx, "test:createActionStellarfield")
+        .await
+        .expect("able to find func")
+        .expect("func is restored");
+    assert_ne!(func.id(), restored.id());
+
+    let (_, _, import_skips) = import_pkg_from_pkg(ctx, &pkg, Some(func_options()))
+        .await
+        .expect("able to import selection");
+    let import_skips = import_skips.expect("import skips are reported");
+    assert_eq!(
+        &[ImportConflict::Func {
+            unique_id: func_unique_id,
+            name: "test:createActionStellarfield".to_owned(),
+        }],
+        import_skips[0].conflicts()
+    );
+}
@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+use dal::job::consumer::JobConsumer;
+use dal::job::definition::RefreshJob;
+use dal::tasks::ResourceScheduler;
+use dal::{ComponentDrift, ComponentId, DalContext, DriftStatus, JobFailure, StandardModel};
+use dal_test::{test, test_harness::create_component_and_schema};
+
+#[test]
+async fn failed_refresh_records_job_failure(ctx: &mut DalContext) {
+    let component = create_component_and_schema(ctx).await;
+    let missing_component_id = ComponentId::generate();
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let job = RefreshJob::new(
+        ctx.access_builder(),
+        *ctx.visibility(),
+        vec![missing_component_id, *component.id()],
+    );
+    job.run(ctx).await.expect("refresh job failed");
+
+    let failures = JobFailure::list(ctx)
+        .await
+        .expect("could not list job failures");
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].kind(), "RefreshJob");
+    assert!(failures[0]
+        .message()
+        .contains(&missing_component_id.to_string()));
+
+    // The failure does not stop the rest of the batch from being refreshed.
+    let drift = ComponentDrift::get_for_component(ctx, *component.id())
+        .await
+        .expect("could not get drift")
+        .expect("drift not stored");
+    assert_eq!(DriftStatus::Unknown, drift.status);
+}
+
+#[test]
+async fn resource_refresh_claim(ctx: &DalContext) {
+    let interval = Duration::from_secs(5 * 60);
+    assert!(ResourceScheduler::claim_workspace(ctx, interval)
+        .await
+        .expect("cannot claim resource refresh"));
+    // Claimed less than an interval ago, by this scheduler or another one
+    assert!(!ResourceScheduler::claim_workspace(ctx, interval)
+        .await
+        .expect("cannot claim resource refresh"));
+    assert!(ResourceScheduler::claim_workspace(ctx, Duration::ZERO)
+        .await
+        .expect("cannot claim resource refresh"));
+}
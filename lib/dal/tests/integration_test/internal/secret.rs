@@ -1,10 +1,15 @@
+use dal::secret::{FileSecretBackend, SecretBackend, SecretReference, FILE_SECRET_BACKEND_NAME};
 use dal::{
-    DalContext, EncryptedSecret, Secret, SecretAlgorithm, SecretVersion, StandardModel,
+    AttributeContext, AttributeValue, ChangeSetPk, Component, DalContext, EncryptedSecret, KeyPair,
+    Prop, PropKind, Secret, SecretAlgorithm, SecretError, SecretVersion, StandardModel, Visibility,
     WorkspaceSignup,
 };
 use dal_test::{
     test,
-    test_harness::{create_secret, generate_fake_name},
+    test_harness::{
+        create_schema, create_schema_variant_with_root, create_secret, create_secret_with_message,
+        encrypt_message, generate_fake_name,
+    },
 };
 
 #[test]
@@ -35,6 +40,35 @@ async fn new_encrypted_secret(ctx: &DalContext, nw: &WorkspaceSignup) {
     assert_eq!(key_pair.pk(), nw.key_pair.pk());
 }
 
+#[test]
+async fn new_encrypted_secret_requires_current_key_pair(ctx: &DalContext, nw: &WorkspaceSignup) {
+    let new_key_pair = KeyPair::new(ctx, generate_fake_name())
+        .await
+        .expect("could not create key pair");
+
+    let message = serde_json::json!({ "song": "the trooper" });
+    let err = EncryptedSecret::new(
+        ctx,
+        generate_fake_name(),
+        "Mock".to_owned(),
+        None,
+        &encrypt_message(ctx, nw.key_pair.pk(), &message).await,
+        nw.key_pair.pk(),
+        SecretVersion::V1,
+        SecretAlgorithm::Sealedbox,
+    )
+    .await
+    .expect_err("created a secret with a stale key pair");
+    assert!(matches!(err, SecretError::NotCurrentKeyPair(pk) if pk == nw.key_pair.pk()));
+
+    let secret = create_secret_with_message(ctx, new_key_pair.pk(), &message).await;
+    let key_pair = secret
+        .key_pair(ctx)
+        .await
+        .expect("failed to fetch key pair");
+    assert_eq!(key_pair.pk(), new_key_pair.pk());
+}
+
 #[test]
 async fn secret_get_by_id(ctx: &DalContext, nw: &WorkspaceSignup) {
     let og_secret = create_secret(ctx, nw.key_pair.pk()).await;
@@ -116,3 +150,221 @@ async fn encrypt_decrypt_round_trip(ctx: &DalContext, nw: &WorkspaceSignup) {
         serde_json::to_value(&decrypted).expect("failed to serial decrypted into Value");
     assert_eq!(decrypted_value["message"], message);
 }
+
+#[test]
+async fn secret_rotation_usage_and_delete(ctx: &DalContext, nw: &WorkspaceSignup) {
+    let mut secret = create_secret(ctx, nw.key_pair.pk()).await;
+    let mut unused_secret = create_secret(ctx, nw.key_pair.pk()).await;
+
+    // A component with "/root/secrets/Mock" set to the secret
+    let schema = create_schema(ctx).await;
+    let (mut schema_variant, root) = create_schema_variant_with_root(ctx, *schema.id()).await;
+    let mock_prop = Prop::new(
+        ctx,
+        "Mock",
+        PropKind::String,
+        None,
+        *schema_variant.id(),
+        Some(root.secrets_prop_id),
+    )
+    .await
+    .expect("could not create prop");
+    schema_variant
+        .finalize(ctx, None)
+        .await
+        .expect("cannot finalize schema variant");
+    let (component, _) = Component::new(ctx, generate_fake_name(), *schema_variant.id())
+        .await
+        .expect("could not create component");
+
+    let secrets_context = AttributeContext::builder()
+        .set_prop_id(root.secrets_prop_id)
+        .set_component_id(*component.id())
+        .to_context()
+        .expect("could not create secrets attribute context");
+    let secrets_value = AttributeValue::find_for_context(ctx, secrets_context.into())
+        .await
+        .expect("could not fetch secrets attribute value")
+        .expect("could not find secrets attribute value");
+    let mock_context = AttributeContext::builder()
+        .set_prop_id(*mock_prop.id())
+        .set_component_id(*component.id())
+        .to_context()
+        .expect("could not create mock attribute context");
+    let mock_value = AttributeValue::find_for_context(ctx, mock_context.into())
+        .await
+        .expect("could not fetch mock attribute value")
+        .expect("could not find mock attribute value");
+    AttributeValue::update_for_context(
+        ctx,
+        *mock_value.id(),
+        Some(*secrets_value.id()),
+        mock_context,
+        Some(serde_json::json!(secret.id().to_string())),
+        None,
+    )
+    .await
+    .expect("could not set secret on component");
+
+    assert_eq!(
+        vec![*component.id()],
+        secret.used_by(ctx).await.expect("could not list usage")
+    );
+    assert!(unused_secret
+        .used_by(ctx)
+        .await
+        .expect("could not list usage")
+        .is_empty());
+    // The component only exists in the change set, but it uses the secret all the same
+    let head_ctx = ctx.clone_with_new_visibility(Visibility::new_head(false));
+    assert_eq!(
+        vec![*component.id()],
+        secret
+            .used_by(&head_ctx)
+            .await
+            .expect("could not list usage")
+    );
+
+    // Rotation requires the current key pair, and a message it can decrypt
+    let message = serde_json::json!({"password": "rotated"});
+    let crypted = encrypt_message(ctx, nw.key_pair.pk(), &message).await;
+    let err = secret
+        .update_encrypted_contents(
+            ctx,
+            b"not-crypted",
+            nw.key_pair.pk(),
+            SecretVersion::V1,
+            SecretAlgorithm::Sealedbox,
+        )
+        .await
+        .expect_err("rotated with an undecryptable message");
+    assert!(matches!(err, SecretError::DecryptionFailed));
+
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+    let change_set_pk = ctx.visibility().change_set_pk;
+    let dependent_values_updates = count_dependent_values_updates(ctx, change_set_pk).await;
+    secret
+        .update_encrypted_contents(
+            ctx,
+            &crypted,
+            nw.key_pair.pk(),
+            SecretVersion::V1,
+            SecretAlgorithm::Sealedbox,
+        )
+        .await
+        .expect("could not rotate secret");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+    assert_eq!(
+        dependent_values_updates + 1,
+        count_dependent_values_updates(ctx, change_set_pk).await,
+        "rotating the secret updates the values computed from it"
+    );
+    let decrypted = EncryptedSecret::get_by_id(ctx, secret.id())
+        .await
+        .expect("failed to fetch encrypted secret")
+        .expect("failed to find encrypted secret")
+        .decrypt(ctx)
+        .await
+        .expect("failed to decrypt rotated secret");
+    let decrypted_value =
+        serde_json::to_value(&decrypted).expect("failed to serialize decrypted into Value");
+    assert_eq!(decrypted_value["message"], message);
+
+    let new_key_pair = KeyPair::new(ctx, generate_fake_name())
+        .await
+        .expect("could not create key pair");
+    let err = secret
+        .update_encrypted_contents(
+            ctx,
+            &crypted,
+            nw.key_pair.pk(),
+            SecretVersion::V1,
+            SecretAlgorithm::Sealedbox,
+        )
+        .await
+        .expect_err("rotated with a stale key pair");
+    assert!(matches!(err, SecretError::NotCurrentKeyPair(pk) if pk == nw.key_pair.pk()));
+    secret
+        .update_encrypted_contents(
+            ctx,
+            &encrypt_message(ctx, new_key_pair.pk(), &message).await,
+            new_key_pair.pk(),
+            SecretVersion::V1,
+            SecretAlgorithm::Sealedbox,
+        )
+        .await
+        .expect("could not rotate secret with the new key pair");
+
+    // Secrets in use can't be deleted
+    let err = secret
+        .delete(ctx)
+        .await
+        .expect_err("deleted a secret in use");
+    assert!(matches!(err, SecretError::InUse(id, 1) if id == *secret.id()));
+    let err = secret
+        .delete(&head_ctx)
+        .await
+        .expect_err("deleted a secret in use in a change set");
+    assert!(matches!(err, SecretError::InUse(id, 1) if id == *secret.id()));
+
+    unused_secret
+        .delete(ctx)
+        .await
+        .expect("could not delete unused secret");
+    assert!(Secret::get_by_id(ctx, unused_secret.id())
+        .await
+        .expect("could not get secret")
+        .is_none());
+}
+
+#[test]
+async fn secret_referencing_backend(ctx: &DalContext, nw: &WorkspaceSignup) {
+    let message = FileSecretBackend.reference_message("docker-hub");
+    let secret = create_secret_with_message(ctx, nw.key_pair.pk(), &message).await;
+
+    let decrypted = EncryptedSecret::get_by_id(ctx, secret.id())
+        .await
+        .expect("failed to fetch encrypted secret")
+        .expect("failed to find encrypted secret")
+        .decrypt(ctx)
+        .await
+        .expect("failed to decrypt secret");
+    let reference = SecretReference::new(FILE_SECRET_BACKEND_NAME, "docker-hub");
+    assert_eq!(Some(reference.clone()), decrypted.reference());
+    // Cyclone only resolves references namespaced by the workspace owning the secret
+    assert_eq!(
+        format!("{}/docker-hub", nw.workspace.pk()),
+        reference.namespaced(nw.workspace.pk()).key
+    );
+
+    let plain_secret = create_secret(ctx, nw.key_pair.pk()).await;
+    let decrypted = EncryptedSecret::get_by_id(ctx, plain_secret.id())
+        .await
+        .expect("failed to fetch encrypted secret")
+        .expect("failed to find encrypted secret")
+        .decrypt(ctx)
+        .await
+        .expect("failed to decrypt secret");
+    assert_eq!(None, decrypted.reference());
+}
+
+/// Counts the dependent values updates that ran in the change set so far: each of them tracks
+/// its progress with a status update of its own.
+async fn count_dependent_values_updates(ctx: &DalContext, change_set_pk: ChangeSetPk) -> i64 {
+    ctx.txns()
+        .await
+        .expect("cannot get transactions")
+        .pg()
+        .query_one(
+            "SELECT count(*) AS count FROM status_updates WHERE change_set_pk = $1",
+            &[&change_set_pk],
+        )
+        .await
+        .expect("cannot count status updates")
+        .try_get("count")
+        .expect("cannot get status update count")
+}
@@ -1,4 +1,7 @@
-use dal::{DalContext, User, UserPk, WorkspaceSignup};
+use dal::{
+    ChangeSet, ChangeSetError, DalContext, HistoryActor, Tenancy, User, UserError, UserPk,
+    Workspace, WorkspaceError, WorkspacePermission, WorkspacePk, WorkspaceRole, WorkspaceSignup,
+};
 use dal_test::test;
 
 #[test]
@@ -15,27 +18,102 @@ async fn new(ctx: &DalContext) {
 }
 
 #[test]
-async fn authorize(ctx: &DalContext, nw: &WorkspaceSignup) {
-    let worked = User::authorize(ctx, &nw.user.pk())
+async fn authorize(ctx: &mut DalContext, nw: &WorkspaceSignup) {
+    let worked = User::authorize(ctx, &nw.user.pk(), WorkspacePermission::ManageWorkspace)
         .await
-        .expect("admin group user should be authorized");
-    assert!(worked, "authorized admin group user returns true");
+        .expect("cannot authorize user");
+    assert!(worked, "the user that signed up administers the workspace");
 
-    // TODO(theo,paulo): re-enable that when capabilities are back
-    /*
-    let user_no_group = User::new(
+    let viewer = User::new(
         ctx,
         UserPk::generate(),
         "funky",
         "bobotclown@systeminit.com",
+        None::<String>,
     )
     .await
     .expect("cannot create user");
+    let worked = User::authorize(ctx, &viewer.pk(), WorkspacePermission::Read)
+        .await
+        .expect("cannot authorize user");
+    assert!(!worked, "users outside of the workspace cannot do anything");
 
-    let f = User::authorize(ctx, &user_no_group.pk()).await;
+    viewer
+        .associate_workspace(ctx, *nw.workspace.pk(), WorkspaceRole::Viewer)
+        .await
+        .expect("cannot associate user with workspace");
     assert!(
-        f.is_err(),
-        "user that is not in the admin group should fail"
+        User::authorize(ctx, &viewer.pk(), WorkspacePermission::Read)
+            .await
+            .expect("cannot authorize user")
     );
-    */
+    assert!(
+        !User::authorize(ctx, &viewer.pk(), WorkspacePermission::ApplyChangeSets)
+            .await
+            .expect("cannot authorize user")
+    );
+
+    let mut change_set = ChangeSet::new(ctx, ChangeSet::generate_name(), None)
+        .await
+        .expect("cannot create change set");
+
+    ctx.update_history_actor(HistoryActor::User(viewer.pk()));
+    let result = change_set.close(ctx).await;
+    assert!(matches!(
+        result,
+        Err(ChangeSetError::User(UserError::PermissionDenied(..)))
+    ));
+    let result = User::ensure_authorized(ctx, WorkspacePermission::EditChangeSets).await;
+    assert!(matches!(
+        result,
+        Err(UserError::PermissionDenied(
+            _,
+            WorkspaceRole::Viewer,
+            _,
+            WorkspacePermission::EditChangeSets
+        ))
+    ));
+    let result = nw
+        .workspace
+        .set_member_role(ctx, viewer.pk(), WorkspaceRole::Admin)
+        .await;
+    assert!(matches!(
+        result,
+        Err(WorkspaceError::User(UserError::PermissionDenied(..)))
+    ));
+
+    ctx.update_history_actor(HistoryActor::User(nw.user.pk()));
+    nw.workspace
+        .set_member_role(ctx, viewer.pk(), WorkspaceRole::Applier)
+        .await
+        .expect("cannot set member role");
+    assert_eq!(
+        User::role_in_workspace(ctx, viewer.pk(), *nw.workspace.pk())
+            .await
+            .expect("cannot get role"),
+        Some(WorkspaceRole::Applier)
+    );
+    let result = nw
+        .workspace
+        .set_member_role(ctx, nw.user.pk(), WorkspaceRole::Editor)
+        .await;
+    assert!(matches!(result, Err(WorkspaceError::LastAdmin(_, _))));
+
+    // Administering a workspace does not allow managing another one.
+    let mut other = Workspace::new(ctx, WorkspacePk::generate(), "other")
+        .await
+        .expect("cannot create workspace");
+    ctx.update_tenancy(Tenancy::new(*nw.workspace.pk()));
+    let result = other.set_required_change_set_approvals(ctx, 1).await;
+    assert!(matches!(
+        result,
+        Err(WorkspaceError::User(UserError::NotAMember(..)))
+    ));
+    let result = other
+        .set_member_role(ctx, nw.user.pk(), WorkspaceRole::Admin)
+        .await;
+    assert!(matches!(
+        result,
+        Err(WorkspaceError::User(UserError::NotAMember(..)))
+    ));
 }
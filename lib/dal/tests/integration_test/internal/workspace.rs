@@ -1,5 +1,19 @@
-use dal::{DalContext, Workspace, WorkspacePk};
-use dal_test::test;
+use std::time::Duration;
+
+use dal::edge::EdgeKind;
+use dal::secret::{FileSecretBackend, SecretBackend};
+use dal::socket::SocketEdgeKind;
+use dal::{
+    pkg::PkgExporter, ChangeSet, ChangeSetStatus, Component, Connection, DalContext, Edge,
+    EncryptedSecret, HistoryActor, KeyPair, Socket, StandardModel, Tenancy, UserClaim, UserError,
+    UserWorkspace, Visibility, Workspace, WorkspaceBackup, WorkspaceError, WorkspacePk,
+    WorkspaceRestoreTarget, WorkspaceRole, WorkspaceSignup,
+};
+use dal_test::helpers::component_bag::ComponentBagger;
+use dal_test::{
+    test,
+    test_harness::{create_change_set, create_secret_with_message},
+};
 
 #[test]
 async fn new(ctx: &mut DalContext) {
@@ -7,3 +21,340 @@ async fn new(ctx: &mut DalContext) {
         .await
         .expect("cannot create workspace");
 }
+
+#[test]
+async fn list_for_user_and_switch(ctx: &mut DalContext, nw: &WorkspaceSignup) {
+    let workspaces = Workspace::list_for_user(ctx, nw.user.pk())
+        .await
+        .expect("cannot list workspaces");
+    assert_eq!(
+        workspaces,
+        vec![UserWorkspace {
+            workspace: nw.workspace.clone(),
+            role: WorkspaceRole::Admin,
+        }]
+    );
+
+    let other = Workspace::new(ctx, WorkspacePk::generate(), "black sabbath")
+        .await
+        .expect("cannot create workspace");
+    let unrelated = Workspace::new(ctx, WorkspacePk::generate(), "deep purple")
+        .await
+        .expect("cannot create workspace");
+    nw.user
+        .associate_workspace(ctx, *other.pk(), WorkspaceRole::Viewer)
+        .await
+        .expect("cannot associate user with workspace");
+
+    let workspaces = Workspace::list_for_user(ctx, nw.user.pk())
+        .await
+        .expect("cannot list workspaces");
+    assert_eq!(workspaces.len(), 2);
+    assert!(workspaces.contains(&UserWorkspace {
+        workspace: other.clone(),
+        role: WorkspaceRole::Viewer,
+    }));
+
+    let mut claim = UserClaim::new(nw.user.pk(), *nw.workspace.pk());
+    let role = claim
+        .switch_workspace(ctx, *other.pk())
+        .await
+        .expect("cannot switch workspace");
+    assert_eq!(role, WorkspaceRole::Viewer);
+    assert_eq!(claim.workspace_pk, *other.pk());
+
+    let result = claim.switch_workspace(ctx, *unrelated.pk()).await;
+    assert!(matches!(result, Err(UserError::NotAMember(_, _))));
+    assert_eq!(claim.workspace_pk, *other.pk());
+}
+
+#[test]
+async fn fork(ctx: &mut DalContext, nw: &WorkspaceSignup) {
+    ctx.update_history_actor(HistoryActor::User(nw.user.pk()));
+    let message = serde_json::json!({ "song": "Run to the Hills" });
+    let secret = create_secret_with_message(ctx, nw.key_pair.pk(), &message).await;
+
+    // Two connected components in the change set of the context
+    let mut bagger = ComponentBagger::new();
+    let fallout_bag = bagger.create_component(ctx, "source", "fallout").await;
+    let starfield_bag = bagger
+        .create_component(ctx, "destination", "starfield")
+        .await;
+    let from_socket = Socket::find_by_name_for_edge_kind_and_node(
+        ctx,
+        "fallout",
+        SocketEdgeKind::ConfigurationOutput,
+        fallout_bag.node_id,
+    )
+    .await
+    .expect("cannot find socket")
+    .expect("socket not found");
+    let to_socket = Socket::find_by_name_for_edge_kind_and_node(
+        ctx,
+        "fallout",
+        SocketEdgeKind::ConfigurationInput,
+        starfield_bag.node_id,
+    )
+    .await
+    .expect("cannot find socket")
+    .expect("socket not found");
+    Connection::new(
+        ctx,
+        fallout_bag.node_id,
+        *from_socket.id(),
+        starfield_bag.node_id,
+        *to_socket.id(),
+        EdgeKind::Configuration,
+    )
+    .await
+    .expect("cannot connect components");
+
+    // And a component in another change set, pending approval
+    let mut pending = create_change_set(ctx).await;
+    let pending_ctx = ctx.clone_with_new_visibility(Visibility::new_change_set(pending.pk, false));
+    bagger
+        .create_component(&pending_ctx, "pending", "starfield")
+        .await;
+    pending
+        .request_approval(&pending_ctx)
+        .await
+        .expect("cannot request approval");
+
+    let change_set_name = ChangeSet::get_by_pk(ctx, &ctx.visibility().change_set_pk)
+        .await
+        .expect("cannot get change set")
+        .expect("change set not found")
+        .name;
+
+    let fork = Workspace::fork(ctx, "iron maiden fork")
+        .await
+        .expect("cannot fork workspace");
+    assert_ne!(nw.workspace.pk(), fork.pk());
+    assert_eq!("iron maiden fork", fork.name().as_str());
+
+    let workspaces = Workspace::list_for_user(ctx, nw.user.pk())
+        .await
+        .expect("cannot list workspaces");
+    assert!(workspaces.contains(&UserWorkspace {
+        workspace: fork.clone(),
+        role: WorkspaceRole::Admin,
+    }));
+
+    ctx.update_tenancy(Tenancy::new(*fork.pk()));
+    let key_pair = KeyPair::get_current(ctx)
+        .await
+        .expect("cannot get key pair of fork");
+    assert_ne!(nw.key_pair.pk(), key_pair.pk());
+
+    // Every open change set is copied with its components and edges, and they all start out open
+    let head_ctx = ctx.clone_with_head();
+    let change_sets = ChangeSet::list_open(&head_ctx)
+        .await
+        .expect("cannot list change sets of fork");
+    let fork_change_set = |name: &str| {
+        change_sets
+            .iter()
+            .find(|change_set| change_set.name == name)
+            .map(|change_set| (change_set.pk, change_set.status.clone()))
+            .expect("change set not copied")
+    };
+    let (copied_pk, copied_status) = fork_change_set(&change_set_name);
+    let (copied_pending_pk, copied_pending_status) = fork_change_set(&pending.name);
+    assert_eq!(ChangeSetStatus::Open, copied_status);
+    assert_eq!(ChangeSetStatus::Open, copied_pending_status);
+
+    let component_names = |ctx: DalContext| async move {
+        let mut names = Vec::new();
+        for component in Component::list(&ctx).await.expect("cannot list components") {
+            names.push(component.name(&ctx).await.expect("cannot get name"));
+        }
+        names.sort();
+        names
+    };
+    let copied_ctx = ctx.clone_with_new_visibility(Visibility::new_change_set(copied_pk, false));
+    assert_eq!(
+        vec!["destination".to_owned(), "source".to_owned()],
+        component_names(copied_ctx.clone()).await
+    );
+    let edges = Edge::list(&copied_ctx).await.expect("cannot list edges");
+    assert!(edges
+        .iter()
+        .any(|edge| *edge.kind() == EdgeKind::Configuration));
+    let copied_pending_ctx =
+        ctx.clone_with_new_visibility(Visibility::new_change_set(copied_pending_pk, false));
+    assert_eq!(
+        vec!["pending".to_owned()],
+        component_names(copied_pending_ctx).await
+    );
+
+    // The secret lands in the copy of the change set it was created in
+    let encrypted_secret = EncryptedSecret::get_by_id(&copied_ctx, secret.id())
+        .await
+        .expect("cannot get forked secret")
+        .expect("forked secret not found");
+    let key_pair_of_secret = encrypted_secret
+        .key_pair(&copied_ctx)
+        .await
+        .expect("cannot get key pair of forked secret");
+    assert_eq!(key_pair.pk(), key_pair_of_secret.pk());
+    let decrypted = encrypted_secret
+        .decrypt(&copied_ctx)
+        .await
+        .expect("cannot decrypt forked secret");
+    let decrypted = serde_json::to_value(&decrypted).expect("cannot serialize decrypted secret");
+    assert_eq!(message, decrypted["message"]);
+}
+
+#[test]
+async fn fork_secret_referencing_backend(ctx: &mut DalContext, nw: &WorkspaceSignup) {
+    ctx.update_history_actor(HistoryActor::User(nw.user.pk()));
+    let message = FileSecretBackend.reference_message("docker-hub");
+    let secret =
+        create_secret_with_message(&ctx.clone_with_head(), nw.key_pair.pk(), &message).await;
+
+    // The file backend keeps the secret in the namespace of the workspace it was created in
+    let backend_dir = tempfile::tempdir().expect("cannot create secret backend dir");
+    let namespace_dir = backend_dir.path().join(nw.workspace.pk().to_string());
+    std::fs::create_dir(&namespace_dir).expect("cannot create secret backend namespace");
+    let stored = serde_json::json!({ "username": "bruce", "password": "dickinson" });
+    std::fs::write(
+        namespace_dir.join("docker-hub.json"),
+        serde_json::to_vec(&stored).expect("cannot serialize stored secret"),
+    )
+    .expect("cannot store secret in backend");
+
+    let fork = Workspace::fork(ctx, "iron maiden fork")
+        .await
+        .expect("cannot fork workspace");
+    ctx.update_tenancy(Tenancy::new(*fork.pk()));
+    let head_ctx = ctx.clone_with_head();
+
+    let encrypted_secret = EncryptedSecret::get_by_id(&head_ctx, secret.id())
+        .await
+        .expect("cannot get forked secret")
+        .expect("forked secret not found");
+    let backend_namespace = encrypted_secret
+        .backend_namespace()
+        .expect("forked secret has no backend namespace");
+    assert_eq!(*nw.workspace.pk(), backend_namespace);
+    let reference = encrypted_secret
+        .decrypt(&head_ctx)
+        .await
+        .expect("cannot decrypt forked secret")
+        .reference()
+        .expect("forked secret holds no reference");
+
+    // Resolved the way the file backend of Cyclone does, from the file of the namespaced key
+    let key = reference.namespaced(backend_namespace).key;
+    let resolved: serde_json::Value = serde_json::from_slice(
+        &std::fs::read(backend_dir.path().join(format!("{key}.json")))
+            .expect("cannot resolve forked secret"),
+    )
+    .expect("cannot deserialize resolved secret");
+    assert_eq!(stored, resolved);
+}
+
+#[test]
+async fn backup_record_list_and_prune(ctx: &mut DalContext) {
+    let mut scheduled = WorkspaceBackup::record(
+        ctx,
+        "01H9ZQCBJ3E7HBTRN3J58JQX8K",
+        "workspace-backup",
+        "2023-09-01_00:00:00",
+        "hash",
+        true,
+    )
+    .await
+    .expect("cannot record backup");
+    let exported = WorkspaceBackup::record(
+        ctx,
+        "01H9ZQD3NWNS9JT4GZ5S1CVCHW",
+        "iron maiden",
+        "2023-09-02_00:00:00",
+        "other hash",
+        false,
+    )
+    .await
+    .expect("cannot record backup");
+
+    let backups = WorkspaceBackup::list(ctx, None)
+        .await
+        .expect("cannot list backups");
+    assert_eq!(vec![exported.clone(), scheduled.clone()], backups);
+    let backups = WorkspaceBackup::list(ctx, Some(true))
+        .await
+        .expect("cannot list backups");
+    assert_eq!(vec![scheduled.clone()], backups);
+
+    scheduled
+        .mark_pruned(ctx)
+        .await
+        .expect("cannot mark backup pruned");
+    assert!(scheduled.pruned_at.is_some());
+    let backups = WorkspaceBackup::list(ctx, None)
+        .await
+        .expect("cannot list backups");
+    assert_eq!(vec![exported], backups);
+    assert!(WorkspaceBackup::get_by_pk(ctx, scheduled.pk)
+        .await
+        .expect("cannot get backup")
+        .is_none());
+}
+
+#[test]
+async fn backup_claim(ctx: &DalContext) {
+    let interval = Duration::from_secs(60 * 60);
+    assert!(WorkspaceBackup::claim_scheduled(ctx, interval)
+        .await
+        .expect("cannot claim backup"));
+    // Claimed less than an interval ago, by this scheduler or another one
+    assert!(!WorkspaceBackup::claim_scheduled(ctx, interval)
+        .await
+        .expect("cannot claim backup"));
+    assert!(WorkspaceBackup::claim_scheduled(ctx, Duration::ZERO)
+        .await
+        .expect("cannot claim backup"));
+}
+
+#[test]
+async fn restore_backup_into_new_workspace(ctx: &mut DalContext, nw: &WorkspaceSignup) {
+    ctx.update_history_actor(HistoryActor::User(nw.user.pk()));
+
+    let pkg = PkgExporter::new_workspace_exporter(
+        "iron maiden",
+        "steve@iron.maiden",
+        "2023-09-01_00:00:00",
+        "workspace backup",
+    )
+    .export(ctx)
+    .await
+    .expect("cannot export workspace");
+
+    let restored = Workspace::restore_backup(
+        ctx,
+        &pkg,
+        WorkspaceRestoreTarget::New {
+            name: "iron maiden restored".to_owned(),
+        },
+    )
+    .await
+    .expect("cannot restore backup");
+    assert_ne!(nw.workspace.pk(), restored.pk());
+    assert_eq!("iron maiden restored", restored.name().as_str());
+
+    let workspaces = Workspace::list_for_user(ctx, nw.user.pk())
+        .await
+        .expect("cannot list workspaces");
+    assert!(workspaces.contains(&UserWorkspace {
+        workspace: restored,
+        role: WorkspaceRole::Admin,
+    }));
+
+    ctx.update_history_actor(HistoryActor::SystemInit);
+    ctx.update_tenancy(Tenancy::new(WorkspacePk::generate()));
+    let result = Workspace::restore_backup(ctx, &pkg, WorkspaceRestoreTarget::Current).await;
+    assert!(matches!(
+        result,
+        Err(WorkspaceError::BackupOfAnotherWorkspace(_))
+    ));
+}
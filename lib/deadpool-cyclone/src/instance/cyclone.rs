@@ -1,5 +1,12 @@
 //! Cyclone implementations of [`Instance`][`super::Instance`].
 
+use cyclone_core::ExecutionLimits;
+use tokio::process::Command;
+
+pub use container::{
+    ContainerEngine, ContainerInstance, ContainerInstanceError, ContainerInstanceSpec,
+    ContainerInstanceSpecBuilder,
+};
 pub use local_http::{
     LocalHttpInstance, LocalHttpInstanceError, LocalHttpInstanceSpec, LocalHttpInstanceSpecBuilder,
     LocalHttpSocketStrategy,
@@ -9,5 +16,28 @@ pub use local_uds::{
     LocalUdsSocketStrategy,
 };
 
+mod container;
 mod local_http;
 mod local_uds;
+
+/// Adds the arguments passing the execution limits on to a spawned Cyclone server.
+fn execution_limits_args(cmd: &mut Command, limits: &ExecutionLimits) {
+    if let Some(secs) = limits.cpu_time_secs {
+        cmd.arg("--limit-cpu-time").arg(secs.to_string());
+    }
+    if let Some(bytes) = limits.memory_bytes {
+        cmd.arg("--limit-memory").arg(bytes.to_string());
+    }
+    if let Some(open_files) = limits.open_files {
+        cmd.arg("--limit-open-files").arg(open_files.to_string());
+    }
+    if let Some(processes) = limits.processes {
+        cmd.arg("--limit-processes").arg(processes.to_string());
+    }
+    if let Some(cgroup_root) = &limits.cgroup_root {
+        cmd.arg("--cgroup-root").arg(cgroup_root);
+    }
+    if limits.no_network {
+        cmd.arg("--disable-network");
+    }
+}
@@ -0,0 +1,728 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+    process::ExitStatus,
+    result,
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use cyclone_client::{
+    Client, ClientError, Connection, CycloneClient, Execution, LivenessStatus, PingExecution,
+    ReadinessStatus, UdsClient, UnixStream, Watch, WatchError, WatchStarted,
+};
+use cyclone_core::{
+    process::{self, ShutdownError},
+    ActionRunRequest, ActionRunResultSuccess, ExecutionLimits, ReconciliationRequest,
+    ReconciliationResultSuccess, ResolverFunctionRequest, ResolverFunctionResultSuccess,
+    SchemaVariantDefinitionRequest, SchemaVariantDefinitionResultSuccess, ValidationRequest,
+    ValidationResultSuccess,
+};
+use derive_builder::Builder;
+use futures::StreamExt;
+use nix::unistd::{getgid, getuid};
+use serde::{Deserialize, Serialize};
+use tempfile::TempDir;
+use thiserror::Error;
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    process::{Child, Command},
+    sync::oneshot,
+    time,
+};
+use tracing::{debug, trace, warn};
+
+use crate::instance::{Instance, Spec, SpecBuilder};
+
+/// Where the directory holding the socket of Cyclone is mounted in the container.
+const CONTAINER_SOCKET_DIR: &str = "/run/cyclone-socket";
+/// Where Cyclone's secret key file is mounted in the container.
+const CONTAINER_DECRYPTION_KEY_PATH: &str = "/run/cyclone/decryption.key";
+/// Where the directory of the file secret backend is mounted in the container.
+const CONTAINER_FILE_SECRET_BACKEND_DIR: &str = "/run/cyclone-secrets";
+/// The name of the socket of Cyclone, in the socket directory.
+const SOCKET_NAME: &str = "cyclone.sock";
+
+/// Error type for [`ContainerInstance`].
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum ContainerInstanceError {
+    /// Spec builder error.
+    #[error(transparent)]
+    Builder(#[from] ContainerInstanceSpecBuilderError),
+    /// Error when waiting for child process to shutdown.
+    #[error(transparent)]
+    ChildShutdown(#[from] ShutdownError),
+    /// Failed to spawn a child process.
+    #[error("failed to spawn container engine child process")]
+    ChildSpawn(#[source] io::Error),
+    /// Failed to check on the status of a child process.
+    #[error("failed to check on the container engine child process")]
+    ChildStatus(#[source] io::Error),
+    /// Cyclone client error.
+    #[error(transparent)]
+    Client(#[from] ClientError),
+    /// The container exited before its Cyclone server started.
+    #[error("container exited before cyclone started: {0}")]
+    ContainerExited(ExitStatus),
+    /// Instance has exhausted its predefined request count.
+    #[error("no remaining requests, cyclone server is considered unhealthy")]
+    NoRemainingRequests,
+    /// Failed to create the directory holding the socket.
+    #[error("failed to create temp socket directory")]
+    SocketDir(#[source] io::Error),
+    /// Cyclone client `watch` endpoint error.
+    #[error(transparent)]
+    Watch(#[from] WatchError),
+    /// Cyclone client `watch` session ended earlier than expected.
+    #[error("server closed watch session before expected")]
+    WatchClosed,
+    /// Cyclone client initial `watch` session connection with retries timed out.
+    #[error("timeout while retrying to start a client watch session")]
+    WatchInitTimeout,
+    /// Cyclone client `watch` session shut down earlier than expected.
+    #[error("watch session is shut down, cyclone server is considered unhealthy")]
+    WatchShutDown,
+}
+
+type Result<T> = result::Result<T, ContainerInstanceError>;
+
+/// A Cyclone [`Instance`] running in a container of its own, managed as a spawned child process
+/// of the container engine, communicating over a Unix domain socket mounted into the container.
+///
+/// Every instance starts from a clean copy of the image filesystem, which is removed along with
+/// the container when the instance terminates.
+#[derive(Debug)]
+pub struct ContainerInstance {
+    // The `TempDir` type is kept around as an [RAII
+    // guard](https://rust-unofficial.github.io/patterns/patterns/behavioural/RAII.html), that is,
+    // when `ContainerInstance` is dropped, the socket directory is deleted.
+    _socket_dir: TempDir,
+    client: UdsClient,
+    limit_requests: Option<u32>,
+    child: Child,
+    watch_shutdown_tx: oneshot::Sender<()>,
+}
+
+#[async_trait]
+impl Instance for ContainerInstance {
+    type SpecBuilder = ContainerInstanceSpecBuilder;
+    type Error = ContainerInstanceError;
+
+    async fn terminate(mut self) -> result::Result<(), Self::Error> {
+        if !self.watch_shutdown_tx.is_closed() && self.watch_shutdown_tx.send(()).is_err() {
+            debug!("sent watch shutdown but receiver was already closed");
+        }
+        // The container engine forwards the signal to Cyclone, and removes the container once
+        // it exits
+        process::child_shutdown(&mut self.child, Some(process::Signal::SIGTERM), None).await?;
+
+        Ok(())
+    }
+
+    async fn ensure_healthy(&mut self) -> result::Result<(), Self::Error> {
+        self.ensure_healthy_client().await?;
+        match self.client.readiness().await? {
+            ReadinessStatus::Ready => {}
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl CycloneClient<UnixStream> for ContainerInstance {
+    async fn watch(&mut self) -> result::Result<Watch<UnixStream>, ClientError> {
+        self.ensure_healthy_client()
+            .await
+            .map_err(ClientError::unhealthy)?;
+
+        self.client.watch().await
+    }
+
+    async fn liveness(&mut self) -> result::Result<LivenessStatus, ClientError> {
+        self.ensure_healthy_client()
+            .await
+            .map_err(ClientError::unhealthy)?;
+
+        self.client.liveness().await
+    }
+
+    async fn readiness(&mut self) -> result::Result<ReadinessStatus, ClientError> {
+        self.ensure_healthy_client()
+            .await
+            .map_err(ClientError::unhealthy)?;
+
+        self.client.readiness().await
+    }
+
+    async fn execute_ping(&mut self) -> result::Result<PingExecution<UnixStream>, ClientError> {
+        self.ensure_healthy_client()
+            .await
+            .map_err(ClientError::unhealthy)?;
+
+        let result = self.client.execute_ping().await;
+        self.count_request();
+
+        result
+    }
+
+    async fn execute_resolver(
+        &mut self,
+        request: ResolverFunctionRequest,
+    ) -> result::Result<
+        Execution<UnixStream, ResolverFunctionRequest, ResolverFunctionResultSuccess>,
+        ClientError,
+    > {
+        self.ensure_healthy_client()
+            .await
+            .map_err(ClientError::unhealthy)?;
+
+        let result = self.client.execute_resolver(request).await;
+        self.count_request();
+
+        result
+    }
+
+    async fn execute_validation(
+        &mut self,
+        request: ValidationRequest,
+    ) -> result::Result<
+        Execution<UnixStream, ValidationRequest, ValidationResultSuccess>,
+        ClientError,
+    > {
+        self.ensure_healthy_client()
+            .await
+            .map_err(ClientError::unhealthy)?;
+
+        let result = self.client.execute_validation(request).await;
+        self.count_request();
+
+        result
+    }
+
+    async fn execute_action_run(
+        &mut self,
+        request: ActionRunRequest,
+    ) -> result::Result<Execution<UnixStream, ActionRunRequest, ActionRunResultSuccess>, ClientError>
+    {
+        self.ensure_healthy_client()
+            .await
+            .map_err(ClientError::unhealthy)?;
+
+        let result = self.client.execute_action_run(request).await;
+        self.count_request();
+
+        result
+    }
+
+    async fn execute_reconciliation(
+        &mut self,
+        request: ReconciliationRequest,
+    ) -> result::Result<
+        Execution<UnixStream, ReconciliationRequest, ReconciliationResultSuccess>,
+        ClientError,
+    > {
+        self.ensure_healthy_client()
+            .await
+            .map_err(ClientError::unhealthy)?;
+
+        let result = self.client.execute_reconciliation(request).await;
+        self.count_request();
+
+        result
+    }
+
+    async fn execute_schema_variant_definition(
+        &mut self,
+        request: SchemaVariantDefinitionRequest,
+    ) -> result::Result<
+        Execution<UnixStream, SchemaVariantDefinitionRequest, SchemaVariantDefinitionResultSuccess>,
+        ClientError,
+    > {
+        self.ensure_healthy_client()
+            .await
+            .map_err(ClientError::unhealthy)?;
+
+        let result = self.client.execute_schema_variant_definition(request).await;
+        self.count_request();
+
+        result
+    }
+}
+
+impl ContainerInstance {
+    async fn ensure_healthy_client(&mut self) -> Result<()> {
+        if !self.is_watch_shutdown_open() {
+            return Err(ContainerInstanceError::WatchShutDown);
+        }
+        if !self.has_remaining_requests() {
+            return Err(ContainerInstanceError::NoRemainingRequests);
+        }
+
+        Ok(())
+    }
+
+    fn has_remaining_requests(&self) -> bool {
+        match self.limit_requests {
+            Some(remaining) if remaining == 0 => false,
+            Some(_) | None => true,
+        }
+    }
+
+    fn is_watch_shutdown_open(&self) -> bool {
+        !self.watch_shutdown_tx.is_closed()
+    }
+
+    fn count_request(&mut self) {
+        if let Some(limit_requests) = self.limit_requests.as_mut() {
+            *limit_requests = limit_requests.saturating_sub(1);
+        }
+    }
+}
+
+/// The [`Spec`] for [`ContainerInstance`]
+#[derive(Builder, Clone, Debug, Eq, PartialEq)]
+pub struct ContainerInstanceSpec {
+    /// Container engine running the containers.
+    #[builder(default)]
+    engine: ContainerEngine,
+
+    /// Image the containers run, holding the `cyclone` and language server programs. It must be
+    /// available locally, as it is never pulled.
+    #[builder(setter(into))]
+    image: String,
+
+    /// Path to the `cyclone` program in the image.
+    #[builder(setter(into), default = "\"/usr/local/bin/cyclone\".to_string()")]
+    cyclone_cmd_path: String,
+
+    /// Canonical path to Cyclone's secret key file, mounted read-only into the containers.
+    #[builder(setter(into))]
+    cyclone_decryption_key_path: String,
+
+    /// Path to the language server program in the image.
+    #[builder(setter(into), default = "\"/usr/local/bin/lang-js\".to_string()")]
+    lang_server_cmd_path: String,
+
+    /// Parent directory of the socket directories mounted into the containers. Defaults to the
+    /// system temp directory.
+    #[builder(setter(into, strip_option), default)]
+    socket_parent_dir: Option<PathBuf>,
+
+    /// How long a spawned container may take to start its Cyclone server.
+    #[builder(default = "Duration::from_secs(30)")]
+    startup_timeout: Duration,
+
+    /// Sets the watch timeout value for a spawned Cyclone server.
+    #[builder(setter(into, strip_option), default)]
+    watch_timeout: Option<Duration>,
+
+    /// Sets the limit requests strategy for a spawned Cyclone server.
+    #[builder(setter(into), default = "Some(1)")]
+    limit_requests: Option<u32>,
+
+    /// Enables the `ping` execution endpoint for a spawned Cyclone server.
+    #[builder(private, setter(name = "_ping"), default = "false")]
+    ping: bool,
+
+    /// Enables the `resolver` execution endpoint for a spawned Cyclone server.
+    #[builder(private, setter(name = "_resolver"), default = "false")]
+    resolver: bool,
+
+    /// Enables the `action` execution endpoint for a spawned Cyclone server.
+    #[builder(private, setter(name = "_action"), default = "false")]
+    action: bool,
+
+    /// Sets the limits every function execution of a spawned Cyclone server runs under. Memory,
+    /// process and network limits apply to the whole container.
+    #[builder(default)]
+    execution_limits: ExecutionLimits,
+
+    /// Directory of the file secret backend, mounted read-only into the containers.
+    #[builder(setter(into, strip_option), default)]
+    file_secret_backend_dir: Option<PathBuf>,
+}
+
+#[async_trait]
+impl Spec for ContainerInstanceSpec {
+    type Instance = ContainerInstance;
+    type Error = ContainerInstanceError;
+
+    async fn spawn(&self) -> result::Result<Self::Instance, Self::Error> {
+        let socket_dir = self.socket_dir()?;
+        let mut cmd = self.build_command(socket_dir.path());
+
+        debug!("spawning child process; cmd={:?}", &cmd);
+        let mut child = cmd.spawn().map_err(Self::Error::ChildSpawn)?;
+
+        let mut client = Client::uds(socket_dir.path().join(SOCKET_NAME))?;
+
+        // Establish the client watch session. As the container may be starting, we will retry
+        // until the startup timeout before giving up and assuming that the server instance has
+        // failed.
+        let watch = {
+            let deadline = time::Instant::now() + self.startup_timeout;
+            loop {
+                trace!("calling client.watch()");
+                if let Ok(watch) = client.watch().await {
+                    trace!("client watch session established");
+                    break watch;
+                }
+                // Fail fast when the container could not start, e.g. when the image is missing
+                if let Some(status) = child.try_wait().map_err(Self::Error::ChildStatus)? {
+                    return Err(Self::Error::ContainerExited(status));
+                }
+                if time::Instant::now() >= deadline {
+                    return Err(Self::Error::WatchInitTimeout);
+                }
+                time::sleep(Duration::from_millis(64)).await;
+            }
+        };
+
+        let mut watch_progress = watch.start().await?;
+        // Establish that we have received our first watch ping, which should happen immediately
+        // after establishing a watch session
+        watch_progress
+            .next()
+            .await
+            .ok_or(Self::Error::WatchClosed)??;
+
+        let (watch_shutdown_tx, watch_shutdown_rx) = oneshot::channel();
+        // Spawn a task to keep the watch session open until we shut it down
+        tokio::spawn(watch_task(watch_progress, watch_shutdown_rx));
+
+        Ok(Self::Instance {
+            _socket_dir: socket_dir,
+            client,
+            limit_requests: self.limit_requests,
+            child,
+            watch_shutdown_tx,
+        })
+    }
+}
+
+impl ContainerInstanceSpec {
+    fn socket_dir(&self) -> Result<TempDir> {
+        let mut builder = tempfile::Builder::new();
+        builder.prefix("cyclone-");
+        match &self.socket_parent_dir {
+            Some(parent_dir) => builder.tempdir_in(parent_dir),
+            None => builder.tempdir(),
+        }
+        .map_err(ContainerInstanceError::SocketDir)
+    }
+
+    fn build_command(&self, socket_dir: &Path) -> Command {
+        let limits = &self.execution_limits;
+
+        let mut cmd = Command::new(self.engine.program());
+        cmd.arg("run")
+            .arg("--rm")
+            .arg("--pull=never")
+            .arg("--volume")
+            .arg(format!("{}:{CONTAINER_SOCKET_DIR}", socket_dir.display()))
+            .arg("--volume")
+            .arg(format!(
+                "{}:{CONTAINER_DECRYPTION_KEY_PATH}:ro",
+                self.cyclone_decryption_key_path
+            ));
+        if let Some(dir) = &self.file_secret_backend_dir {
+            cmd.arg("--volume").arg(format!(
+                "{}:{CONTAINER_FILE_SECRET_BACKEND_DIR}:ro",
+                dir.display()
+            ));
+        }
+        match self.engine {
+            // Runs as the user running veritech, so that it owns the socket
+            ContainerEngine::Docker => {
+                cmd.arg("--user").arg(format!("{}:{}", getuid(), getgid()));
+            }
+            // Rootless, the root user of the container is the user running veritech, which then
+            // owns the socket
+            ContainerEngine::Podman => {}
+        }
+        if let Some(bytes) = limits.memory_bytes {
+            // A swap limit equal to the memory limit leaves no swap
+            cmd.arg("--memory")
+                .arg(bytes.to_string())
+                .arg("--memory-swap")
+                .arg(bytes.to_string());
+        }
+        if let Some(processes) = limits.processes {
+            cmd.arg("--pids-limit").arg(processes.to_string());
+        }
+        if limits.no_network {
+            cmd.arg("--network=none");
+        }
+
+        cmd.arg("--entrypoint")
+            .arg(&self.cyclone_cmd_path)
+            .arg(&self.image)
+            .arg("--bind-uds")
+            .arg(format!("{CONTAINER_SOCKET_DIR}/{SOCKET_NAME}"))
+            .arg("--decryption-key")
+            .arg(CONTAINER_DECRYPTION_KEY_PATH)
+            .arg("--lang-server")
+            .arg(&self.lang_server_cmd_path)
+            .arg("--enable-watch");
+        if let Some(limit_requests) = self.limit_requests {
+            cmd.arg("--limit-requests").arg(limit_requests.to_string());
+        }
+        if let Some(timeout) = self.watch_timeout {
+            cmd.arg("--watch-timeout")
+                .arg(timeout.as_secs().to_string());
+        }
+        if self.ping {
+            cmd.arg("--enable-ping");
+        }
+        if self.resolver {
+            cmd.arg("--enable-resolver");
+        }
+        if self.action {
+            cmd.arg("--enable-action-run");
+        }
+        if self.file_secret_backend_dir.is_some() {
+            cmd.arg("--file-secret-backend-dir")
+                .arg(CONTAINER_FILE_SECRET_BACKEND_DIR);
+        }
+        // The container enforces the other limits
+        super::execution_limits_args(
+            &mut cmd,
+            &ExecutionLimits {
+                cpu_time_secs: limits.cpu_time_secs,
+                open_files: limits.open_files,
+                ..Default::default()
+            },
+        );
+
+        cmd
+    }
+}
+
+impl SpecBuilder for ContainerInstanceSpecBuilder {
+    type Spec = ContainerInstanceSpec;
+    type Error = ContainerInstanceError;
+
+    fn build(&self) -> result::Result<Self::Spec, Self::Error> {
+        self.build().map_err(Into::into)
+    }
+}
+
+impl ContainerInstanceSpecBuilder {
+    /// Sets the limit requests strategy to `1` for a spawned Cyclone server.
+    pub fn oneshot(&mut self) -> &mut Self {
+        self.limit_requests(Some(1))
+    }
+
+    /// Enables the `ping` execution endpoint for a spawned Cyclone server.
+    pub fn ping(&mut self) -> &mut Self {
+        self._ping(true)
+    }
+
+    /// Enables the `resolver` execution endpoint for a spawned Cyclone server.
+    pub fn resolver(&mut self) -> &mut Self {
+        self._resolver(true)
+    }
+
+    /// Enables the `action` execution endpoint for a spawned Cyclone server.
+    pub fn action(&mut self) -> &mut Self {
+        self._action(true)
+    }
+
+    /// Enables all available endpoints for a spawned Cyclone server
+    pub fn all_endpoints(&mut self) -> &mut Self {
+        self.action().resolver()
+    }
+}
+
+/// Container engine running the containers of [`ContainerInstance`]s.
+#[remain::sorted]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ContainerEngine {
+    /// Docker, which runs rootless when its daemon does.
+    Docker,
+    /// Podman, which runs rootless when run by a user other than root.
+    Podman,
+}
+
+impl Default for ContainerEngine {
+    fn default() -> Self {
+        Self::Podman
+    }
+}
+
+impl ContainerEngine {
+    /// Returns the name of the program of the container engine.
+    #[must_use]
+    pub fn program(&self) -> &'static str {
+        match self {
+            Self::Docker => "docker",
+            Self::Podman => "podman",
+        }
+    }
+}
+
+async fn watch_task<Strm>(
+    mut watch_progress: WatchStarted<Strm>,
+    mut shutdown_rx: oneshot::Receiver<()>,
+) where
+    Strm: AsyncRead + AsyncWrite + Connection + Unpin + Send + Sync + 'static,
+{
+    loop {
+        tokio::select! {
+            // Got a shutdown message
+            _ = Pin::new(&mut shutdown_rx) => {
+                trace!("watch task received shutdown");
+                if let Err(err) = watch_progress.stop().await {
+                    warn!(error = ?err, "failed to cleanly close the watch session");
+                }
+                break;
+            }
+            // Got progress on the watch session
+            result = watch_progress.next() => {
+                match result {
+                    // Got a ping, good news, proceed
+                    Some(Ok(())) => {},
+                    // An error occurred on the stream. We are going to treat this as catastrophic
+                    // and end the watch.
+                    Some(Err(err)) => {
+                        warn!(error = ?err, "error on watch stream");
+                        if let Err(err) = watch_progress.stop().await {
+                            warn!(error = ?err, "failed to cleanly close the watch session");
+                        }
+                        break
+                    }
+                    // Stream is closed
+                    None => {
+                        trace!("watch stream has closed");
+                        break
+                    }
+                }
+            }
+            // All other arms are closed, nothing left to do but return
+            else => {
+                trace!("returning from watch task with all select arms closed");
+                break
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(spec: &ContainerInstanceSpec) -> Vec<String> {
+        spec.build_command(Path::new("/tmp/cyclone-socket"))
+            .as_std()
+            .get_args()
+            .map(|arg| arg.to_string_lossy().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn container_enforces_memory_processes_and_network_limits() {
+        let spec = ContainerInstance::spec()
+            .image("cyclone:test")
+            .cyclone_decryption_key_path("/run/cyclone/decryption.key")
+            .execution_limits(ExecutionLimits {
+                cpu_time_secs: Some(10),
+                memory_bytes: Some(1024),
+                processes: Some(8),
+                no_network: true,
+                ..Default::default()
+            })
+            .build()
+            .expect("failed to build spec");
+        let args = args(&spec);
+        let image_index = args
+            .iter()
+            .position(|arg| arg == "cyclone:test")
+            .expect("image is not run");
+        let (run_args, cyclone_args) = args.split_at(image_index);
+
+        assert!(run_args.contains(&"--pids-limit".to_string()));
+        assert!(run_args.contains(&"--network=none".to_string()));
+        assert!(!run_args.contains(&"--user".to_string()));
+        assert!(cyclone_args.contains(&"--limit-cpu-time".to_string()));
+        assert!(!cyclone_args.contains(&"--limit-memory".to_string()));
+        assert!(!cyclone_args.contains(&"--limit-processes".to_string()));
+        assert!(!cyclone_args.contains(&"--disable-network".to_string()));
+    }
+
+    #[test]
+    fn docker_runs_as_current_user() {
+        let spec = ContainerInstance::spec()
+            .engine(ContainerEngine::Docker)
+            .image("cyclone:test")
+            .cyclone_decryption_key_path("/run/cyclone/decryption.key")
+            .build()
+            .expect("failed to build spec");
+
+        assert!(args(&spec).contains(&format!("{}:{}", getuid(), getgid())));
+    }
+
+    #[test]
+    fn container_mounts_file_secret_backend_dir() {
+        let spec = ContainerInstance::spec()
+            .image("cyclone:test")
+            .cyclone_decryption_key_path("/run/cyclone/decryption.key")
+            .file_secret_backend_dir("/var/lib/cyclone/secrets")
+            .build()
+            .expect("failed to build spec");
+        let args = args(&spec);
+
+        assert!(args.contains(&format!(
+            "/var/lib/cyclone/secrets:{CONTAINER_FILE_SECRET_BACKEND_DIR}:ro"
+        )));
+        let flag = args
+            .iter()
+            .position(|arg| arg == "--file-secret-backend-dir")
+            .expect("file secret backend dir is not passed");
+        assert_eq!(CONTAINER_FILE_SECRET_BACKEND_DIR, args[flag + 1]);
+    }
+
+    // Needs the container engine and the image of the default container config, which is never
+    // pulled. Run with `cargo test -- --ignored`.
+    #[tokio::test]
+    #[ignore = "requires a container engine and a local veritech image"]
+    async fn container_spawns_and_terminates() {
+        let mut config_file = veritech_server::ConfigFile::default_container();
+        veritech_server::detect_and_configure_development(&mut config_file)
+            .expect("failed to determine test configuration");
+        let spec: veritech_server::CycloneSpec = config_file
+            .cyclone
+            .try_into()
+            .expect("failed to build spec");
+        let spec = match spec {
+            veritech_server::CycloneSpec::Container(spec) => spec,
+            _ => unreachable!("the default container config builds a container spec"),
+        };
+
+        let mut instance = spec.spawn().await.expect("failed to spawn instance");
+
+        let status = instance
+            .liveness()
+            .await
+            .expect("failed to run liveness check");
+        assert_eq!(status, LivenessStatus::Ok);
+        let status = instance
+            .readiness()
+            .await
+            .expect("failed to run readiness check");
+        assert_eq!(status, ReadinessStatus::Ready);
+        instance.ensure_healthy().await.expect("failed healthy");
+
+        instance
+            .execute_ping()
+            .await
+            .expect("failed execute ping")
+            .start()
+            .await
+            .expect("failed to start protocol");
+
+        instance.terminate().await.expect("failed to terminate");
+    }
+}
@@ -1,5 +1,6 @@
 use std::{
     net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+    path::PathBuf,
     result,
     time::Duration,
 };
@@ -11,10 +12,10 @@ use cyclone_client::{
 };
 use cyclone_core::{
     process::{self, ShutdownError},
-    ActionRunRequest, ActionRunResultSuccess, CanonicalCommand, ReconciliationRequest,
-    ReconciliationResultSuccess, ResolverFunctionRequest, ResolverFunctionResultSuccess,
-    SchemaVariantDefinitionRequest, SchemaVariantDefinitionResultSuccess, ValidationRequest,
-    ValidationResultSuccess,
+    ActionRunRequest, ActionRunResultSuccess, CanonicalCommand, ExecutionLimits,
+    ReconciliationRequest, ReconciliationResultSuccess, ResolverFunctionRequest,
+    ResolverFunctionResultSuccess, SchemaVariantDefinitionRequest,
+    SchemaVariantDefinitionResultSuccess, ValidationRequest, ValidationResultSuccess,
 };
 use derive_builder::Builder;
 use futures::StreamExt;
@@ -289,6 +290,14 @@ pub struct LocalHttpInstanceSpec {
     /// Enables the `action` execution endpoint for a spawned Cyclone server.
     #[builder(private, setter(name = "_action"), default = "false")]
     action: bool,
+
+    /// Sets the limits every function execution of a spawned Cyclone server runs under.
+    #[builder(default)]
+    execution_limits: ExecutionLimits,
+
+    /// Directory of the file secret backend of a spawned Cyclone server.
+    #[builder(setter(into, strip_option), default)]
+    file_secret_backend_dir: Option<PathBuf>,
 }
 
 #[async_trait]
@@ -370,6 +379,10 @@ impl LocalHttpInstanceSpec {
         if self.action {
             cmd.arg("--enable-action-run");
         }
+        if let Some(dir) = &self.file_secret_backend_dir {
+            cmd.arg("--file-secret-backend-dir").arg(dir);
+        }
+        super::execution_limits_args(&mut cmd, &self.execution_limits);
 
         cmd
     }
@@ -16,16 +16,19 @@
 )]
 
 use async_trait::async_trait;
-use deadpool::managed;
+use deadpool::{managed, Runtime};
 use thiserror::Error;
 
 pub use self::instance::{Instance, Spec};
+pub use self::scaling::{PoolScaler, ScalingPolicy, ScalingPolicyError};
 
 pub use cyclone_client::{
     ClientError, CycloneClient, EncryptionKey, EncryptionKeyError, ExecutionError,
+    ExecutionStarted, UnixStream,
 };
 pub use cyclone_core::{
-    ActionRunRequest, ActionRunResultSuccess, ComponentView, FunctionResult, FunctionResultFailure,
+    ActionRunRequest, ActionRunResultSuccess, CancelExecutionRequest, ComponentView,
+    ExecutionLimits, ExecutionLimitsError, FunctionResult, FunctionResultFailure,
     FunctionResultFailureError, OutputStream, ProgressMessage, ReconciliationRequest,
     ReconciliationResultSuccess, ResolverFunctionRequest, ResolverFunctionResultSuccess,
     ResourceStatus, SchemaVariantDefinitionRequest, SchemaVariantDefinitionResultSuccess,
@@ -34,6 +37,7 @@ pub use cyclone_core::{
 
 /// [`Instance`] implementations.
 pub mod instance;
+mod scaling;
 
 /// Type alias for using [`managed::Pool`] with Cyclone.
 pub type Pool<S> = managed::Pool<Manager<S>>;
@@ -73,12 +77,45 @@ pub enum ManagerError<T> {
 #[derive(Debug)]
 pub struct Manager<S> {
     spec: S,
+    scaling_policy: ScalingPolicy,
 }
 
 impl<S> Manager<S> {
-    /// Creates a new [`Manager`] from the given instance specification.
+    /// Creates a new [`Manager`] from the given instance specification, with the default
+    /// [`ScalingPolicy`].
     pub fn new(spec: S) -> Self {
-        Self { spec }
+        Self {
+            spec,
+            scaling_policy: ScalingPolicy::default(),
+        }
+    }
+
+    /// Sets the [`ScalingPolicy`] of the pools built with [`Self::pool_builder`].
+    #[must_use]
+    pub fn with_scaling_policy(mut self, scaling_policy: ScalingPolicy) -> Self {
+        self.scaling_policy = scaling_policy;
+        self
+    }
+
+    /// Gets a reference to the manager's scaling policy.
+    pub fn scaling_policy(&self) -> &ScalingPolicy {
+        &self.scaling_policy
+    }
+}
+
+impl<B, S, I, E> Manager<S>
+where
+    S: Spec<Error = E, Instance = I> + Send + Sync,
+    I: Instance<SpecBuilder = B, Error = E> + Send,
+{
+    /// Returns a [`PoolBuilder`] for a pool which runs at most as many instances as the
+    /// manager's [`ScalingPolicy`] allows. Run a [`PoolScaler`] on the built pool to keep it
+    /// sized by the policy.
+    pub fn pool_builder(self) -> PoolBuilder<S> {
+        let max_size = self.scaling_policy.max_instances;
+        Pool::builder(self)
+            .max_size(max_size)
+            .runtime(Runtime::Tokio1)
     }
 }
 
@@ -0,0 +1,486 @@
+use std::{
+    fmt, thread,
+    time::{Duration, Instant},
+};
+
+use deadpool::managed::{self, PoolError, Timeouts};
+use futures::future;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::time;
+use tracing::{debug, info, warn};
+
+use crate::{Instance, Manager, Object, Pool, Spec};
+
+/// Error type for an invalid [`ScalingPolicy`].
+#[remain::sorted]
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum ScalingPolicyError {
+    /// The policy keeps more instances than it runs at most.
+    #[error("min instances ({min}) is above max instances ({max})")]
+    MinAboveMax {
+        /// The fewest instances the pool keeps.
+        min: usize,
+        /// The most instances the pool runs.
+        max: usize,
+    },
+    /// The policy keeps more idle instances than it runs at most.
+    #[error("warm instances ({warm}) is above max instances ({max})")]
+    WarmAboveMax {
+        /// The idle instances the pool keeps.
+        warm: usize,
+        /// The most instances the pool runs.
+        max: usize,
+    },
+    /// The policy resizes the pool without pause.
+    #[error("interval must be at least 1 millisecond")]
+    ZeroInterval,
+    /// The policy runs no instances at all.
+    #[error("max instances must be at least 1")]
+    ZeroMaxInstances,
+}
+
+/// How a [`Pool`] of instances grows and shrinks with its load.
+///
+/// On top of the instances busy with requests, and one for each request waiting on an instance,
+/// the pool keeps [`warm_instances`](Self::warm_instances) spawned and idle so that bursts do not
+/// wait on instances starting up. It never keeps fewer than
+/// [`min_instances`](Self::min_instances) instances, nor runs more than
+/// [`max_instances`](Self::max_instances). Idle instances past that are terminated once they went
+/// unused for [`idle_timeout_secs`](Self::idle_timeout_secs).
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ScalingPolicy {
+    /// The fewest instances, busy or idle, the pool keeps.
+    #[serde(default = "default_min_instances")]
+    pub min_instances: usize,
+    /// The most instances, busy or idle, the pool runs.
+    #[serde(default = "default_max_instances")]
+    pub max_instances: usize,
+    /// How many idle instances the pool keeps ready on top of the busy ones.
+    #[serde(default = "default_warm_instances")]
+    pub warm_instances: usize,
+    /// How often, in milliseconds, the pool is resized.
+    #[serde(default = "default_interval_ms")]
+    pub interval_ms: u64,
+    /// How long, in seconds, instances may be surplus before they are terminated.
+    #[serde(default = "default_idle_timeout_secs")]
+    pub idle_timeout_secs: u64,
+}
+
+impl Default for ScalingPolicy {
+    fn default() -> Self {
+        Self {
+            min_instances: default_min_instances(),
+            max_instances: default_max_instances(),
+            warm_instances: default_warm_instances(),
+            interval_ms: default_interval_ms(),
+            idle_timeout_secs: default_idle_timeout_secs(),
+        }
+    }
+}
+
+impl ScalingPolicy {
+    /// Checks that the policy can be followed: it runs at least one instance, at least as many as
+    /// it keeps, and resizes the pool on a non-zero interval.
+    pub fn validate(&self) -> Result<(), ScalingPolicyError> {
+        if self.max_instances == 0 {
+            return Err(ScalingPolicyError::ZeroMaxInstances);
+        }
+        if self.min_instances > self.max_instances {
+            return Err(ScalingPolicyError::MinAboveMax {
+                min: self.min_instances,
+                max: self.max_instances,
+            });
+        }
+        if self.warm_instances > self.max_instances {
+            return Err(ScalingPolicyError::WarmAboveMax {
+                warm: self.warm_instances,
+                max: self.max_instances,
+            });
+        }
+        if self.interval_ms == 0 {
+            return Err(ScalingPolicyError::ZeroInterval);
+        }
+        Ok(())
+    }
+
+    /// Returns how many instances the pool should have, given how many are busy and how many
+    /// requests are waiting for one.
+    #[must_use]
+    pub fn target_instances(&self, busy: usize, waiting: usize) -> usize {
+        busy.saturating_add(waiting)
+            .saturating_add(self.warm_instances)
+            .max(self.min_instances)
+            .min(self.max_instances)
+    }
+
+    /// Returns how often the pool is resized, which is never more often than every millisecond.
+    #[must_use]
+    pub fn interval(&self) -> Duration {
+        Duration::from_millis(self.interval_ms.max(1))
+    }
+
+    /// Returns how long instances may be surplus before they are terminated.
+    #[must_use]
+    pub fn idle_timeout(&self) -> Duration {
+        Duration::from_secs(self.idle_timeout_secs)
+    }
+}
+
+fn default_min_instances() -> usize {
+    1
+}
+
+// The same as the default size of a `deadpool` pool
+fn default_max_instances() -> usize {
+    thread::available_parallelism().map_or(1, usize::from) * 4
+}
+
+fn default_warm_instances() -> usize {
+    1
+}
+
+fn default_interval_ms() -> u64 {
+    500
+}
+
+fn default_idle_timeout_secs() -> u64 {
+    60
+}
+
+/// The counts of instances a [`PoolScaler`] resizes its pool by.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Utilization {
+    size: usize,
+    busy: usize,
+    idle: usize,
+    waiting: usize,
+    target: usize,
+}
+
+/// Keeps a [`Pool`] sized by the [`ScalingPolicy`] of its [`Manager`], and
+/// reports the utilization of the pool whenever it changes.
+pub struct PoolScaler<S>
+where
+    Manager<S>: managed::Manager,
+{
+    pool: Pool<S>,
+    policy: ScalingPolicy,
+    surplus_since: Option<Instant>,
+    last_utilization: Option<Utilization>,
+}
+
+impl<B, S, I, E> PoolScaler<S>
+where
+    S: Spec<Error = E, Instance = I> + Send + Sync,
+    I: Instance<SpecBuilder = B, Error = E> + Send,
+    E: fmt::Debug,
+{
+    /// Creates a new [`PoolScaler`] for the given pool.
+    pub fn new(pool: Pool<S>) -> Self {
+        let policy = *pool.manager().scaling_policy();
+        Self {
+            pool,
+            policy,
+            surplus_since: None,
+            last_utilization: None,
+        }
+    }
+
+    /// Resizes the pool on every interval of the policy, until dropped.
+    pub async fn run(mut self) {
+        let mut interval = time::interval(self.policy.interval());
+        interval.set_missed_tick_behavior(time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            self.scale().await;
+        }
+    }
+
+    async fn scale(&mut self) {
+        let status = self.pool.status();
+        // A negative count of available instances is the count of requests waiting for one
+        let idle = usize::try_from(status.available).unwrap_or(0);
+        let waiting = usize::try_from(status.available.saturating_neg()).unwrap_or(0);
+        let busy = status.size.saturating_sub(idle);
+        let target = self.policy.target_instances(busy, waiting);
+        self.report(Utilization {
+            size: status.size,
+            busy,
+            idle,
+            waiting,
+            target,
+        });
+
+        let wanted_idle = target.saturating_sub(busy);
+        self.warm(idle, wanted_idle).await;
+
+        let idle = usize::try_from(self.pool.status().available).unwrap_or(0);
+        if idle <= wanted_idle {
+            self.surplus_since = None;
+            return;
+        }
+        let surplus_since = *self.surplus_since.get_or_insert_with(Instant::now);
+        if surplus_since.elapsed() >= self.policy.idle_timeout() {
+            self.retire(idle - wanted_idle).await;
+            self.surplus_since = None;
+        }
+    }
+
+    /// Logs the utilization of the pool when it changed since the last resize, so that a steady
+    /// pool does not flood the logs.
+    fn report(&mut self, utilization: Utilization) {
+        if self.last_utilization == Some(utilization) {
+            return;
+        }
+        self.last_utilization = Some(utilization);
+        info!(
+            pool.size = utilization.size,
+            pool.busy = utilization.busy,
+            pool.idle = utilization.idle,
+            pool.waiting = utilization.waiting,
+            pool.max_size = self.policy.max_instances,
+            pool.target_size = utilization.target,
+            pool.utilization = utilization.busy as f64 / self.policy.max_instances.max(1) as f64,
+            "cyclone pool utilization",
+        );
+    }
+
+    /// Spawns the instances missing for `wanted` instances to be idle, given `idle` are. Nothing
+    /// is checked out when none are missing. Otherwise, the idle instances are held along with
+    /// the new ones, as the pool hands out idle instances before spawning any. They are idle
+    /// again once returned.
+    async fn warm(&self, idle: usize, wanted: usize) {
+        let missing = wanted.saturating_sub(idle);
+        if missing == 0 {
+            return;
+        }
+        debug!(missing, "warming cyclone instances");
+
+        let size_before = self.pool.status().size;
+        let instances = future::join_all((0..wanted).map(|_| self.checkout())).await;
+        for result in &instances {
+            match result {
+                // Busy instances took the remaining room in the pool
+                Ok(_) | Err(PoolError::Timeout(_)) => {}
+                Err(err) => warn!(error = ?err, "failed to warm cyclone instance"),
+            }
+        }
+        drop(instances);
+
+        let size = self.pool.status().size;
+        if size > size_before {
+            info!(
+                spawned = size - size_before,
+                pool.size = size,
+                "warmed cyclone instances"
+            );
+        }
+    }
+
+    /// Terminates `count` idle instances.
+    async fn retire(&self, count: usize) {
+        let instances = future::join_all((0..count).map(|_| self.checkout())).await;
+        let mut retired = 0;
+        for instance in instances.into_iter().flatten() {
+            if let Err(err) = managed::Object::take(instance).terminate().await {
+                warn!(error = ?err, "failed to terminate idle cyclone instance");
+            }
+            retired += 1;
+        }
+        if retired > 0 {
+            info!(
+                retired,
+                pool.size = self.pool.status().size,
+                "retired idle cyclone instances"
+            );
+        }
+    }
+
+    /// Checks an instance out of the pool without waiting for room in it.
+    async fn checkout(&self) -> Result<Object<S>, PoolError<E>> {
+        let timeouts = Timeouts {
+            wait: Some(Duration::ZERO),
+            create: None,
+            recycle: None,
+        };
+        self.pool.timeout_get(&timeouts).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        convert::Infallible,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
+
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::instance::SpecBuilder;
+
+    #[derive(Clone, Debug, Default)]
+    struct Counts {
+        spawned: Arc<AtomicUsize>,
+        recycled: Arc<AtomicUsize>,
+        terminated: Arc<AtomicUsize>,
+    }
+
+    impl Counts {
+        fn get(&self) -> (usize, usize, usize) {
+            (
+                self.spawned.load(Ordering::SeqCst),
+                self.recycled.load(Ordering::SeqCst),
+                self.terminated.load(Ordering::SeqCst),
+            )
+        }
+    }
+
+    #[derive(Default)]
+    struct TestSpecBuilder;
+
+    impl SpecBuilder for TestSpecBuilder {
+        type Spec = TestSpec;
+        type Error = Infallible;
+
+        fn build(&self) -> Result<Self::Spec, Self::Error> {
+            Ok(TestSpec::default())
+        }
+    }
+
+    #[derive(Default)]
+    struct TestSpec {
+        counts: Counts,
+    }
+
+    #[async_trait]
+    impl Spec for TestSpec {
+        type Instance = TestInstance;
+        type Error = Infallible;
+
+        async fn spawn(&self) -> Result<Self::Instance, Self::Error> {
+            self.counts.spawned.fetch_add(1, Ordering::SeqCst);
+            Ok(TestInstance {
+                counts: self.counts.clone(),
+            })
+        }
+    }
+
+    #[derive(Debug)]
+    struct TestInstance {
+        counts: Counts,
+    }
+
+    #[async_trait]
+    impl Instance for TestInstance {
+        type SpecBuilder = TestSpecBuilder;
+        type Error = Infallible;
+
+        async fn ensure_healthy(&mut self) -> Result<(), Self::Error> {
+            self.counts.recycled.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+
+        async fn terminate(mut self) -> Result<(), Self::Error> {
+            self.counts.terminated.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    fn policy(min_instances: usize, max_instances: usize, warm_instances: usize) -> ScalingPolicy {
+        ScalingPolicy {
+            min_instances,
+            max_instances,
+            warm_instances,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn target_instances_keeps_warm_instances() {
+        let policy = policy(1, 8, 2);
+
+        assert_eq!(2, policy.target_instances(0, 0));
+        assert_eq!(5, policy.target_instances(3, 0));
+        assert_eq!(7, policy.target_instances(3, 2));
+    }
+
+    #[test]
+    fn validate() {
+        assert_eq!(Ok(()), ScalingPolicy::default().validate());
+        assert_eq!(Ok(()), policy(2, 2, 2).validate());
+        assert_eq!(
+            Err(ScalingPolicyError::ZeroMaxInstances),
+            policy(0, 0, 0).validate()
+        );
+        assert_eq!(
+            Err(ScalingPolicyError::MinAboveMax { min: 4, max: 2 }),
+            policy(4, 2, 1).validate()
+        );
+        assert_eq!(
+            Err(ScalingPolicyError::WarmAboveMax { warm: 3, max: 2 }),
+            policy(1, 2, 3).validate()
+        );
+        let zero_interval = ScalingPolicy {
+            interval_ms: 0,
+            ..Default::default()
+        };
+        assert_eq!(
+            Err(ScalingPolicyError::ZeroInterval),
+            zero_interval.validate()
+        );
+        // A zero interval never reaches `tokio::time::interval`, which panics on it
+        assert_eq!(Duration::from_millis(1), zero_interval.interval());
+    }
+
+    #[test]
+    fn target_instances_is_bounded() {
+        let policy = policy(4, 8, 1);
+
+        assert_eq!(4, policy.target_instances(0, 0));
+        assert_eq!(8, policy.target_instances(6, 0));
+        assert_eq!(8, policy.target_instances(8, 12));
+    }
+
+    #[tokio::test]
+    async fn pool_scaler_spawns_missing_and_retires_surplus_instances() {
+        let spec = TestInstance::spec().build().expect("failed to build spec");
+        let counts = spec.counts.clone();
+        let pool = Manager::new(spec)
+            .with_scaling_policy(ScalingPolicy {
+                idle_timeout_secs: 0,
+                ..policy(2, 4, 1)
+            })
+            .pool_builder()
+            .build()
+            .expect("failed to build pool");
+        let mut scaler = PoolScaler::new(pool.clone());
+
+        // The min instances are spawned
+        scaler.scale().await;
+        assert_eq!(2, pool.status().size);
+        assert_eq!((2, 0, 0), counts.get());
+
+        // Nothing is missing, so the idle instances are left alone
+        scaler.scale().await;
+        assert_eq!(2, pool.status().size);
+        assert_eq!((2, 0, 0), counts.get());
+
+        // Both instances get busy, and only the warm instance is spawned
+        let busy = future::join_all((0..2).map(|_| pool.get())).await;
+        assert_eq!((2, 2, 0), counts.get());
+        scaler.scale().await;
+        assert_eq!(3, pool.status().size);
+        assert_eq!((3, 2, 0), counts.get());
+
+        // Once idle again, the instance past the min instances is retired
+        drop(busy);
+        scaler.scale().await;
+        assert_eq!(2, pool.status().size);
+        assert_eq!((3, 3, 1), counts.get());
+    }
+}
@@ -13,7 +13,7 @@ use crate::app_state::AppState;
 use crate::routes::upsert_module_route::UpsertModuleError;
 use crate::whoami::{is_systeminit_auth_token, WhoamiError};
 use crate::{
-    extract::{Authorization, DbConnection, ExtractedS3Bucket},
+    extract::{Authorization, DbConnection, ExtractedS3Bucket, UserPk},
     models::si_module::{self, ModuleId},
 };
 
@@ -47,7 +47,7 @@ impl IntoResponse for RejectModuleError {
 pub async fn reject_module(
     Path(module_id): Path<ModuleId>,
     Authorization {
-        user_claim: _user_claim,
+        user_claim,
         auth_token,
     }: Authorization,
     ExtractedS3Bucket(_s3_bucket): ExtractedS3Bucket,
@@ -55,7 +55,14 @@ pub async fn reject_module(
     State(state): State<AppState>,
     mut multipart: Multipart,
 ) -> Result<Json<Option<ModuleDetailsResponse>>, RejectModuleError> {
-    if !is_systeminit_auth_token(&auth_token, state.token_emails()).await? {
+    let module = match si_module::Entity::find_by_id(module_id).one(&txn).await? {
+        Some(module) => module,
+        _ => return Err(RejectModuleError::NotFound(module_id)),
+    };
+
+    if !is_own_workspace_backup(&module, user_claim.user_pk)
+        && !is_systeminit_auth_token(&auth_token, state.token_emails()).await?
+    {
         return Ok(Json(None));
     }
 
@@ -68,11 +75,6 @@ pub async fn reject_module(
     let data = dbg!(field.text().await.unwrap());
     info!("Got part data");
 
-    let module = match si_module::Entity::find_by_id(module_id).one(&txn).await? {
-        Some(module) => module,
-        _ => return Err(RejectModuleError::NotFound(module_id)),
-    };
-
     let active_module = si_module::ActiveModel {
         id: Set(module.id),
         name: Set(module.name),
@@ -99,3 +101,49 @@ pub async fn reject_module(
 
     Ok(Json(Some(updated_module.try_into()?)))
 }
+
+/// Owners may reject their own workspace backups, which is how backup retention prunes them.
+/// Every other module can only be rejected by SI.
+fn is_own_workspace_backup(module: &si_module::Model, user_pk: UserPk) -> bool {
+    module.kind == si_module::ModuleKind::WorkspaceBackup
+        && module.owner_user_id == user_pk.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use ulid::Ulid;
+
+    use super::*;
+
+    fn module(kind: si_module::ModuleKind, owner: UserPk) -> si_module::Model {
+        let now = DateTime::<FixedOffset>::from_utc(Utc::now().naive_utc(), Utc.fix());
+        si_module::Model {
+            id: ModuleId(Ulid::new()),
+            name: "workspace-backup".to_owned(),
+            description: None,
+            owner_user_id: owner.to_string(),
+            owner_display_name: None,
+            metadata: serde_json::json!({}),
+            latest_hash: "hash".to_owned(),
+            latest_hash_created_at: now,
+            created_at: now,
+            rejected_at: None,
+            rejected_by_display_name: None,
+            kind,
+            is_builtin_at: None,
+            is_builtin_at_by_display_name: None,
+        }
+    }
+
+    #[test]
+    fn owners_may_only_reject_their_workspace_backups() {
+        let (owner, someone_else) = (Ulid::new(), Ulid::new());
+
+        let backup = module(si_module::ModuleKind::WorkspaceBackup, owner);
+        assert!(is_own_workspace_backup(&backup, owner));
+        assert!(!is_own_workspace_backup(&backup, someone_else));
+
+        let module = module(si_module::ModuleKind::Module, owner);
+        assert!(!is_own_workspace_backup(&module, owner));
+    }
+}
@@ -5,6 +5,7 @@ use std::{
 };
 
 use buck2_resources::Buck2Resources;
+use dal::job::definition::FixesJob;
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
 use si_data_nats::NatsConfig;
@@ -15,7 +16,10 @@ use si_std::SensitiveString;
 use telemetry::prelude::*;
 use thiserror::Error;
 
-pub use dal::{CycloneKeyPair, MigrationMode};
+pub use dal::{
+    tasks::{ActionSchedulerConfig, BackupSchedulerConfig, ResourceSchedulerConfig},
+    CycloneKeyPair, MigrationMode,
+};
 pub use si_settings::{StandardConfig, StandardConfigFile};
 
 const DEFAULT_SIGNUP_SECRET: &str = "cool-steam";
@@ -66,6 +70,18 @@ pub struct Config {
     #[builder(default = "MigrationMode::default()")]
     migration_mode: MigrationMode,
 
+    #[builder(default = "ResourceSchedulerConfig::default()")]
+    resource_refresh: ResourceSchedulerConfig,
+
+    #[builder(default = "ActionSchedulerConfig::default()")]
+    action_scheduler: ActionSchedulerConfig,
+
+    #[builder(default = "BackupSchedulerConfig::default()")]
+    backup_scheduler: BackupSchedulerConfig,
+
+    #[builder(default = "FixesJob::DEFAULT_MAX_CONCURRENCY")]
+    max_concurrent_fixes: usize,
+
     jwt_signing_public_key_path: CanonicalFile,
 
     cyclone_encryption_key_path: CanonicalFile,
@@ -136,6 +152,30 @@ impl Config {
         &self.posthog
     }
 
+    /// Gets a reference to the config's resource refresh scheduler config.
+    #[must_use]
+    pub fn resource_refresh(&self) -> &ResourceSchedulerConfig {
+        &self.resource_refresh
+    }
+
+    /// Gets a reference to the config's action scheduler config.
+    #[must_use]
+    pub fn action_scheduler(&self) -> &ActionSchedulerConfig {
+        &self.action_scheduler
+    }
+
+    /// Gets a reference to the config's workspace backup scheduler config.
+    #[must_use]
+    pub fn backup_scheduler(&self) -> &BackupSchedulerConfig {
+        &self.backup_scheduler
+    }
+
+    /// Gets the number of fixes in a batch that are allowed to run at the same time.
+    #[must_use]
+    pub fn max_concurrent_fixes(&self) -> usize {
+        self.max_concurrent_fixes
+    }
+
     /// URL to the module index service
     #[must_use]
     pub fn module_index_url(&self) -> &str {
@@ -173,6 +213,14 @@ pub struct ConfigFile {
     pub posthog: PosthogConfig,
     #[serde(default)]
     pub module_index_url: String,
+    #[serde(default)]
+    pub resource_refresh: ResourceSchedulerConfig,
+    #[serde(default)]
+    pub action_scheduler: ActionSchedulerConfig,
+    #[serde(default)]
+    pub backup_scheduler: BackupSchedulerConfig,
+    #[serde(default = "default_max_concurrent_fixes")]
+    pub max_concurrent_fixes: usize,
 }
 
 impl Default for ConfigFile {
@@ -187,6 +235,10 @@ impl Default for ConfigFile {
             pkgs_path: default_pkgs_path(),
             posthog: Default::default(),
             module_index_url: default_module_index_url(),
+            resource_refresh: Default::default(),
+            action_scheduler: Default::default(),
+            backup_scheduler: Default::default(),
+            max_concurrent_fixes: default_max_concurrent_fixes(),
         }
     }
 }
@@ -211,6 +263,10 @@ impl TryFrom<ConfigFile> for Config {
         config.pkgs_path(value.pkgs_path.try_into()?);
         config.posthog(value.posthog);
         config.module_index_url(value.module_index_url);
+        config.resource_refresh(value.resource_refresh);
+        config.action_scheduler(value.action_scheduler);
+        config.backup_scheduler(value.backup_scheduler);
+        config.max_concurrent_fixes(value.max_concurrent_fixes);
         config.build().map_err(Into::into)
     }
 }
@@ -256,6 +312,10 @@ fn default_signup_secret() -> SensitiveString {
     DEFAULT_SIGNUP_SECRET.into()
 }
 
+fn default_max_concurrent_fixes() -> usize {
+    FixesJob::DEFAULT_MAX_CONCURRENCY
+}
+
 fn default_pkgs_path() -> String {
     "/run/sdf/pkgs/".to_string()
 }
@@ -3,17 +3,23 @@ use std::{collections::HashMap, fmt};
 use axum::{
     async_trait,
     extract::{FromRequestParts, Query},
-    http::request::Parts,
+    http::{request::Parts, Method},
     Json,
 };
 use dal::{
+    api_token::API_TOKEN_PREFIX,
     context::{self, DalContextBuilder},
-    User, UserClaim,
+    ApiToken, ApiTokenPk, DalContext, User, UserClaim, UserError, WorkspacePermission, WorkspacePk,
+    WorkspaceRole,
 };
 use hyper::StatusCode;
 
 use super::state::AppState;
 
+/// The header a request picks its active workspace with, among the ones its user belongs to.
+/// Websocket requests use the `workspacePk` query parameter instead.
+pub const WORKSPACE_PK_HEADER: &str = "WorkspacePk";
+
 pub struct AccessBuilder(pub context::AccessBuilder);
 
 #[async_trait]
@@ -24,13 +30,27 @@ impl FromRequestParts<AppState> for AccessBuilder {
         parts: &mut Parts,
         state: &AppState,
     ) -> Result<Self, Self::Rejection> {
-        let Authorization(claim) = Authorization::from_request_parts(parts, state).await?;
+        let AuthorizedClaim {
+            claim,
+            role,
+            max_role,
+            api_token_pk,
+        } = authorize_bearer_token(parts, state).await?;
+        // Viewers can look at everything but change nothing.
+        if !matches!(parts.method, Method::GET | Method::HEAD | Method::OPTIONS) {
+            ensure_role_allows(&claim, role, max_role, WorkspacePermission::EditChangeSets)?;
+        }
         let Tenancy(tenancy) = tenancy_from_claim(&claim).await?;
 
-        Ok(Self(context::AccessBuilder::new(
-            tenancy,
-            dal::HistoryActor::from(claim.user_pk),
-        )))
+        let mut access_builder =
+            context::AccessBuilder::new(tenancy, dal::HistoryActor::from(claim.user_pk));
+        if let Some(max_role) = max_role {
+            access_builder = access_builder.with_max_role(max_role);
+        }
+        if let Some(api_token_pk) = api_token_pk {
+            access_builder = access_builder.with_api_token_pk(api_token_pk);
+        }
+        Ok(Self(access_builder))
     }
 }
 
@@ -121,26 +141,7 @@ impl FromRequestParts<AppState> for Authorization {
         parts: &mut Parts,
         state: &AppState,
     ) -> Result<Self, Self::Rejection> {
-        let HandlerContext(builder) = HandlerContext::from_request_parts(parts, state).await?;
-        let mut ctx = builder.build_default().await.map_err(internal_error)?;
-        let jwt_public_signing_key = state.jwt_public_signing_key().clone();
-
-        let headers = &parts.headers;
-        let authorization_header_value = headers
-            .get("Authorization")
-            .ok_or_else(unauthorized_error)?;
-        let authorization = authorization_header_value
-            .to_str()
-            .map_err(internal_error)?;
-        let claim = UserClaim::from_bearer_token(jwt_public_signing_key, authorization)
-            .await
-            .map_err(|_| unauthorized_error())?;
-        ctx.update_tenancy(dal::Tenancy::new(claim.workspace_pk));
-
-        User::authorize(&ctx, &claim.user_pk)
-            .await
-            .map_err(|_| unauthorized_error())?;
-
+        let AuthorizedClaim { claim, .. } = authorize_bearer_token(parts, state).await?;
         Ok(Self(claim))
     }
 }
@@ -156,22 +157,25 @@ impl FromRequestParts<AppState> for WsAuthorization {
         state: &AppState,
     ) -> Result<Self, Self::Rejection> {
         let HandlerContext(builder) = HandlerContext::from_request_parts(parts, state).await?;
-        let mut ctx = builder.build_default().await.map_err(internal_error)?;
-        let jwt_public_signing_key = state.jwt_public_signing_key().clone();
+        let ctx = builder.build_default().await.map_err(internal_error)?;
 
         let query: Query<HashMap<String, String>> = Query::from_request_parts(parts, state)
             .await
             .map_err(|_| unauthorized_error())?;
         let authorization = query.get("token").ok_or_else(unauthorized_error)?;
-
-        let claim = UserClaim::from_bearer_token(jwt_public_signing_key, authorization)
-            .await
-            .map_err(|_| unauthorized_error())?;
-        ctx.update_tenancy(dal::Tenancy::new(claim.workspace_pk));
-
-        User::authorize(&ctx, &claim.user_pk)
-            .await
-            .map_err(|_| unauthorized_error())?;
+        let active_workspace_pk = query
+            .get("workspacePk")
+            .map(String::as_str)
+            .map(parse_workspace_pk)
+            .transpose()?;
+
+        let AuthorizedClaim {
+            claim,
+            role,
+            max_role,
+            ..
+        } = authenticate(&ctx, state, authorization, active_workspace_pk).await?;
+        ensure_role_allows(&claim, role, max_role, WorkspacePermission::Read)?;
 
         Ok(Self(claim))
     }
@@ -192,6 +196,166 @@ impl FromRequestParts<AppState> for Tenancy {
     }
 }
 
+/// The [`UserClaim`] of a request, the [`WorkspaceRole`] of its user and the role its token is
+/// restricted to, if any.
+struct AuthorizedClaim {
+    claim: UserClaim,
+    role: WorkspaceRole,
+    max_role: Option<WorkspaceRole>,
+    api_token_pk: Option<ApiTokenPk>,
+}
+
+/// Validates the bearer token of the request and looks up the [`WorkspaceRole`] of its user in
+/// the active workspace. Users that are not a member of the workspace are rejected.
+async fn authorize_bearer_token(
+    parts: &mut Parts,
+    state: &AppState,
+) -> Result<AuthorizedClaim, (StatusCode, Json<serde_json::Value>)> {
+    let HandlerContext(builder) = HandlerContext::from_request_parts(parts, state).await?;
+    let ctx = builder.build_default().await.map_err(internal_error)?;
+
+    let headers = &parts.headers;
+    let authorization_header_value = headers
+        .get("Authorization")
+        .ok_or_else(unauthorized_error)?;
+    let authorization = authorization_header_value
+        .to_str()
+        .map_err(internal_error)?;
+    let active_workspace_pk = headers
+        .get(WORKSPACE_PK_HEADER)
+        .map(|workspace_pk| {
+            workspace_pk
+                .to_str()
+                .map_err(bad_request_error)
+                .and_then(parse_workspace_pk)
+        })
+        .transpose()?;
+
+    let authorized = authenticate(&ctx, state, authorization, active_workspace_pk).await?;
+    ensure_role_allows(
+        &authorized.claim,
+        authorized.role,
+        authorized.max_role,
+        WorkspacePermission::Read,
+    )?;
+
+    Ok(authorized)
+}
+
+/// Authenticates a bearer token, which is either a session JWT or an [`ApiToken`], and looks up
+/// the [`WorkspaceRole`] of its user in the active workspace. The use of an [`ApiToken`] is
+/// recorded.
+///
+/// The active workspace is the one of the token, unless the request picks another workspace the
+/// user belongs to, which lets users switch workspaces without authenticating again. API tokens
+/// cannot switch: they only grant access to the workspace they were created in.
+async fn authenticate(
+    ctx: &DalContext,
+    state: &AppState,
+    bearer_token: &str,
+    active_workspace_pk: Option<WorkspacePk>,
+) -> Result<AuthorizedClaim, (StatusCode, Json<serde_json::Value>)> {
+    let api_token = bearer_token
+        .strip_prefix("Bearer ")
+        .filter(|token| token.starts_with(API_TOKEN_PREFIX));
+    let (mut claim, max_role, api_token_pk) = match api_token {
+        Some(api_token) => {
+            let api_token = ApiToken::authenticate(ctx, api_token)
+                .await
+                .map_err(|err| {
+                    if err.is_unauthorized() {
+                        unauthorized_error()
+                    } else {
+                        internal_error(err)
+                    }
+                })?;
+            ctx.commit().await.map_err(internal_error)?;
+            let workspace_pk = api_token
+                .tenancy
+                .workspace_pk()
+                .ok_or_else(unauthorized_error)?;
+
+            if active_workspace_pk.map_or(false, |active| active != workspace_pk) {
+                return Err(forbidden_error(
+                    "api tokens only grant access to the workspace they were created in",
+                ));
+            }
+
+            (
+                UserClaim::new(api_token.user_pk, workspace_pk),
+                api_token.max_role,
+                Some(api_token.pk),
+            )
+        }
+        None => {
+            let claim =
+                UserClaim::from_bearer_token(state.jwt_public_signing_key().clone(), bearer_token)
+                    .await
+                    .map_err(|_| unauthorized_error())?;
+            (claim, None, None)
+        }
+    };
+
+    let role = match active_workspace_pk {
+        Some(workspace_pk) if workspace_pk != claim.workspace_pk => claim
+            .switch_workspace(ctx, workspace_pk)
+            .await
+            .map_err(|err| {
+                if err.is_permission_denied() {
+                    forbidden_error(err)
+                } else {
+                    internal_error(err)
+                }
+            })?,
+        _ => workspace_role(ctx, &claim).await?,
+    };
+
+    Ok(AuthorizedClaim {
+        claim,
+        role,
+        max_role,
+        api_token_pk,
+    })
+}
+
+fn parse_workspace_pk(
+    workspace_pk: &str,
+) -> Result<WorkspacePk, (StatusCode, Json<serde_json::Value>)> {
+    workspace_pk.parse().map_err(bad_request_error)
+}
+
+async fn workspace_role(
+    ctx: &dal::DalContext,
+    claim: &UserClaim,
+) -> Result<WorkspaceRole, (StatusCode, Json<serde_json::Value>)> {
+    User::role_in_workspace(ctx, claim.user_pk, claim.workspace_pk)
+        .await
+        .map_err(internal_error)?
+        .ok_or_else(|| forbidden_error(UserError::NotAMember(claim.user_pk, claim.workspace_pk)))
+}
+
+/// Fails unless both the role of the user and the role their token is restricted to, if any,
+/// grant the permission.
+fn ensure_role_allows(
+    claim: &UserClaim,
+    role: WorkspaceRole,
+    max_role: Option<WorkspaceRole>,
+    permission: WorkspacePermission,
+) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    match std::iter::once(role)
+        .chain(max_role)
+        .find(|role| !role.allows(permission))
+    {
+        Some(role) => Err(forbidden_error(UserError::PermissionDenied(
+            claim.user_pk,
+            role,
+            claim.workspace_pk,
+            permission,
+        ))),
+        None => Ok(()),
+    }
+}
+
 async fn tenancy_from_claim(
     claim: &UserClaim,
 ) -> Result<Tenancy, (StatusCode, Json<serde_json::Value>)> {
@@ -212,6 +376,34 @@ fn internal_error(message: impl fmt::Display) -> (StatusCode, Json<serde_json::V
     )
 }
 
+fn bad_request_error(message: impl fmt::Display) -> (StatusCode, Json<serde_json::Value>) {
+    let status_code = StatusCode::BAD_REQUEST;
+    (
+        status_code,
+        Json(serde_json::json!({
+            "error": {
+                "message": message.to_string(),
+                "statusCode": status_code.as_u16(),
+                "code": 42,
+            },
+        })),
+    )
+}
+
+fn forbidden_error(message: impl fmt::Display) -> (StatusCode, Json<serde_json::Value>) {
+    let status_code = StatusCode::FORBIDDEN;
+    (
+        status_code,
+        Json(serde_json::json!({
+            "error": {
+                "message": message.to_string(),
+                "statusCode": status_code.as_u16(),
+                "code": 42,
+            },
+        })),
+    )
+}
+
 fn unauthorized_error() -> (StatusCode, Json<serde_json::Value>) {
     let status_code = StatusCode::UNAUTHORIZED;
     (
@@ -11,8 +11,13 @@ use dal::{
     Workspace, WorkspaceError,
 };
 use dal::{
-    cyclone_key_pair::CycloneKeyPairError, job::processor::JobQueueProcessor,
-    tasks::ResourceScheduler, ServicesContext,
+    cyclone_key_pair::CycloneKeyPairError,
+    job::{definition::FixesJob, processor::JobQueueProcessor},
+    tasks::{
+        ActionScheduler, ActionSchedulerConfig, BackupScheduler, BackupSchedulerConfig,
+        ResourceScheduler, ResourceSchedulerConfig,
+    },
+    ServicesContext,
 };
 use hyper::server::{accept::Accept, conn::AddrIncoming};
 use module_index_client::types::BuiltinsDetailsResponse;
@@ -140,6 +145,7 @@ impl Server<(), ()> {
                     jwt_public_signing_key,
                     config.signup_secret().clone(),
                     posthog_client,
+                    config.max_concurrent_fixes(),
                 )?;
 
                 info!("binding to HTTP socket; socket_addr={}", &socket_addr);
@@ -192,6 +198,7 @@ impl Server<(), ()> {
                     jwt_public_signing_key,
                     config.signup_secret().clone(),
                     posthog_client,
+                    config.max_concurrent_fixes(),
                 )?;
 
                 info!("binding to Unix domain socket; path={}", path.display());
@@ -289,6 +296,7 @@ impl Server<(), ()> {
         job_processor: Box<dyn JobQueueProcessor + Send + Sync>,
         veritech: VeritechClient,
         encryption_key: EncryptionKey,
+        config: ResourceSchedulerConfig,
         shutdown_broadcast_rx: broadcast::Receiver<()>,
     ) {
         let services_context = ServicesContext::new(
@@ -300,7 +308,54 @@ impl Server<(), ()> {
             None,
             None,
         );
-        ResourceScheduler::new(services_context).start(shutdown_broadcast_rx);
+        ResourceScheduler::new(services_context, config).start(shutdown_broadcast_rx);
+    }
+
+    /// Start the scheduler that runs the [`ActionSchedules`](dal::ActionSchedule) that are due
+    pub async fn start_action_scheduler(
+        pg: PgPool,
+        nats: NatsClient,
+        job_processor: Box<dyn JobQueueProcessor + Send + Sync>,
+        veritech: VeritechClient,
+        encryption_key: EncryptionKey,
+        config: ActionSchedulerConfig,
+        shutdown_broadcast_rx: broadcast::Receiver<()>,
+    ) {
+        let services_context = ServicesContext::new(
+            pg,
+            nats,
+            job_processor,
+            veritech,
+            Arc::new(encryption_key),
+            None,
+            None,
+        );
+        ActionScheduler::new(services_context, config).start(shutdown_broadcast_rx);
+    }
+
+    /// Start the scheduler that backs up every [`Workspace`] to the module index
+    #[allow(clippy::too_many_arguments)]
+    pub async fn start_backup_scheduler(
+        pg: PgPool,
+        nats: NatsClient,
+        job_processor: Box<dyn JobQueueProcessor + Send + Sync>,
+        veritech: VeritechClient,
+        encryption_key: EncryptionKey,
+        module_index_url: String,
+        config: BackupSchedulerConfig,
+        shutdown_broadcast_rx: broadcast::Receiver<()>,
+    ) {
+        let services_context = ServicesContext::new(
+            pg,
+            nats,
+            job_processor,
+            veritech,
+            Arc::new(encryption_key),
+            None,
+            None,
+        );
+        BackupScheduler::new(services_context, module_index_url, config)
+            .start(shutdown_broadcast_rx);
     }
 
     pub async fn start_status_updater(
@@ -464,7 +519,10 @@ async fn install_builtins(
                         schemas: None,
                         skip_import_funcs: None,
                         no_record: false,
+                        permission: None,
                         is_builtin: true,
+                        workspace_pk: None,
+                        selection: None,
                     }),
                 )
                 .await
@@ -512,6 +570,7 @@ pub fn build_service_for_tests(
         jwt_public_signing_key,
         signup_secret,
         posthog_client,
+        FixesJob::DEFAULT_MAX_CONCURRENCY,
         true,
     )
 }
@@ -521,12 +580,14 @@ pub fn build_service(
     jwt_public_signing_key: JwtPublicSigningKey,
     signup_secret: SensitiveString,
     posthog_client: PosthogClient,
+    max_concurrent_fixes: usize,
 ) -> Result<(Router, oneshot::Receiver<()>, broadcast::Receiver<()>)> {
     build_service_inner(
         services_context,
         jwt_public_signing_key,
         signup_secret,
         posthog_client,
+        max_concurrent_fixes,
         false,
     )
 }
@@ -536,6 +597,7 @@ fn build_service_inner(
     jwt_public_signing_key: JwtPublicSigningKey,
     signup_secret: SensitiveString,
     posthog_client: PosthogClient,
+    max_concurrent_fixes: usize,
     for_tests: bool,
 ) -> Result<(Router, oneshot::Receiver<()>, broadcast::Receiver<()>)> {
     let (shutdown_tx, shutdown_rx) = mpsc::channel(1);
@@ -548,6 +610,7 @@ fn build_service_inner(
         posthog_client,
         shutdown_broadcast_tx.clone(),
         shutdown_tx,
+        max_concurrent_fixes,
         for_tests,
     );
 
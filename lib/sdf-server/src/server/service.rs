@@ -1,3 +1,6 @@
+pub mod action_schedule;
+pub mod api_token;
+pub mod audit_log;
 pub mod change_set;
 pub mod component;
 pub mod diagram;
@@ -11,6 +14,7 @@ pub mod secret;
 pub mod session;
 pub mod status;
 pub mod variant_definition;
+pub mod workspace;
 pub mod ws;
 
 /// A module containing dev routes for local development only.
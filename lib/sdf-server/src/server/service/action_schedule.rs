@@ -0,0 +1,79 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use thiserror::Error;
+
+use dal::action_schedule::RecurrenceParseError;
+use dal::{
+    ActionScheduleError as DalActionScheduleError, ActionScheduleId, ComponentError,
+    StandardModelError, TransactionsError, UserError, UserPk,
+};
+
+use crate::server::state::AppState;
+
+pub mod create;
+pub mod delete;
+pub mod list;
+pub mod pause;
+pub mod resume;
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum ActionScheduleError {
+    #[error("action schedule {0} not found")]
+    ActionScheduleNotFound(ActionScheduleId),
+    #[error(transparent)]
+    Component(#[from] ComponentError),
+    #[error(transparent)]
+    DalActionSchedule(#[from] DalActionScheduleError),
+    #[error("invalid user {0}")]
+    InvalidUser(UserPk),
+    #[error("invalid user system init")]
+    InvalidUserSystemInit,
+    #[error(transparent)]
+    Recurrence(#[from] RecurrenceParseError),
+    #[error(transparent)]
+    StandardModel(#[from] StandardModelError),
+    #[error(transparent)]
+    Transactions(#[from] TransactionsError),
+    #[error(transparent)]
+    User(#[from] UserError),
+}
+
+pub type ActionScheduleResult<T> = std::result::Result<T, ActionScheduleError>;
+
+impl IntoResponse for ActionScheduleError {
+    fn into_response(self) -> Response {
+        let (status, error_message) = match &self {
+            ActionScheduleError::ActionScheduleNotFound(_) => {
+                (StatusCode::NOT_FOUND, self.to_string())
+            }
+            ActionScheduleError::Recurrence(_)
+            | ActionScheduleError::DalActionSchedule(
+                DalActionScheduleError::NotInHead
+                | DalActionScheduleError::NotSchedulable(_)
+                | DalActionScheduleError::RecurrenceNeverMatches(_)
+                | DalActionScheduleError::RunAtOrRecurrenceRequired,
+            ) => (StatusCode::BAD_REQUEST, self.to_string()),
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
+        };
+
+        let body = Json(
+            serde_json::json!({ "error": { "message": error_message, "code": 42, "statusCode": status.as_u16() } }),
+        );
+
+        (status, body).into_response()
+    }
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/list", get(list::list))
+        .route("/create", post(create::create))
+        .route("/pause", post(pause::pause))
+        .route("/resume", post(resume::resume))
+        .route("/delete", post(delete::delete))
+}
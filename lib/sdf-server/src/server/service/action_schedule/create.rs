@@ -0,0 +1,85 @@
+use axum::extract::OriginalUri;
+use axum::Json;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{ActionScheduleError, ActionScheduleResult};
+use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::tracking::track;
+use dal::{
+    ActionPrototypeId, ActionSchedule, ActionScheduleId, ActionScheduleRecurrence, ComponentId,
+    HistoryActor, StandardModel, User, Visibility,
+};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateActionScheduleRequest {
+    pub action_prototype_id: ActionPrototypeId,
+    pub component_id: ComponentId,
+    /// When to run first. Defaults to the next time the recurrence matches.
+    pub run_at: Option<DateTime<Utc>>,
+    /// A cron expression (e.g. `0 2 * * *` for every night at 02:00 UTC). Without one, the action
+    /// only runs once.
+    pub recurrence: Option<String>,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateActionScheduleResponse {
+    pub id: ActionScheduleId,
+    pub next_run_at: Option<DateTime<Utc>>,
+}
+
+pub async fn create(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Json(request): Json<CreateActionScheduleRequest>,
+) -> ActionScheduleResult<Json<CreateActionScheduleResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let user = match ctx.history_actor() {
+        HistoryActor::User(user_pk) => User::get_by_pk(&ctx, *user_pk)
+            .await?
+            .ok_or(ActionScheduleError::InvalidUser(*user_pk))?,
+        HistoryActor::SystemInit => return Err(ActionScheduleError::InvalidUserSystemInit),
+    };
+
+    let recurrence = request
+        .recurrence
+        .as_deref()
+        .map(str::parse::<ActionScheduleRecurrence>)
+        .transpose()?;
+    let schedule = ActionSchedule::new(
+        &ctx,
+        request.action_prototype_id,
+        request.component_id,
+        user.email(),
+        request.run_at,
+        recurrence,
+    )
+    .await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "create_action_schedule",
+        serde_json::json!({
+            "action_schedule_id": schedule.id(),
+            "action_prototype_id": schedule.action_prototype_id(),
+            "component_id": schedule.component_id(),
+            "recurrence": schedule.recurrence(),
+        }),
+    );
+
+    ctx.commit().await?;
+
+    Ok(Json(CreateActionScheduleResponse {
+        id: *schedule.id(),
+        next_run_at: schedule.next_run_at(),
+    }))
+}
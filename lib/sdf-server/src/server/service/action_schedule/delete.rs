@@ -0,0 +1,31 @@
+use axum::Json;
+use serde::{Deserialize, Serialize};
+
+use super::{ActionScheduleError, ActionScheduleResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+use dal::{ActionSchedule, ActionScheduleId, StandardModel, Visibility};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteActionScheduleRequest {
+    pub id: ActionScheduleId,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+pub async fn delete(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Json(request): Json<DeleteActionScheduleRequest>,
+) -> ActionScheduleResult<Json<()>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let mut schedule = ActionSchedule::get_by_id(&ctx, &request.id)
+        .await?
+        .ok_or(ActionScheduleError::ActionScheduleNotFound(request.id))?;
+    schedule.delete_by_id(&ctx).await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(()))
+}
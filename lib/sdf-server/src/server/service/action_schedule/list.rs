@@ -0,0 +1,79 @@
+use axum::{extract::Query, Json};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::ActionScheduleResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+use dal::{
+    ActionPrototype, ActionPrototypeId, ActionSchedule, ActionScheduleId, Component, ComponentId,
+    FixBatchId, StandardModel, Visibility,
+};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ListActionSchedulesRequest {
+    /// Only list the schedules of this [`Component`].
+    pub component_id: Option<ComponentId>,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionScheduleView {
+    pub id: ActionScheduleId,
+    pub action_prototype_id: ActionPrototypeId,
+    pub action_name: Option<String>,
+    pub component_id: ComponentId,
+    pub component_name: Option<String>,
+    pub author: String,
+    pub recurrence: Option<String>,
+    pub next_run_at: Option<DateTime<Utc>>,
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub last_fix_batch_id: Option<FixBatchId>,
+    pub paused: bool,
+}
+
+pub type ListActionSchedulesResponse = Vec<ActionScheduleView>;
+
+pub async fn list(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Query(request): Query<ListActionSchedulesRequest>,
+) -> ActionScheduleResult<Json<ListActionSchedulesResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let schedules = match request.component_id {
+        Some(component_id) => ActionSchedule::list_for_component(&ctx, component_id).await?,
+        None => ActionSchedule::list(&ctx).await?,
+    };
+
+    let mut views = Vec::with_capacity(schedules.len());
+    for schedule in schedules {
+        let action_name =
+            match ActionPrototype::get_by_id(&ctx, schedule.action_prototype_id()).await? {
+                Some(prototype) => Some(ActionPrototypeView::new(&ctx, prototype).await?.name),
+                None => None,
+            };
+        let component_name = match Component::get_by_id(&ctx, schedule.component_id()).await? {
+            Some(component) => Some(component.name(&ctx).await?),
+            None => None,
+        };
+
+        views.push(ActionScheduleView {
+            id: *schedule.id(),
+            action_prototype_id: *schedule.action_prototype_id(),
+            action_name,
+            component_id: *schedule.component_id(),
+            component_name,
+            author: schedule.author().clone(),
+            recurrence: schedule.recurrence().map(ToString::to_string),
+            next_run_at: schedule.next_run_at(),
+            last_run_at: schedule.last_run_at(),
+            last_fix_batch_id: schedule.last_fix_batch_id(),
+            paused: schedule.paused(),
+        });
+    }
+
+    Ok(Json(views))
+}
@@ -0,0 +1,32 @@
+use axum::Json;
+use serde::{Deserialize, Serialize};
+
+use super::{ActionScheduleError, ActionScheduleResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+use dal::{ActionSchedule, ActionScheduleId, StandardModel, Visibility};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PauseActionScheduleRequest {
+    pub id: ActionScheduleId,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+/// Stops a schedule from running until it is resumed.
+pub async fn pause(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Json(request): Json<PauseActionScheduleRequest>,
+) -> ActionScheduleResult<Json<()>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let mut schedule = ActionSchedule::get_by_id(&ctx, &request.id)
+        .await?
+        .ok_or(ActionScheduleError::ActionScheduleNotFound(request.id))?;
+    schedule.pause(&ctx).await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(()))
+}
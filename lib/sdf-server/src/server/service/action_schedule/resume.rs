@@ -0,0 +1,32 @@
+use axum::Json;
+use serde::{Deserialize, Serialize};
+
+use super::{ActionScheduleError, ActionScheduleResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+use dal::{ActionSchedule, ActionScheduleId, StandardModel, Visibility};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ResumeActionScheduleRequest {
+    pub id: ActionScheduleId,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+/// Lets a paused schedule run again. Recurring schedules skip the runs they missed.
+pub async fn resume(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Json(request): Json<ResumeActionScheduleRequest>,
+) -> ActionScheduleResult<Json<()>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let mut schedule = ActionSchedule::get_by_id(&ctx, &request.id)
+        .await?
+        .ok_or(ActionScheduleError::ActionScheduleNotFound(request.id))?;
+    schedule.resume(&ctx).await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(()))
+}
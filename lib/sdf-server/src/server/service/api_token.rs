@@ -0,0 +1,64 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use thiserror::Error;
+
+use dal::{ApiTokenError as DalApiTokenError, ApiTokenPk, TransactionsError};
+
+use crate::server::state::AppState;
+
+pub mod create_api_token;
+pub mod list_api_tokens;
+pub mod revoke_api_token;
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum ApiTokenError {
+    #[error("api token not found: {0}")]
+    ApiTokenNotFound(ApiTokenPk),
+    #[error(transparent)]
+    DalApiToken(#[from] DalApiTokenError),
+    #[error(transparent)]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type ApiTokenResult<T> = std::result::Result<T, ApiTokenError>;
+
+impl IntoResponse for ApiTokenError {
+    fn into_response(self) -> Response {
+        let (status, error_message) = match &self {
+            ApiTokenError::ApiTokenNotFound(_)
+            | ApiTokenError::DalApiToken(DalApiTokenError::NotFound(_)) => {
+                (StatusCode::NOT_FOUND, self.to_string())
+            }
+            ApiTokenError::DalApiToken(DalApiTokenError::RequiresUser) => {
+                (StatusCode::BAD_REQUEST, self.to_string())
+            }
+            ApiTokenError::DalApiToken(DalApiTokenError::User(err))
+                if err.is_permission_denied() =>
+            {
+                (StatusCode::FORBIDDEN, self.to_string())
+            }
+            ApiTokenError::DalApiToken(err) if err.is_above_caller() => {
+                (StatusCode::FORBIDDEN, self.to_string())
+            }
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
+        };
+
+        let body = Json(
+            serde_json::json!({ "error": { "message": error_message, "code": 42, "statusCode": status.as_u16() } }),
+        );
+
+        (status, body).into_response()
+    }
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/create", post(create_api_token::create_api_token))
+        .route("/list", get(list_api_tokens::list_api_tokens))
+        .route("/revoke", post(revoke_api_token::revoke_api_token))
+}
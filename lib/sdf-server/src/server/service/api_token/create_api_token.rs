@@ -0,0 +1,54 @@
+use axum::extract::OriginalUri;
+use axum::Json;
+use chrono::{DateTime, Utc};
+use dal::{ApiToken, WorkspaceRole};
+use serde::{Deserialize, Serialize};
+
+use super::ApiTokenResult;
+use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::tracking::track;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateApiTokenRequest {
+    pub name: String,
+    pub max_role: Option<WorkspaceRole>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateApiTokenResponse {
+    pub api_token: ApiToken,
+    /// The token itself, which is only ever returned here.
+    pub token: String,
+}
+
+pub async fn create_api_token(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Json(request): Json<CreateApiTokenRequest>,
+) -> ApiTokenResult<Json<CreateApiTokenResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let (api_token, token) =
+        ApiToken::new(&ctx, &request.name, request.max_role, request.expires_at).await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "create_api_token",
+        serde_json::json!({
+            "api_token_pk": api_token.pk,
+            "max_role": api_token.max_role,
+            "expires_at": api_token.expires_at,
+        }),
+    );
+
+    ctx.commit().await?;
+
+    Ok(Json(CreateApiTokenResponse { api_token, token }))
+}
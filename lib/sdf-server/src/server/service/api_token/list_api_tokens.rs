@@ -0,0 +1,33 @@
+use axum::{extract::Query, Json};
+use dal::ApiToken;
+use serde::{Deserialize, Serialize};
+
+use super::ApiTokenResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ListApiTokensRequest {
+    /// Lists the tokens of every member of the workspace instead of the current user's. Takes
+    /// managing the workspace.
+    #[serde(default)]
+    pub all_users: bool,
+}
+
+pub type ListApiTokensResponse = Vec<ApiToken>;
+
+pub async fn list_api_tokens(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Query(request): Query<ListApiTokensRequest>,
+) -> ApiTokenResult<Json<ListApiTokensResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let api_tokens = if request.all_users {
+        ApiToken::list_for_workspace(&ctx).await?
+    } else {
+        ApiToken::list(&ctx).await?
+    };
+
+    Ok(Json(api_tokens))
+}
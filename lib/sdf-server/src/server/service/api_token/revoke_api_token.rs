@@ -0,0 +1,43 @@
+use axum::extract::OriginalUri;
+use axum::Json;
+use dal::{ApiToken, ApiTokenPk};
+use serde::{Deserialize, Serialize};
+
+use super::{ApiTokenError, ApiTokenResult};
+use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::tracking::track;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RevokeApiTokenRequest {
+    pub api_token_pk: ApiTokenPk,
+}
+
+pub async fn revoke_api_token(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Json(request): Json<RevokeApiTokenRequest>,
+) -> ApiTokenResult<Json<ApiToken>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let mut api_token = ApiToken::get_by_pk(&ctx, request.api_token_pk)
+        .await?
+        .ok_or(ApiTokenError::ApiTokenNotFound(request.api_token_pk))?;
+    api_token.revoke(&ctx).await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "revoke_api_token",
+        serde_json::json!({
+            "api_token_pk": api_token.pk,
+        }),
+    );
+
+    ctx.commit().await?;
+
+    Ok(Json(api_token))
+}
@@ -0,0 +1,56 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use thiserror::Error;
+
+use dal::{HistoryEventError, TransactionsError, UserError};
+
+use crate::server::state::AppState;
+
+pub mod export;
+pub mod list;
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum AuditLogError {
+    #[error(transparent)]
+    HistoryEvent(#[from] HistoryEventError),
+    #[error("invalid limit: expected a positive number")]
+    InvalidLimit,
+    #[error(transparent)]
+    SerdeJson(#[from] serde_json::Error),
+    #[error(transparent)]
+    Transactions(#[from] TransactionsError),
+    #[error(transparent)]
+    User(#[from] UserError),
+}
+
+pub type AuditLogResult<T> = std::result::Result<T, AuditLogError>;
+
+impl IntoResponse for AuditLogError {
+    fn into_response(self) -> Response {
+        let (status, error_message) = match &self {
+            AuditLogError::HistoryEvent(HistoryEventError::CursorNotFound(_))
+            | AuditLogError::InvalidLimit => (StatusCode::BAD_REQUEST, self.to_string()),
+            AuditLogError::User(err) if err.is_permission_denied() => {
+                (StatusCode::FORBIDDEN, self.to_string())
+            }
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
+        };
+
+        let body = Json(
+            serde_json::json!({ "error": { "message": error_message, "code": 42, "statusCode": status.as_u16() } }),
+        );
+
+        (status, body).into_response()
+    }
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/list", get(list::list))
+        .route("/export", get(export::export))
+}
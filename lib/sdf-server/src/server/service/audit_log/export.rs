@@ -0,0 +1,81 @@
+use axum::{
+    body::StreamBody,
+    extract::Query,
+    http::header,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use futures::stream;
+use serde::{Deserialize, Serialize};
+
+use super::{AuditLogError, AuditLogResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+use dal::{
+    ChangeSetPk, HistoryActor, HistoryEvent, HistoryEventQuery, User, UserPk, WorkspacePermission,
+};
+
+/// How many [`HistoryEvents`](HistoryEvent) are fetched at a time while exporting.
+const EXPORT_PAGE_SIZE: i64 = 500;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportAuditLogRequest {
+    pub user_pk: Option<UserPk>,
+    pub label_prefix: Option<String>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+    pub change_set_pk: Option<ChangeSetPk>,
+}
+
+/// Streams every [`HistoryEvent`] of the workspace matching the filters as newline-delimited
+/// JSON, most recent first. The events are read a page at a time, each page in a transaction of
+/// its own, and every page starts where the previous one ended. As events are never updated,
+/// pages neither repeat nor skip events, but events recorded once the export started are left
+/// out.
+pub async fn export(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Query(request): Query<ExportAuditLogRequest>,
+) -> AuditLogResult<Response> {
+    let ctx = builder.build_head(request_ctx).await?;
+    User::ensure_authorized(&ctx, WorkspacePermission::ManageWorkspace).await?;
+
+    let query = HistoryEventQuery {
+        actor: request.user_pk.map(HistoryActor::User),
+        label_prefix: request.label_prefix,
+        created_after: request.created_after,
+        created_before: request.created_before,
+        change_set_pk: request.change_set_pk,
+        cursor: None,
+        limit: Some(EXPORT_PAGE_SIZE),
+    };
+
+    // The state is the context and the query for the next page, if there is one
+    let pages = stream::try_unfold((ctx, Some(query)), |(ctx, query)| async move {
+        let query = match query {
+            Some(query) => query,
+            None => return Ok(None),
+        };
+        let page = HistoryEvent::list(&ctx, &query).await?;
+        // Don't hold a transaction open while the page is sent
+        ctx.rollback().await?;
+
+        let mut lines = String::new();
+        for event in &page.events {
+            lines.push_str(&serde_json::to_string(event)?);
+            lines.push('\n');
+        }
+        let next_query = page.next_cursor.map(|cursor| HistoryEventQuery {
+            cursor: Some(cursor),
+            ..query
+        });
+
+        Ok::<_, AuditLogError>(Some((lines, (ctx, next_query))))
+    });
+
+    Ok((
+        [(header::CONTENT_TYPE, "application/x-ndjson")],
+        StreamBody::new(pages),
+    )
+        .into_response())
+}
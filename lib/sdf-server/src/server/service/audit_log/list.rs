@@ -0,0 +1,52 @@
+use axum::{extract::Query, Json};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{AuditLogError, AuditLogResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+use dal::{
+    ChangeSetPk, HistoryActor, HistoryEvent, HistoryEventPage, HistoryEventPk, HistoryEventQuery,
+    User, UserPk, WorkspacePermission,
+};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ListAuditLogRequest {
+    pub user_pk: Option<UserPk>,
+    pub label_prefix: Option<String>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+    pub change_set_pk: Option<ChangeSetPk>,
+    pub cursor: Option<HistoryEventPk>,
+    pub limit: Option<i64>,
+}
+
+pub type ListAuditLogResponse = HistoryEventPage;
+
+/// Lists the [`HistoryEvents`](HistoryEvent) of the workspace, most recent first, optionally
+/// filtered by user, label prefix, time range and [`ChangeSet`](dal::ChangeSet). The
+/// `nextCursor` of the response is passed back as `cursor` to get the next page.
+pub async fn list(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Query(request): Query<ListAuditLogRequest>,
+) -> AuditLogResult<Json<ListAuditLogResponse>> {
+    let ctx = builder.build_head(request_ctx).await?;
+    User::ensure_authorized(&ctx, WorkspacePermission::ManageWorkspace).await?;
+
+    if request.limit.map_or(false, |limit| limit < 1) {
+        return Err(AuditLogError::InvalidLimit);
+    }
+    let query = HistoryEventQuery {
+        actor: request.user_pk.map(HistoryActor::User),
+        label_prefix: request.label_prefix,
+        created_after: request.created_after,
+        created_before: request.created_before,
+        change_set_pk: request.change_set_pk,
+        cursor: request.cursor,
+        limit: request.limit,
+    };
+    let page = HistoryEvent::list(&ctx, &query).await?;
+
+    Ok(Json(page))
+}
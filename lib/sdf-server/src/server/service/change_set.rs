@@ -15,13 +15,23 @@ use thiserror::Error;
 
 use crate::{server::state::AppState, service::pkg::PkgError};
 
+pub mod abandon_change_set;
 pub mod add_action;
 pub mod apply_change_set;
+pub mod approve_change_set;
+pub mod close_change_set;
 pub mod create_change_set;
+pub mod get_apply_preview;
 pub mod get_change_set;
+pub mod get_change_set_approvals;
 pub mod get_stats;
+pub mod list_closed_change_sets;
 pub mod list_open_change_sets;
+pub mod rebase_change_set;
+pub mod reject_change_set;
 pub mod remove_action;
+pub mod reopen_change_set;
+pub mod request_change_set_approval;
 pub mod update_selected_change_set;
 
 #[remain::sorted]
@@ -71,8 +81,24 @@ pub type ChangeSetResult<T> = std::result::Result<T, ChangeSetError>;
 
 impl IntoResponse for ChangeSetError {
     fn into_response(self) -> Response {
-        let (status, error_message) = match self {
+        let (status, error_message) = match &self {
             ChangeSetError::ChangeSetNotFound => (StatusCode::NOT_FOUND, self.to_string()),
+            ChangeSetError::ChangeSet(DalChangeSetError::ApplyConflicts(_, _))
+            | ChangeSetError::ChangeSet(DalChangeSetError::NotClosed(_, _))
+            | ChangeSetError::ChangeSet(DalChangeSetError::NotEnoughApprovals(_, _, _))
+            | ChangeSetError::ChangeSet(DalChangeSetError::NotOpen(_, _))
+            | ChangeSetError::ChangeSet(DalChangeSetError::NotPendingApproval(_, _)) => {
+                (StatusCode::CONFLICT, self.to_string())
+            }
+            ChangeSetError::ChangeSet(DalChangeSetError::ApprovalRequiresUser)
+            | ChangeSetError::ChangeSet(DalChangeSetError::SelfApproval(_)) => {
+                (StatusCode::FORBIDDEN, self.to_string())
+            }
+            ChangeSetError::ChangeSet(DalChangeSetError::User(err)) | ChangeSetError::User(err)
+                if err.is_permission_denied() =>
+            {
+                (StatusCode::FORBIDDEN, self.to_string())
+            }
             _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
         };
 
@@ -90,6 +116,10 @@ pub fn routes() -> Router<AppState> {
             "/list_open_change_sets",
             get(list_open_change_sets::list_open_change_sets),
         )
+        .route(
+            "/list_closed_change_sets",
+            get(list_closed_change_sets::list_closed_change_sets),
+        )
         .route("/remove_action", post(remove_action::remove_action))
         .route("/add_action", post(add_action::add_action))
         .route(
@@ -98,10 +128,46 @@ pub fn routes() -> Router<AppState> {
         )
         .route("/get_change_set", get(get_change_set::get_change_set))
         .route("/get_stats", get(get_stats::get_stats))
+        .route(
+            "/get_apply_preview",
+            get(get_apply_preview::get_apply_preview),
+        )
         .route(
             "/apply_change_set",
             post(apply_change_set::apply_change_set),
         )
+        .route(
+            "/request_change_set_approval",
+            post(request_change_set_approval::request_change_set_approval),
+        )
+        .route(
+            "/approve_change_set",
+            post(approve_change_set::approve_change_set),
+        )
+        .route(
+            "/reject_change_set",
+            post(reject_change_set::reject_change_set),
+        )
+        .route(
+            "/get_change_set_approvals",
+            get(get_change_set_approvals::get_change_set_approvals),
+        )
+        .route(
+            "/abandon_change_set",
+            post(abandon_change_set::abandon_change_set),
+        )
+        .route(
+            "/close_change_set",
+            post(close_change_set::close_change_set),
+        )
+        .route(
+            "/reopen_change_set",
+            post(reopen_change_set::reopen_change_set),
+        )
+        .route(
+            "/rebase_change_set",
+            post(rebase_change_set::rebase_change_set),
+        )
         .route(
             "/update_selected_change_set",
             post(update_selected_change_set::update_selected_change_set),
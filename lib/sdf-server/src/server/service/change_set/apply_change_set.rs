@@ -1,8 +1,9 @@
 use super::ChangeSetResult;
 use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
 use crate::server::service::change_set::ChangeSetError;
+use crate::server::state::MaxConcurrentFixes;
 use crate::server::tracking::track;
-use axum::extract::OriginalUri;
+use axum::extract::{OriginalUri, State};
 use axum::Json;
 use dal::job::definition::{FixItem, FixesJob};
 use dal::{ChangeSet, ChangeSetPk, Fix, FixBatch, HistoryActor, StandardModel, User};
@@ -13,6 +14,9 @@ use serde::{Deserialize, Serialize};
 #[serde(rename_all = "camelCase")]
 pub struct ApplyChangeSetRequest {
     pub change_set_pk: ChangeSetPk,
+    /// Apply even if head changed the same things since the change set was based on it.
+    #[serde(default)]
+    pub override_conflicts: bool,
 }
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -26,6 +30,7 @@ pub async fn apply_change_set(
     AccessBuilder(access_builder): AccessBuilder,
     PosthogClient(posthog_client): PosthogClient,
     OriginalUri(original_uri): OriginalUri,
+    State(MaxConcurrentFixes(max_concurrent_fixes)): State<MaxConcurrentFixes>,
     Json(request): Json<ApplyChangeSetRequest>,
 ) -> ChangeSetResult<Json<ApplyChangeSetResponse>> {
     let mut ctx = builder.build_head(access_builder).await?;
@@ -34,7 +39,11 @@ pub async fn apply_change_set(
         .await?
         .ok_or(ChangeSetError::ChangeSetNotFound)?;
     let actions = change_set.actions(&ctx).await?;
-    change_set.apply(&mut ctx).await?;
+    if request.override_conflicts {
+        change_set.apply_overriding_conflicts(&mut ctx).await?;
+    } else {
+        change_set.apply(&mut ctx).await?;
+    }
 
     track(
         &posthog_client,
@@ -43,6 +52,7 @@ pub async fn apply_change_set(
         "apply_change_set",
         serde_json::json!({
             "merged_change_set": request.change_set_pk,
+            "override_conflicts": request.override_conflicts,
         }),
     );
 
@@ -88,8 +98,13 @@ pub async fn apply_change_set(
             }),
         );
 
-        ctx.enqueue_job(FixesJob::new(&ctx, fixes, *batch.id()))
-            .await?;
+        ctx.enqueue_job(FixesJob::new_with_max_concurrency(
+            &ctx,
+            fixes,
+            *batch.id(),
+            max_concurrent_fixes,
+        ))
+        .await?;
     }
 
     ctx.commit().await?;
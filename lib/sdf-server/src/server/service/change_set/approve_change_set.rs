@@ -0,0 +1,53 @@
+use super::{ChangeSetError, ChangeSetResult};
+use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::tracking::track;
+use axum::extract::OriginalUri;
+use axum::Json;
+use dal::{ChangeSet, ChangeSetApproval, ChangeSetPk};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ApproveChangeSetRequest {
+    pub change_set_pk: ChangeSetPk,
+    pub comment: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ApproveChangeSetResponse {
+    pub change_set: ChangeSet,
+    pub approval: ChangeSetApproval,
+}
+
+pub async fn approve_change_set(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Json(request): Json<ApproveChangeSetRequest>,
+) -> ChangeSetResult<Json<ApproveChangeSetResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let mut change_set = ChangeSet::get_by_pk(&ctx, &request.change_set_pk)
+        .await?
+        .ok_or(ChangeSetError::ChangeSetNotFound)?;
+    let approval = change_set.approve(&ctx, request.comment).await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "approve_change_set",
+        serde_json::json!({
+            "change_set": request.change_set_pk,
+        }),
+    );
+
+    ctx.commit().await?;
+
+    Ok(Json(ApproveChangeSetResponse {
+        change_set,
+        approval,
+    }))
+}
@@ -0,0 +1,40 @@
+use super::{ChangeSetError, ChangeSetResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+use axum::extract::Query;
+use axum::Json;
+use dal::{change_set::ChangeSetApplyPreview, ChangeSet, ChangeSetPk};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GetApplyPreviewRequest {
+    pub change_set_pk: ChangeSetPk,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GetApplyPreviewResponse {
+    pub preview: ChangeSetApplyPreview,
+    pub conflict_count: usize,
+}
+
+pub async fn get_apply_preview(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Query(request): Query<GetApplyPreviewRequest>,
+) -> ChangeSetResult<Json<GetApplyPreviewResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let change_set = ChangeSet::get_by_pk(&ctx, &request.change_set_pk)
+        .await?
+        .ok_or(ChangeSetError::ChangeSetNotFound)?;
+    let preview = change_set.apply_preview(&ctx).await?;
+    let conflict_count = preview.conflict_count();
+
+    ctx.commit().await?;
+
+    Ok(Json(GetApplyPreviewResponse {
+        preview,
+        conflict_count,
+    }))
+}
@@ -0,0 +1,35 @@
+use super::{ChangeSetError, ChangeSetResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+use axum::extract::Query;
+use axum::Json;
+use dal::{ChangeSet, ChangeSetApprovalStatus, ChangeSetPk};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GetChangeSetApprovalsRequest {
+    pub change_set_pk: ChangeSetPk,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GetChangeSetApprovalsResponse {
+    pub status: ChangeSetApprovalStatus,
+}
+
+pub async fn get_change_set_approvals(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Query(request): Query<GetChangeSetApprovalsRequest>,
+) -> ChangeSetResult<Json<GetChangeSetApprovalsResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let change_set = ChangeSet::get_by_pk(&ctx, &request.change_set_pk)
+        .await?
+        .ok_or(ChangeSetError::ChangeSetNotFound)?;
+    let status = change_set.approval_status(&ctx).await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(GetChangeSetApprovalsResponse { status }))
+}
@@ -0,0 +1,17 @@
+use super::ChangeSetResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+use axum::Json;
+use dal::ChangeSet;
+
+pub type ListClosedChangeSetsResponse = Vec<ChangeSet>;
+
+pub async fn list_closed_change_sets(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+) -> ChangeSetResult<Json<ListClosedChangeSetsResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let list = ChangeSet::list_closed(&ctx).await?;
+
+    Ok(Json(list))
+}
@@ -0,0 +1,53 @@
+use super::{ChangeSetError, ChangeSetResult};
+use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::tracking::track;
+use axum::extract::OriginalUri;
+use axum::Json;
+use dal::{AttributeValueConflict, ChangeSet, ChangeSetPk};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RebaseChangeSetRequest {
+    pub change_set_pk: ChangeSetPk,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RebaseChangeSetResponse {
+    pub change_set: ChangeSet,
+    pub conflicts: Vec<AttributeValueConflict>,
+}
+
+pub async fn rebase_change_set(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Json(request): Json<RebaseChangeSetRequest>,
+) -> ChangeSetResult<Json<RebaseChangeSetResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let mut change_set = ChangeSet::get_by_pk(&ctx, &request.change_set_pk)
+        .await?
+        .ok_or(ChangeSetError::ChangeSetNotFound)?;
+    let conflicts = change_set.rebase(&ctx).await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "rebase_change_set",
+        serde_json::json!({
+            "rebased_change_set": request.change_set_pk,
+            "number_of_conflicts": conflicts.len(),
+        }),
+    );
+
+    ctx.commit().await?;
+
+    Ok(Json(RebaseChangeSetResponse {
+        change_set,
+        conflicts,
+    }))
+}
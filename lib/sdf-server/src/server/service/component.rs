@@ -9,9 +9,10 @@ use dal::{
     component::view::debug::ComponentDebugViewError, node::NodeError,
     property_editor::PropertyEditorError, AttributeContextBuilderError,
     AttributePrototypeArgumentError, AttributePrototypeError, AttributeValueError, ChangeSetError,
-    ComponentError as DalComponentError, ComponentId, DiagramError, ExternalProviderError,
-    FuncBindingError, FuncError, InternalProviderError, PropId, ReconciliationPrototypeError,
-    SchemaError as DalSchemaError, StandardModelError, TransactionsError, WsEventError,
+    ComponentError as DalComponentError, ComponentId, DiagramError, DriftError,
+    ExternalProviderError, FuncBindingError, FuncError, InternalProviderError, PropId,
+    ReconciliationPrototypeError, SchemaError as DalSchemaError, StandardModelError,
+    TransactionsError, WsEventError,
 };
 use thiserror::Error;
 
@@ -22,6 +23,7 @@ pub mod debug;
 pub mod get_code;
 pub mod get_components_metadata;
 pub mod get_diff;
+pub mod get_drift_summary;
 pub mod get_property_editor_schema;
 pub mod get_property_editor_validations;
 pub mod get_property_editor_values;
@@ -69,6 +71,8 @@ pub enum ComponentError {
     DalSchema(#[from] DalSchemaError),
     #[error("diagram error: {0}")]
     Diagram(#[from] DiagramError),
+    #[error("drift error: {0}")]
+    Drift(#[from] DriftError),
     #[error("external provider error: {0}")]
     ExternalProvider(#[from] ExternalProviderError),
     #[error("func error: {0}")]
@@ -146,6 +150,10 @@ pub fn routes() -> Router<AppState> {
         .route("/list_resources", get(list_resources::list_resources))
         .route("/get_code", get(get_code::get_code))
         .route("/get_diff", get(get_diff::get_diff))
+        .route(
+            "/get_drift_summary",
+            get(get_drift_summary::get_drift_summary),
+        )
         .route(
             "/get_property_editor_schema",
             get(get_property_editor_schema::get_property_editor_schema),
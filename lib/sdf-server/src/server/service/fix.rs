@@ -9,13 +9,16 @@ use thiserror::Error;
 use dal::fix::FixError as DalFixError;
 use dal::schema::SchemaError as DalSchemaError;
 use dal::{
-    ComponentError, ComponentId, FixResolverError, FuncBindingReturnValueError, StandardModelError,
-    TransactionsError, UserError, UserPk,
+    ComponentError, ComponentId, FixBatchId, FixResolverError, FixRunPk,
+    FuncBindingReturnValueError, StandardModelError, TransactionsError, UserError, UserPk,
 };
 
 use crate::server::state::AppState;
 
+pub mod get_run;
 pub mod list;
+pub mod list_runs;
+pub mod resume;
 pub mod run;
 
 #[remain::sorted]
@@ -29,10 +32,16 @@ pub enum FixError {
     DalFix(#[from] DalFixError),
     #[error(transparent)]
     DalSchema(#[from] DalSchemaError),
+    #[error("fix batch {0} not found")]
+    FixBatchNotFound(FixBatchId),
     #[error(transparent)]
     FixResolver(#[from] FixResolverError),
+    #[error("fix run {0} not found")]
+    FixRunNotFound(FixRunPk),
     #[error(transparent)]
     FuncBindingReturnValue(#[from] FuncBindingReturnValueError),
+    #[error("invalid limit: expected a positive number")]
+    InvalidLimit,
     #[error("invalid user {0}")]
     InvalidUser(UserPk),
     #[error("invalid user system init")]
@@ -53,7 +62,16 @@ pub type FixResult<T> = std::result::Result<T, FixError>;
 
 impl IntoResponse for FixError {
     fn into_response(self) -> Response {
-        let (status, error_message) = (StatusCode::INTERNAL_SERVER_ERROR, self.to_string());
+        let (status, error_message) = match &self {
+            FixError::FixBatchNotFound(_) | FixError::FixRunNotFound(_) => {
+                (StatusCode::NOT_FOUND, self.to_string())
+            }
+            FixError::InvalidLimit => (StatusCode::BAD_REQUEST, self.to_string()),
+            FixError::DalFix(
+                DalFixError::BatchNotFinished(_) | DalFixError::NoUnsuccessfulFixesInBatch(_),
+            ) => (StatusCode::CONFLICT, self.to_string()),
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
+        };
 
         let body = Json(
             serde_json::json!({ "error": { "message": error_message, "code": 42, "statusCode": status.as_u16() } }),
@@ -66,5 +84,8 @@ impl IntoResponse for FixError {
 pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/list", get(list::list))
+        .route("/list_runs", get(list_runs::list_runs))
+        .route("/get_run", get(get_run::get_run))
         .route("/run", post(run::run))
+        .route("/resume", post(resume::resume))
 }
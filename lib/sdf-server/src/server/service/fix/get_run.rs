@@ -0,0 +1,30 @@
+use axum::{extract::Query, Json};
+use serde::{Deserialize, Serialize};
+
+use super::{FixError, FixResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+use dal::{FixRun, FixRunPk, Visibility};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GetFixRunRequest {
+    pub pk: FixRunPk,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+pub type GetFixRunResponse = FixRun;
+
+pub async fn get_run(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Query(request): Query<GetFixRunRequest>,
+) -> FixResult<Json<GetFixRunResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let run = FixRun::get_by_pk(&ctx, request.pk)
+        .await?
+        .ok_or(FixError::FixRunNotFound(request.pk))?;
+
+    Ok(Json(run))
+}
@@ -0,0 +1,52 @@
+use axum::{extract::Query, Json};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{FixError, FixResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+use dal::{ComponentId, FixBatchId, FixRun, FixRunQuery, Visibility};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ListFixRunsRequest {
+    pub component_id: Option<ComponentId>,
+    pub fix_batch_id: Option<FixBatchId>,
+    pub started_after: Option<DateTime<Utc>>,
+    pub started_before: Option<DateTime<Utc>>,
+    // Flattening the visibility makes every query parameter a string, so the limit is parsed by
+    // hand.
+    pub limit: Option<String>,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+pub type ListFixRunsResponse = Vec<FixRun>;
+
+/// Lists recorded [`FixRuns`](FixRun), most recent first, optionally filtered by
+/// [`Component`](dal::Component), by [`FixBatch`](dal::FixBatch) and by time range.
+pub async fn list_runs(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Query(request): Query<ListFixRunsRequest>,
+) -> FixResult<Json<ListFixRunsResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let limit: Option<i64> = request
+        .limit
+        .map(|limit| limit.parse())
+        .transpose()
+        .map_err(|_| FixError::InvalidLimit)?;
+    if limit.map_or(false, |limit| limit < 1) {
+        return Err(FixError::InvalidLimit);
+    }
+    let query = FixRunQuery {
+        component_id: request.component_id,
+        fix_batch_id: request.fix_batch_id,
+        started_after: request.started_after,
+        started_before: request.started_before,
+        limit,
+    };
+    let runs = FixRun::list(&ctx, &query).await?;
+
+    Ok(Json(runs))
+}
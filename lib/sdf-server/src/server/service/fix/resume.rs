@@ -0,0 +1,75 @@
+use axum::extract::{OriginalUri, State};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+
+use super::{FixError, FixResult};
+use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::state::MaxConcurrentFixes;
+use crate::server::tracking::track;
+use dal::job::definition::{FixItem, FixesJob};
+use dal::{FixBatch, FixBatchId, StandardModel, Visibility};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FixesResumeRequest {
+    pub id: FixBatchId,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FixesResumeResponse {
+    pub id: FixBatchId,
+}
+
+/// Re-runs the fixes of a finished batch that did not succeed. Their previous attempts are kept
+/// in their attempt history.
+pub async fn resume(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    State(MaxConcurrentFixes(max_concurrent_fixes)): State<MaxConcurrentFixes>,
+    Json(request): Json<FixesResumeRequest>,
+) -> FixResult<Json<FixesResumeResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let mut batch = FixBatch::get_by_id(&ctx, &request.id)
+        .await?
+        .ok_or(FixError::FixBatchNotFound(request.id))?;
+    let fixes: Vec<FixItem> = batch
+        .reset_for_resume(&ctx)
+        .await?
+        .into_iter()
+        .map(|fix| FixItem {
+            id: *fix.id(),
+            component_id: *fix.component_id(),
+            action_prototype_id: *fix.action_prototype_id(),
+        })
+        .collect();
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "resume_fix_batch",
+        serde_json::json!({
+            "fix_batch_id": batch.id(),
+            "number_of_fixes_resumed": fixes.len(),
+            "fixes_resumed": fixes,
+        }),
+    );
+
+    ctx.enqueue_job(FixesJob::new_with_max_concurrency(
+        &ctx,
+        fixes,
+        *batch.id(),
+        max_concurrent_fixes,
+    ))
+    .await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(FixesResumeResponse { id: *batch.id() }))
+}
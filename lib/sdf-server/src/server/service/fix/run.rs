@@ -1,9 +1,10 @@
-use axum::extract::OriginalUri;
+use axum::extract::{OriginalUri, State};
 use axum::Json;
 use serde::{Deserialize, Serialize};
 
 use super::{FixError, FixResult};
 use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::state::MaxConcurrentFixes;
 use crate::server::tracking::track;
 use dal::job::definition::{FixItem, FixesJob};
 use dal::{
@@ -37,6 +38,7 @@ pub async fn run(
     AccessBuilder(request_ctx): AccessBuilder,
     PosthogClient(posthog_client): PosthogClient,
     OriginalUri(original_uri): OriginalUri,
+    State(MaxConcurrentFixes(max_concurrent_fixes)): State<MaxConcurrentFixes>,
     Json(request): Json<FixesRunRequest>,
 ) -> FixResult<Json<FixesRunResponse>> {
     let ctx = builder.build(request_ctx.build(request.visibility)).await?;
@@ -79,8 +81,13 @@ pub async fn run(
         }),
     );
 
-    ctx.enqueue_job(FixesJob::new(&ctx, fixes, *batch.id()))
-        .await?;
+    ctx.enqueue_job(FixesJob::new_with_max_concurrency(
+        &ctx,
+        fixes,
+        *batch.id(),
+        max_concurrent_fixes,
+    ))
+    .await?;
 
     ctx.commit().await?;
 
@@ -5,7 +5,7 @@ use axum::{
     routing::{get, post},
     Json, Router,
 };
-use dal::func::execution::FuncExecutionError;
+use dal::func::execution::{FuncExecutionError, FuncExecutionPk};
 use dal::{
     attribute::context::{AttributeContextBuilder, AttributeContextBuilderError},
     func::{
@@ -29,6 +29,7 @@ use std::collections::HashMap;
 use thiserror::Error;
 use tokio::task::JoinError;
 
+pub mod cancel_execution;
 pub mod create_func;
 pub mod delete_func;
 pub mod execute;
@@ -117,6 +118,8 @@ pub enum FuncError {
     FuncExecutionFailed(String),
     #[error("Function execution failed: this function is not connected to any assets, and was not executed")]
     FuncExecutionFailedNoPrototypes,
+    #[error("Function execution not found: {0}")]
+    FuncExecutionNotFound(FuncExecutionPk),
     #[error("Function still has associations: {0}")]
     FuncHasAssociations(FuncId),
     #[error("Function named \"{0}\" already exists in this changeset")]
@@ -902,6 +905,10 @@ pub fn routes() -> Router<AppState> {
         .route("/delete_func", post(delete_func::delete_func))
         .route("/save_and_exec", post(save_and_exec::save_and_exec))
         .route("/execute", post(execute::execute))
+        .route(
+            "/cancel_execution",
+            post(cancel_execution::cancel_execution),
+        )
         .route("/revert_func", post(revert_func::revert_func))
         .route(
             "/list_input_sources",
@@ -0,0 +1,37 @@
+use super::{FuncError, FuncResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+use axum::Json;
+use dal::func::execution::{FuncExecution, FuncExecutionPk};
+use dal::Visibility;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelExecutionRequest {
+    pub func_execution_pk: FuncExecutionPk,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelExecutionResponse {
+    /// Whether the function was still running when it was asked to stop.
+    pub cancelled: bool,
+}
+
+pub async fn cancel_execution(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Json(request): Json<CancelExecutionRequest>,
+) -> FuncResult<Json<CancelExecutionResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let func_execution = FuncExecution::get_by_pk(&ctx, &request.func_execution_pk).await?;
+    if func_execution.tenancy() != ctx.tenancy() {
+        return Err(FuncError::FuncExecutionNotFound(request.func_execution_pk));
+    }
+    let cancelled = func_execution.cancel(&ctx).await?;
+
+    Ok(Json(CancelExecutionResponse { cancelled }))
+}
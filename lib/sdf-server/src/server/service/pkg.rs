@@ -1,6 +1,7 @@
-use crate::server::{impl_default_error_into_response, state::AppState};
+use crate::server::state::AppState;
 use axum::{
-    response::Response,
+    http::StatusCode,
+    response::{IntoResponse, Response},
     routing::{get, post},
     Json, Router,
 };
@@ -87,9 +88,9 @@ pub enum PkgError {
     Tenancy(#[from] TenancyError),
     #[error("Unable to parse URL: {0}")]
     Url(#[from] url::ParseError),
-    #[error("transparent")]
+    #[error(transparent)]
     User(#[from] UserError),
-    #[error("transparent")]
+    #[error(transparent)]
     Workspace(#[from] WorkspaceError),
     #[error("Could not find current workspace {0}")]
     WorkspaceNotFound(WorkspacePk),
@@ -99,7 +100,24 @@ pub enum PkgError {
 
 pub type PkgResult<T> = Result<T, PkgError>;
 
-impl_default_error_into_response!(PkgError);
+impl IntoResponse for PkgError {
+    fn into_response(self) -> Response {
+        let (status, error_message) = match &self {
+            PkgError::DalPkg(DalPkgError::User(err)) | PkgError::User(err)
+                if err.is_permission_denied() =>
+            {
+                (StatusCode::FORBIDDEN, self.to_string())
+            }
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
+        };
+
+        let body = Json(
+            serde_json::json!({ "error": { "message": error_message, "code": 42, "statusCode": status.as_u16() } }),
+        );
+
+        (status, body).into_response()
+    }
+}
 
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
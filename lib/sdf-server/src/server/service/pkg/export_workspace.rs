@@ -4,7 +4,7 @@ use crate::server::tracking::track;
 use axum::extract::OriginalUri;
 use axum::Json;
 use chrono::Utc;
-use dal::{HistoryActor, User, Visibility, Workspace, WorkspacePk, WsEvent};
+use dal::{HistoryActor, User, Visibility, Workspace, WorkspaceBackup, WorkspacePk, WsEvent};
 use serde::{Deserialize, Serialize};
 use telemetry::prelude::*;
 
@@ -73,6 +73,15 @@ pub async fn export_workspace(
     let response = index_client
         .upload_module(workspace.name().as_str(), &version, module_payload)
         .await?;
+    WorkspaceBackup::record(
+        &ctx,
+        &response.id,
+        workspace.name(),
+        &version,
+        &response.latest_hash,
+        false,
+    )
+    .await?;
 
     track(
         &posthog_client,
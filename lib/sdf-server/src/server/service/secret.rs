@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use axum::extract::Query;
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
-use axum::routing::{delete, get, post};
+use axum::routing::{delete, get, post, put};
 use axum::Json;
 use axum::Router;
 use chrono::Utc;
@@ -24,8 +24,11 @@ use crate::server::state::AppState;
 use crate::service::secret::list_secrets::{ListSecretResponse, SecretDefinitionViewWithSecrets};
 
 pub mod create_secret;
+pub mod delete_secret;
 pub mod get_public_key;
+pub mod get_secret_usage;
 pub mod list_secrets;
+pub mod update_secret;
 
 #[remain::sorted]
 #[derive(Debug, Error)]
@@ -42,6 +45,8 @@ pub enum SecretError {
     Pg(#[from] si_data_pg::PgError),
     #[error(transparent)]
     Secret(#[from] dal::SecretError),
+    #[error("secret not found: {0}")]
+    SecretNotFound(SecretId),
     #[error("definition not found for secret: {0}")]
     SecretWithInvalidDefinition(SecretId),
     #[error(transparent)]
@@ -73,8 +78,21 @@ static SECRETOS: Lazy<Mutex<HashMap<String, Vec<Secret>>>> = Lazy::new(Mutex::de
 
 impl IntoResponse for SecretError {
     fn into_response(self) -> Response {
-        let (status, error_message) = (StatusCode::INTERNAL_SERVER_ERROR, self.to_string());
-        //SecretError::SecretNotFound => (StatusCode::NOT_FOUND, self.to_string()),
+        let (status, error_message) = match &self {
+            SecretError::Secret(dal::SecretError::User(err)) | SecretError::User(err)
+                if err.is_permission_denied() =>
+            {
+                (StatusCode::FORBIDDEN, self.to_string())
+            }
+            SecretError::Secret(dal::SecretError::InUse(..)) => {
+                (StatusCode::CONFLICT, self.to_string())
+            }
+            SecretError::Secret(
+                dal::SecretError::DecryptionFailed | dal::SecretError::NotCurrentKeyPair(_),
+            ) => (StatusCode::UNPROCESSABLE_ENTITY, self.to_string()),
+            SecretError::SecretNotFound(_) => (StatusCode::NOT_FOUND, self.to_string()),
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
+        };
 
         let body = Json(serde_json::json!({
             "error": {
@@ -93,7 +111,9 @@ pub fn routes() -> Router<AppState> {
         .route("/get_public_key", get(get_public_key::get_public_key))
         .route("/", post(create_secret::create_secret))
         .route("/", get(list_secrets::list_secrets))
-        .route("/", delete(delete_secrets))
+        .route("/", delete(delete_secret::delete_secret))
+        .route("/", put(update_secret::update_secret))
+        .route("/usage", get(get_secret_usage::get_secret_usage))
 }
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -175,24 +195,3 @@ pub async fn list_secrets(
             .collect::<HashMap<String, SecretDefinitionViewWithSecrets>>(),
     ))
 }
-
-#[derive(Deserialize, Serialize, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct DeleteSecretRequest {
-    id: SecretId,
-    #[serde(flatten)]
-    pub visibility: Visibility,
-}
-
-pub async fn delete_secrets(Json(request): Json<DeleteSecretRequest>) -> SecretResult<Json<()>> {
-    // TODO Block deleting secrets that are set on any props
-    tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
-
-    SECRETOS
-        .lock()
-        .await
-        .values_mut()
-        .for_each(|v| v.retain(|secret| secret.id != request.id));
-
-    Ok(Json(()))
-}
@@ -0,0 +1,37 @@
+use axum::extract::Query;
+use axum::Json;
+use dal::{ComponentId, Secret, SecretId, StandardModel, Visibility};
+use serde::{Deserialize, Serialize};
+
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+use super::{SecretError, SecretResult};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GetSecretUsageRequest {
+    pub id: SecretId,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GetSecretUsageResponse {
+    pub component_ids: Vec<ComponentId>,
+}
+
+pub async fn get_secret_usage(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_tx): AccessBuilder,
+    Query(request): Query<GetSecretUsageRequest>,
+) -> SecretResult<Json<GetSecretUsageResponse>> {
+    let ctx = builder.build(request_tx.build(request.visibility)).await?;
+
+    let secret = Secret::get_by_id(&ctx, &request.id)
+        .await?
+        .ok_or(SecretError::SecretNotFound(request.id))?;
+    let component_ids = secret.used_by(&ctx).await?;
+
+    Ok(Json(GetSecretUsageResponse { component_ids }))
+}
@@ -0,0 +1,74 @@
+use axum::Json;
+use dal::secret::SecretView;
+use dal::{
+    key_pair::KeyPairPk, Secret, SecretAlgorithm, SecretId, SecretVersion, StandardModel,
+    Visibility, WsEvent,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+use super::{SecretError, SecretResult};
+
+/// The new encrypted message of a secret being rotated.
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateSecretData {
+    pub crypted: Vec<u8>,
+    pub key_pair_pk: KeyPairPk,
+    pub version: SecretVersion,
+    pub algorithm: SecretAlgorithm,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateSecretRequest {
+    pub id: SecretId,
+    pub name: String,
+    pub description: Option<String>,
+    /// If given, the secret is rotated.
+    pub new_secret_data: Option<UpdateSecretData>,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+pub type UpdateSecretResponse = SecretView;
+
+pub async fn update_secret(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_tx): AccessBuilder,
+    Json(request): Json<UpdateSecretRequest>,
+) -> SecretResult<Json<UpdateSecretResponse>> {
+    let ctx = builder.build(request_tx.build(request.visibility)).await?;
+
+    let mut secret = Secret::get_by_id(&ctx, &request.id)
+        .await?
+        .ok_or(SecretError::SecretNotFound(request.id))?;
+
+    if secret.name() != request.name {
+        secret.set_name(&ctx, request.name).await?;
+    }
+    if *secret.description() != request.description {
+        secret.set_description(&ctx, request.description).await?;
+    }
+    if let Some(data) = request.new_secret_data {
+        secret
+            .update_encrypted_contents(
+                &ctx,
+                &data.crypted,
+                data.key_pair_pk,
+                data.version,
+                data.algorithm,
+            )
+            .await?;
+    }
+
+    WsEvent::change_set_written(&ctx)
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(SecretView::from_secret(&ctx, secret).await?))
+}
@@ -12,6 +12,7 @@ use thiserror::Error;
 use crate::server::state::AppState;
 
 pub mod auth_connect;
+pub mod list_workspaces;
 pub mod load_workspace;
 pub mod restore_authentication;
 
@@ -81,4 +82,5 @@ pub fn routes() -> Router<AppState> {
             get(restore_authentication::restore_authentication),
         )
         .route("/load_workspace", get(load_workspace::load_workspace))
+        .route("/list_workspaces", get(list_workspaces::list_workspaces))
 }
@@ -1,7 +1,9 @@
 use super::{SessionError, SessionResult};
 use crate::server::extract::{HandlerContext, RawAccessToken};
 use axum::Json;
-use dal::{DalContext, HistoryActor, KeyPair, Tenancy, User, UserPk, Workspace, WorkspacePk};
+use dal::{
+    DalContext, HistoryActor, KeyPair, Tenancy, User, UserPk, Workspace, WorkspacePk, WorkspaceRole,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
@@ -50,8 +52,8 @@ pub struct AuthApiUser {
 pub struct AuthApiWorkspace {
     pub id: WorkspacePk,
     pub display_name: String,
-    // dont need to do anything with these for now
     pub creator_user_id: UserPk,
+    // dont need to do anything with these for now
     pub instance_url: String,
     pub instance_env_type: String,
 }
@@ -115,8 +117,15 @@ async fn find_or_create_user_and_workspace(
         }
     };
 
-    // ensure workspace is associated to user
-    user.associate_workspace(&ctx, *workspace.pk()).await?;
+    // ensure workspace is associated to user; whoever created the workspace administers it and
+    // everyone else starts out as a viewer until an admin gives them more permissions
+    let role = if auth_api_workspace.creator_user_id == user.pk() {
+        WorkspaceRole::Admin
+    } else {
+        WorkspaceRole::Viewer
+    };
+    user.associate_workspace(&ctx, *workspace.pk(), role)
+        .await?;
 
     ctx.commit().await?;
 
@@ -0,0 +1,25 @@
+use axum::Json;
+use dal::{UserWorkspace, Workspace};
+use serde::{Deserialize, Serialize};
+
+use super::SessionResult;
+use crate::server::extract::{Authorization, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ListWorkspacesResponse {
+    pub workspaces: Vec<UserWorkspace>,
+}
+
+/// Lists the workspaces the current user belongs to, with their role in each. Any of them can be
+/// made the active one with the `WorkspacePk` header, without authenticating again.
+pub async fn list_workspaces(
+    HandlerContext(builder): HandlerContext,
+    Authorization(claim): Authorization,
+) -> SessionResult<Json<ListWorkspacesResponse>> {
+    let ctx = builder.build_default().await?;
+
+    let workspaces = Workspace::list_for_user(&ctx, claim.user_pk).await?;
+
+    Ok(Json(ListWorkspacesResponse { workspaces }))
+}
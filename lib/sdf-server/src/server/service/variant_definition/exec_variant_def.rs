@@ -19,7 +19,8 @@ use dal::{
         SchemaVariantDefinition, SchemaVariantDefinitionJson, SchemaVariantDefinitionMetadataJson,
     },
     AttributePrototypeId, ChangeSet, Func, FuncBinding, FuncId, HistoryActor, SchemaVariant,
-    SchemaVariantError, SchemaVariantId, StandardModel, User, Visibility, WsEvent,
+    SchemaVariantError, SchemaVariantId, StandardModel, User, Visibility, WorkspacePermission,
+    WsEvent,
 };
 use serde::{Deserialize, Serialize};
 use si_pkg::{
@@ -182,7 +183,11 @@ pub async fn exec_variant_def(
                 asset_func.clone(),
             )])),
             no_record: true,
+            // The asset is being authored in a change set, so it is not installed as a module.
+            permission: Some(WorkspacePermission::EditChangeSets),
             is_builtin: false,
+            workspace_pk: None,
+            selection: None,
         }),
     )
     .await?;
@@ -0,0 +1,151 @@
+use std::str::FromStr;
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use module_index_client::{IndexClient, IndexClientError};
+use si_pkg::{SiPkg, SiPkgError};
+use thiserror::Error;
+use ulid::Ulid;
+
+use dal::{
+    pkg::PkgError, DalContext, KeyPairError, TransactionsError, UserError, Workspace,
+    WorkspaceBackup, WorkspaceBackupPk, WorkspaceError as DalWorkspaceError, WorkspacePk,
+    WsEventError,
+};
+
+use crate::server::state::AppState;
+
+pub mod fork_workspace;
+pub mod list_backups;
+pub mod list_members;
+pub mod restore_backup;
+pub mod restore_backup_selection;
+pub mod rotate_key_pair;
+pub mod set_member_role;
+pub mod set_required_change_set_approvals;
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum WorkspaceError {
+    #[error("workspace backup not found: {0}")]
+    BackupNotFound(WorkspaceBackupPk),
+    #[error(transparent)]
+    DalWorkspace(#[from] DalWorkspaceError),
+    #[error("invalid module id: {0}")]
+    InvalidModuleId(#[from] ulid::DecodeError),
+    #[error(transparent)]
+    KeyPair(#[from] KeyPairError),
+    #[error("module index: {0}")]
+    ModuleIndex(#[from] IndexClientError),
+    #[error("module index not configured")]
+    ModuleIndexNotConfigured,
+    #[error("no workspace in tenancy")]
+    NoWorkspaceInTenancy,
+    #[error(transparent)]
+    SiPkg(#[from] SiPkgError),
+    #[error(transparent)]
+    Transactions(#[from] TransactionsError),
+    #[error("unable to parse url: {0}")]
+    Url(#[from] url::ParseError),
+    #[error(transparent)]
+    User(#[from] UserError),
+    #[error("workspace not found: {0}")]
+    WorkspaceNotFound(WorkspacePk),
+    #[error(transparent)]
+    WsEvent(#[from] WsEventError),
+}
+
+pub type WorkspaceResult<T> = std::result::Result<T, WorkspaceError>;
+
+impl IntoResponse for WorkspaceError {
+    fn into_response(self) -> Response {
+        let (status, error_message) = match &self {
+            WorkspaceError::BackupNotFound(_)
+            | WorkspaceError::WorkspaceNotFound(_)
+            | WorkspaceError::DalWorkspace(DalWorkspaceError::NotAMember(_, _)) => {
+                (StatusCode::NOT_FOUND, self.to_string())
+            }
+            WorkspaceError::DalWorkspace(
+                DalWorkspaceError::BackupOfAnotherWorkspace(_)
+                | DalWorkspaceError::InvalidRequiredChangeSetApprovals(_)
+                | DalWorkspaceError::LastAdmin(_, _)
+                | DalWorkspaceError::NotAWorkspaceBackup(_),
+            ) => (StatusCode::BAD_REQUEST, self.to_string()),
+            WorkspaceError::DalWorkspace(DalWorkspaceError::Pkg(err))
+                if matches!(**err, PkgError::SelectedItemNotInBackup(_)) =>
+            {
+                (StatusCode::BAD_REQUEST, self.to_string())
+            }
+            WorkspaceError::DalWorkspace(DalWorkspaceError::User(err))
+            | WorkspaceError::KeyPair(KeyPairError::User(err))
+            | WorkspaceError::User(err)
+                if err.is_permission_denied() =>
+            {
+                (StatusCode::FORBIDDEN, self.to_string())
+            }
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
+        };
+
+        let body = Json(
+            serde_json::json!({ "error": { "message": error_message, "code": 42, "statusCode": status.as_u16() } }),
+        );
+
+        (status, body).into_response()
+    }
+}
+
+/// Downloads a [`WorkspaceBackup`] of the workspace of the context's tenancy from the module
+/// index.
+async fn download_backup(
+    ctx: &DalContext,
+    raw_access_token: &str,
+    backup_pk: WorkspaceBackupPk,
+) -> WorkspaceResult<(WorkspaceBackup, SiPkg)> {
+    let backup = WorkspaceBackup::get_by_pk(ctx, backup_pk)
+        .await?
+        .ok_or(WorkspaceError::BackupNotFound(backup_pk))?;
+
+    let module_index_url = match ctx.module_index_url() {
+        Some(url) => url,
+        None => return Err(WorkspaceError::ModuleIndexNotConfigured),
+    };
+    let module_index_client = IndexClient::new(module_index_url.try_into()?, raw_access_token);
+    let pkg_data = module_index_client
+        .download_module(Ulid::from_str(&backup.module_id)?)
+        .await?;
+
+    Ok((backup, SiPkg::load_from_bytes(pkg_data)?))
+}
+
+/// Looks up the [`Workspace`] of the context's tenancy.
+async fn current_workspace(ctx: &DalContext) -> WorkspaceResult<Workspace> {
+    let workspace_pk = ctx
+        .tenancy()
+        .workspace_pk()
+        .ok_or(WorkspaceError::NoWorkspaceInTenancy)?;
+    Workspace::get_by_pk(ctx, &workspace_pk)
+        .await?
+        .ok_or(WorkspaceError::WorkspaceNotFound(workspace_pk))
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/fork", post(fork_workspace::fork_workspace))
+        .route("/list_backups", get(list_backups::list_backups))
+        .route("/list_members", get(list_members::list_members))
+        .route("/restore_backup", post(restore_backup::restore_backup))
+        .route(
+            "/restore_backup_selection",
+            post(restore_backup_selection::restore_backup_selection),
+        )
+        .route("/rotate_key_pair", post(rotate_key_pair::rotate_key_pair))
+        .route("/set_member_role", post(set_member_role::set_member_role))
+        .route(
+            "/set_required_change_set_approvals",
+            post(set_required_change_set_approvals::set_required_change_set_approvals),
+        )
+}
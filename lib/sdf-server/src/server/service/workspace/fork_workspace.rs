@@ -0,0 +1,46 @@
+use axum::extract::OriginalUri;
+use axum::Json;
+use dal::Workspace;
+use serde::{Deserialize, Serialize};
+
+use super::WorkspaceResult;
+use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::tracking::track;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ForkWorkspaceRequest {
+    pub name: String,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ForkWorkspaceResponse {
+    pub workspace: Workspace,
+}
+
+pub async fn fork_workspace(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Json(request): Json<ForkWorkspaceRequest>,
+) -> WorkspaceResult<Json<ForkWorkspaceResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let workspace = Workspace::fork(&ctx, &request.name).await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "fork_workspace",
+        serde_json::json!({
+            "fork_workspace_pk": workspace.pk(),
+        }),
+    );
+
+    ctx.commit().await?;
+
+    Ok(Json(ForkWorkspaceResponse { workspace }))
+}
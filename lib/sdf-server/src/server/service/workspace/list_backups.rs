@@ -0,0 +1,23 @@
+use axum::Json;
+use dal::WorkspaceBackup;
+use serde::{Deserialize, Serialize};
+
+use super::WorkspaceResult;
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ListBackupsResponse {
+    pub backups: Vec<WorkspaceBackup>,
+}
+
+pub async fn list_backups(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+) -> WorkspaceResult<Json<ListBackupsResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let backups = WorkspaceBackup::list(&ctx, None).await?;
+
+    Ok(Json(ListBackupsResponse { backups }))
+}
@@ -0,0 +1,24 @@
+use axum::Json;
+use dal::WorkspaceMember;
+use serde::{Deserialize, Serialize};
+
+use super::{current_workspace, WorkspaceResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ListMembersResponse {
+    pub members: Vec<WorkspaceMember>,
+}
+
+pub async fn list_members(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+) -> WorkspaceResult<Json<ListMembersResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let workspace = current_workspace(&ctx).await?;
+    let members = workspace.members(&ctx).await?;
+
+    Ok(Json(ListMembersResponse { members }))
+}
@@ -0,0 +1,52 @@
+use axum::extract::OriginalUri;
+use axum::Json;
+use dal::{Workspace, WorkspaceBackupPk, WorkspaceRestoreTarget};
+use serde::{Deserialize, Serialize};
+
+use super::{download_backup, WorkspaceResult};
+use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient, RawAccessToken};
+use crate::server::tracking::track;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreBackupRequest {
+    pub backup_pk: WorkspaceBackupPk,
+    pub target: WorkspaceRestoreTarget,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreBackupResponse {
+    pub workspace: Workspace,
+}
+
+pub async fn restore_backup(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    RawAccessToken(raw_access_token): RawAccessToken,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Json(request): Json<RestoreBackupRequest>,
+) -> WorkspaceResult<Json<RestoreBackupResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let (backup, pkg) = download_backup(&ctx, &raw_access_token, request.backup_pk).await?;
+
+    let workspace = Workspace::restore_backup(&ctx, &pkg, request.target).await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "restore_workspace_backup",
+        serde_json::json!({
+            "backup_pk": backup.pk,
+            "backup_version": backup.version,
+            "restored_workspace_pk": workspace.pk(),
+        }),
+    );
+
+    ctx.commit().await?;
+
+    Ok(Json(RestoreBackupResponse { workspace }))
+}
@@ -0,0 +1,59 @@
+use axum::extract::OriginalUri;
+use axum::Json;
+use dal::pkg::{ImportSelection, ImportSkips};
+use dal::{Visibility, Workspace, WorkspaceBackupPk, WsEvent};
+use serde::{Deserialize, Serialize};
+
+use super::{download_backup, WorkspaceResult};
+use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient, RawAccessToken};
+use crate::server::tracking::track;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreBackupSelectionRequest {
+    pub backup_pk: WorkspaceBackupPk,
+    pub selection: ImportSelection,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreBackupSelectionResponse {
+    pub import_skips: ImportSkips,
+}
+
+pub async fn restore_backup_selection(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    RawAccessToken(raw_access_token): RawAccessToken,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Json(request): Json<RestoreBackupSelectionRequest>,
+) -> WorkspaceResult<Json<RestoreBackupSelectionResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let (backup, pkg) = download_backup(&ctx, &raw_access_token, request.backup_pk).await?;
+
+    let import_skips = Workspace::restore_backup_selection(&ctx, &pkg, request.selection).await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "restore_workspace_backup_selection",
+        serde_json::json!({
+            "backup_pk": backup.pk,
+            "backup_version": backup.version,
+            "conflict_count": import_skips.conflicts().len(),
+        }),
+    );
+
+    WsEvent::change_set_written(&ctx)
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+    ctx.commit().await?;
+
+    Ok(Json(RestoreBackupSelectionResponse { import_skips }))
+}
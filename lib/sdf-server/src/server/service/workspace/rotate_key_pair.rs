@@ -0,0 +1,43 @@
+use axum::extract::OriginalUri;
+use axum::Json;
+use dal::{key_pair::KeyPairPk, KeyPair};
+use serde::{Deserialize, Serialize};
+
+use super::WorkspaceResult;
+use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::tracking::track;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RotateKeyPairResponse {
+    pub key_pair_pk: KeyPairPk,
+    pub reencrypted_secret_count: usize,
+}
+
+pub async fn rotate_key_pair(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+) -> WorkspaceResult<Json<RotateKeyPairResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let rotation = KeyPair::rotate(&ctx).await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "rotate_key_pair",
+        serde_json::json!({
+            "reencrypted_secret_count": rotation.reencrypted_secret_count,
+        }),
+    );
+
+    ctx.commit().await?;
+
+    Ok(Json(RotateKeyPairResponse {
+        key_pair_pk: rotation.key_pair.pk(),
+        reencrypted_secret_count: rotation.reencrypted_secret_count,
+    }))
+}
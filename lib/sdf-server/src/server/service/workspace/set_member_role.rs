@@ -0,0 +1,45 @@
+use axum::extract::OriginalUri;
+use axum::Json;
+use dal::{UserPk, WorkspaceRole};
+use serde::{Deserialize, Serialize};
+
+use super::{current_workspace, WorkspaceResult};
+use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::tracking::track;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SetMemberRoleRequest {
+    pub user_pk: UserPk,
+    pub role: WorkspaceRole,
+}
+
+pub async fn set_member_role(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Json(request): Json<SetMemberRoleRequest>,
+) -> WorkspaceResult<Json<()>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let workspace = current_workspace(&ctx).await?;
+    workspace
+        .set_member_role(&ctx, request.user_pk, request.role)
+        .await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "set_workspace_member_role",
+        serde_json::json!({
+            "user_pk": request.user_pk,
+            "role": request.role,
+        }),
+    );
+
+    ctx.commit().await?;
+
+    Ok(Json(()))
+}
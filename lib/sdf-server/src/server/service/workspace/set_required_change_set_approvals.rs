@@ -0,0 +1,42 @@
+use axum::extract::OriginalUri;
+use axum::Json;
+use serde::{Deserialize, Serialize};
+
+use super::{current_workspace, WorkspaceResult};
+use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::tracking::track;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SetRequiredChangeSetApprovalsRequest {
+    pub required: i32,
+}
+
+pub async fn set_required_change_set_approvals(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Json(request): Json<SetRequiredChangeSetApprovalsRequest>,
+) -> WorkspaceResult<Json<()>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let mut workspace = current_workspace(&ctx).await?;
+    workspace
+        .set_required_change_set_approvals(&ctx, request.required)
+        .await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "set_required_change_set_approvals",
+        serde_json::json!({
+            "required": request.required,
+        }),
+    );
+
+    ctx.commit().await?;
+
+    Ok(Json(()))
+}
@@ -14,6 +14,7 @@ pub struct AppState {
     jwt_public_signing_key: JwtPublicSigningKey,
     posthog_client: PosthogClient,
     shutdown_broadcast: ShutdownBroadcast,
+    max_concurrent_fixes: MaxConcurrentFixes,
     for_tests: bool,
 
     // TODO(fnichol): we're likely going to use this, but we can't allow it to be dropped because
@@ -23,6 +24,7 @@ pub struct AppState {
 }
 
 impl AppState {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         services_context: impl Into<ServicesContext>,
         signup_secret: impl Into<SignupSecret>,
@@ -30,6 +32,7 @@ impl AppState {
         posthog_client: impl Into<PosthogClient>,
         shutdown_broadcast_tx: broadcast::Sender<()>,
         tmp_shutdown_tx: mpsc::Sender<ShutdownSource>,
+        max_concurrent_fixes: usize,
         for_tests: bool,
     ) -> Self {
         Self {
@@ -38,6 +41,7 @@ impl AppState {
             jwt_public_signing_key: jwt_public_signing_key.into(),
             posthog_client: posthog_client.into(),
             shutdown_broadcast: ShutdownBroadcast(shutdown_broadcast_tx),
+            max_concurrent_fixes: MaxConcurrentFixes(max_concurrent_fixes),
             for_tests,
             _tmp_shutdown_tx: Arc::new(tmp_shutdown_tx),
         }
@@ -144,3 +148,7 @@ impl From<broadcast::Sender<()>> for ShutdownBroadcast {
         Self(value)
     }
 }
+
+/// The number of fixes in a batch that are allowed to run at the same time.
+#[derive(Clone, Copy, Debug)]
+pub struct MaxConcurrentFixes(pub usize);
@@ -1,13 +1,17 @@
 use axum::{http::Method, Router};
+use dal::ChangeSetStatus;
 use dal_test::{
     sdf_test, test_harness::create_change_set as dal_create_change_set, AuthTokenRef,
     DalContextHead,
 };
 use sdf_server::service::change_set::{
+    abandon_change_set::{AbandonChangeSetRequest, AbandonChangeSetResponse},
     apply_change_set::{ApplyChangeSetRequest, ApplyChangeSetResponse},
     create_change_set::{CreateChangeSetRequest, CreateChangeSetResponse},
     get_change_set::{GetChangeSetRequest, GetChangeSetResponse},
+    list_closed_change_sets::ListClosedChangeSetsResponse,
     list_open_change_sets::ListOpenChangeSetsResponse,
+    rebase_change_set::{RebaseChangeSetRequest, RebaseChangeSetResponse},
 };
 
 use crate::service_tests::{
@@ -34,6 +38,32 @@ async fn list_open_change_sets(
     assert_eq!(response.len(), 2);
 }
 
+#[sdf_test]
+async fn list_closed_change_sets(
+    DalContextHead(ctx): DalContextHead,
+    app: Router,
+    AuthTokenRef(auth_token): AuthTokenRef<'_>,
+) {
+    let _open_change_set = dal_create_change_set(&ctx).await;
+    let mut closed_change_set = dal_create_change_set(&ctx).await;
+    closed_change_set
+        .close(&ctx)
+        .await
+        .expect("cannot close change set");
+    ctx.commit().await.expect("cannot commit transaction");
+
+    let response: ListClosedChangeSetsResponse = api_request_auth_empty(
+        app,
+        Method::GET,
+        "/api/change_set/list_closed_change_sets",
+        auth_token,
+    )
+    .await;
+    assert_eq!(response.len(), 1);
+    assert_eq!(response[0].pk, closed_change_set.pk);
+    assert_eq!(&response[0].status, &ChangeSetStatus::Closed);
+}
+
 #[sdf_test]
 async fn create_change_set(app: Router, AuthTokenRef(auth_token): AuthTokenRef<'_>) {
     let request: CreateChangeSetRequest = CreateChangeSetRequest {
@@ -76,6 +106,7 @@ async fn apply_change_set(
     ctx.commit().await.expect("cannot commit txn");
     let request = ApplyChangeSetRequest {
         change_set_pk: change_set.pk,
+        override_conflicts: false,
     };
 
     let _response: ApplyChangeSetResponse = api_request_auth_json_body(
@@ -87,3 +118,50 @@ async fn apply_change_set(
     )
     .await;
 }
+
+#[sdf_test]
+async fn rebase_change_set(
+    DalContextHead(ctx): DalContextHead,
+    app: Router,
+    AuthTokenRef(auth_token): AuthTokenRef<'_>,
+) {
+    let change_set = dal_create_change_set(&ctx).await;
+    ctx.commit().await.expect("cannot commit txn");
+    let request = RebaseChangeSetRequest {
+        change_set_pk: change_set.pk,
+    };
+
+    let response: RebaseChangeSetResponse = api_request_auth_json_body(
+        app,
+        Method::POST,
+        "/api/change_set/rebase_change_set",
+        auth_token,
+        &request,
+    )
+    .await;
+    assert!(response.conflicts.is_empty());
+    assert!(response.change_set.rebased_at.is_some());
+}
+
+#[sdf_test]
+async fn abandon_change_set(
+    DalContextHead(ctx): DalContextHead,
+    app: Router,
+    AuthTokenRef(auth_token): AuthTokenRef<'_>,
+) {
+    let change_set = dal_create_change_set(&ctx).await;
+    ctx.commit().await.expect("cannot commit txn");
+    let request = AbandonChangeSetRequest {
+        change_set_pk: change_set.pk,
+    };
+
+    let response: AbandonChangeSetResponse = api_request_auth_json_body(
+        app,
+        Method::POST,
+        "/api/change_set/abandon_change_set",
+        auth_token,
+        &request,
+    )
+    .await;
+    assert_eq!(response.change_set.status, ChangeSetStatus::Abandoned);
+}
@@ -560,6 +560,7 @@ impl ScenarioHarness {
         assert!(!ctx.visibility().is_head());
         let request = ApplyChangeSetRequest {
             change_set_pk: ctx.visibility().change_set_pk,
+            override_conflicts: false,
         };
         let _response: ApplyChangeSetResponse = self
             .query_post("/api/change_set/apply_change_set", &request)
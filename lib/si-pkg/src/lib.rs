@@ -154,4 +154,69 @@ mod tests {
 
         let _ = dbg!(props.lock().await);
     }
+
+    #[tokio::test]
+    async fn action_func_retry_policy_round_trip() {
+        let mut spec: PkgSpec = serde_json::from_str(PACKAGE_JSON).unwrap();
+        let retry_policy = ActionFuncRetryPolicySpec::builder()
+            .max_attempts(3u32)
+            .backoff_ms(500u64)
+            .backoff_multiplier(2u32)
+            .retryable_status(ActionFuncRetryableStatus::Error)
+            .build()
+            .expect("able to build retry policy");
+        let variant_spec = spec
+            .schemas
+            .get_mut(0)
+            .expect("has schema")
+            .variants
+            .get_mut(0)
+            .expect("has variant");
+        let func_unique_id = variant_spec.func_unique_id.to_owned();
+        variant_spec.action_funcs = vec![
+            ActionFuncSpec::builder()
+                .kind(ActionFuncSpecKind::Create)
+                .func_unique_id(&func_unique_id)
+                .retry_policy(retry_policy.clone())
+                .build()
+                .expect("able to build action func"),
+            ActionFuncSpec::builder()
+                .kind(ActionFuncSpecKind::Refresh)
+                .func_unique_id(&func_unique_id)
+                .build()
+                .expect("able to build action func"),
+        ];
+
+        let pkg = SiPkg::load_from_spec(spec).expect("failed to load spec");
+        let pkg_data = pkg.write_to_bytes().expect("failed to serialize pkg");
+        let read_pkg = SiPkg::load_from_bytes(pkg_data).expect("failed to load pkg from bytes");
+
+        let variant = read_pkg
+            .schemas()
+            .expect("get schema")
+            .pop()
+            .expect("has schema")
+            .variants()
+            .expect("get variants")
+            .pop()
+            .expect("has a variant");
+        let action_funcs = variant.action_funcs().expect("get action funcs");
+        assert_eq!(2, action_funcs.len());
+        for action_func in action_funcs {
+            match action_func.kind() {
+                ActionFuncSpecKind::Create => {
+                    assert_eq!(Some(&retry_policy), action_func.retry_policy())
+                }
+                _ => assert_eq!(None, action_func.retry_policy()),
+            }
+        }
+
+        let variant_spec = variant.to_spec().await.expect("able to convert to spec");
+        let create_spec = variant_spec
+            .action_funcs
+            .iter()
+            .find(|action_func| action_func.kind == ActionFuncSpecKind::Create)
+            .expect("has create action func");
+        assert_eq!(Some(retry_policy), create_spec.retry_policy);
+    }
 }
@@ -8,13 +8,14 @@ use object_tree::{
     GraphError, NodeChild, NodeKind, NodeWithChildren, ReadBytes, WriteBytes,
 };
 
-use crate::{ActionFuncSpec, ActionFuncSpecKind};
+use crate::{ActionFuncRetryPolicySpec, ActionFuncSpec, ActionFuncSpecKind};
 
 use super::{read_common_fields, write_common_fields, PkgNode};
 
 const KEY_KIND_STR: &str = "kind";
 const KEY_FUNC_UNIQUE_ID_STR: &str = "func_unique_id";
 const KEY_NAME_STR: &str = "name";
+const KEY_RETRY_POLICY_STR: &str = "retry_policy";
 
 #[derive(Clone, Debug)]
 pub struct ActionFuncNode {
@@ -23,6 +24,7 @@ pub struct ActionFuncNode {
     pub kind: ActionFuncSpecKind,
     pub unique_id: Option<String>,
     pub deleted: bool,
+    pub retry_policy: Option<ActionFuncRetryPolicySpec>,
 }
 
 impl WriteBytes for ActionFuncNode {
@@ -39,6 +41,16 @@ impl WriteBytes for ActionFuncNode {
 
         write_common_fields(writer, self.unique_id.as_deref(), self.deleted)?;
 
+        // Written after the common fields so that packages without a retry policy keep the
+        // same bytes (and hashes) as before retry policies existed.
+        if let Some(retry_policy) = &self.retry_policy {
+            write_key_value_line(
+                writer,
+                KEY_RETRY_POLICY_STR,
+                serde_json::to_string(retry_policy).map_err(GraphError::parse)?,
+            )?;
+        }
+
         Ok(())
     }
 }
@@ -57,12 +69,20 @@ impl ReadBytes for ActionFuncNode {
 
         let (unique_id, deleted) = read_common_fields(reader)?;
 
+        let retry_policy = match read_key_value_line_opt(reader, KEY_RETRY_POLICY_STR)? {
+            None => None,
+            Some(retry_policy_str) => {
+                Some(serde_json::from_str(&retry_policy_str).map_err(GraphError::parse)?)
+            }
+        };
+
         Ok(Some(Self {
             name,
             kind,
             func_unique_id,
             unique_id,
             deleted,
+            retry_policy,
         }))
     }
 }
@@ -79,6 +99,7 @@ impl NodeChild for ActionFuncSpec {
                 kind: self.kind,
                 unique_id: self.unique_id.to_owned(),
                 deleted: self.deleted,
+                retry_policy: self.retry_policy.to_owned(),
             }),
             vec![],
         )
@@ -4,8 +4,8 @@ use std::{
 };
 
 use object_tree::{
-    read_key_value_line, write_key_value_line, GraphError, NameStr, NodeChild, NodeKind,
-    NodeWithChildren, ReadBytes, WriteBytes,
+    read_key_value_line, read_key_value_line_opt, write_key_value_line, write_key_value_line_opt,
+    GraphError, NameStr, NodeChild, NodeKind, NodeWithChildren, ReadBytes, WriteBytes,
 };
 
 use super::PkgNode;
@@ -17,12 +17,14 @@ use crate::{
 const KEY_NAME_STR: &str = "name";
 const KEY_BASED_ON_CHANGE_SET: &str = "based_on_change_set";
 const KEY_STATUS: &str = "status";
+const KEY_SOURCE_PK: &str = "source_pk";
 
 #[derive(Clone, Debug)]
 pub struct ChangeSetNode {
     pub name: String,
     pub based_on_change_set: Option<String>,
     pub status: ChangeSetSpecStatus,
+    pub source_pk: Option<String>,
 }
 
 impl NameStr for ChangeSetNode {
@@ -40,6 +42,8 @@ impl WriteBytes for ChangeSetNode {
             self.based_on_change_set.as_deref().unwrap_or(""),
         )?;
         write_key_value_line(writer, KEY_STATUS, self.status)?;
+        // Written last, and only when recorded, so that packages without it keep the same bytes
+        write_key_value_line_opt(writer, KEY_SOURCE_PK, self.source_pk.as_deref())?;
 
         Ok(())
     }
@@ -60,11 +64,13 @@ impl ReadBytes for ChangeSetNode {
 
         let status_str = read_key_value_line(reader, KEY_STATUS)?;
         let status = ChangeSetSpecStatus::from_str(&status_str).map_err(GraphError::parse)?;
+        let source_pk = read_key_value_line_opt(reader, KEY_SOURCE_PK)?;
 
         Ok(Some(Self {
             name,
             based_on_change_set,
             status,
+            source_pk,
         }))
     }
 }
@@ -79,6 +85,7 @@ impl NodeChild for ChangeSetSpec {
                 name: self.name.to_owned(),
                 status: self.status,
                 based_on_change_set: self.based_on_change_set.to_owned(),
+                source_pk: self.source_pk.to_owned(),
             }),
             vec![
                 Box::new(ChangeSetChild::Components(self.components.clone()))
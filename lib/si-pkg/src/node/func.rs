@@ -23,6 +23,7 @@ const KEY_RESPONSE_TYPE_STR: &str = "response_type";
 const KEY_HIDDEN_STR: &str = "hidden";
 const KEY_LINK_STR: &str = "link";
 const KEY_IS_FROM_BUILTIN: &str = "is_from_builtin";
+const KEY_IMPURE_STR: &str = "impure";
 
 #[derive(Clone, Debug)]
 pub struct FuncData {
@@ -35,6 +36,7 @@ pub struct FuncData {
     pub response_type: FuncSpecBackendResponseType,
     pub hidden: bool,
     pub link: Option<Url>,
+    pub impure: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -81,6 +83,12 @@ impl WriteBytes for FuncNode {
         write_common_fields(writer, Some(self.unique_id.as_str()), self.deleted)?;
         write_key_value_line_opt(writer, KEY_IS_FROM_BUILTIN, self.is_from_builtin)?;
 
+        // Written last, and only for impure funcs, so that packages without impure funcs keep
+        // the same bytes (and hashes) as before impure funcs existed.
+        if self.data.as_ref().map_or(false, |data| data.impure) {
+            write_key_value_line(writer, KEY_IMPURE_STR, true)?;
+        }
+
         Ok(())
     }
 }
@@ -91,7 +99,7 @@ impl ReadBytes for FuncNode {
         Self: std::marker::Sized,
     {
         let name = read_key_value_line(reader, KEY_NAME_STR)?;
-        let data = match read_key_value_line_opt(reader, KEY_DISPLAY_NAME_STR)? {
+        let mut data = match read_key_value_line_opt(reader, KEY_DISPLAY_NAME_STR)? {
             None => None,
             Some(display_name_str) => {
                 let display_name = if display_name_str.is_empty() {
@@ -132,6 +140,7 @@ impl ReadBytes for FuncNode {
                     response_type,
                     hidden,
                     link,
+                    impure: false,
                 })
             }
         };
@@ -143,6 +152,11 @@ impl ReadBytes for FuncNode {
         } else {
             None
         };
+        if let Some(impure_str) = read_key_value_line_opt(reader, KEY_IMPURE_STR)? {
+            if let Some(data) = data.as_mut() {
+                data.impure = bool::from_str(&impure_str).map_err(GraphError::parse)?;
+            }
+        }
 
         Ok(Some(Self {
             name,
@@ -178,6 +192,7 @@ impl NodeChild for FuncSpec {
                     response_type: data.response_type,
                     hidden: data.hidden,
                     link: data.link.as_ref().cloned(),
+                    impure: data.impure,
                 }),
                 unique_id: self.unique_id.to_owned(),
                 deleted: self.deleted,
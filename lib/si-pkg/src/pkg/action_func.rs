@@ -3,7 +3,7 @@ use petgraph::prelude::*;
 
 use super::{PkgResult, SiPkgError, Source};
 
-use crate::{node::PkgNode, ActionFuncSpec, ActionFuncSpecKind};
+use crate::{node::PkgNode, ActionFuncRetryPolicySpec, ActionFuncSpec, ActionFuncSpecKind};
 
 #[derive(Clone, Debug)]
 pub struct SiPkgActionFunc<'a> {
@@ -12,6 +12,7 @@ pub struct SiPkgActionFunc<'a> {
     kind: ActionFuncSpecKind,
     unique_id: Option<String>,
     deleted: bool,
+    retry_policy: Option<ActionFuncRetryPolicySpec>,
 
     hash: Hash,
     source: Source<'a>,
@@ -39,6 +40,7 @@ impl<'a> SiPkgActionFunc<'a> {
             kind: node.kind,
             unique_id: node.unique_id,
             deleted: node.deleted,
+            retry_policy: node.retry_policy,
 
             hash: hashed_node.hash(),
             source: Source::new(graph, node_idx),
@@ -65,6 +67,10 @@ impl<'a> SiPkgActionFunc<'a> {
         self.deleted
     }
 
+    pub fn retry_policy(&self) -> Option<&ActionFuncRetryPolicySpec> {
+        self.retry_policy.as_ref()
+    }
+
     pub fn hash(&self) -> Hash {
         self.hash
     }
@@ -78,9 +84,14 @@ impl<'a> TryFrom<SiPkgActionFunc<'a>> for ActionFuncSpec {
     type Error = SiPkgError;
 
     fn try_from(value: SiPkgActionFunc<'a>) -> Result<Self, Self::Error> {
-        Ok(ActionFuncSpec::builder()
-            .kind(value.kind())
-            .deleted(value.deleted())
+        let mut builder = ActionFuncSpec::builder();
+        if let Some(retry_policy) = value.retry_policy {
+            builder.retry_policy(retry_policy);
+        }
+
+        Ok(builder
+            .kind(value.kind)
+            .deleted(value.deleted)
             .func_unique_id(value.func_unique_id)
             .unique_id(value.unique_id)
             .build()?)
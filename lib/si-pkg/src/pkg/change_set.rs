@@ -12,6 +12,7 @@ pub struct SiPkgChangeSet<'a> {
     name: String,
     based_on_change_set: Option<String>,
     status: ChangeSetSpecStatus,
+    source_pk: Option<String>,
 
     hash: Hash,
 
@@ -69,6 +70,7 @@ impl<'a> SiPkgChangeSet<'a> {
             name: change_set_node.name,
             status: change_set_node.status,
             based_on_change_set: change_set_node.based_on_change_set,
+            source_pk: change_set_node.source_pk,
             hash: change_set_hashed_node.hash(),
             source: Source::new(graph, node_idx),
         };
@@ -88,6 +90,12 @@ impl<'a> SiPkgChangeSet<'a> {
         self.based_on_change_set.as_deref()
     }
 
+    /// The pk of the change set in the workspace the package was exported from, if it was
+    /// recorded.
+    pub fn source_pk(&self) -> Option<&str> {
+        self.source_pk.as_deref()
+    }
+
     pub fn hash(&self) -> Hash {
         self.hash
     }
@@ -108,6 +116,9 @@ impl<'a> SiPkgChangeSet<'a> {
         if let Some(based_on_change_set) = self.based_on_change_set() {
             builder.based_on_change_set(based_on_change_set);
         }
+        if let Some(source_pk) = self.source_pk() {
+            builder.source_pk(source_pk);
+        }
 
         for func in self.funcs()? {
             builder.func(FuncSpec::try_from(func)?);
@@ -105,6 +105,7 @@ pub struct SiPkgFuncData {
     response_type: FuncSpecBackendResponseType,
     hidden: bool,
     link: Option<Url>,
+    impure: bool,
 }
 
 impl SiPkgFuncData {
@@ -143,6 +144,10 @@ impl SiPkgFuncData {
     pub fn link(&self) -> Option<&Url> {
         self.link.as_ref()
     }
+
+    pub fn impure(&self) -> bool {
+        self.impure
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -185,6 +190,7 @@ impl<'a> SiPkgFunc<'a> {
                 response_type: data.response_type,
                 hidden: data.hidden,
                 link: data.link,
+                impure: data.impure,
             }),
             hash: func_hashed_node.hash(),
             unique_id: func_node.unique_id,
@@ -296,7 +302,8 @@ impl<'a> TryFrom<SiPkgFunc<'a>> for FuncSpec {
                 .code_base64(&data.code_base64)
                 .backend_kind(data.backend_kind)
                 .response_type(data.response_type)
-                .hidden(data.hidden);
+                .hidden(data.hidden)
+                .impure(data.impure);
 
             if let Some(display_name) = &data.display_name {
                 data_builder.display_name(display_name);
@@ -45,6 +45,10 @@ pub struct ActionFuncSpec {
     #[builder(setter(into), default)]
     #[serde(default)]
     pub deleted: bool,
+
+    #[builder(setter(into, strip_option), default)]
+    #[serde(default)]
+    pub retry_policy: Option<ActionFuncRetryPolicySpec>,
 }
 
 impl ActionFuncSpec {
@@ -52,3 +56,58 @@ impl ActionFuncSpec {
         ActionFuncSpecBuilder::default()
     }
 }
+
+/// The resource statuses an action can return that are worth retrying.
+#[derive(
+    Debug,
+    Serialize,
+    Deserialize,
+    Clone,
+    PartialEq,
+    Eq,
+    AsRefStr,
+    Display,
+    EnumIter,
+    EnumString,
+    Copy,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum ActionFuncRetryableStatus {
+    Error,
+    Warning,
+}
+
+/// How an action is retried when it does not succeed. Executions that fail without returning a
+/// resource are always retryable.
+#[derive(Builder, Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[builder(build_fn(error = "SpecError"))]
+pub struct ActionFuncRetryPolicySpec {
+    /// The total number of attempts, including the first one.
+    #[builder(setter(into))]
+    pub max_attempts: u32,
+
+    /// How long to wait before the first retry.
+    #[builder(setter(into), default)]
+    #[serde(default)]
+    pub backoff_ms: u64,
+
+    /// What the wait is multiplied by after every retry.
+    #[builder(setter(into), default = "1")]
+    #[serde(default = "default_backoff_multiplier")]
+    pub backoff_multiplier: u32,
+
+    #[builder(setter(each(name = "retryable_status", into)), default)]
+    #[serde(default)]
+    pub retryable_statuses: Vec<ActionFuncRetryableStatus>,
+}
+
+fn default_backoff_multiplier() -> u32 {
+    1
+}
+
+impl ActionFuncRetryPolicySpec {
+    pub fn builder() -> ActionFuncRetryPolicySpecBuilder {
+        ActionFuncRetryPolicySpecBuilder::default()
+    }
+}
@@ -26,6 +26,11 @@ pub struct ChangeSetSpec {
     #[builder(setter(into), default = "ChangeSetSpecStatus::Open")]
     pub status: ChangeSetSpecStatus,
 
+    /// The pk of the change set in the workspace it was exported from, if it was recorded.
+    #[builder(setter(into, strip_option), default)]
+    #[serde(default)]
+    pub source_pk: Option<String>,
+
     #[builder(setter(each(name = "component", into)), default)]
     #[serde(default)]
     pub components: Vec<ComponentSpec>,
@@ -120,6 +120,12 @@ pub struct FuncSpecData {
     pub hidden: bool,
     #[builder(setter(into, strip_option), default)]
     pub link: Option<Url>,
+    /// Whether the func may return different values for the same arguments, e.g. because it
+    /// depends on the time or has side effects. The values of impure funcs are never reused.
+    // Left out when `false` so that the unique ids of existing funcs do not change
+    #[builder(setter(into), default)]
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub impure: bool,
 }
 
 impl FuncSpecData {
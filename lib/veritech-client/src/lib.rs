@@ -6,18 +6,20 @@ use thiserror::Error;
 use tokio::sync::mpsc;
 
 use veritech_core::{
-    nats_action_run_subject, nats_reconciliation_subject, nats_resolver_function_subject,
-    nats_schema_variant_definition_subject, nats_subject, nats_validation_subject,
-    reply_mailbox_for_output, reply_mailbox_for_result, FINAL_MESSAGE_HEADER_KEY,
+    nats_action_run_subject, nats_cancel_execution_subject, nats_reconciliation_subject,
+    nats_resolver_function_subject, nats_schema_variant_definition_subject, nats_subject,
+    nats_validation_subject, reply_mailbox_for_output, reply_mailbox_for_result,
+    FINAL_MESSAGE_HEADER_KEY,
 };
 
 pub use cyclone_core::{
-    ActionRunRequest, ActionRunResultSuccess, ComponentKind, ComponentView, EncryptionKey,
-    EncryptionKeyError, FunctionResult, FunctionResultFailure, OutputStream, ReconciliationRequest,
-    ReconciliationResultSuccess, ResolverFunctionComponent, ResolverFunctionRequest,
-    ResolverFunctionResponseType, ResolverFunctionResultSuccess, ResourceStatus,
-    SchemaVariantDefinitionRequest, SchemaVariantDefinitionResultSuccess, SensitiveContainer,
-    ValidationRequest, ValidationResultSuccess,
+    ActionRunRequest, ActionRunResultSuccess, CancelExecutionRequest, ComponentKind, ComponentView,
+    EncryptionKey, EncryptionKeyError, FunctionResult, FunctionResultFailure,
+    FunctionResultFailureError, OutputStream, ReconciliationRequest, ReconciliationResultSuccess,
+    ResolverFunctionComponent, ResolverFunctionRequest, ResolverFunctionResponseType,
+    ResolverFunctionResultSuccess, ResourceStatus, SchemaVariantDefinitionRequest,
+    SchemaVariantDefinitionResultSuccess, SecretReference, SensitiveContainer, ValidationRequest,
+    ValidationResultSuccess, FILE_SECRET_BACKEND_NAME, SECRET_REFERENCE_MARKER,
 };
 use si_data_nats::NatsClient;
 
@@ -199,6 +201,25 @@ impl Client {
         .await
     }
 
+    /// Asks veritech to cancel the in-flight execution with the given id. The result of the
+    /// execution is then a failure of the
+    /// [`cancelled`](FunctionResultFailureError::KIND_CANCELLED) kind. Nothing happens if no
+    /// function is executing under that id.
+    #[instrument(name = "client.cancel_execution", skip_all)]
+    pub async fn cancel_execution(&self, execution_id: impl Into<String>) -> ClientResult<()> {
+        let msg = serde_json::to_vec(&CancelExecutionRequest {
+            execution_id: execution_id.into(),
+        })
+        .map_err(ClientError::JSONSerialize)?;
+        self.nats
+            .publish(
+                nats_cancel_execution_subject(self.nats_subject_prefix()),
+                msg,
+            )
+            .await?;
+        Ok(())
+    }
+
     async fn execute_request<R, S>(
         &self,
         subject: impl Into<String>,
@@ -12,6 +12,7 @@
 )]
 
 const NATS_ACTION_RUN_DEFAULT_SUBJECT: &str = "veritech.fn.actionrun";
+const NATS_CANCEL_EXECUTION_DEFAULT_SUBJECT: &str = "veritech.cancel";
 const NATS_CONCILIATION_DEFAULT_SUBJECT: &str = "veritech.fn.reconciliation";
 const NATS_RESOLVER_FUNCTION_DEFAULT_SUBJECT: &str = "veritech.fn.resolverfunction";
 const NATS_SCHEMA_VARIANT_DEFINITION_DEFAULT_SUBJECT: &str = "veritech.fn.schemavariantdefinition";
@@ -47,6 +48,10 @@ pub fn nats_schema_variant_definition_subject(prefix: Option<&str>) -> String {
     nats_subject(prefix, NATS_SCHEMA_VARIANT_DEFINITION_DEFAULT_SUBJECT)
 }
 
+pub fn nats_cancel_execution_subject(prefix: Option<&str>) -> String {
+    nats_subject(prefix, NATS_CANCEL_EXECUTION_DEFAULT_SUBJECT)
+}
+
 pub fn nats_subject(prefix: Option<&str>, suffix: impl AsRef<str>) -> String {
     let suffix = suffix.as_ref();
     match prefix {
@@ -8,10 +8,11 @@ use std::{
 use buck2_resources::Buck2Resources;
 use deadpool_cyclone::{
     instance::cyclone::{
-        LocalHttpInstance, LocalHttpInstanceSpec, LocalHttpSocketStrategy, LocalUdsInstance,
-        LocalUdsInstanceSpec, LocalUdsSocketStrategy,
+        ContainerEngine, ContainerInstance, ContainerInstanceSpec, LocalHttpInstance,
+        LocalHttpInstanceSpec, LocalHttpSocketStrategy, LocalUdsInstance, LocalUdsInstanceSpec,
+        LocalUdsSocketStrategy,
     },
-    Instance,
+    ExecutionLimits, ExecutionLimitsError, Instance, ScalingPolicy, ScalingPolicyError,
 };
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
@@ -28,9 +29,13 @@ pub enum ConfigError {
     Builder(#[from] ConfigBuilderError),
     #[error("cyclone spec build error")]
     CycloneSpecBuild(#[source] Box<dyn std::error::Error + 'static + Sync + Send>),
+    #[error(transparent)]
+    ExecutionLimits(#[from] ExecutionLimitsError),
     #[error("no socket addrs where resolved")]
     NoSocketAddrResolved,
     #[error(transparent)]
+    ScalingPolicy(#[from] ScalingPolicyError),
+    #[error(transparent)]
     Settings(#[from] si_settings::SettingsError),
     #[error("failed to resolve socket addrs")]
     SocketAddrResolve(#[source] std::io::Error),
@@ -50,11 +55,18 @@ pub struct Config {
     nats: NatsConfig,
 
     cyclone_spec: CycloneSpec,
+
+    #[builder(default)]
+    function_timeouts: FunctionTimeouts,
+
+    #[builder(default)]
+    cyclone_pool: ScalingPolicy,
 }
 
 #[remain::sorted]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum CycloneSpec {
+    Container(ContainerInstanceSpec),
     LocalHttp(LocalHttpInstanceSpec),
     LocalUds(LocalUdsInstanceSpec),
 }
@@ -67,13 +79,28 @@ impl StandardConfig for Config {
 pub struct ConfigFile {
     pub nats: NatsConfig,
     pub cyclone: CycloneConfig,
+    #[serde(default)]
+    pub function_timeouts: FunctionTimeouts,
+    #[serde(default)]
+    pub cyclone_pool: ScalingPolicy,
 }
 
 impl ConfigFile {
+    pub fn default_container() -> Self {
+        Self {
+            nats: Default::default(),
+            cyclone: CycloneConfig::default_container(),
+            function_timeouts: Default::default(),
+            cyclone_pool: Default::default(),
+        }
+    }
+
     pub fn default_local_http() -> Self {
         Self {
             nats: Default::default(),
             cyclone: CycloneConfig::default_local_http(),
+            function_timeouts: Default::default(),
+            cyclone_pool: Default::default(),
         }
     }
 
@@ -81,6 +108,8 @@ impl ConfigFile {
         Self {
             nats: Default::default(),
             cyclone: CycloneConfig::default_local_uds(),
+            function_timeouts: Default::default(),
+            cyclone_pool: Default::default(),
         }
     }
 }
@@ -98,6 +127,9 @@ impl TryFrom<ConfigFile> for Config {
         let mut config = Config::builder();
         config.nats(value.nats);
         config.cyclone_spec(value.cyclone.try_into()?);
+        config.function_timeouts(value.function_timeouts);
+        value.cyclone_pool.validate()?;
+        config.cyclone_pool(value.cyclone_pool);
         config.build().map_err(Into::into)
     }
 }
@@ -114,6 +146,16 @@ impl Config {
         &self.nats
     }
 
+    /// Gets a reference to the config's default function timeouts.
+    pub fn function_timeouts(&self) -> &FunctionTimeouts {
+        &self.function_timeouts
+    }
+
+    /// Gets a reference to the config's cyclone pool scaling policy.
+    pub fn cyclone_pool(&self) -> &ScalingPolicy {
+        &self.cyclone_pool
+    }
+
     /// Gets a reference to the config's subject prefix.
     pub fn subject_prefix(&self) -> Option<&str> {
         self.nats.subject_prefix.as_deref()
@@ -125,6 +167,34 @@ impl Config {
     }
 }
 
+/// How long, in seconds, each kind of function may run at most. A request may ask for a shorter
+/// timeout, never a longer one. Cyclone kills the functions that run for longer.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct FunctionTimeouts {
+    #[serde(default = "default_resolver_function_timeout_secs")]
+    pub resolver_function_secs: u64,
+    #[serde(default = "default_validation_timeout_secs")]
+    pub validation_secs: u64,
+    #[serde(default = "default_action_run_timeout_secs")]
+    pub action_run_secs: u64,
+    #[serde(default = "default_reconciliation_timeout_secs")]
+    pub reconciliation_secs: u64,
+    #[serde(default = "default_schema_variant_definition_timeout_secs")]
+    pub schema_variant_definition_secs: u64,
+}
+
+impl Default for FunctionTimeouts {
+    fn default() -> Self {
+        Self {
+            resolver_function_secs: default_resolver_function_timeout_secs(),
+            validation_secs: default_validation_timeout_secs(),
+            action_run_secs: default_action_run_timeout_secs(),
+            reconciliation_secs: default_reconciliation_timeout_secs(),
+            schema_variant_definition_secs: default_schema_variant_definition_timeout_secs(),
+        }
+    }
+}
+
 #[remain::sorted]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum CycloneStream {
@@ -157,6 +227,34 @@ impl CycloneStream {
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(tag = "kind")]
 pub enum CycloneConfig {
+    Container {
+        #[serde(default)]
+        engine: ContainerEngine,
+        #[serde(default = "default_container_image")]
+        image: String,
+        #[serde(default = "default_cyclone_cmd_path")]
+        cyclone_cmd_path: String,
+        #[serde(default = "default_cyclone_decryption_key_path")]
+        cyclone_decryption_key_path: String,
+        #[serde(default = "default_lang_server_cmd_path")]
+        lang_server_cmd_path: String,
+        #[serde(default)]
+        socket_parent_dir: Option<PathBuf>,
+        #[serde(default)]
+        watch_timeout: Option<Duration>,
+        #[serde(default = "default_limit_requests")]
+        limit_requets: Option<u32>,
+        #[serde(default = "default_enable_endpoint")]
+        ping: bool,
+        #[serde(default = "default_enable_endpoint")]
+        resolver: bool,
+        #[serde(default = "default_enable_endpoint")]
+        action: bool,
+        #[serde(default)]
+        execution_limits: ExecutionLimits,
+        #[serde(default)]
+        file_secret_backend_dir: Option<PathBuf>,
+    },
     LocalHttp {
         #[serde(default = "default_cyclone_cmd_path")]
         cyclone_cmd_path: String,
@@ -176,6 +274,10 @@ pub enum CycloneConfig {
         resolver: bool,
         #[serde(default = "default_enable_endpoint")]
         action: bool,
+        #[serde(default)]
+        execution_limits: ExecutionLimits,
+        #[serde(default)]
+        file_secret_backend_dir: Option<PathBuf>,
     },
     LocalUds {
         #[serde(default = "default_cyclone_cmd_path")]
@@ -196,10 +298,32 @@ pub enum CycloneConfig {
         resolver: bool,
         #[serde(default = "default_enable_endpoint")]
         action: bool,
+        #[serde(default)]
+        execution_limits: ExecutionLimits,
+        #[serde(default)]
+        file_secret_backend_dir: Option<PathBuf>,
     },
 }
 
 impl CycloneConfig {
+    pub fn default_container() -> Self {
+        Self::Container {
+            engine: Default::default(),
+            image: default_container_image(),
+            cyclone_cmd_path: default_cyclone_cmd_path(),
+            cyclone_decryption_key_path: default_cyclone_decryption_key_path(),
+            lang_server_cmd_path: default_lang_server_cmd_path(),
+            socket_parent_dir: Default::default(),
+            watch_timeout: Default::default(),
+            limit_requets: default_limit_requests(),
+            ping: default_enable_endpoint(),
+            resolver: default_enable_endpoint(),
+            action: default_enable_endpoint(),
+            execution_limits: Default::default(),
+            file_secret_backend_dir: Default::default(),
+        }
+    }
+
     pub fn default_local_http() -> Self {
         Self::LocalHttp {
             cyclone_cmd_path: default_cyclone_cmd_path(),
@@ -211,6 +335,8 @@ impl CycloneConfig {
             ping: default_enable_endpoint(),
             resolver: default_enable_endpoint(),
             action: default_enable_endpoint(),
+            execution_limits: Default::default(),
+            file_secret_backend_dir: Default::default(),
         }
     }
 
@@ -225,6 +351,8 @@ impl CycloneConfig {
             ping: default_enable_endpoint(),
             resolver: default_enable_endpoint(),
             action: default_enable_endpoint(),
+            execution_limits: Default::default(),
+            file_secret_backend_dir: Default::default(),
         }
     }
 
@@ -236,6 +364,9 @@ impl CycloneConfig {
             CycloneConfig::LocalHttp {
                 cyclone_cmd_path, ..
             } => cyclone_cmd_path,
+            CycloneConfig::Container {
+                cyclone_cmd_path, ..
+            } => cyclone_cmd_path,
         }
     }
 
@@ -247,6 +378,9 @@ impl CycloneConfig {
             CycloneConfig::LocalHttp {
                 cyclone_cmd_path, ..
             } => *cyclone_cmd_path = value,
+            CycloneConfig::Container {
+                cyclone_cmd_path, ..
+            } => *cyclone_cmd_path = value,
         };
     }
 
@@ -260,6 +394,10 @@ impl CycloneConfig {
                 cyclone_decryption_key_path,
                 ..
             } => cyclone_decryption_key_path,
+            CycloneConfig::Container {
+                cyclone_decryption_key_path,
+                ..
+            } => cyclone_decryption_key_path,
         }
     }
 
@@ -273,6 +411,10 @@ impl CycloneConfig {
                 cyclone_decryption_key_path,
                 ..
             } => *cyclone_decryption_key_path = value,
+            CycloneConfig::Container {
+                cyclone_decryption_key_path,
+                ..
+            } => *cyclone_decryption_key_path = value,
         };
     }
 
@@ -286,6 +428,10 @@ impl CycloneConfig {
                 lang_server_cmd_path,
                 ..
             } => lang_server_cmd_path,
+            CycloneConfig::Container {
+                lang_server_cmd_path,
+                ..
+            } => lang_server_cmd_path,
         }
     }
 
@@ -299,6 +445,10 @@ impl CycloneConfig {
                 lang_server_cmd_path,
                 ..
             } => *lang_server_cmd_path = value,
+            CycloneConfig::Container {
+                lang_server_cmd_path,
+                ..
+            } => *lang_server_cmd_path = value,
         };
     }
 
@@ -306,6 +456,7 @@ impl CycloneConfig {
         match self {
             CycloneConfig::LocalUds { limit_requets, .. } => *limit_requets = value.into(),
             CycloneConfig::LocalHttp { limit_requets, .. } => *limit_requets = value.into(),
+            CycloneConfig::Container { limit_requets, .. } => *limit_requets = value.into(),
         };
     }
 
@@ -313,6 +464,7 @@ impl CycloneConfig {
         match self {
             CycloneConfig::LocalUds { ping, .. } => *ping = value,
             CycloneConfig::LocalHttp { ping, .. } => *ping = value,
+            CycloneConfig::Container { ping, .. } => *ping = value,
         };
     }
 
@@ -320,6 +472,7 @@ impl CycloneConfig {
         match self {
             CycloneConfig::LocalUds { resolver, .. } => *resolver = value,
             CycloneConfig::LocalHttp { resolver, .. } => *resolver = value,
+            CycloneConfig::Container { resolver, .. } => *resolver = value,
         };
     }
 
@@ -327,6 +480,39 @@ impl CycloneConfig {
         match self {
             CycloneConfig::LocalUds { action, .. } => *action = value,
             CycloneConfig::LocalHttp { action, .. } => *action = value,
+            CycloneConfig::Container { action, .. } => *action = value,
+        };
+    }
+
+    pub fn set_execution_limits(&mut self, value: ExecutionLimits) {
+        match self {
+            CycloneConfig::LocalUds {
+                execution_limits, ..
+            } => *execution_limits = value,
+            CycloneConfig::LocalHttp {
+                execution_limits, ..
+            } => *execution_limits = value,
+            CycloneConfig::Container {
+                execution_limits, ..
+            } => *execution_limits = value,
+        };
+    }
+
+    pub fn set_file_secret_backend_dir(&mut self, value: impl Into<PathBuf>) {
+        let value = Some(value.into());
+        match self {
+            CycloneConfig::LocalUds {
+                file_secret_backend_dir,
+                ..
+            } => *file_secret_backend_dir = value,
+            CycloneConfig::LocalHttp {
+                file_secret_backend_dir,
+                ..
+            } => *file_secret_backend_dir = value,
+            CycloneConfig::Container {
+                file_secret_backend_dir,
+                ..
+            } => *file_secret_backend_dir = value,
         };
     }
 }
@@ -342,6 +528,53 @@ impl TryFrom<CycloneConfig> for CycloneSpec {
 
     fn try_from(value: CycloneConfig) -> std::result::Result<Self, Self::Error> {
         match value {
+            CycloneConfig::Container {
+                engine,
+                image,
+                cyclone_cmd_path,
+                cyclone_decryption_key_path,
+                lang_server_cmd_path,
+                socket_parent_dir,
+                watch_timeout,
+                limit_requets,
+                ping,
+                resolver,
+                action,
+                execution_limits,
+                file_secret_backend_dir,
+            } => {
+                let mut builder = ContainerInstance::spec();
+                builder.engine(engine);
+                builder.image(image);
+                builder.cyclone_cmd_path(cyclone_cmd_path);
+                builder.cyclone_decryption_key_path(cyclone_decryption_key_path);
+                builder.lang_server_cmd_path(lang_server_cmd_path);
+                if let Some(socket_parent_dir) = socket_parent_dir {
+                    builder.socket_parent_dir(socket_parent_dir);
+                }
+                if let Some(watch_timeout) = watch_timeout {
+                    builder.watch_timeout(watch_timeout);
+                }
+                builder.limit_requests(limit_requets);
+                if ping {
+                    builder.ping();
+                }
+                if resolver {
+                    builder.resolver();
+                }
+                if action {
+                    builder.action();
+                }
+                execution_limits.validate()?;
+                builder.execution_limits(execution_limits);
+                if let Some(file_secret_backend_dir) = file_secret_backend_dir {
+                    builder.file_secret_backend_dir(file_secret_backend_dir);
+                }
+
+                Ok(Self::Container(
+                    builder.build().map_err(ConfigError::cyclone_spec_build)?,
+                ))
+            }
             CycloneConfig::LocalUds {
                 cyclone_cmd_path,
                 cyclone_decryption_key_path,
@@ -352,6 +585,8 @@ impl TryFrom<CycloneConfig> for CycloneSpec {
                 ping,
                 resolver,
                 action,
+                execution_limits,
+                file_secret_backend_dir,
             } => {
                 let mut builder = LocalUdsInstance::spec();
                 builder
@@ -375,6 +610,11 @@ impl TryFrom<CycloneConfig> for CycloneSpec {
                 if action {
                     builder.action();
                 }
+                execution_limits.validate()?;
+                builder.execution_limits(execution_limits);
+                if let Some(file_secret_backend_dir) = file_secret_backend_dir {
+                    builder.file_secret_backend_dir(file_secret_backend_dir);
+                }
 
                 Ok(Self::LocalUds(
                     builder.build().map_err(ConfigError::cyclone_spec_build)?,
@@ -390,6 +630,8 @@ impl TryFrom<CycloneConfig> for CycloneSpec {
                 ping,
                 resolver,
                 action,
+                execution_limits,
+                file_secret_backend_dir,
             } => {
                 let mut builder = LocalHttpInstance::spec();
                 builder
@@ -413,6 +655,11 @@ impl TryFrom<CycloneConfig> for CycloneSpec {
                 if action {
                     builder.action();
                 }
+                execution_limits.validate()?;
+                builder.execution_limits(execution_limits);
+                if let Some(file_secret_backend_dir) = file_secret_backend_dir {
+                    builder.file_secret_backend_dir(file_secret_backend_dir);
+                }
 
                 Ok(Self::LocalHttp(
                     builder.build().map_err(ConfigError::cyclone_spec_build)?,
@@ -422,6 +669,10 @@ impl TryFrom<CycloneConfig> for CycloneSpec {
     }
 }
 
+fn default_container_image() -> String {
+    "systeminit/veritech:stable".to_string()
+}
+
 fn default_cyclone_cmd_path() -> String {
     "/usr/local/bin/cyclone".to_string()
 }
@@ -442,6 +693,26 @@ fn default_enable_endpoint() -> bool {
     true
 }
 
+fn default_resolver_function_timeout_secs() -> u64 {
+    5 * 60
+}
+
+fn default_validation_timeout_secs() -> u64 {
+    60
+}
+
+fn default_action_run_timeout_secs() -> u64 {
+    15 * 60
+}
+
+fn default_reconciliation_timeout_secs() -> u64 {
+    5 * 60
+}
+
+fn default_schema_variant_definition_timeout_secs() -> u64 {
+    60
+}
+
 #[allow(clippy::disallowed_methods)] // Used to determine if running in development
 pub fn detect_and_configure_development(config: &mut ConfigFile) -> Result<()> {
     if env::var("BUCK_RUN_BUILD_ID").is_ok() || env::var("BUCK_BUILD_ID").is_ok() {
@@ -479,13 +750,16 @@ fn buck2_development(config: &mut ConfigFile) -> Result<()> {
         "detected development run",
     );
 
-    config.cyclone.set_cyclone_cmd_path(cyclone_cmd_path);
     config
         .cyclone
         .set_cyclone_decryption_key_path(cyclone_decryption_key_path);
-    config
-        .cyclone
-        .set_lang_server_cmd_path(lang_server_cmd_path);
+    // A container runs the programs of its image
+    if !matches!(config.cyclone, CycloneConfig::Container { .. }) {
+        config.cyclone.set_cyclone_cmd_path(cyclone_cmd_path);
+        config
+            .cyclone
+            .set_lang_server_cmd_path(lang_server_cmd_path);
+    }
 
     Ok(())
 }
@@ -519,13 +793,16 @@ fn cargo_development(dir: String, config: &mut ConfigFile) -> Result<()> {
         "detected development run",
     );
 
-    config.cyclone.set_cyclone_cmd_path(cyclone_cmd_path);
     config
         .cyclone
         .set_cyclone_decryption_key_path(cyclone_decryption_key_path);
-    config
-        .cyclone
-        .set_lang_server_cmd_path(lang_server_cmd_path);
+    // A container runs the programs of its image
+    if !matches!(config.cyclone, CycloneConfig::Container { .. }) {
+        config.cyclone.set_cyclone_cmd_path(cyclone_cmd_path);
+        config
+            .cyclone
+            .set_lang_server_cmd_path(lang_server_cmd_path);
+    }
 
     Ok(())
 }
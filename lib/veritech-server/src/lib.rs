@@ -6,7 +6,7 @@ mod subscriber;
 pub use crate::{
     config::{
         detect_and_configure_development, Config, ConfigBuilder, ConfigError, ConfigFile,
-        CycloneSpec, CycloneStream, StandardConfig, StandardConfigFile,
+        CycloneSpec, CycloneStream, FunctionTimeouts, StandardConfig, StandardConfigFile,
     },
     server::{Server, ServerError, VeritechShutdownHandle},
 };
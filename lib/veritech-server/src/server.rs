@@ -1,15 +1,21 @@
 use chrono::Utc;
 use deadpool_cyclone::{
-    instance::cyclone::LocalUdsInstanceSpec, ActionRunRequest, ActionRunResultSuccess,
-    CycloneClient, FunctionResult, FunctionResultFailure, FunctionResultFailureError, Manager,
-    Pool, ProgressMessage, ReconciliationRequest, ReconciliationResultSuccess,
-    ResolverFunctionRequest, ResolverFunctionResultSuccess, SchemaVariantDefinitionRequest,
-    SchemaVariantDefinitionResultSuccess, ValidationRequest, ValidationResultSuccess,
+    instance::cyclone::{ContainerInstanceSpec, LocalUdsInstanceSpec},
+    ActionRunRequest, ActionRunResultSuccess, CancelExecutionRequest, CycloneClient,
+    ExecutionError, ExecutionStarted, FunctionResult, FunctionResultFailure,
+    FunctionResultFailureError, Manager, Object, Pool, PoolScaler, ProgressMessage,
+    ReconciliationRequest, ReconciliationResultSuccess, ResolverFunctionRequest,
+    ResolverFunctionResultSuccess, SchemaVariantDefinitionRequest,
+    SchemaVariantDefinitionResultSuccess, UnixStream, ValidationRequest, ValidationResultSuccess,
 };
 use futures::{channel::oneshot, join, StreamExt};
-use nats_subscriber::Request;
+use nats_subscriber::{Request, Subscriber};
+use serde::de::DeserializeOwned;
 use si_data_nats::NatsClient;
-use std::io;
+use std::{
+    fmt, io,
+    ops::{Deref, DerefMut},
+};
 use telemetry::prelude::*;
 use thiserror::Error;
 use tokio::{
@@ -17,7 +23,9 @@ use tokio::{
     sync::{broadcast, mpsc},
 };
 
-use crate::{config::CycloneSpec, Config, FunctionSubscriber, Publisher, PublisherError};
+use crate::{
+    config::CycloneSpec, Config, FunctionSubscriber, FunctionTimeouts, Publisher, PublisherError,
+};
 
 #[remain::sorted]
 #[derive(Error, Debug)]
@@ -61,7 +69,10 @@ type ServerResult<T> = Result<T, ServerError>;
 pub struct Server {
     nats: NatsClient,
     subject_prefix: Option<String>,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: CyclonePool,
+    function_timeouts: FunctionTimeouts,
+    /// Carries the ids of the executions to cancel to every in-flight execution.
+    cancel_broadcast_tx: broadcast::Sender<String>,
     shutdown_broadcast_tx: broadcast::Sender<()>,
     shutdown_tx: mpsc::Sender<ShutdownSource>,
     shutdown_rx: oneshot::Receiver<()>,
@@ -88,10 +99,9 @@ impl Server {
                 // Ok(Server { nats, cyclone_pool })
                 unimplemented!("get ready for a surprise!!")
             }
-            wrong @ CycloneSpec::LocalUds(_) => Err(ServerError::WrongCycloneSpec(
-                "LocalHttp",
-                Box::new(wrong.clone()),
-            )),
+            wrong @ (CycloneSpec::Container(_) | CycloneSpec::LocalUds(_)) => Err(
+                ServerError::WrongCycloneSpec("LocalHttp", Box::new(wrong.clone())),
+            ),
         }
     }
 
@@ -99,38 +109,66 @@ impl Server {
     pub async fn for_cyclone_uds(config: Config) -> ServerResult<Server> {
         match config.cyclone_spec() {
             CycloneSpec::LocalUds(spec) => {
-                let (shutdown_tx, shutdown_rx) = mpsc::channel(4);
-                // Note the channel parameter corresponds to the number of channels that may be
-                // maintained when the sender is guaranteeing delivery. While this number may end
-                // of being related to the number of subscribers, it's not
-                // necessarily the same number.
-                let (shutdown_broadcast_tx, _) = broadcast::channel(16);
-
-                let nats = connect_to_nats(&config).await?;
-                let manager = Manager::new(spec.clone());
-                let cyclone_pool = Pool::builder(manager)
+                let manager =
+                    Manager::new(spec.clone()).with_scaling_policy(*config.cyclone_pool());
+                let cyclone_pool = manager
+                    .pool_builder()
                     .build()
                     .map_err(|err| ServerError::CycloneSpec(Box::new(err)))?;
 
-                let graceful_shutdown_rx =
-                    prepare_graceful_shutdown(shutdown_rx, shutdown_broadcast_tx.clone())?;
-
-                Ok(Server {
-                    nats,
-                    subject_prefix: config.subject_prefix().map(|s| s.to_string()),
-                    cyclone_pool,
-                    shutdown_broadcast_tx,
-                    shutdown_tx,
-                    shutdown_rx: graceful_shutdown_rx,
-                })
+                Self::from_cyclone_pool(&config, CyclonePool::LocalUds(cyclone_pool)).await
             }
-            wrong @ CycloneSpec::LocalHttp(_) => Err(ServerError::WrongCycloneSpec(
-                "LocalUds",
-                Box::new(wrong.clone()),
-            )),
+            wrong @ (CycloneSpec::Container(_) | CycloneSpec::LocalHttp(_)) => Err(
+                ServerError::WrongCycloneSpec("LocalUds", Box::new(wrong.clone())),
+            ),
+        }
+    }
+
+    #[instrument(name = "veritech.init.cyclone.container", skip(config))]
+    pub async fn for_cyclone_container(config: Config) -> ServerResult<Server> {
+        match config.cyclone_spec() {
+            CycloneSpec::Container(spec) => {
+                let manager =
+                    Manager::new(spec.clone()).with_scaling_policy(*config.cyclone_pool());
+                let cyclone_pool = manager
+                    .pool_builder()
+                    .build()
+                    .map_err(|err| ServerError::CycloneSpec(Box::new(err)))?;
+
+                Self::from_cyclone_pool(&config, CyclonePool::Container(cyclone_pool)).await
+            }
+            wrong @ (CycloneSpec::LocalHttp(_) | CycloneSpec::LocalUds(_)) => Err(
+                ServerError::WrongCycloneSpec("Container", Box::new(wrong.clone())),
+            ),
         }
     }
 
+    async fn from_cyclone_pool(config: &Config, cyclone_pool: CyclonePool) -> ServerResult<Server> {
+        let (shutdown_tx, shutdown_rx) = mpsc::channel(4);
+        // Note the channel parameter corresponds to the number of channels that may be
+        // maintained when the sender is guaranteeing delivery. While this number may end
+        // of being related to the number of subscribers, it's not
+        // necessarily the same number.
+        let (shutdown_broadcast_tx, _) = broadcast::channel(16);
+        let (cancel_broadcast_tx, _) = broadcast::channel(64);
+
+        let nats = connect_to_nats(config).await?;
+
+        let graceful_shutdown_rx =
+            prepare_graceful_shutdown(shutdown_rx, shutdown_broadcast_tx.clone())?;
+
+        Ok(Server {
+            nats,
+            subject_prefix: config.subject_prefix().map(|s| s.to_string()),
+            cyclone_pool,
+            function_timeouts: *config.function_timeouts(),
+            cancel_broadcast_tx,
+            shutdown_broadcast_tx,
+            shutdown_tx,
+            shutdown_rx: graceful_shutdown_rx,
+        })
+    }
+
     /// Gets a shutdown handle that can trigger the server's graceful shutdown process.
     pub fn shutdown_handle(&self) -> VeritechShutdownHandle {
         VeritechShutdownHandle {
@@ -146,29 +184,49 @@ impl Server {
                 self.nats.clone(),
                 self.subject_prefix.clone(),
                 self.cyclone_pool.clone(),
+                self.function_timeouts.resolver_function_secs,
+                self.cancel_broadcast_tx.clone(),
                 self.shutdown_broadcast_tx.subscribe(),
             ),
             process_validation_requests_task(
                 self.nats.clone(),
                 self.subject_prefix.clone(),
                 self.cyclone_pool.clone(),
+                self.function_timeouts.validation_secs,
+                self.cancel_broadcast_tx.clone(),
                 self.shutdown_broadcast_tx.subscribe(),
             ),
             process_action_run_requests_task(
                 self.nats.clone(),
                 self.subject_prefix.clone(),
                 self.cyclone_pool.clone(),
+                self.function_timeouts.action_run_secs,
+                self.cancel_broadcast_tx.clone(),
                 self.shutdown_broadcast_tx.subscribe(),
             ),
             process_reconciliation_requests_task(
                 self.nats.clone(),
                 self.subject_prefix.clone(),
                 self.cyclone_pool.clone(),
+                self.function_timeouts.reconciliation_secs,
+                self.cancel_broadcast_tx.clone(),
                 self.shutdown_broadcast_tx.subscribe(),
             ),
             process_schema_variant_definition_requests_task(
                 self.nats.clone(),
                 self.subject_prefix.clone(),
+                self.cyclone_pool.clone(),
+                self.function_timeouts.schema_variant_definition_secs,
+                self.cancel_broadcast_tx.clone(),
+                self.shutdown_broadcast_tx.subscribe(),
+            ),
+            process_cancel_execution_requests_task(
+                self.nats.clone(),
+                self.subject_prefix.clone(),
+                self.cancel_broadcast_tx.clone(),
+                self.shutdown_broadcast_tx.subscribe(),
+            ),
+            scale_cyclone_pool_task(
                 self.cyclone_pool.clone(),
                 self.shutdown_broadcast_tx.subscribe(),
             ),
@@ -181,6 +239,67 @@ impl Server {
     }
 }
 
+/// The pool of Cyclone instances of a [`Server`], spawned from either kind of [`CycloneSpec`]
+/// the server supports.
+#[remain::sorted]
+#[derive(Clone)]
+enum CyclonePool {
+    Container(Pool<ContainerInstanceSpec>),
+    LocalUds(Pool<LocalUdsInstanceSpec>),
+}
+
+impl CyclonePool {
+    async fn get(&self) -> ServerResult<CycloneObject> {
+        match self {
+            Self::Container(pool) => pool
+                .get()
+                .await
+                .map(CycloneObject::Container)
+                .map_err(|err| ServerError::CyclonePool(Box::new(err))),
+            Self::LocalUds(pool) => pool
+                .get()
+                .await
+                .map(CycloneObject::LocalUds)
+                .map_err(|err| ServerError::CyclonePool(Box::new(err))),
+        }
+    }
+
+    /// Keeps the pool sized by its scaling policy, until dropped.
+    async fn scale(self) {
+        match self {
+            Self::Container(pool) => PoolScaler::new(pool).run().await,
+            Self::LocalUds(pool) => PoolScaler::new(pool).run().await,
+        }
+    }
+}
+
+/// A Cyclone instance checked out of a [`CyclonePool`], returned to it when dropped.
+#[remain::sorted]
+enum CycloneObject {
+    Container(Object<ContainerInstanceSpec>),
+    LocalUds(Object<LocalUdsInstanceSpec>),
+}
+
+impl Deref for CycloneObject {
+    type Target = dyn CycloneClient<UnixStream> + Send;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::Container(object) => &**object,
+            Self::LocalUds(object) => &**object,
+        }
+    }
+}
+
+impl DerefMut for CycloneObject {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match self {
+            Self::Container(object) => &mut **object,
+            Self::LocalUds(object) => &mut **object,
+        }
+    }
+}
+
 pub struct VeritechShutdownHandle {
     shutdown_tx: mpsc::Sender<ShutdownSource>,
 }
@@ -193,6 +312,19 @@ impl VeritechShutdownHandle {
     }
 }
 
+async fn scale_cyclone_pool_task(
+    cyclone_pool: CyclonePool,
+    mut shutdown_broadcast_rx: broadcast::Receiver<()>,
+) {
+    tokio::select! {
+        // Got a broadcasted shutdown message
+        _ = shutdown_broadcast_rx.recv() => {
+            trace!("scale cyclone pool task received shutdown");
+        }
+        _ = cyclone_pool.scale() => {}
+    }
+}
+
 // NOTE(fnichol): resolver function, action are parallel and extremely similar, so there
 // is a lurking "unifying" refactor here. It felt like waiting until the third time adding one of
 // these would do the trick, and as a result the first 2 impls are here and not split apart into
@@ -201,13 +333,17 @@ impl VeritechShutdownHandle {
 async fn process_resolver_function_requests_task(
     nats: NatsClient,
     subject_prefix: Option<String>,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: CyclonePool,
+    default_timeout_secs: u64,
+    cancel_broadcast_tx: broadcast::Sender<String>,
     shutdown_broadcast_rx: broadcast::Receiver<()>,
 ) {
     if let Err(err) = process_resolver_function_requests(
         nats,
         subject_prefix,
         cyclone_pool,
+        default_timeout_secs,
+        cancel_broadcast_tx,
         shutdown_broadcast_rx,
     )
     .await
@@ -219,7 +355,9 @@ async fn process_resolver_function_requests_task(
 async fn process_resolver_function_requests(
     nats: NatsClient,
     subject_prefix: Option<String>,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: CyclonePool,
+    default_timeout_secs: u64,
+    cancel_broadcast_tx: broadcast::Sender<String>,
     mut shutdown_broadcast_rx: broadcast::Receiver<()>,
 ) -> ServerResult<()> {
     let mut requests =
@@ -240,6 +378,8 @@ async fn process_resolver_function_requests(
                         tokio::spawn(resolver_function_request_task(
                             nats.clone(),
                             cyclone_pool.clone(),
+                            default_timeout_secs,
+                            cancel_broadcast_tx.subscribe(),
                             request,
                         ));
                     }
@@ -268,10 +408,12 @@ async fn process_resolver_function_requests(
 
 async fn resolver_function_request_task(
     nats: NatsClient,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: CyclonePool,
+    default_timeout_secs: u64,
+    cancel_broadcast_rx: broadcast::Receiver<String>,
     request: Request<ResolverFunctionRequest>,
 ) {
-    let (cyclone_request, reply_mailbox) = request.into_parts();
+    let (mut cyclone_request, reply_mailbox) = request.into_parts();
     let reply_mailbox = match reply_mailbox {
         Some(reply_mailbox) => reply_mailbox,
         None => {
@@ -280,10 +422,19 @@ async fn resolver_function_request_task(
         }
     };
     let execution_id = cyclone_request.execution_id.clone();
+    cyclone_request.timeout_secs = Some(capped_timeout_secs(
+        cyclone_request.timeout_secs,
+        default_timeout_secs,
+    ));
     let publisher = Publisher::new(&nats, &reply_mailbox);
 
-    let function_result =
-        resolver_function_request(&publisher, cyclone_pool, cyclone_request).await;
+    let function_result = resolver_function_request(
+        &publisher,
+        cyclone_pool,
+        cancel_broadcast_rx,
+        cyclone_request,
+    )
+    .await;
 
     if let Err(err) = publisher.finalize_output().await {
         error!(error = ?err, "failed to finalize output by sending final message");
@@ -327,33 +478,25 @@ async fn resolver_function_request_task(
 
 async fn resolver_function_request(
     publisher: &Publisher<'_>,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: CyclonePool,
+    mut cancel_broadcast_rx: broadcast::Receiver<String>,
     cyclone_request: ResolverFunctionRequest,
 ) -> ServerResult<FunctionResult<ResolverFunctionResultSuccess>> {
-    let mut client = cyclone_pool
-        .get()
-        .await
-        .map_err(|err| ServerError::CyclonePool(Box::new(err)))?;
+    let execution_id = cyclone_request.execution_id.clone();
+    let mut client = cyclone_pool.get().await?;
     let mut progress = client
         .execute_resolver(cyclone_request)
         .await?
         .start()
         .await?;
 
-    while let Some(msg) = progress.next().await {
-        match msg {
-            Ok(ProgressMessage::OutputStream(output)) => {
-                publisher.publish_output(&output).await?;
-            }
-            Ok(ProgressMessage::Heartbeat) => {
-                trace!("received heartbeat message");
-            }
-            Err(err) => {
-                warn!(error = ?err, "next progress message was an error, bailing out");
-                break;
-            }
-        }
-    }
+    forward_progress(
+        publisher,
+        &mut progress,
+        &execution_id,
+        &mut cancel_broadcast_rx,
+    )
+    .await?;
 
     let function_result = progress.finish().await?;
 
@@ -363,11 +506,20 @@ async fn resolver_function_request(
 async fn process_validation_requests_task(
     nats: NatsClient,
     subject_prefix: Option<String>,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: CyclonePool,
+    default_timeout_secs: u64,
+    cancel_broadcast_tx: broadcast::Sender<String>,
     shutdown_broadcast_rx: broadcast::Receiver<()>,
 ) {
-    if let Err(err) =
-        process_validation_requests(nats, subject_prefix, cyclone_pool, shutdown_broadcast_rx).await
+    if let Err(err) = process_validation_requests(
+        nats,
+        subject_prefix,
+        cyclone_pool,
+        default_timeout_secs,
+        cancel_broadcast_tx,
+        shutdown_broadcast_rx,
+    )
+    .await
     {
         warn!(error = ?err, "processing validation requests failed");
     }
@@ -376,7 +528,9 @@ async fn process_validation_requests_task(
 async fn process_validation_requests(
     nats: NatsClient,
     subject_prefix: Option<String>,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: CyclonePool,
+    default_timeout_secs: u64,
+    cancel_broadcast_tx: broadcast::Sender<String>,
     mut shutdown_broadcast_rx: broadcast::Receiver<()>,
 ) -> ServerResult<()> {
     let mut requests = FunctionSubscriber::validation(&nats, subject_prefix.as_deref()).await?;
@@ -396,6 +550,8 @@ async fn process_validation_requests(
                         tokio::spawn(validation_request_task(
                             nats.clone(),
                             cyclone_pool.clone(),
+                            default_timeout_secs,
+                            cancel_broadcast_tx.subscribe(),
                             request,
                         ));
                     }
@@ -424,47 +580,54 @@ async fn process_validation_requests(
 
 async fn validation_request_task(
     nats: NatsClient,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: CyclonePool,
+    default_timeout_secs: u64,
+    cancel_broadcast_rx: broadcast::Receiver<String>,
     request: Request<ValidationRequest>,
 ) {
-    if let Err(err) = validation_request(nats, cyclone_pool, request).await {
+    if let Err(err) = validation_request(
+        nats,
+        cyclone_pool,
+        default_timeout_secs,
+        cancel_broadcast_rx,
+        request,
+    )
+    .await
+    {
         warn!(error = ?err, "validation execution failed");
     }
 }
 
 async fn validation_request(
     nats: NatsClient,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: CyclonePool,
+    default_timeout_secs: u64,
+    mut cancel_broadcast_rx: broadcast::Receiver<String>,
     request: Request<ValidationRequest>,
 ) -> ServerResult<()> {
-    let (cyclone_request, reply_mailbox) = request.into_parts();
+    let (mut cyclone_request, reply_mailbox) = request.into_parts();
     let reply_mailbox = reply_mailbox.ok_or(ServerError::NoReplyMailboxFound)?;
+    let execution_id = cyclone_request.execution_id.clone();
+    cyclone_request.timeout_secs = Some(capped_timeout_secs(
+        cyclone_request.timeout_secs,
+        default_timeout_secs,
+    ));
 
     let publisher = Publisher::new(&nats, &reply_mailbox);
-    let mut client = cyclone_pool
-        .get()
-        .await
-        .map_err(|err| ServerError::CyclonePool(Box::new(err)))?;
+    let mut client = cyclone_pool.get().await?;
     let mut progress = client
         .execute_validation(cyclone_request)
         .await?
         .start()
         .await?;
 
-    while let Some(msg) = progress.next().await {
-        match msg {
-            Ok(ProgressMessage::OutputStream(output)) => {
-                publisher.publish_output(&output).await?;
-            }
-            Ok(ProgressMessage::Heartbeat) => {
-                trace!("received heartbeat message");
-            }
-            Err(err) => {
-                warn!(error = ?err, "next progress message was an error, bailing out");
-                break;
-            }
-        }
-    }
+    forward_progress(
+        &publisher,
+        &mut progress,
+        &execution_id,
+        &mut cancel_broadcast_rx,
+    )
+    .await?;
     publisher.finalize_output().await?;
 
     let function_result = progress.finish().await?;
@@ -476,13 +639,17 @@ async fn validation_request(
 async fn process_schema_variant_definition_requests_task(
     nats: NatsClient,
     subject_prefix: Option<String>,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: CyclonePool,
+    default_timeout_secs: u64,
+    cancel_broadcast_tx: broadcast::Sender<String>,
     shutdown_broadcast_rx: broadcast::Receiver<()>,
 ) {
     if let Err(err) = process_schema_variant_definition_requests(
         nats,
         subject_prefix,
         cyclone_pool,
+        default_timeout_secs,
+        cancel_broadcast_tx,
         shutdown_broadcast_rx,
     )
     .await
@@ -494,7 +661,9 @@ async fn process_schema_variant_definition_requests_task(
 async fn process_schema_variant_definition_requests(
     nats: NatsClient,
     subject_prefix: Option<String>,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: CyclonePool,
+    default_timeout_secs: u64,
+    cancel_broadcast_tx: broadcast::Sender<String>,
     mut shutdown_broadcast_rx: broadcast::Receiver<()>,
 ) -> ServerResult<()> {
     let mut requests =
@@ -515,6 +684,8 @@ async fn process_schema_variant_definition_requests(
                         tokio::spawn(schema_variant_definition_request_task(
                             nats.clone(),
                             cyclone_pool.clone(),
+                            default_timeout_secs,
+                            cancel_broadcast_tx.subscribe(),
                             request,
                         ));
                     }
@@ -543,27 +714,41 @@ async fn process_schema_variant_definition_requests(
 
 async fn schema_variant_definition_request_task(
     nats: NatsClient,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: CyclonePool,
+    default_timeout_secs: u64,
+    cancel_broadcast_rx: broadcast::Receiver<String>,
     request: Request<SchemaVariantDefinitionRequest>,
 ) {
-    if let Err(err) = schema_variant_definition_request(nats, cyclone_pool, request).await {
+    if let Err(err) = schema_variant_definition_request(
+        nats,
+        cyclone_pool,
+        default_timeout_secs,
+        cancel_broadcast_rx,
+        request,
+    )
+    .await
+    {
         warn!(error = ?err, "schema variant definition execution failed");
     }
 }
 
 async fn schema_variant_definition_request(
     nats: NatsClient,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: CyclonePool,
+    default_timeout_secs: u64,
+    mut cancel_broadcast_rx: broadcast::Receiver<String>,
     request: Request<SchemaVariantDefinitionRequest>,
 ) -> ServerResult<()> {
-    let (cyclone_request, reply_mailbox) = request.into_parts();
+    let (mut cyclone_request, reply_mailbox) = request.into_parts();
     let reply_mailbox = reply_mailbox.ok_or(ServerError::NoReplyMailboxFound)?;
+    let execution_id = cyclone_request.execution_id.clone();
+    cyclone_request.timeout_secs = Some(capped_timeout_secs(
+        cyclone_request.timeout_secs,
+        default_timeout_secs,
+    ));
 
     let publisher = Publisher::new(&nats, &reply_mailbox);
-    let mut client = cyclone_pool
-        .get()
-        .await
-        .map_err(|err| ServerError::CyclonePool(Box::new(err)))?;
+    let mut client = cyclone_pool.get().await?;
 
     let mut progress = client
         .execute_schema_variant_definition(cyclone_request)
@@ -571,20 +756,13 @@ async fn schema_variant_definition_request(
         .start()
         .await?;
 
-    while let Some(msg) = progress.next().await {
-        match msg {
-            Ok(ProgressMessage::OutputStream(output)) => {
-                publisher.publish_output(&output).await?;
-            }
-            Ok(ProgressMessage::Heartbeat) => {
-                trace!("received heartbeat message");
-            }
-            Err(err) => {
-                warn!(error = ?err, "next progress message was an error, bailing out");
-                break;
-            }
-        }
-    }
+    forward_progress(
+        &publisher,
+        &mut progress,
+        &execution_id,
+        &mut cancel_broadcast_rx,
+    )
+    .await?;
     publisher.finalize_output().await?;
 
     let function_result = progress.finish().await?;
@@ -596,11 +774,20 @@ async fn schema_variant_definition_request(
 async fn process_action_run_requests_task(
     nats: NatsClient,
     subject_prefix: Option<String>,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: CyclonePool,
+    default_timeout_secs: u64,
+    cancel_broadcast_tx: broadcast::Sender<String>,
     shutdown_broadcast_rx: broadcast::Receiver<()>,
 ) {
-    if let Err(err) =
-        process_action_run_requests(nats, subject_prefix, cyclone_pool, shutdown_broadcast_rx).await
+    if let Err(err) = process_action_run_requests(
+        nats,
+        subject_prefix,
+        cyclone_pool,
+        default_timeout_secs,
+        cancel_broadcast_tx,
+        shutdown_broadcast_rx,
+    )
+    .await
     {
         warn!(error = ?err, "processing action run requests failed");
     }
@@ -609,7 +796,9 @@ async fn process_action_run_requests_task(
 async fn process_action_run_requests(
     nats: NatsClient,
     subject_prefix: Option<String>,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: CyclonePool,
+    default_timeout_secs: u64,
+    cancel_broadcast_tx: broadcast::Sender<String>,
     mut shutdown_broadcast_rx: broadcast::Receiver<()>,
 ) -> ServerResult<()> {
     let mut requests = FunctionSubscriber::action_run(&nats, subject_prefix.as_deref()).await?;
@@ -629,6 +818,8 @@ async fn process_action_run_requests(
                         tokio::spawn(action_run_request_task(
                             nats.clone(),
                             cyclone_pool.clone(),
+                            default_timeout_secs,
+                            cancel_broadcast_tx.subscribe(),
                             request,
                         ));
                     }
@@ -657,27 +848,41 @@ async fn process_action_run_requests(
 
 async fn action_run_request_task(
     nats: NatsClient,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: CyclonePool,
+    default_timeout_secs: u64,
+    cancel_broadcast_rx: broadcast::Receiver<String>,
     request: Request<ActionRunRequest>,
 ) {
-    if let Err(err) = action_run_request(nats, cyclone_pool, request).await {
+    if let Err(err) = action_run_request(
+        nats,
+        cyclone_pool,
+        default_timeout_secs,
+        cancel_broadcast_rx,
+        request,
+    )
+    .await
+    {
         warn!(error = ?err, "action run execution failed");
     }
 }
 
 async fn action_run_request(
     nats: NatsClient,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: CyclonePool,
+    default_timeout_secs: u64,
+    mut cancel_broadcast_rx: broadcast::Receiver<String>,
     request: Request<ActionRunRequest>,
 ) -> ServerResult<()> {
-    let (cyclone_request, reply_mailbox) = request.into_parts();
+    let (mut cyclone_request, reply_mailbox) = request.into_parts();
     let reply_mailbox = reply_mailbox.ok_or(ServerError::NoReplyMailboxFound)?;
+    let execution_id = cyclone_request.execution_id.clone();
+    cyclone_request.timeout_secs = Some(capped_timeout_secs(
+        cyclone_request.timeout_secs,
+        default_timeout_secs,
+    ));
 
     let publisher = Publisher::new(&nats, &reply_mailbox);
-    let mut client = cyclone_pool
-        .get()
-        .await
-        .map_err(|err| ServerError::CyclonePool(Box::new(err)))?;
+    let mut client = cyclone_pool.get().await?;
 
     let mut progress = client
         .execute_action_run(cyclone_request)
@@ -685,20 +890,13 @@ async fn action_run_request(
         .start()
         .await?;
 
-    while let Some(msg) = progress.next().await {
-        match msg {
-            Ok(ProgressMessage::OutputStream(output)) => {
-                publisher.publish_output(&output).await?;
-            }
-            Ok(ProgressMessage::Heartbeat) => {
-                trace!("received heartbeat message");
-            }
-            Err(err) => {
-                warn!(error = ?err, "next progress message was an error, bailing out");
-                break;
-            }
-        }
-    }
+    forward_progress(
+        &publisher,
+        &mut progress,
+        &execution_id,
+        &mut cancel_broadcast_rx,
+    )
+    .await?;
     publisher.finalize_output().await?;
 
     let function_result = progress.finish().await?;
@@ -710,12 +908,20 @@ async fn action_run_request(
 async fn process_reconciliation_requests_task(
     nats: NatsClient,
     subject_prefix: Option<String>,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: CyclonePool,
+    default_timeout_secs: u64,
+    cancel_broadcast_tx: broadcast::Sender<String>,
     shutdown_broadcast_rx: broadcast::Receiver<()>,
 ) {
-    if let Err(err) =
-        process_reconciliation_requests(nats, subject_prefix, cyclone_pool, shutdown_broadcast_rx)
-            .await
+    if let Err(err) = process_reconciliation_requests(
+        nats,
+        subject_prefix,
+        cyclone_pool,
+        default_timeout_secs,
+        cancel_broadcast_tx,
+        shutdown_broadcast_rx,
+    )
+    .await
     {
         warn!(error = ?err, "processing reconciliation requests failed");
     }
@@ -724,7 +930,9 @@ async fn process_reconciliation_requests_task(
 async fn process_reconciliation_requests(
     nats: NatsClient,
     subject_prefix: Option<String>,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: CyclonePool,
+    default_timeout_secs: u64,
+    cancel_broadcast_tx: broadcast::Sender<String>,
     mut shutdown_broadcast_rx: broadcast::Receiver<()>,
 ) -> ServerResult<()> {
     let mut requests = FunctionSubscriber::reconciliation(&nats, subject_prefix.as_deref()).await?;
@@ -744,6 +952,8 @@ async fn process_reconciliation_requests(
                         tokio::spawn(reconciliation_request_task(
                             nats.clone(),
                             cyclone_pool.clone(),
+                            default_timeout_secs,
+                            cancel_broadcast_tx.subscribe(),
                             request,
                         ));
                     }
@@ -772,27 +982,41 @@ async fn process_reconciliation_requests(
 
 async fn reconciliation_request_task(
     nats: NatsClient,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: CyclonePool,
+    default_timeout_secs: u64,
+    cancel_broadcast_rx: broadcast::Receiver<String>,
     request: Request<ReconciliationRequest>,
 ) {
-    if let Err(err) = reconciliation_request(nats, cyclone_pool, request).await {
+    if let Err(err) = reconciliation_request(
+        nats,
+        cyclone_pool,
+        default_timeout_secs,
+        cancel_broadcast_rx,
+        request,
+    )
+    .await
+    {
         warn!(error = ?err, "reconciliation execution failed");
     }
 }
 
 async fn reconciliation_request(
     nats: NatsClient,
-    cyclone_pool: Pool<LocalUdsInstanceSpec>,
+    cyclone_pool: CyclonePool,
+    default_timeout_secs: u64,
+    mut cancel_broadcast_rx: broadcast::Receiver<String>,
     request: Request<ReconciliationRequest>,
 ) -> ServerResult<()> {
-    let (cyclone_request, reply_mailbox) = request.into_parts();
+    let (mut cyclone_request, reply_mailbox) = request.into_parts();
     let reply_mailbox = reply_mailbox.ok_or(ServerError::NoReplyMailboxFound)?;
+    let execution_id = cyclone_request.execution_id.clone();
+    cyclone_request.timeout_secs = Some(capped_timeout_secs(
+        cyclone_request.timeout_secs,
+        default_timeout_secs,
+    ));
 
     let publisher = Publisher::new(&nats, &reply_mailbox);
-    let mut client = cyclone_pool
-        .get()
-        .await
-        .map_err(|err| ServerError::CyclonePool(Box::new(err)))?;
+    let mut client = cyclone_pool.get().await?;
 
     let mut progress = client
         .execute_reconciliation(cyclone_request)
@@ -800,28 +1024,144 @@ async fn reconciliation_request(
         .start()
         .await?;
 
-    while let Some(msg) = progress.next().await {
-        match msg {
-            Ok(ProgressMessage::OutputStream(output)) => {
-                publisher.publish_output(&output).await?;
+    forward_progress(
+        &publisher,
+        &mut progress,
+        &execution_id,
+        &mut cancel_broadcast_rx,
+    )
+    .await?;
+    publisher.finalize_output().await?;
+
+    let function_result = progress.finish().await?;
+    publisher.publish_result(&function_result).await?;
+
+    Ok(())
+}
+
+async fn process_cancel_execution_requests_task(
+    nats: NatsClient,
+    subject_prefix: Option<String>,
+    cancel_broadcast_tx: broadcast::Sender<String>,
+    shutdown_broadcast_rx: broadcast::Receiver<()>,
+) {
+    if let Err(err) = process_cancel_execution_requests(
+        nats,
+        subject_prefix,
+        cancel_broadcast_tx,
+        shutdown_broadcast_rx,
+    )
+    .await
+    {
+        warn!(error = ?err, "processing cancel execution requests failed");
+    }
+}
+
+async fn process_cancel_execution_requests(
+    nats: NatsClient,
+    subject_prefix: Option<String>,
+    cancel_broadcast_tx: broadcast::Sender<String>,
+    mut shutdown_broadcast_rx: broadcast::Receiver<()>,
+) -> ServerResult<()> {
+    let mut requests: Subscriber<CancelExecutionRequest> =
+        FunctionSubscriber::cancel_execution(&nats, subject_prefix.as_deref()).await?;
+
+    loop {
+        tokio::select! {
+            // Got a broadcasted shutdown message
+            _ = shutdown_broadcast_rx.recv() => {
+                trace!("process cancel execution requests task received shutdown");
+                break;
             }
-            Ok(ProgressMessage::Heartbeat) => {
-                trace!("received heartbeat message");
+            // Got the next message on from the subscriber
+            request = requests.next() => {
+                match request {
+                    Some(Ok(request)) => {
+                        let (request, _) = request.into_parts();
+                        // Only the tasks executing the function, if any, act on it, and there
+                        // may be none listening at all
+                        let _ = cancel_broadcast_tx.send(request.execution_id);
+                    }
+                    Some(Err(err)) => {
+                        warn!(error = ?err, "next cancel execution request had error");
+                    }
+                    None => {
+                        trace!("cancel execution requests subscriber stream has closed");
+                        break;
+                    }
+                }
             }
-            Err(err) => {
-                warn!(error = ?err, "next progress message was an error, bailing out");
-                break;
+            // All other arms are closed, nothing left to do but return
+            else => {
+                trace!("returning with all select arms closed");
+                break
             }
         }
     }
-    publisher.finalize_output().await?;
 
-    let function_result = progress.finish().await?;
-    publisher.publish_result(&function_result).await?;
+    // Unsubscribe from subscriber without draining the channel
+    requests.unsubscribe_after(0).await?;
 
     Ok(())
 }
 
+/// Publishes the output of an execution until cyclone is done with it. If the execution is
+/// cancelled meanwhile, cyclone is asked to kill the function, and is then done with it once it
+/// sent the failure result.
+async fn forward_progress<Success>(
+    publisher: &Publisher<'_>,
+    progress: &mut ExecutionStarted<UnixStream, Success>,
+    execution_id: &str,
+    cancel_broadcast_rx: &mut broadcast::Receiver<String>,
+) -> ServerResult<()>
+where
+    Success: DeserializeOwned + Unpin + fmt::Debug,
+    ServerError: From<ExecutionError<Success>>,
+{
+    let mut cancellable = true;
+    loop {
+        tokio::select! {
+            msg = progress.next() => {
+                match msg {
+                    Some(Ok(ProgressMessage::OutputStream(output))) => {
+                        publisher.publish_output(&output).await?;
+                    }
+                    Some(Ok(ProgressMessage::Heartbeat)) => {
+                        trace!("received heartbeat message");
+                    }
+                    Some(Err(err)) => {
+                        warn!(error = ?err, "next progress message was an error, bailing out");
+                        break;
+                    }
+                    None => break,
+                }
+            }
+            cancelled = cancel_broadcast_rx.recv(), if cancellable => {
+                match cancelled {
+                    Ok(cancelled) if cancelled == execution_id => {
+                        info!(execution_id, "cancelling execution");
+                        progress.cancel().await?;
+                        cancellable = false;
+                    }
+                    Ok(_) => {}
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!(skipped, "missed cancel execution requests");
+                    }
+                    Err(broadcast::error::RecvError::Closed) => cancellable = false,
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The timeout of a request: the one it asks for, but never longer than the configured default
+/// of its kind of function.
+fn capped_timeout_secs(requested_secs: Option<u64>, default_secs: u64) -> u64 {
+    requested_secs.map_or(default_secs, |secs| secs.min(default_secs))
+}
+
 async fn connect_to_nats(config: &Config) -> ServerResult<NatsClient> {
     info!("connecting to NATS; url={}", config.nats().url);
 
@@ -1,13 +1,14 @@
 use deadpool_cyclone::{
-    ActionRunRequest, ReconciliationRequest, ResolverFunctionRequest,
+    ActionRunRequest, CancelExecutionRequest, ReconciliationRequest, ResolverFunctionRequest,
     SchemaVariantDefinitionRequest, ValidationRequest,
 };
 use nats_subscriber::Subscriber;
 use si_data_nats::NatsClient;
 use telemetry::prelude::*;
 use veritech_core::{
-    nats_action_run_subject, nats_reconciliation_subject, nats_resolver_function_subject,
-    nats_schema_variant_definition_subject, nats_validation_subject,
+    nats_action_run_subject, nats_cancel_execution_subject, nats_reconciliation_subject,
+    nats_resolver_function_subject, nats_schema_variant_definition_subject,
+    nats_validation_subject,
 };
 
 type Result<T> = std::result::Result<T, nats_subscriber::SubscriberError>;
@@ -94,4 +95,18 @@ impl FunctionSubscriber {
             .start(nats)
             .await
     }
+
+    /// Every server receives every cancel execution request, as only the one executing the
+    /// function can act on it.
+    pub async fn cancel_execution(
+        nats: &NatsClient,
+        subject_prefix: Option<&str>,
+    ) -> Result<Subscriber<CancelExecutionRequest>> {
+        let subject = nats_cancel_execution_subject(subject_prefix);
+        debug!(
+            messaging.destination = &subject.as_str(),
+            "subscribing for cancel execution requests"
+        );
+        Subscriber::create(subject).start(nats).await
+    }
 }